@@ -0,0 +1,495 @@
+//! Host-side client for the Great Communications Protocol
+//!
+//! A [`Client`] sends GCP commands to a device over a [`Transport`] and
+//! uses the `core` class's introspection verbs to discover the classes
+//! and verbs the device provides, so that verbs can be called by name:
+//!
+//! ```no_run
+//! use cynthion::gcp::{Client, UsbTransport, Value};
+//!
+//! let mut client = Client::connect(UsbTransport::open()?)?;
+//! let result = client.call("selftest", "test_error_return_code", &[Value::from(0_u32)])?;
+//! assert_eq!(result, [Value::from("ok")]);
+//! # Ok::<(), cynthion::gcp::Error>(())
+//! ```
+//!
+//! Commands are encoded as a [`CommandPrelude`] followed by the verb's
+//! arguments, exactly as they are parsed by [`libgreat::gcp::Command`].
+
+pub mod loopback;
+pub mod usb;
+pub mod value;
+
+pub use loopback::Loopback;
+pub use usb::UsbTransport;
+pub use value::Value;
+
+use libgreat::gcp::{ClassId, CommandPrelude, VerbDescriptor};
+use libgreat::GreatError;
+use zerocopy::AsBytes;
+
+// - Error --------------------------------------------------------------------
+
+/// GCP client error type
+#[derive(Debug)]
+pub enum Error {
+    /// A USB transfer failed.
+    Usb(rusb::Error),
+    /// No device was found.
+    DeviceNotFound,
+    /// The device failed the command with a known error.
+    Device(GreatError),
+    /// The device failed the command with an unknown error code.
+    DeviceCode(u32),
+    /// The device does not provide a class with the given name.
+    UnknownClass(String),
+    /// The class does not provide a verb with the given name.
+    UnknownVerb(String, String),
+    /// The arguments do not match the verb's signature.
+    InvalidArguments(String),
+    /// The response does not match the verb's signature.
+    InvalidResponse(String),
+}
+
+impl Error {
+    /// Returns the error for a device error code.
+    #[must_use]
+    pub fn from_code(code: u32) -> Self {
+        match GreatError::try_from(code) {
+            Ok(error) => Error::Device(error),
+            Err(code) => Error::DeviceCode(code),
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Usb(error) => write!(f, "usb error: {error}"),
+            Error::DeviceNotFound => write!(f, "no device found"),
+            Error::Device(error) => write!(f, "device error: {error}"),
+            Error::DeviceCode(code) => write!(f, "device error: unknown error code {code}"),
+            Error::UnknownClass(class) => write!(f, "unknown class '{class}'"),
+            Error::UnknownVerb(class, verb) => write!(f, "unknown verb '{class}.{verb}'"),
+            Error::InvalidArguments(message) => write!(f, "invalid arguments: {message}"),
+            Error::InvalidResponse(message) => write!(f, "invalid response: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusb::Error> for Error {
+    fn from(error: rusb::Error) -> Self {
+        Error::Usb(error)
+    }
+}
+
+// - Transport ----------------------------------------------------------------
+
+/// A connection to a device that executes GCP commands
+pub trait Transport {
+    /// Execute a command, starting with its [`CommandPrelude`], and
+    /// return its response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] or [`Error::DeviceCode`] if the device
+    /// failed the command, or an error of the transport.
+    fn execute(&mut self, command: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Abort the command in progress and any jobs running on the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the transport.
+    fn cancel(&mut self) -> Result<(), Error>;
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn execute(&mut self, command: &[u8]) -> Result<Vec<u8>, Error> {
+        (**self).execute(command)
+    }
+
+    fn cancel(&mut self) -> Result<(), Error> {
+        (**self).cancel()
+    }
+}
+
+// - ClassInfo ----------------------------------------------------------------
+
+/// A class provided by a device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassInfo {
+    pub id: u32,
+    pub name: String,
+    pub docs: String,
+    pub verbs: Vec<VerbInfo>,
+}
+
+impl ClassInfo {
+    #[must_use]
+    pub fn verb(&self, name: &str) -> Option<&VerbInfo> {
+        self.verbs.iter().find(|verb| verb.name == name)
+    }
+}
+
+/// A verb provided by a device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerbInfo {
+    pub id: u32,
+    pub name: String,
+    pub in_signature: String,
+    pub in_param_names: String,
+    pub out_signature: String,
+    pub out_param_names: String,
+    pub doc: String,
+}
+
+// - Client -------------------------------------------------------------------
+
+/// GCP client
+pub struct Client<T: Transport> {
+    transport: T,
+    classes: Vec<ClassInfo>,
+}
+
+impl<T: Transport> Client<T> {
+    /// Create a new client without discovering the device's classes.
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            classes: Vec::new(),
+        }
+    }
+
+    /// Create a new client and discover the device's classes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn connect(transport: T) -> Result<Self, Error> {
+        let mut client = Self::new(transport);
+        client.discover()?;
+        Ok(client)
+    }
+
+    /// Returns the transport of the client.
+    pub fn transport(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Returns the classes found by [`discover`](Self::discover).
+    #[must_use]
+    pub fn classes(&self) -> &[ClassInfo] {
+        &self.classes
+    }
+
+    /// Returns the class with the given name.
+    #[must_use]
+    pub fn class(&self, name: &str) -> Option<&ClassInfo> {
+        self.classes.iter().find(|class| class.name == name)
+    }
+
+    /// Execute a verb with raw arguments, returning its raw response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Device`] or [`Error::DeviceCode`] if the device
+    /// failed the command, or an error of the transport.
+    pub fn execute(
+        &mut self,
+        class_number: u32,
+        verb_number: u32,
+        arguments: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let prelude = CommandPrelude {
+            class: class_number.into(),
+            verb: verb_number.into(),
+        };
+        let mut command = prelude.as_bytes().to_vec();
+        command.extend_from_slice(arguments);
+        self.transport.execute(&command)
+    }
+
+    /// Abort the command in progress and any jobs running on the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of the transport.
+    pub fn cancel(&mut self) -> Result<(), Error> {
+        self.transport.cancel()
+    }
+
+    /// Call a verb by name, encoding the arguments and decoding the
+    /// response according to the verb's signatures.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownClass`] or [`Error::UnknownVerb`] if the
+    /// verb was not discovered, [`Error::InvalidArguments`] or
+    /// [`Error::InvalidResponse`] if the values do not match the
+    /// signatures, or the error the command failed with.
+    pub fn call(
+        &mut self,
+        class_name: &str,
+        verb_name: &str,
+        arguments: &[Value],
+    ) -> Result<Vec<Value>, Error> {
+        let class = self
+            .class(class_name)
+            .ok_or_else(|| Error::UnknownClass(class_name.into()))?;
+        let verb = class
+            .verb(verb_name)
+            .ok_or_else(|| Error::UnknownVerb(class_name.into(), verb_name.into()))?;
+        let (class_number, verb_number) = (class.id, verb.id);
+        let out_signature = verb.out_signature.clone();
+
+        let arguments = value::encode(&verb.in_signature, arguments)?;
+        let response = self.execute(class_number, verb_number, &arguments)?;
+        value::decode(&out_signature, &response)
+    }
+
+    /// Query the classes and verbs provided by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn discover(&mut self) -> Result<(), Error> {
+        let mut classes = Vec::new();
+        for class_number in self.get_available_classes()? {
+            let mut verbs = Vec::new();
+            for verb_number in self.get_available_verbs(class_number)? {
+                let name = self.get_verb_name(class_number, verb_number)?;
+                let mut descriptor =
+                    |descriptor| self.get_verb_descriptor(class_number, verb_number, descriptor);
+                verbs.push(VerbInfo {
+                    id: verb_number,
+                    name,
+                    in_signature: descriptor(VerbDescriptor::InSignature)?,
+                    in_param_names: descriptor(VerbDescriptor::InParamNames)?,
+                    out_signature: descriptor(VerbDescriptor::OutSignature)?,
+                    out_param_names: descriptor(VerbDescriptor::OutParamNames)?,
+                    doc: descriptor(VerbDescriptor::Doc)?,
+                });
+            }
+            classes.push(ClassInfo {
+                id: class_number,
+                name: self.get_class_name(class_number)?,
+                docs: self.get_class_docs(class_number)?,
+                verbs,
+            });
+        }
+        self.classes = classes;
+        Ok(())
+    }
+
+    // - core introspection --
+
+    /// Returns the ids of the classes provided by the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_available_classes(&mut self) -> Result<Vec<u32>, Error> {
+        let response = self.execute_core(0x4, &[])?;
+        u32_list(&response)
+    }
+
+    /// Returns the ids of the verbs provided by a class.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_available_verbs(&mut self, class_number: u32) -> Result<Vec<u32>, Error> {
+        let response = self.execute_core(0x5, &class_number.to_le_bytes())?;
+        u32_list(&response)
+    }
+
+    /// Returns the name of a verb.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_verb_name(&mut self, class_number: u32, verb_number: u32) -> Result<String, Error> {
+        let mut arguments = class_number.to_le_bytes().to_vec();
+        arguments.extend_from_slice(&verb_number.to_le_bytes());
+        let response = self.execute_core(0x6, &arguments)?;
+        string(response)
+    }
+
+    /// Returns a descriptor of a verb.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_verb_descriptor(
+        &mut self,
+        class_number: u32,
+        verb_number: u32,
+        descriptor: VerbDescriptor,
+    ) -> Result<String, Error> {
+        let mut arguments = class_number.to_le_bytes().to_vec();
+        arguments.extend_from_slice(&verb_number.to_le_bytes());
+        arguments.extend_from_slice(&u32::from(descriptor).to_le_bytes());
+        let response = self.execute_core(0x7, &arguments)?;
+        string(response)
+    }
+
+    /// Returns the name of a class.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_class_name(&mut self, class_number: u32) -> Result<String, Error> {
+        let response = self.execute_core(0x8, &class_number.to_le_bytes())?;
+        string(response)
+    }
+
+    /// Returns the documentation of a class.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be queried.
+    pub fn get_class_docs(&mut self, class_number: u32) -> Result<String, Error> {
+        let response = self.execute_core(0x9, &class_number.to_le_bytes())?;
+        string(response)
+    }
+
+    fn execute_core(&mut self, verb_number: u32, arguments: &[u8]) -> Result<Vec<u8>, Error> {
+        self.execute(ClassId::core.into_u32(), verb_number, arguments)
+    }
+}
+
+// - helpers ------------------------------------------------------------------
+
+fn u32_list(response: &[u8]) -> Result<Vec<u32>, Error> {
+    if response.len() % 4 != 0 {
+        return Err(Error::InvalidResponse(format!(
+            "expected a list of u32, got {} bytes",
+            response.len()
+        )));
+    }
+    Ok(response
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+/// Decode a string response, ignoring the trailing `NUL` of C strings.
+fn string(response: Vec<u8>) -> Result<String, Error> {
+    let mut string =
+        String::from_utf8(response).map_err(|e| Error::InvalidResponse(e.to_string()))?;
+    let length = string.trim_end_matches('\0').len();
+    string.truncate(length);
+    Ok(string)
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libgreat::firmware::BoardInformation;
+    use libgreat::gcp::{verbs, Class};
+    use libgreat::GreatResult;
+
+    const BOARD_INFORMATION: BoardInformation = BoardInformation {
+        board_id: [0x00, 0x00, 0x00, 0x00],
+        version_string: "v2023.0.1\0",
+        part_id: [0x30, 0xa, 0x00, 0xa0, 0x5e, 0x4f, 0x60, 0x00],
+        serial_number: [0; 16],
+    };
+
+    static CLASS_SELFTEST: Class = Class {
+        id: ClassId::selftest,
+        name: "selftest",
+        docs: "Test class\0",
+        verbs: &Selftest::VERBS,
+    };
+
+    struct Selftest;
+
+    #[verbs]
+    impl Selftest {
+        /// Adds two numbers.
+        #[verb(id = 0x10, in_param_names = "a, b", out_param_names = "sum")]
+        fn add(&mut self, a: u32, b: u16) -> GreatResult<u32> {
+            a.checked_add(b.into()).ok_or(GreatError::ResultTooLarge)
+        }
+    }
+
+    libgreat::registry! {
+        struct Classes {
+            selftest: Selftest => CLASS_SELFTEST,
+        }
+    }
+
+    fn with_client(f: impl FnOnce(&mut Client<&mut dyn Transport>)) {
+        let mut classes = Classes::new(BOARD_INFORMATION, Selftest);
+        let mut transport = Loopback::new(|class, verb, arguments, buffer| {
+            classes.dispatch(class, verb, arguments, buffer)
+        });
+        let mut client = Client::connect(&mut transport as &mut dyn Transport).unwrap();
+        f(&mut client);
+    }
+
+    #[test]
+    fn test_discover() {
+        with_client(|client| {
+            let names: Vec<&str> = client.classes().iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, ["core", "selftest"]);
+
+            let selftest = client.class("selftest").unwrap();
+            assert_eq!(selftest.id, ClassId::selftest.into_u32());
+            assert_eq!(selftest.docs, "Test class");
+            let add = selftest.verb("add").unwrap();
+            assert_eq!(add.id, 0x10);
+            assert_eq!(add.in_signature, "<IH");
+            assert_eq!(add.in_param_names, "a, b");
+            assert_eq!(add.out_signature, "<I");
+            assert_eq!(add.out_param_names, "sum");
+        });
+    }
+
+    #[test]
+    fn test_call() {
+        with_client(|client| {
+            let sum = client.call("selftest", "add", &[1_u32.into(), 2_u16.into()]);
+            assert_eq!(sum.unwrap(), [Value::from(3_u32)]);
+
+            let version = client.call("core", "read_version_string", &[]);
+            assert_eq!(version.unwrap(), [Value::from(b"v2023.0.1\0".to_vec())]);
+        });
+    }
+
+    #[test]
+    fn test_call_errors() {
+        with_client(|client| {
+            assert!(matches!(
+                client.call("selftest", "add", &[u32::MAX.into(), 1_u16.into()]),
+                Err(Error::Device(GreatError::ResultTooLarge))
+            ));
+            assert!(matches!(
+                client.call("selftest", "add", &[1_u32.into()]),
+                Err(Error::InvalidArguments(_))
+            ));
+            assert!(matches!(
+                client.call("nonesuch", "add", &[]),
+                Err(Error::UnknownClass(_))
+            ));
+            assert!(matches!(
+                client.call("selftest", "nonesuch", &[]),
+                Err(Error::UnknownVerb(..))
+            ));
+        });
+        assert!(matches!(
+            Error::from_code(22),
+            Error::Device(GreatError::InvalidArgument)
+        ));
+        assert!(matches!(
+            Error::from_code(0xffff),
+            Error::DeviceCode(0xffff)
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+//! In-process GCP transport
+//!
+//! A [`Loopback`] executes commands by calling a dispatch function
+//! directly, such as the `dispatch` of a [`registry!`](libgreat::registry),
+//! so clients can be tested against firmware classes without a device:
+//!
+//! ```ignore
+//! let mut classes = Classes::new(BOARD_INFORMATION, Selftest::new());
+//! let transport = Loopback::new(|class, verb, arguments, buffer| {
+//!     classes.dispatch(class, verb, arguments, buffer)
+//! });
+//! let mut client = Client::connect(transport)?;
+//! ```
+
+use libgreat::gcp::{ClassId, Command, LIBGREAT_MAX_RESPONSE_SIZE};
+use libgreat::{GreatError, GreatResult};
+
+use super::{Error, Transport};
+
+/// Transport calling a dispatch function in the same process
+pub struct Loopback<F>
+where
+    F: FnMut(ClassId, u32, &[u8], &mut [u8]) -> GreatResult<usize>,
+{
+    dispatch: F,
+}
+
+impl<F> Loopback<F>
+where
+    F: FnMut(ClassId, u32, &[u8], &mut [u8]) -> GreatResult<usize>,
+{
+    pub fn new(dispatch: F) -> Self {
+        Self { dispatch }
+    }
+}
+
+impl<F> Transport for Loopback<F>
+where
+    F: FnMut(ClassId, u32, &[u8], &mut [u8]) -> GreatResult<usize>,
+{
+    fn execute(&mut self, command: &[u8]) -> Result<Vec<u8>, Error> {
+        let command = Command::parse(command).ok_or(Error::Device(GreatError::BadMessage))?;
+        let mut response = vec![0; LIBGREAT_MAX_RESPONSE_SIZE];
+        let length = (self.dispatch)(
+            command.class_id(),
+            command.verb_number(),
+            command.arguments,
+            &mut response,
+        )
+        .map_err(Error::Device)?;
+        response.truncate(length);
+        Ok(response)
+    }
+
+    /// Commands run to completion before `execute` returns so there is
+    /// never anything to cancel.
+    fn cancel(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
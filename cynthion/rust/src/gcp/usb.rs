@@ -0,0 +1,110 @@
+//! GCP transport over USB control requests
+//!
+//! Commands are sent to the device's control endpoint as libgreat vendor
+//! requests, the same way as pygreat does:
+//!
+//! * The command is sent with an OUT request.
+//! * The response is read with IN requests until one returns less than
+//!   was asked for.
+//! * A failed command stalls the first IN request and a second IN
+//!   request returns the error code.
+
+use std::time::Duration;
+
+use rusb::{DeviceHandle, Direction, GlobalContext, Recipient, RequestType};
+
+use crate::shared::libgreat::vendor::command_request;
+use crate::shared::usb::{bProductId, bVendorId};
+
+use super::{Error, Transport};
+
+/// Vendor request value of a command.
+const VALUE_EXECUTE: u16 = 0x0000;
+/// Vendor request value cancelling the command in progress.
+const VALUE_CANCEL: u16 = 0xdead;
+
+/// Length of each response IN request.
+const CHUNK_SIZE: usize = 4096;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Transport over the control endpoint of a Cynthion
+pub struct UsbTransport {
+    handle: DeviceHandle<GlobalContext>,
+}
+
+impl UsbTransport {
+    /// Open the first Cynthion found.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeviceNotFound`] if there is no Cynthion, or the
+    /// error opening it.
+    pub fn open() -> Result<Self, Error> {
+        let handle = rusb::open_device_with_vid_pid(bVendorId::cynthion, bProductId::cynthion)
+            .ok_or(Error::DeviceNotFound)?;
+        Ok(Self::from_handle(handle))
+    }
+
+    /// Use an already opened device.
+    #[must_use]
+    pub fn from_handle(handle: DeviceHandle<GlobalContext>) -> Self {
+        Self { handle }
+    }
+
+    /// Returns the handle of the device.
+    #[must_use]
+    pub fn handle(&self) -> &DeviceHandle<GlobalContext> {
+        &self.handle
+    }
+
+    fn read(&self, value: u16, buffer: &mut [u8]) -> rusb::Result<usize> {
+        let request_type =
+            rusb::request_type(Direction::In, RequestType::Vendor, Recipient::Endpoint);
+        self.handle
+            .read_control(request_type, command_request, value, 0, buffer, TIMEOUT)
+    }
+
+    fn write(&self, value: u16, data: &[u8]) -> rusb::Result<usize> {
+        let request_type =
+            rusb::request_type(Direction::Out, RequestType::Vendor, Recipient::Endpoint);
+        self.handle
+            .write_control(request_type, command_request, value, 0, data, TIMEOUT)
+    }
+}
+
+impl Transport for UsbTransport {
+    fn execute(&mut self, command: &[u8]) -> Result<Vec<u8>, Error> {
+        self.write(VALUE_EXECUTE, command)?;
+
+        let mut response = Vec::new();
+        let mut chunk = [0; CHUNK_SIZE];
+        loop {
+            match self.read(VALUE_EXECUTE, &mut chunk) {
+                Ok(length) => {
+                    response.extend_from_slice(&chunk[..length]);
+                    if length < CHUNK_SIZE {
+                        return Ok(response);
+                    }
+                }
+                Err(rusb::Error::Pipe) => {
+                    let mut code = [0; 4];
+                    let length = self.read(VALUE_EXECUTE, &mut code)?;
+                    if length != code.len() {
+                        return Err(Error::InvalidResponse(format!(
+                            "expected a 4 byte error code, got {length} bytes"
+                        )));
+                    }
+                    return Err(Error::from_code(u32::from_le_bytes(code)));
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    fn cancel(&mut self) -> Result<(), Error> {
+        let mut code = [0; 4];
+        self.read(VALUE_CANCEL, &mut code)?;
+        Ok(())
+    }
+}
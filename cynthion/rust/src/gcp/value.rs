@@ -0,0 +1,359 @@
+//! Dynamically typed verb arguments and responses
+//!
+//! The host only learns the signatures of a device's verbs at runtime,
+//! so arguments and responses are passed as [`Value`]s that are encoded
+//! and decoded by walking the fields of a
+//! [`Signature`](libgreat::gcp::signature::Signature):
+//!
+//! | field                         | value                                  |
+//! |-------------------------------|----------------------------------------|
+//! | integer codes                 | [`Value::Int`]                         |
+//! | `?`                           | [`Value::Bool`]                        |
+//! | `X` / `NX`                    | [`Value::Bytes`] of length `N`         |
+//! | `S`                           | [`Value::Str`]                         |
+//! | `(...)`                       | [`Value::List`] of the group's fields  |
+//! | `*X`, `*B`                    | [`Value::Bytes`]                       |
+//! | `*c`, `*(...)`                | [`Value::List`] of the elements        |
+//!
+//! Verbs that don't describe their arguments or response have the
+//! signature `*`, which is passed as a single [`Value::Bytes`].
+
+use libgreat::gcp::signature::{Code, Field, Format, Signature};
+
+use super::Error;
+
+// - Value --------------------------------------------------------------------
+
+/// A verb argument or response field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Int(i128),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    Str(String),
+    List(Vec<Value>),
+}
+
+macro_rules! impl_from_integer {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for Value {
+                fn from(value: $type) -> Self {
+                    Value::Int(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8 u8 i16 u16 i32 u32 i64 u64);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(value: &[u8]) -> Self {
+        Value::Bytes(value.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Bytes(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Value::List(value)
+    }
+}
+
+// - encode -------------------------------------------------------------------
+
+/// Encode values according to a signature.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArguments`] if the signature is malformed or
+/// the values do not match it.
+pub fn encode(signature: &str, values: &[Value]) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::InvalidArguments(format!("{values:?} do not match '{signature}'"));
+    if is_unspecified(signature) {
+        return match values {
+            [] => Ok(Vec::new()),
+            [Value::Bytes(bytes)] => Ok(bytes.clone()),
+            _ => Err(invalid()),
+        };
+    }
+    let signature = Signature::parse(signature).map_err(|_| invalid())?;
+
+    let mut bytes = Vec::new();
+    encode_fields(signature.format(), values, &mut bytes).ok_or_else(invalid)?;
+    Ok(bytes)
+}
+
+fn encode_fields(mut format: Format<'_>, values: &[Value], bytes: &mut Vec<u8>) -> Option<()> {
+    let mut values = values.iter();
+    while let Some(field) = format.next_field().ok()? {
+        encode_field(field, format.big_endian(), values.next()?, bytes)?;
+    }
+    values.next().is_none().then_some(())
+}
+
+fn encode_field(
+    field: Field<'_>,
+    big_endian: bool,
+    value: &Value,
+    bytes: &mut Vec<u8>,
+) -> Option<()> {
+    match (field, value) {
+        (Field::Value(Code::Bool), Value::Bool(value)) => bytes.push(u8::from(*value)),
+        (Field::Value(code), Value::Int(value)) => {
+            let (size, signed) = code_size(code)?;
+            let (min, max) = if signed {
+                (-(1_i128 << (size * 8 - 1)), (1_i128 << (size * 8 - 1)) - 1)
+            } else {
+                (0, (1_i128 << (size * 8)) - 1)
+            };
+            if !(min..=max).contains(value) {
+                return None;
+            }
+            let le = value.to_le_bytes();
+            if big_endian {
+                bytes.extend(le[..size].iter().rev());
+            } else {
+                bytes.extend_from_slice(&le[..size]);
+            }
+        }
+        (Field::Bytes(1), Value::Int(value)) => bytes.push(u8::try_from(*value).ok()?),
+        (Field::Bytes(length), Value::Bytes(value)) if value.len() == length => {
+            bytes.extend_from_slice(value);
+        }
+        (Field::Str, Value::Str(value)) if !value.contains('\0') => {
+            bytes.extend_from_slice(value.as_bytes());
+            bytes.push(0);
+        }
+        (Field::Group(format), Value::List(values)) => encode_fields(format, values, bytes)?,
+        (Field::Repeat(element), Value::Bytes(value)) if is_byte(element) => {
+            bytes.extend_from_slice(value);
+        }
+        (Field::Repeat(element), Value::List(values)) => {
+            for value in values {
+                match single_field(element)? {
+                    Some(field) => encode_field(field, element.big_endian(), value, bytes)?,
+                    None => match value {
+                        Value::List(values) => encode_fields(element, values, bytes)?,
+                        _ => return None,
+                    },
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+// - decode -------------------------------------------------------------------
+
+/// Decode values according to a signature.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidResponse`] if the signature is malformed or
+/// the bytes do not match it.
+pub fn decode(signature: &str, bytes: &[u8]) -> Result<Vec<Value>, Error> {
+    let invalid = || Error::InvalidResponse(format!("{bytes:02x?} does not match '{signature}'"));
+    if is_unspecified(signature) {
+        return Ok(vec![Value::Bytes(bytes.to_vec())]);
+    }
+    let signature = Signature::parse(signature).map_err(|_| invalid())?;
+
+    let mut bytes = bytes;
+    let values = decode_fields(signature.format(), &mut bytes).ok_or_else(invalid)?;
+    bytes.is_empty().then_some(values).ok_or_else(invalid)
+}
+
+fn decode_fields(mut format: Format<'_>, bytes: &mut &[u8]) -> Option<Vec<Value>> {
+    let mut values = Vec::new();
+    while let Some(field) = format.next_field().ok()? {
+        values.push(decode_field(field, format.big_endian(), bytes)?);
+    }
+    Some(values)
+}
+
+fn decode_field(field: Field<'_>, big_endian: bool, bytes: &mut &[u8]) -> Option<Value> {
+    let value = match field {
+        Field::Value(Code::Bool) => Value::Bool(take(bytes, 1)?[0] != 0),
+        Field::Value(code) => {
+            let (size, signed) = code_size(code)?;
+            let mut le = take(bytes, size)?.to_vec();
+            if big_endian {
+                le.reverse();
+            }
+            let negative = signed && le[size - 1] & 0x80 != 0;
+            le.resize(16, if negative { 0xff } else { 0 });
+            Value::Int(i128::from_le_bytes(le.try_into().ok()?))
+        }
+        Field::Bytes(length) => Value::Bytes(take(bytes, length)?.to_vec()),
+        Field::Str => {
+            // the terminator is optional at the end of the data
+            let length = bytes.iter().position(|&byte| byte == 0);
+            let string = take(bytes, length.unwrap_or(bytes.len()))?;
+            let string = String::from_utf8(string.to_vec()).ok()?;
+            if length.is_some() {
+                take(bytes, 1)?;
+            }
+            Value::Str(string)
+        }
+        Field::Group(format) => Value::List(decode_fields(format, bytes)?),
+        Field::Repeat(element) if is_byte(element) => {
+            Value::Bytes(take(bytes, bytes.len())?.to_vec())
+        }
+        Field::Repeat(element) => {
+            let mut values = Vec::new();
+            while !bytes.is_empty() {
+                let value = match single_field(element)? {
+                    Some(field) => decode_field(field, element.big_endian(), bytes)?,
+                    None => Value::List(decode_fields(element, bytes)?),
+                };
+                values.push(value);
+            }
+            Value::List(values)
+        }
+    };
+    Some(value)
+}
+
+// - helpers ------------------------------------------------------------------
+
+/// Returns `true` if the signature is `*`.
+fn is_unspecified(signature: &str) -> bool {
+    signature.trim_end_matches('\0').trim() == "*"
+}
+
+/// Returns the size in bytes of an integer code and whether it is signed.
+fn code_size(code: Code) -> Option<(usize, bool)> {
+    match code {
+        Code::I8 => Some((1, true)),
+        Code::U8 => Some((1, false)),
+        Code::I16 => Some((2, true)),
+        Code::U16 => Some((2, false)),
+        Code::I32 => Some((4, true)),
+        Code::U32 => Some((4, false)),
+        Code::I64 => Some((8, true)),
+        Code::U64 => Some((8, false)),
+        Code::Bool => None,
+    }
+}
+
+/// Returns the field of a format with exactly one field, `None` if it
+/// has more than one.
+fn single_field(mut format: Format<'_>) -> Option<Option<Field<'_>>> {
+    let field = format.next_field().ok()??;
+    match format.next_field().ok()? {
+        Some(_) => Some(None),
+        None => Some(Some(field)),
+    }
+}
+
+/// Returns `true` if the element of a repeated field is a single byte.
+fn is_byte(element: Format<'_>) -> bool {
+    matches!(
+        single_field(element),
+        Some(Some(Field::Bytes(1) | Field::Value(Code::U8)))
+    )
+}
+
+fn take<'a>(bytes: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if bytes.len() < length {
+        return None;
+    }
+    let (head, rest) = bytes.split_at(length);
+    *bytes = rest;
+    Some(head)
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let signature = "<Ib?2XS(Hh)*(BH)";
+        let values = vec![
+            Value::from(0x1234_5678_u32),
+            Value::from(-2_i8),
+            Value::from(true),
+            Value::from(&[1_u8, 2][..]),
+            Value::from("great"),
+            Value::List(vec![Value::from(512_u16), Value::from(-1_i16)]),
+            Value::List(vec![
+                Value::List(vec![Value::from(1_u8), Value::from(2_u16)]),
+                Value::List(vec![Value::from(3_u8), Value::from(4_u16)]),
+            ]),
+        ];
+        let bytes = encode(signature, &values).unwrap();
+        assert_eq!(
+            bytes,
+            [
+                0x78, 0x56, 0x34, 0x12, 0xfe, 1, 1, 2, b'g', b'r', b'e', b'a', b't', 0, 0x00, 0x02,
+                0xff, 0xff, 1, 2, 0, 3, 4, 0
+            ]
+        );
+        assert_eq!(decode(signature, &bytes).unwrap(), values);
+    }
+
+    #[test]
+    fn test_repeated() {
+        assert_eq!(
+            encode(
+                ">*H",
+                &[Value::List(vec![Value::from(1_u16), Value::from(2_u16)])]
+            )
+            .unwrap(),
+            [0, 1, 0, 2]
+        );
+        assert_eq!(
+            decode("<B*X", &[1, 2, 3]).unwrap(),
+            [Value::from(1_u8), Value::from(vec![2, 3])]
+        );
+        assert_eq!(decode("<*B", &[]).unwrap(), [Value::Bytes(vec![])]);
+
+        // unspecified
+        assert_eq!(encode("*", &[]).unwrap(), []);
+        assert_eq!(encode("*", &[Value::from(vec![1, 2])]).unwrap(), [1, 2]);
+        assert_eq!(decode("*", &[1, 2]).unwrap(), [Value::from(vec![1, 2])]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        // out of range
+        assert!(encode("<B", &[Value::from(256_u32)]).is_err());
+        assert!(encode("<b", &[Value::from(128_u32)]).is_err());
+        // wrong number or type of values
+        assert!(encode("<BB", &[Value::from(1_u8)]).is_err());
+        assert!(encode("<B", &[Value::from(1_u8), Value::from(2_u8)]).is_err());
+        assert!(encode("<S", &[Value::from(1_u8)]).is_err());
+        // short or trailing data
+        assert!(decode("<I", &[1, 2, 3]).is_err());
+        assert!(decode("<B", &[1, 2]).is_err());
+    }
+}
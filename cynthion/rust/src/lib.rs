@@ -1,5 +1,7 @@
 #![cfg_attr(feature = "nightly", feature(error_in_core))]
 #![cfg_attr(feature = "nightly", feature(panic_info_message))]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "gcp")), no_std)]
 
+#[cfg(feature = "gcp")]
+pub mod gcp;
 pub mod shared;
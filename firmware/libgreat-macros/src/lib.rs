@@ -0,0 +1,656 @@
+//! Procedural macros for generating libgreat class verb tables and dispatch.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Error, Expr, ExprLit, FnArg, GenericArgument, Ident, ImplItem,
+    ImplItemFn, ItemImpl, Lit, LitStr, Pat, PathArguments, ReturnType, Signature, Type,
+};
+
+/// Generate the `Verb` table and `GreatDispatch` implementation of a GCP
+/// class from an `impl` block.
+///
+/// Each method annotated with `#[verb(id = ...)]` becomes a verb. Its
+/// arguments are decoded from the command with `zerocopy` and its
+/// `GreatResult<T>` return value encoded as the response. The verbs are
+/// collected in an associated `VERBS` constant sorted by id.
+///
+/// Signatures and parameter names are derived from the method when they
+/// are not given:
+///
+/// | Rust type                         | signature |
+/// |-----------------------------------|-----------|
+/// | `u8`, `u16`, `u32`, `u64`         | `B`, `H`, `I`, `Q` |
+/// | `i8`, `i16`, `i32`, `i64`         | `b`, `h`, `i`, `q` |
+/// | `bool`                            | `?`       |
+/// | `[u8; N]`                         | `NX`      |
+/// | `&[u8]`, last argument or result  | `*X`      |
+/// | `&str`, result                    | `S`       |
+/// | tuples of the above, result       | ...       |
+///
+/// The last argument may also be a `&[T]` of a `zerocopy` type and
+/// results of any other type are returned as an `IntoIterator<Item = u8>`,
+/// both need an explicit signature or default to `*` for results.
+///
+/// Verbs whose last argument is a `&mut [u8]` are passed the response
+/// buffer instead, write their response to its start and return its
+/// length as a `GreatResult<usize>`.
+///
+/// Verb fields can be overridden with `doc`, `in_signature`,
+/// `in_param_names`, `out_signature` and `out_param_names` arguments and
+/// `#[verbs(doc = "...")]` sets the default `doc` of all verbs. Use `"*"`
+/// for fields that are `NULL` in the C implementation.
+///
+/// Classes that start jobs name the field holding their `Jobs` with
+/// `#[verbs(jobs = "...")]`, it is returned by `GreatDispatch::jobs`.
+///
+/// ```ignore
+/// #[verbs]
+/// impl Firmware {
+///     #[verb(id = 0x0, out_param_names = "page_size, total_size")]
+///     pub fn initialize(&mut self) -> GreatResult<(u32, u32)> {
+///         Ok((256, 256 * 8192))
+///     }
+///
+///     #[verb(id = 0x3)]
+///     pub fn write_page(&mut self, address: u32, data: &[u8]) -> GreatResult<()> {
+///         ...
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn verbs(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut item = parse_macro_input!(input as ItemImpl);
+
+    let mut default_doc = String::new();
+    let mut jobs = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("doc") {
+            default_doc = meta.value()?.parse::<LitStr>()?.value();
+            Ok(())
+        } else if meta.path.is_ident("jobs") {
+            jobs = Some(meta.value()?.parse::<LitStr>()?.parse::<Ident>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `verbs` argument"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    match expand(&mut item, &default_doc, jobs.as_ref()) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(
+    item: &mut ItemImpl,
+    default_doc: &str,
+    jobs: Option<&Ident>,
+) -> syn::Result<TokenStream2> {
+    let mut verbs = Vec::new();
+    for impl_item in &mut item.items {
+        if let ImplItem::Fn(method) = impl_item {
+            if let Some(verb) = Verb::parse(method, default_doc)? {
+                verbs.push(verb);
+            }
+        }
+    }
+    verbs.sort_by_key(|verb| verb.id);
+    for pair in verbs.windows(2) {
+        if pair[0].id == pair[1].id {
+            return Err(Error::new(
+                pair[1].method.span(),
+                format!("duplicate verb id {:#x}", pair[1].id),
+            ));
+        }
+    }
+
+    let table = verbs.iter().map(Verb::table_entry);
+    let arms = verbs.iter().map(Verb::dispatch_arm);
+    let count = verbs.len();
+    let jobs = jobs.map(|field| {
+        quote! {
+            fn jobs(
+                &mut self,
+            ) -> ::core::option::Option<&mut dyn ::libgreat::gcp::job::JobControl> {
+                ::core::option::Option::Some(&mut self.#field)
+            }
+        }
+    });
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #self_ty #where_clause {
+            /// Verbs supported by this class.
+            pub const VERBS: [::libgreat::gcp::Verb; #count] = [#(#table),*];
+        }
+
+        impl #impl_generics ::libgreat::gcp::GreatDispatch for #self_ty #where_clause {
+            fn dispatch(
+                &mut self,
+                verb_number: u32,
+                arguments: &[u8],
+                response: &mut [u8],
+            ) -> ::libgreat::GreatResult<usize> {
+                match verb_number {
+                    #(#arms)*
+                    _verb_number => Err(::libgreat::GreatError::InvalidArgument),
+                }
+            }
+
+            #jobs
+        }
+    })
+}
+
+// - Verb ---------------------------------------------------------------------
+
+struct Verb {
+    id: u32,
+    method: Ident,
+    doc: String,
+    in_signature: String,
+    in_param_names: String,
+    out_signature: String,
+    out_param_names: String,
+    fields: Vec<Field>,
+    trailing: Option<Trailing>,
+    /// The verb writes its response itself, see [`Arguments::response`].
+    writes_response: bool,
+    output: Output,
+}
+
+/// A fixed size argument.
+struct Field {
+    name: Ident,
+    ty: TokenStream2,
+    value: fn(&TokenStream2) -> TokenStream2,
+}
+
+/// A variable length argument consuming the rest of the command.
+struct Trailing {
+    name: Ident,
+    element: Type,
+}
+
+/// Arguments of the `#[verb]` attribute.
+#[derive(Default)]
+struct VerbArgs {
+    id: Option<u32>,
+    doc: Option<String>,
+    in_signature: Option<String>,
+    in_param_names: Option<String>,
+    out_signature: Option<String>,
+    out_param_names: Option<String>,
+}
+
+impl VerbArgs {
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut args = Self::default();
+        attr.parse_nested_meta(|meta| {
+            let field = if meta.path.is_ident("id") {
+                args.id = Some(
+                    meta.value()?
+                        .parse::<syn::LitInt>()?
+                        .base10_parse::<u32>()?,
+                );
+                return Ok(());
+            } else if meta.path.is_ident("doc") {
+                &mut args.doc
+            } else if meta.path.is_ident("in_signature") {
+                &mut args.in_signature
+            } else if meta.path.is_ident("in_param_names") {
+                &mut args.in_param_names
+            } else if meta.path.is_ident("out_signature") {
+                &mut args.out_signature
+            } else if meta.path.is_ident("out_param_names") {
+                &mut args.out_param_names
+            } else {
+                return Err(meta.error("unsupported `verb` argument"));
+            };
+            *field = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        })?;
+        Ok(args)
+    }
+}
+
+/// Arguments of a verb method.
+struct Arguments {
+    names: Vec<String>,
+    /// Derived signature, `None` if it can't be derived.
+    signature: Option<String>,
+    fields: Vec<Field>,
+    trailing: Option<Trailing>,
+    /// The verb takes the response buffer as its last argument and
+    /// returns the length of the response it wrote.
+    response: bool,
+}
+
+impl Arguments {
+    fn parse(sig: &Signature, has_signature: bool) -> syn::Result<Self> {
+        let mut inputs = sig.inputs.iter();
+        if !matches!(inputs.next(), Some(FnArg::Receiver(_))) {
+            return Err(Error::new(
+                sig.span(),
+                "verbs must take `&self` or `&mut self`",
+            ));
+        }
+
+        let mut arguments = Self {
+            names: Vec::new(),
+            signature: Some(String::new()),
+            fields: Vec::new(),
+            trailing: None,
+            response: false,
+        };
+        let count = inputs.len();
+        for (index, input) in inputs.enumerate() {
+            let FnArg::Typed(input) = input else {
+                unreachable!()
+            };
+            let Pat::Ident(pat) = &*input.pat else {
+                return Err(Error::new(input.pat.span(), "verb arguments must be named"));
+            };
+            if is_response_buffer(&input.ty) {
+                if index + 1 != count {
+                    return Err(Error::new(
+                        input.ty.span(),
+                        "only the last verb argument can be the response buffer",
+                    ));
+                }
+                arguments.response = true;
+                continue;
+            }
+            let name = pat.ident.clone();
+            arguments
+                .names
+                .push(name.to_string().trim_start_matches('_').to_string());
+
+            if let Some(element) = slice_element(&input.ty) {
+                if index + 1 != count {
+                    return Err(Error::new(
+                        input.ty.span(),
+                        "only the last verb argument can be a slice",
+                    ));
+                }
+                let code = is_ident(element, "u8").then(|| String::from("*X"));
+                append(&mut arguments.signature, code);
+                arguments.trailing = Some(Trailing {
+                    name,
+                    element: element.clone(),
+                });
+            } else {
+                let (ty, value, code) = field(&input.ty);
+                if code.is_none() && !has_signature {
+                    return Err(Error::new(
+                        input.ty.span(),
+                        "can't derive the signature of this type, set `in_signature`",
+                    ));
+                }
+                append(&mut arguments.signature, code);
+                arguments.fields.push(Field { name, ty, value });
+            }
+        }
+        Ok(arguments)
+    }
+}
+
+impl Verb {
+    /// Parse a method annotated with `#[verb]`, removing the attribute.
+    fn parse(method: &mut ImplItemFn, default_doc: &str) -> syn::Result<Option<Self>> {
+        let Some(index) = method
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("verb"))
+        else {
+            return Ok(None);
+        };
+        let attr = method.attrs.remove(index);
+        let args = VerbArgs::parse(&attr)?;
+        let id = args
+            .id
+            .ok_or_else(|| Error::new(attr.span(), "`verb` requires an `id`"))?;
+
+        // arguments
+        let sig = &method.sig;
+        let arguments = Arguments::parse(sig, args.in_signature.is_some())?;
+        let in_signature = match (args.in_signature, arguments.signature) {
+            (Some(explicit), _) => explicit,
+            (None, Some(derived)) if derived.is_empty() => derived,
+            (None, Some(derived)) => format!("<{derived}"),
+            (None, None) => {
+                return Err(Error::new(
+                    sig.inputs.span(),
+                    "can't derive the signature of the arguments, set `in_signature`",
+                ))
+            }
+        };
+        let in_param_names = args.in_param_names.unwrap_or_else(|| {
+            if arguments.names.is_empty() {
+                "*".into()
+            } else {
+                arguments.names.join(", ")
+            }
+        });
+
+        // result, the length of the response for verbs that write it
+        let output = Output::parse(&sig.output)?;
+        let out_signature = args
+            .out_signature
+            .unwrap_or_else(|| match &output.signature {
+                _ if arguments.response => "*".into(),
+                Some(derived) if derived.is_empty() => derived.clone(),
+                Some(derived) => format!("<{derived}"),
+                None => "*".into(),
+            });
+
+        Ok(Some(Self {
+            id,
+            method: sig.ident.clone(),
+            doc: args.doc.unwrap_or_else(|| default_doc.into()),
+            in_signature,
+            in_param_names,
+            out_signature,
+            out_param_names: args.out_param_names.unwrap_or_else(|| "*".into()),
+            fields: arguments.fields,
+            trailing: arguments.trailing,
+            writes_response: arguments.response,
+            output,
+        }))
+    }
+
+    fn table_entry(&self) -> TokenStream2 {
+        let id = self.id;
+        let string = |value: &str| LitStr::new(&format!("{value}\0"), Span::call_site());
+        let name = string(&self.method.to_string());
+        let doc = string(&self.doc);
+        let in_signature = string(&self.in_signature);
+        let in_param_names = string(&self.in_param_names);
+        let out_signature = string(&self.out_signature);
+        let out_param_names = string(&self.out_param_names);
+        quote! {
+            ::libgreat::gcp::Verb {
+                id: #id,
+                name: #name,
+                doc: #doc,
+                in_signature: #in_signature,
+                in_param_names: #in_param_names,
+                out_signature: #out_signature,
+                out_param_names: #out_param_names,
+            }
+        }
+    }
+
+    fn dispatch_arm(&self) -> TokenStream2 {
+        let id = self.id;
+        let method = &self.method;
+        let invalid = quote!(::libgreat::GreatError::InvalidArgument);
+
+        let mut decode = TokenStream2::new();
+        let mut values = Vec::new();
+        if !self.fields.is_empty() {
+            let names: Vec<_> = self.fields.iter().map(|field| &field.name).collect();
+            let types = self.fields.iter().map(|field| &field.ty);
+            decode.extend(quote! {
+                #[repr(C)]
+                #[derive(::zerocopy::FromBytes, ::zerocopy::FromZeroes, ::zerocopy::Unaligned)]
+                struct Args {
+                    #(#names: #types,)*
+                }
+                let __args = <Args as ::zerocopy::FromBytes>::read_from_prefix(arguments)
+                    .ok_or(#invalid)?;
+                let arguments = &arguments[::core::mem::size_of::<Args>()..];
+            });
+            for field in &self.fields {
+                let name = &field.name;
+                values.push((field.value)(&quote!(__args.#name)));
+            }
+        }
+        match &self.trailing {
+            Some(Trailing { name, element }) if is_ident(element, "u8") => {
+                decode.extend(quote!(let #name = arguments;));
+                values.push(name.to_token_stream());
+            }
+            Some(Trailing { name, element }) => {
+                decode.extend(quote! {
+                    let #name = ::zerocopy::Ref::<_, [#element]>::new_slice_unaligned(arguments)
+                        .ok_or(#invalid)?
+                        .into_slice();
+                });
+                values.push(name.to_token_stream());
+            }
+            // verbs without arguments have always ignored them
+            None if !self.fields.is_empty() => {
+                decode.extend(quote! {
+                    if !arguments.is_empty() {
+                        return Err(#invalid);
+                    }
+                });
+            }
+            None => (),
+        }
+
+        if self.writes_response {
+            return quote! {
+                #id => {
+                    #decode
+                    self.#method(#(#values,)* response)
+                }
+            };
+        }
+        let encode = (self.output.encode)(quote!(__value));
+        quote! {
+            #id => {
+                #decode
+                let __value = self.#method(#(#values),*)?;
+                ::libgreat::gcp::iter_to_response(#encode, response)
+            }
+        }
+    }
+}
+
+// - Output -------------------------------------------------------------------
+
+/// Encoding of a verb's result.
+struct Output {
+    /// Derived signature, `None` if it can't be derived.
+    signature: Option<String>,
+    encode: Box<dyn Fn(TokenStream2) -> TokenStream2>,
+}
+
+impl Output {
+    fn parse(output: &ReturnType) -> syn::Result<Self> {
+        let error = || Error::new(output.span(), "verbs must return `GreatResult<T>`");
+        let ReturnType::Type(_, ty) = output else {
+            return Err(error());
+        };
+        let Type::Path(path) = &**ty else {
+            return Err(error());
+        };
+        let segment = path.path.segments.last().ok_or_else(error)?;
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return Err(error());
+        };
+        let Some(GenericArgument::Type(ty)) = arguments.args.first() else {
+            return Err(error());
+        };
+        Ok(Self::from_type(ty))
+    }
+
+    fn from_type(ty: &Type) -> Self {
+        match ty {
+            Type::Tuple(tuple) if tuple.elems.is_empty() => Self {
+                signature: Some(String::new()),
+                encode: Box::new(|_| quote!(::core::iter::empty::<u8>())),
+            },
+            Type::Tuple(tuple) => {
+                let elements: Vec<Self> = tuple.elems.iter().map(Self::from_type).collect();
+                let signature = elements
+                    .iter()
+                    .map(|element| element.signature.clone())
+                    .collect::<Option<String>>();
+                Self {
+                    signature,
+                    encode: Box::new(move |value| {
+                        let names: Vec<_> = (0..elements.len())
+                            .map(|index| format_ident!("__value{}", index))
+                            .collect();
+                        let mut iter = elements
+                            .iter()
+                            .zip(&names)
+                            .map(|(element, name)| (element.encode)(name.to_token_stream()));
+                        let first = iter.next().unwrap_or_default();
+                        let chained = iter.fold(first, |chain, next| quote!(#chain.chain(#next)));
+                        quote!({
+                            let (#(#names,)*) = #value;
+                            #chained
+                        })
+                    }),
+                }
+            }
+            Type::Reference(reference) if is_ident(&reference.elem, "str") => Self {
+                signature: Some("S".into()),
+                encode: Box::new(|value| quote!(#value.bytes())),
+            },
+            _ if slice_element(ty).is_some_and(|element| is_ident(element, "u8")) => Self {
+                signature: Some("*X".into()),
+                encode: Box::new(|value| quote!(#value.iter().copied())),
+            },
+            Type::Array(_) if byte_array_length(ty).is_some() => Self {
+                signature: byte_array_length(ty).map(|length| format!("{length}X")),
+                encode: Box::new(|value| quote!(::core::iter::IntoIterator::into_iter(#value))),
+            },
+            _ if is_ident(ty, "bool") => Self {
+                signature: Some("?".into()),
+                encode: Box::new(|value| quote!(::core::iter::once(u8::from(#value)))),
+            },
+            _ => match integer_code(ty) {
+                Some(code) => Self {
+                    signature: Some(code.into()),
+                    encode: Box::new(
+                        |value| quote!(::core::iter::IntoIterator::into_iter(#value.to_le_bytes())),
+                    ),
+                },
+                None => Self {
+                    signature: None,
+                    encode: Box::new(|value| quote!(::core::iter::IntoIterator::into_iter(#value))),
+                },
+            },
+        }
+    }
+}
+
+// - helpers ------------------------------------------------------------------
+
+/// Returns the zerocopy field type, conversion and signature of a fixed
+/// size argument.
+fn field(
+    ty: &Type,
+) -> (
+    TokenStream2,
+    fn(&TokenStream2) -> TokenStream2,
+    Option<String>,
+) {
+    fn identity(value: &TokenStream2) -> TokenStream2 {
+        value.clone()
+    }
+    fn get(value: &TokenStream2) -> TokenStream2 {
+        quote!(#value.get())
+    }
+    fn boolean(value: &TokenStream2) -> TokenStream2 {
+        quote!(#value != 0)
+    }
+
+    let byteorder = |name: &str| {
+        let name = format_ident!("{}", name);
+        quote!(::zerocopy::byteorder::#name<::zerocopy::byteorder::LittleEndian>)
+    };
+    let code = integer_code(ty).map(String::from);
+    match code.as_deref() {
+        Some("B" | "b") => (ty.to_token_stream(), identity, code),
+        Some("H") => (byteorder("U16"), get, code),
+        Some("h") => (byteorder("I16"), get, code),
+        Some("I") => (byteorder("U32"), get, code),
+        Some("i") => (byteorder("I32"), get, code),
+        Some("Q") => (byteorder("U64"), get, code),
+        Some("q") => (byteorder("I64"), get, code),
+        _ if is_ident(ty, "bool") => (quote!(u8), boolean, Some("?".into())),
+        _ => {
+            let code = byte_array_length(ty).map(|length| format!("{length}X"));
+            (ty.to_token_stream(), identity, code)
+        }
+    }
+}
+
+fn integer_code(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    Some(match ident.as_str() {
+        "u8" => "B",
+        "i8" => "b",
+        "u16" => "H",
+        "i16" => "h",
+        "u32" => "I",
+        "i32" => "i",
+        "u64" => "Q",
+        "i64" => "q",
+        _ => return None,
+    })
+}
+
+/// Returns `N` if the type is `[u8; N]`.
+fn byte_array_length(ty: &Type) -> Option<usize> {
+    let Type::Array(array) = ty else {
+        return None;
+    };
+    let Expr::Lit(ExprLit {
+        lit: Lit::Int(length),
+        ..
+    }) = &array.len
+    else {
+        return None;
+    };
+    if !is_ident(&array.elem, "u8") {
+        return None;
+    }
+    length.base10_parse().ok()
+}
+
+/// Returns `true` if the type is `&mut [u8]`.
+fn is_response_buffer(ty: &Type) -> bool {
+    matches!(ty, Type::Reference(reference) if reference.mutability.is_some())
+        && slice_element(ty).is_some_and(|element| is_ident(element, "u8"))
+}
+
+/// Returns `T` if the type is `&[T]`.
+fn slice_element(ty: &Type) -> Option<&Type> {
+    let Type::Reference(reference) = ty else {
+        return None;
+    };
+    let Type::Slice(slice) = &*reference.elem else {
+        return None;
+    };
+    Some(&slice.elem)
+}
+
+fn is_ident(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident(name))
+}
+
+/// Append a signature code, the signature can't be derived if any code is
+/// missing.
+fn append(signature: &mut Option<String>, code: Option<String>) {
+    match (signature.as_mut(), code) {
+        (Some(signature), Some(code)) => signature.push_str(&code),
+        _ => *signature = None,
+    }
+}
@@ -0,0 +1,8 @@
+//! Compile tests of the `#[verbs]` attribute.
+
+#[test]
+fn test_verbs() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/verbs/pass/*.rs");
+    cases.compile_fail("tests/verbs/fail/*.rs");
+}
@@ -0,0 +1,18 @@
+use libgreat::gcp::verbs;
+
+struct Class;
+
+#[verbs]
+impl Class {
+    #[verb(id = 0x1)]
+    pub fn first(&self) -> libgreat::GreatResult<()> {
+        Ok(())
+    }
+
+    #[verb(id = 0x1)]
+    pub fn second(&self) -> libgreat::GreatResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use libgreat::gcp::verbs;
+
+struct Class;
+
+#[verbs]
+impl Class {
+    #[verb(doc = "no id")]
+    pub fn verb(&self) -> libgreat::GreatResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use libgreat::gcp::verbs;
+
+struct Class;
+
+#[verbs]
+impl Class {
+    #[verb(id = 0x0)]
+    pub fn verb(value: u8) -> libgreat::GreatResult<u8> {
+        Ok(value)
+    }
+}
+
+fn main() {}
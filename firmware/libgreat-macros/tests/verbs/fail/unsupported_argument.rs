@@ -0,0 +1,13 @@
+use libgreat::gcp::verbs;
+
+struct Class;
+
+#[verbs]
+impl Class {
+    #[verb(id = 0x0, name = "verb")]
+    pub fn verb(&self) -> libgreat::GreatResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
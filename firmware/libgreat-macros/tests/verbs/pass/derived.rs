@@ -0,0 +1,121 @@
+//! Signatures and parameter names derived from the verb methods.
+
+use libgreat::gcp::{verbs, GreatDispatch, LIBGREAT_MAX_RESPONSE_SIZE};
+use libgreat::{GreatError, GreatResult};
+
+struct Class {
+    name: &'static str,
+}
+
+#[verbs]
+impl Class {
+    #[verb(id = 0x0)]
+    pub fn integers(&self, a: u8, b: i16, c: u32, d: i64) -> GreatResult<(u8, i16, u32, i64)> {
+        Ok((a, b, c, d))
+    }
+
+    #[verb(id = 0x1)]
+    pub fn bytes(
+        &self,
+        flag: bool,
+        array: [u8; 2],
+        data: &[u8],
+    ) -> GreatResult<(bool, [u8; 2], u16)> {
+        Ok((flag, array, data.len() as u16))
+    }
+
+    #[verb(id = 0x2)]
+    pub fn name(&mut self) -> GreatResult<&'static str> {
+        Ok(self.name)
+    }
+
+    #[verb(id = 0x3)]
+    pub fn echo<'a>(&self, data: &'a [u8]) -> GreatResult<&'a [u8]> {
+        Ok(data)
+    }
+
+    #[verb(id = 0x4)]
+    pub fn nothing(&self) -> GreatResult<()> {
+        Ok(())
+    }
+
+    #[verb(id = 0x5)]
+    pub fn fill(&self, value: u8, response: &mut [u8]) -> GreatResult<usize> {
+        response[..3].fill(value);
+        Ok(3)
+    }
+
+    pub fn not_a_verb(&self) {}
+}
+
+fn dispatch(class: &mut Class, verb_number: u32, arguments: &[u8]) -> GreatResult<Vec<u8>> {
+    let mut response = [0; LIBGREAT_MAX_RESPONSE_SIZE];
+    let length = class.dispatch(verb_number, arguments, &mut response)?;
+    Ok(response[..length].to_vec())
+}
+
+fn main() {
+    let table: Vec<_> = Class::VERBS
+        .iter()
+        .map(|verb| {
+            (
+                verb.name,
+                verb.in_signature,
+                verb.in_param_names,
+                verb.out_signature,
+            )
+        })
+        .collect();
+    assert_eq!(
+        table,
+        [
+            ("integers\0", "<BhIq\0", "a, b, c, d\0", "<BhIq\0"),
+            ("bytes\0", "<?2X*X\0", "flag, array, data\0", "<?2XH\0"),
+            ("name\0", "\0", "*\0", "<S\0"),
+            ("echo\0", "<*X\0", "data\0", "<*X\0"),
+            ("nothing\0", "\0", "*\0", "\0"),
+            ("fill\0", "<B\0", "value\0", "*\0"),
+        ]
+    );
+    for verb in &Class::VERBS {
+        assert_eq!(verb.out_param_names, "*\0");
+        assert_eq!(verb.doc, "\0");
+    }
+
+    let mut class = Class { name: "class" };
+    let arguments = [
+        1, 2, 0, 3, 0, 0, 0, 0xfc, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+    let response = dispatch(&mut class, 0x0, &arguments).unwrap();
+    assert_eq!(response, arguments);
+
+    let response = dispatch(&mut class, 0x1, &[1, 0xaa, 0xbb, 1, 2, 3]).unwrap();
+    assert_eq!(response, [1, 0xaa, 0xbb, 3, 0]);
+
+    let response = dispatch(&mut class, 0x2, &[]).unwrap();
+    assert_eq!(response, b"class");
+
+    let response = dispatch(&mut class, 0x3, b"echo").unwrap();
+    assert_eq!(response, b"echo");
+
+    // short and trailing arguments are rejected, verbs without arguments ignore them
+    assert!(matches!(
+        dispatch(&mut class, 0x0, &arguments[1..]),
+        Err(GreatError::InvalidArgument)
+    ));
+    assert!(matches!(
+        dispatch(&mut class, 0x0, &[arguments.as_slice(), &[0]].concat()),
+        Err(GreatError::InvalidArgument)
+    ));
+    assert!(dispatch(&mut class, 0x4, &[0]).is_ok());
+
+    // verbs taking the response buffer write their response themselves
+    let response = dispatch(&mut class, 0x5, &[9]).unwrap();
+    assert_eq!(response, [9, 9, 9]);
+
+    // unknown verbs
+    assert!(matches!(
+        dispatch(&mut class, 0x6, &[]),
+        Err(GreatError::InvalidArgument)
+    ));
+}
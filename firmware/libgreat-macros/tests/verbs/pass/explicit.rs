@@ -0,0 +1,72 @@
+//! Explicit verb fields, generic classes and zerocopy arguments.
+
+use libgreat::gcp::{verbs, GreatDispatch, LIBGREAT_MAX_RESPONSE_SIZE};
+use libgreat::GreatResult;
+use zerocopy::byteorder::{LittleEndian, U16};
+use zerocopy::{FromBytes, FromZeroes, Unaligned};
+
+#[repr(C)]
+#[derive(FromBytes, FromZeroes, Unaligned)]
+pub struct Pair {
+    address: u8,
+    length: U16<LittleEndian>,
+}
+
+pub trait Port {
+    fn value(&self) -> u8;
+}
+
+impl Port for u8 {
+    fn value(&self) -> u8 {
+        *self
+    }
+}
+
+struct Class<'a, P> {
+    port: &'a P,
+}
+
+#[verbs(doc = "*")]
+impl<'a, P: Port> Class<'a, P> {
+    #[verb(id = 0x10, in_signature = "<*(BH)", in_param_names = "pairs")]
+    pub fn pairs(&self, pairs: &[Pair]) -> GreatResult<impl Iterator<Item = u8>> {
+        let total: u16 = pairs.iter().map(|pair| pair.length.get()).sum();
+        let addresses: Vec<u8> = pairs.iter().map(|pair| pair.address).collect();
+        Ok(addresses.into_iter().chain(total.to_le_bytes()))
+    }
+
+    #[verb(
+        id = 0x11,
+        doc = "Read the port.",
+        out_signature = "<B",
+        out_param_names = "value"
+    )]
+    pub fn read(&self) -> GreatResult<impl Iterator<Item = u8>> {
+        Ok(core::iter::once(self.port.value()))
+    }
+}
+
+fn main() {
+    type Table = Class<'static, u8>;
+    assert_eq!(Table::VERBS[0].in_signature, "<*(BH)\0");
+    assert_eq!(Table::VERBS[0].in_param_names, "pairs\0");
+    assert_eq!(Table::VERBS[0].out_signature, "*\0");
+    assert_eq!(Table::VERBS[0].doc, "*\0");
+    assert_eq!(Table::VERBS[1].doc, "Read the port.\0");
+    assert_eq!(Table::VERBS[1].out_signature, "<B\0");
+    assert_eq!(Table::VERBS[1].out_param_names, "value\0");
+
+    let port = 7;
+    let mut class = Class { port: &port };
+    let mut response = [0; LIBGREAT_MAX_RESPONSE_SIZE];
+    let length = class
+        .dispatch(0x10, &[1, 2, 0, 3, 4, 0], &mut response)
+        .unwrap();
+    assert_eq!(response[..length], [1, 3, 6, 0]);
+
+    // a partial element is rejected
+    assert!(class.dispatch(0x10, &[1, 2], &mut response).is_err());
+
+    let length = class.dispatch(0x11, &[], &mut response).unwrap();
+    assert_eq!(response[..length], [7]);
+}
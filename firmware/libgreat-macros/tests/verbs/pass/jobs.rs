@@ -0,0 +1,53 @@
+//! Classes exposing the jobs they start.
+
+use libgreat::gcp::job::{Job, JobPoll, Jobs};
+use libgreat::gcp::{verbs, GreatDispatch};
+use libgreat::GreatResult;
+
+struct Done;
+
+impl Job for Done {
+    fn poll(&mut self) -> JobPoll {
+        JobPoll::Ready(Ok(()))
+    }
+
+    fn result(&mut self, _response: &mut [u8]) -> GreatResult<usize> {
+        Ok(0)
+    }
+}
+
+struct Class {
+    jobs: Jobs<Done>,
+}
+
+#[verbs(jobs = "jobs")]
+impl Class {
+    #[verb(id = 0x0)]
+    pub fn start(&mut self) -> GreatResult<u32> {
+        self.jobs.start(Done)
+    }
+}
+
+struct NoJobs;
+
+#[verbs]
+impl NoJobs {
+    #[verb(id = 0x0)]
+    pub fn nothing(&self) -> GreatResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {
+    let mut class = Class { jobs: Jobs::new() };
+    let mut response = [0; 4];
+    let length = class.dispatch(0x0, &[], &mut response).unwrap();
+    let job_id = u32::from_le_bytes(response[..length].try_into().unwrap());
+
+    let jobs = class.jobs().unwrap();
+    assert!(jobs.owns(job_id));
+    jobs.poll();
+    assert_eq!(jobs.status(job_id).unwrap(), (1, 0, 0));
+
+    assert!(NoJobs.jobs().is_none());
+}
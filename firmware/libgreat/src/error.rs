@@ -3,16 +3,37 @@
 /// libgreat [`Result`] type.
 pub type GreatResult<T> = core::result::Result<T, GreatError>;
 
-/// Provides a uniform set of error codes common to all libgreat
-/// implementations, and that are consistent over platform and RPC
-/// boundaries.
-///
-/// Derived from: [libgreat/firmware/include/errno.h](https://github.com/greatscottgadgets/libgreat/blob/master/firmware/include/errno.h)
+macro_rules! great_error {
+    ($($name:ident = $value:literal,)*) => {
+        /// Provides a uniform set of error codes common to all libgreat
+        /// implementations, and that are consistent over platform and RPC
+        /// boundaries.
+        ///
+        /// Derived from: [libgreat/firmware/include/errno.h](https://github.com/greatscottgadgets/libgreat/blob/master/firmware/include/errno.h)
+        #[derive(Debug, Copy, Clone)]
+        #[repr(u32)]
+        pub enum GreatError {
+            $($name = $value,)*
+        }
+
+        impl TryFrom<u32> for GreatError {
+            type Error = u32;
+
+            /// Returns the error with the given code, or the code if it
+            /// is not a known error.
+            fn try_from(value: u32) -> Result<Self, u32> {
+                match value {
+                    $($value => Ok(GreatError::$name),)*
+                    _ => Err(value),
+                }
+            }
+        }
+    };
+}
+
 #[cfg(feature = "errno_minimal")]
-#[derive(Debug, Copy, Clone)]
-#[repr(u32)]
 #[rustfmt::skip]
-pub enum GreatError {
+great_error! {
     IoError = 5,                           // EIO             - I/O error
     ArgumentListTooLong = 7,               // E2BIG           - Arg list too long
     OperationWouldBlock = 11,              // EWOULDBLOCK     - Operation would block
@@ -44,10 +65,8 @@ pub enum GreatError {
 }
 
 #[cfg(not(feature = "errno_minimal"))]
-#[derive(Debug, Copy, Clone)]
-#[repr(u32)]
 #[rustfmt::skip]
-pub enum GreatError {
+great_error! {
     NotOwner = 1,                          // EPERM           - Not owner
     NoSuchFileOrDirectory = 2,             // ENOENT          - No such file or directory
     NoSuchProcess = 3,                     // ENOSRCH         - No such process
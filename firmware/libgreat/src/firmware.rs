@@ -1,4 +1,5 @@
 /// Board information.
+#[derive(Clone, Copy)]
 pub struct BoardInformation {
     pub board_id: [u8; 4],
     pub version_string: &'static str,
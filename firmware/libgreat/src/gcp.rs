@@ -7,13 +7,26 @@
 
 pub mod class;
 pub mod class_core;
+pub mod frame;
+pub mod job;
+pub mod registry;
+pub mod serial;
+pub mod signature;
 pub use class::*;
 
+pub use libgreat_macros::verbs;
+
 // - constants ----------------------------------------------------------------
 
-/// Maximum length of a libgreat command or response
+/// Maximum length of a libgreat command
 pub const LIBGREAT_MAX_COMMAND_SIZE: usize = 1024;
 
+/// Maximum length of a libgreat response
+///
+/// Responses longer than the host's read request are returned in
+/// chunks, see [`GreatResponse::next_chunk`].
+pub const LIBGREAT_MAX_RESPONSE_SIZE: usize = 4096;
+
 // - types --------------------------------------------------------------------
 
 use zerocopy::byteorder::{LittleEndian, U32};
@@ -55,14 +68,118 @@ where
     }
 }
 
-pub type GreatResponse = core::iter::Take<core::array::IntoIter<u8, LIBGREAT_MAX_COMMAND_SIZE>>;
+/// Response to a GCP command
+///
+/// The firmware keeps a single response that every command is
+/// [`dispatch`](GreatResponse::dispatch)ed into, the transport the
+/// command arrived on then sends it from there.
+///
+/// Hosts read the response with one or more vendor IN requests. Each
+/// request is answered with the next [`chunk`](GreatResponse::next_chunk)
+/// of the response and a chunk shorter than the request marks the end of
+/// the response, so responses that fit in a single request look the same
+/// as they always have to existing clients.
+pub struct GreatResponse {
+    buffer: [u8; LIBGREAT_MAX_RESPONSE_SIZE],
+    length: usize,
+    position: usize,
+}
+
+impl GreatResponse {
+    /// Create a new, empty, response.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; LIBGREAT_MAX_RESPONSE_SIZE],
+            length: 0,
+            position: 0,
+        }
+    }
+
+    /// Replace the response with the one written by `dispatch`, which
+    /// returns the length of the response it wrote to the start of the
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error `dispatch` failed with, the response is left
+    /// empty.
+    pub fn dispatch(
+        &mut self,
+        dispatch: impl FnOnce(&mut [u8]) -> GreatResult<usize>,
+    ) -> GreatResult<()> {
+        self.clear();
+        let length = dispatch(&mut self.buffer)?;
+        if length > self.buffer.len() {
+            return Err(crate::error::GreatError::ResultTooLarge);
+        }
+        self.length = length;
+        Ok(())
+    }
+
+    /// Discard the response.
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.position = 0;
+    }
+
+    /// Returns the bytes of the response that have not been sent yet.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[self.position..self.length]
+    }
+
+    /// Returns up to `max_length` bytes of the response that have not
+    /// been sent yet and advances past them.
+    pub fn next_chunk(&mut self, max_length: usize) -> &[u8] {
+        let start = self.position;
+        self.position += max_length.min(self.length - start);
+        &self.buffer[start..self.position]
+    }
+
+    /// Returns `true` if the whole response has been sent.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.position == self.length
+    }
+}
+
+impl Default for GreatResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for GreatResponse {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        self.next_chunk(1).first().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for GreatResponse {}
+
+impl core::fmt::Debug for GreatResponse {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("GreatResponse")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
 
 // - traits -------------------------------------------------------------------
 
 use crate::GreatResult;
 
 pub trait GreatDispatch {
-    /// Dispatches a GCP verb.
+    /// Dispatches a GCP verb, writing its response to the start of
+    /// `response` and returning the length of the response.
     ///
     /// # Errors
     ///
@@ -71,27 +188,43 @@ pub trait GreatDispatch {
         &mut self,
         verb_number: u32,
         arguments: &[u8],
-        response_buffer: [u8; LIBGREAT_MAX_COMMAND_SIZE],
-    ) -> GreatResult<GreatResponse>;
+        response: &mut [u8],
+    ) -> GreatResult<usize>;
+
+    /// Returns the jobs started by the verbs of the class, if it has
+    /// any, see [`job`].
+    fn jobs(&mut self) -> Option<&mut dyn job::JobControl> {
+        None
+    }
 }
 
 // - helpers ------------------------------------------------------------------
 
-/// Squashes an arbitrary Iterator type into a [`GreatResponse`].
+/// Squashes an arbitrary Iterator type into a response buffer,
+/// returning the length of the response.
 ///
 /// This is not entirely great but it is one solution to the problem
 /// of how to dispatch on verbs that return arbiratory iterator types
 /// as their response.
+///
+/// # Errors
+///
+/// Returns [`GreatError::ResultTooLarge`](crate::error::GreatError::ResultTooLarge)
+/// rather than truncating the response if `iter` does not fit in
+/// `response`.
 pub fn iter_to_response(
-    iter: impl Iterator<Item = u8>,
-    mut response: [u8; LIBGREAT_MAX_COMMAND_SIZE],
-) -> GreatResponse {
+    mut iter: impl Iterator<Item = u8>,
+    response: &mut [u8],
+) -> GreatResult<usize> {
     let mut length = 0;
-    for (ret, src) in response.iter_mut().zip(iter) {
+    for (ret, src) in response.iter_mut().zip(&mut iter) {
         *ret = src;
         length += 1;
     }
-    response.into_iter().take(length)
+    if iter.next().is_some() {
+        return Err(crate::error::GreatError::ResultTooLarge);
+    }
+    Ok(length)
 }
 
 // - tests --------------------------------------------------------------------
@@ -104,6 +237,7 @@ mod tests {
 
     use zerocopy::byteorder::U16;
 
+    use crate::error::GreatError;
     use crate::firmware::BoardInformation;
 
     use super::*;
@@ -123,12 +257,12 @@ mod tests {
         0x08, 0x00, 0x00, 0x00, // verb  = 8 (get_class_name)
         0x01, 0x00, 0x00, 0x00, // arg0: class_number = 1
     ];
-    const COMMAND_GET_VERB_DESCRIPTOR: [u8; 17] = [
+    const COMMAND_GET_VERB_DESCRIPTOR: [u8; 20] = [
         0x00, 0x00, 0x00, 0x00, // class = 0 (core)
         0x07, 0x00, 0x00, 0x00, // verb  = 7 (get_verb_descriptor)
         0x00, 0x00, 0x00, 0x00, // arg0: class_number = 0
         0x07, 0x00, 0x00, 0x00, // arg1: verb_number  = 7
-        0x01, //                   arg2: descriptor = 1 (in_signature)
+        0x01, 0x00, 0x00, 0x00, // arg2: descriptor = 1 (in_signature)
     ];
     const COMMAND_SET_UP_ENDPOINTS: [u8; 16] = [
         0x20, 0x01, 0x00, 0x00, // class = 0x0120 (moondancer)
@@ -145,7 +279,7 @@ mod tests {
         id: ClassId::core,
         name: "core",
         docs: class_core::CLASS_DOCS,
-        verbs: &class_core::VERBS,
+        verbs: &class_core::Core::VERBS,
     };
 
     static SUPPORTED_CLASSES: [Class; 1] = [CLASS_CORE];
@@ -221,7 +355,7 @@ mod tests {
         struct Args {
             class_number: U32<LittleEndian>,
             verb_number: U32<LittleEndian>,
-            descriptor: u8,
+            descriptor: U32<LittleEndian>,
         }
 
         let command =
@@ -235,7 +369,7 @@ mod tests {
         assert_eq!(command.verb_number(), 7);
         assert_eq!(args.class_number.get(), 0);
         assert_eq!(args.verb_number.get(), 7);
-        assert_eq!(args.descriptor, 1);
+        assert_eq!(args.descriptor.get(), 1);
     }
 
     #[test]
@@ -291,9 +425,9 @@ mod tests {
         let command = Command::parse(&COMMAND_READ_BOARD_ID[..]).expect("failed parsing command");
         println!("\ntest_dispatch_read_board_id: {:?}", command);
 
-        let response_buffer = [0_u8; LIBGREAT_MAX_COMMAND_SIZE];
-        let response = core
-            .dispatch(command.verb_number(), &command.arguments, response_buffer)
+        let mut response = GreatResponse::new();
+        response
+            .dispatch(|buffer| core.dispatch(command.verb_number(), command.arguments, buffer))
             .expect("failed dispatch");
         println!("  -> {:?}", response);
 
@@ -312,9 +446,9 @@ mod tests {
             Command::parse(&COMMAND_GET_VERB_DESCRIPTOR[..]).expect("failed parsing command");
         println!("\ntest_dispatch_get_verb_descriptor: {:?}", command);
 
-        let response_buffer = [0_u8; LIBGREAT_MAX_COMMAND_SIZE];
-        let response = core
-            .dispatch(command.verb_number(), &command.arguments, response_buffer)
+        let mut response = GreatResponse::new();
+        response
+            .dispatch(|buffer| core.dispatch(command.verb_number(), command.arguments, buffer))
             .expect("failed dispatch");
         println!("  -> {:?}", response);
 
@@ -324,6 +458,121 @@ mod tests {
         assert!(response.eq(expected.iter().copied()));
     }
 
+    // - test_verbs --
+
+    struct Typed;
+
+    #[verbs]
+    impl Typed {
+        #[verb(id = 0x1, out_param_names = "sum, overflow")]
+        fn add(&mut self, a: u32, b: u16, saturate: bool) -> GreatResult<(u32, bool)> {
+            match a.checked_add(b.into()) {
+                Some(sum) => Ok((sum, false)),
+                None if saturate => Ok((u32::MAX, true)),
+                None => Err(GreatError::InvalidArgument),
+            }
+        }
+
+        #[verb(id = 0x0)]
+        fn echo<'a>(&self, _count: u8, data: &'a [u8]) -> GreatResult<&'a [u8]> {
+            Ok(data)
+        }
+
+        #[verb(id = 0x2, doc = "Returns the class name.")]
+        fn name(&self) -> GreatResult<&'static str> {
+            Ok("typed")
+        }
+    }
+
+    #[test]
+    fn test_verbs_table() {
+        let verbs = Typed::VERBS;
+        assert!(verbs.iter().map(|verb| verb.id).eq([0, 1, 2]));
+
+        assert_eq!(verbs[0].name, "echo\0");
+        assert_eq!(verbs[0].in_signature, "<B*X\0");
+        assert_eq!(verbs[0].in_param_names, "count, data\0");
+        assert_eq!(verbs[0].out_signature, "<*X\0");
+        assert_eq!(verbs[0].out_param_names, "*\0");
+        assert_eq!(verbs[0].doc, "\0");
+
+        assert_eq!(verbs[1].in_signature, "<IH?\0");
+        assert_eq!(verbs[1].in_param_names, "a, b, saturate\0");
+        assert_eq!(verbs[1].out_signature, "<I?\0");
+        assert_eq!(verbs[1].out_param_names, "sum, overflow\0");
+
+        assert_eq!(verbs[2].in_signature, "\0");
+        assert_eq!(verbs[2].in_param_names, "*\0");
+        assert_eq!(verbs[2].out_signature, "<S\0");
+        assert_eq!(verbs[2].doc, "Returns the class name.\0");
+    }
+
+    #[test]
+    fn test_verbs_dispatch() {
+        let mut typed = Typed;
+        let mut dispatch = |verb_number, arguments: &[u8]| {
+            let mut buffer = [0_u8; 16];
+            let length = typed.dispatch(verb_number, arguments, &mut buffer)?;
+            GreatResult::Ok(buffer[..length].to_vec())
+        };
+
+        let response = dispatch(0x1, &[1, 0, 0, 0, 2, 0, 0]).unwrap();
+        assert_eq!(response, [3, 0, 0, 0, 0]);
+        let response = dispatch(0x1, &[0xff, 0xff, 0xff, 0xff, 2, 0, 1]).unwrap();
+        assert_eq!(response, [0xff, 0xff, 0xff, 0xff, 1]);
+        let response = dispatch(0x0, &[3, 0xa, 0xb, 0xc]).unwrap();
+        assert_eq!(response, [0xa, 0xb, 0xc]);
+        let response = dispatch(0x2, &[]).unwrap();
+        assert_eq!(response, b"typed");
+
+        // arguments must match the signature
+        assert!(dispatch(0x1, &[1, 0, 0, 0, 2, 0]).is_err());
+        assert!(dispatch(0x1, &[1, 0, 0, 0, 2, 0, 0, 0]).is_err());
+        assert!(dispatch(0x0, &[]).is_err());
+        assert!(dispatch(0x3, &[]).is_err());
+
+        // responses must fit the buffer
+        let response = dispatch(0x0, &[17; 18]);
+        assert!(matches!(response, Err(GreatError::ResultTooLarge)));
+    }
+
+    // - test_response --
+
+    #[test]
+    fn test_response_chunks() {
+        let mut response = GreatResponse::new();
+        response
+            .dispatch(|buffer| super::iter_to_response((0..100).map(|n| n as u8), buffer))
+            .unwrap();
+        assert_eq!(response.len(), 100);
+
+        assert_eq!(response.next_chunk(64), &(0..64).collect::<Vec<u8>>()[..]);
+        assert!(!response.is_complete());
+        assert_eq!(response.next_chunk(64), &(64..100).collect::<Vec<u8>>()[..]);
+        assert!(response.is_complete());
+        assert_eq!(response.next_chunk(64), &[]);
+    }
+
+    #[test]
+    fn test_response_too_large() {
+        let mut response = GreatResponse::new();
+        let result = response.dispatch(|buffer| {
+            super::iter_to_response(iter::repeat(0xa5).take(LIBGREAT_MAX_RESPONSE_SIZE), buffer)
+        });
+        assert!(result.is_ok());
+        assert_eq!(response.len(), LIBGREAT_MAX_RESPONSE_SIZE);
+
+        // a failed dispatch leaves the response empty
+        let result = response.dispatch(|buffer| {
+            super::iter_to_response(
+                iter::repeat(0xa5).take(LIBGREAT_MAX_RESPONSE_SIZE + 1),
+                buffer,
+            )
+        });
+        assert!(matches!(result, Err(GreatError::ResultTooLarge)));
+        assert_eq!(response.len(), 0);
+    }
+
     // - test_introspection --
 
     fn get_available_classes<'a>() -> impl Iterator<Item = u8> {
@@ -352,12 +601,13 @@ mod tests {
         .copied();
         assert!(classes.eq(expected));
 
-        let verbs = class_core::VERBS;
+        let verbs = class_core::Core::VERBS;
         let verbs = get_available_verbs_core(&verbs);
         let expected = [
             0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00,
             0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00,
-            0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00,
+            0x07, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x30, 0x00,
+            0x00, 0x00, 0x31, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00,
         ]
         .iter()
         .copied();
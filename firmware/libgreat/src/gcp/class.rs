@@ -6,11 +6,11 @@ use zerocopy::byteorder::{LittleEndian, U32};
 
 /// Collection of Great Communications Protocol classes
 #[derive(Copy, Clone)]
-pub struct Classes(pub &'static [Class]);
+pub struct Classes<'a>(pub &'a [Class]);
 
-impl Classes {
+impl<'a> Classes<'a> {
     #[must_use]
-    pub fn class(&self, id: ClassId) -> Option<&Class> {
+    pub fn class(&self, id: ClassId) -> Option<&'a Class> {
         self.0.iter().find(|&class| class.id == id)
     }
 
@@ -20,14 +20,14 @@ impl Classes {
     }
 }
 
-impl Default for Classes {
+impl Default for Classes<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl core::ops::Deref for Classes {
-    type Target = &'static [Class];
+impl<'a> core::ops::Deref for Classes<'a> {
+    type Target = &'a [Class];
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -54,7 +54,7 @@ impl Class {
 // - Verb ---------------------------------------------------------------------
 
 /// Great Communications Protocol verb
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Verb {
     pub id: u32,
     pub name: &'static str,
@@ -73,11 +73,17 @@ pub enum VerbDescriptor {
     Doc = 2,
     OutParamNames = 3,
     InParamNames = 4,
-    Unknown(u8),
+    Unknown(u32),
 }
 
 impl core::convert::From<u8> for VerbDescriptor {
     fn from(value: u8) -> Self {
+        VerbDescriptor::from(u32::from(value))
+    }
+}
+
+impl core::convert::From<u32> for VerbDescriptor {
+    fn from(value: u32) -> Self {
         use VerbDescriptor::*;
         match value {
             0 => OutSignature,
@@ -90,6 +96,20 @@ impl core::convert::From<u8> for VerbDescriptor {
     }
 }
 
+impl core::convert::From<VerbDescriptor> for u32 {
+    fn from(value: VerbDescriptor) -> Self {
+        use VerbDescriptor::*;
+        match value {
+            OutSignature => 0,
+            InSignature => 1,
+            Doc => 2,
+            OutParamNames => 3,
+            InParamNames => 4,
+            Unknown(value) => value,
+        }
+    }
+}
+
 // - ClassId ------------------------------------------------------------------
 
 /// Great Communications Protocol class id
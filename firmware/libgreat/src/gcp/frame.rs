@@ -0,0 +1,501 @@
+//! Great Communications Protocol Framing
+//!
+//! Frames carry GCP commands and their responses over transports that,
+//! unlike the control endpoint, have no setup stage to delimit them
+//! such as the libgreat bulk endpoints.
+//!
+//! Each frame is sent as a single transfer starting with a
+//! [`FrameHeader`] followed by `length` bytes of payload:
+//!
+//! * `Command` frames carry a [`CommandPrelude`](super::CommandPrelude)
+//!   followed by the command arguments.
+//! * `Cancel` frames have no payload and ask the device to stop sending
+//!   the response to the command with the same `tag`.
+//! * `Response` frames carry the response to the command with the same
+//!   `tag`. If `status` is non-zero it holds the
+//!   [`GreatError`] the command failed with and there is no payload.
+//!
+//! Every `Command` and `Cancel` frame is answered by exactly one
+//! `Response` frame. A cancelled response ends early with a short
+//! packet and is followed by the response to the `Cancel` frame, which
+//! has a `status` of [`GreatError::OperationCanceled`] if there was a
+//! response to cancel.
+
+use zerocopy::byteorder::{LittleEndian, U16, U32};
+use zerocopy::{AsBytes, FromBytes, FromZeroes, Unaligned};
+
+use crate::error::{GreatError, GreatResult};
+
+use super::{GreatResponse, LIBGREAT_MAX_COMMAND_SIZE};
+
+// - constants ----------------------------------------------------------------
+
+/// First byte of every frame
+pub const FRAME_MAGIC: u8 = b'G';
+
+/// Length of a [`FrameHeader`]
+pub const FRAME_HEADER_SIZE: usize = core::mem::size_of::<FrameHeader>();
+
+// - FrameHeader --------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FrameKind {
+    Command = 0x01,
+    Cancel = 0x02,
+    Response = 0x81,
+}
+
+impl TryFrom<u8> for FrameKind {
+    type Error = GreatError;
+
+    fn try_from(value: u8) -> GreatResult<Self> {
+        match value {
+            0x01 => Ok(FrameKind::Command),
+            0x02 => Ok(FrameKind::Cancel),
+            0x81 => Ok(FrameKind::Response),
+            _ => Err(GreatError::BadMessage),
+        }
+    }
+}
+
+/// Great Communication Protocol frame header
+#[repr(C)]
+#[derive(Clone, Copy, Debug, FromBytes, FromZeroes, AsBytes, Unaligned)]
+pub struct FrameHeader {
+    pub magic: u8,
+    pub kind: u8,
+    /// Pairs a response with its command.
+    pub tag: U16<LittleEndian>,
+    /// Zero, or the error a command failed with.
+    pub status: U32<LittleEndian>,
+    /// Length of the payload following the header.
+    pub length: U32<LittleEndian>,
+}
+
+impl FrameHeader {
+    #[must_use]
+    pub fn new(kind: FrameKind, tag: u16, status: u32, length: u32) -> Self {
+        Self {
+            magic: FRAME_MAGIC,
+            kind: kind as u8,
+            tag: tag.into(),
+            status: status.into(),
+            length: length.into(),
+        }
+    }
+
+    /// Returns the kind of frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::BadMessage`] if this is not a valid frame header.
+    pub fn kind(&self) -> GreatResult<FrameKind> {
+        if self.magic != FRAME_MAGIC {
+            return Err(GreatError::BadMessage);
+        }
+        FrameKind::try_from(self.kind)
+    }
+}
+
+// - FrameReceiver ------------------------------------------------------------
+
+/// A frame received from the host
+#[derive(Clone, Copy, Debug)]
+pub enum Received {
+    /// A command, see [`FrameReceiver::command`].
+    Command { tag: u16 },
+    /// A request to cancel the response to a command.
+    Cancel { tag: u16 },
+    /// A frame that could not be received.
+    Error { tag: u16, error: GreatError },
+}
+
+/// Reassembles the frames sent by the host from the packets of a transfer.
+pub struct FrameReceiver {
+    buffer: [u8; FRAME_HEADER_SIZE + LIBGREAT_MAX_COMMAND_SIZE],
+    /// Bytes received in the current transfer, including any that did not fit the buffer.
+    received: usize,
+    complete: bool,
+}
+
+impl FrameReceiver {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; FRAME_HEADER_SIZE + LIBGREAT_MAX_COMMAND_SIZE],
+            received: 0,
+            complete: false,
+        }
+    }
+
+    /// Discard any partially received frame.
+    pub fn reset(&mut self) {
+        self.received = 0;
+        self.complete = false;
+    }
+
+    /// Receive the next packet of a transfer.
+    ///
+    /// Returns the frame once the header and all of its payload have
+    /// been received. A short packet before then ends the transfer and
+    /// returns [`Received::Error`].
+    pub fn receive(&mut self, packet: &[u8], max_packet_size: usize) -> Option<Received> {
+        if self.complete {
+            self.reset();
+        }
+
+        let start = self.received.min(self.buffer.len());
+        let length = packet.len().min(self.buffer.len() - start);
+        self.buffer[start..start + length].copy_from_slice(&packet[..length]);
+        self.received += packet.len();
+
+        let frame = self.frame(packet.len() < max_packet_size);
+        self.complete = frame.is_some();
+        frame
+    }
+
+    /// Returns the arguments of the last [`Received::Command`] frame,
+    /// starting with its [`CommandPrelude`](super::CommandPrelude).
+    #[must_use]
+    pub fn command(&self) -> &[u8] {
+        if self.complete && self.received <= self.buffer.len() {
+            &self.buffer[FRAME_HEADER_SIZE.min(self.received)..self.received]
+        } else {
+            &[]
+        }
+    }
+
+    fn frame(&self, short: bool) -> Option<Received> {
+        let truncated = |tag| {
+            short.then_some(Received::Error {
+                tag,
+                error: GreatError::BadMessage,
+            })
+        };
+
+        let header = match FrameHeader::read_from_prefix(&self.buffer[..]) {
+            Some(header) if self.received >= FRAME_HEADER_SIZE => header,
+            _ => return truncated(0),
+        };
+        let tag = header.tag.get();
+
+        // wait for the end of the transfer before reporting a bad header
+        let Ok(kind) = header.kind() else {
+            return truncated(tag);
+        };
+
+        let frame_length = FRAME_HEADER_SIZE.saturating_add(header.length.get() as usize);
+        if self.received < frame_length {
+            return truncated(tag);
+        }
+
+        let error = |error| Some(Received::Error { tag, error });
+        match kind {
+            _ if self.received > frame_length => error(GreatError::BadMessage),
+            FrameKind::Command if frame_length > self.buffer.len() => {
+                error(GreatError::NoBufferSpaceAvailable)
+            }
+            FrameKind::Command => Some(Received::Command { tag }),
+            FrameKind::Cancel if frame_length == FRAME_HEADER_SIZE => {
+                Some(Received::Cancel { tag })
+            }
+            FrameKind::Cancel | FrameKind::Response => error(GreatError::BadMessage),
+        }
+    }
+}
+
+impl Default for FrameReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - FrameTransmitter ---------------------------------------------------------
+
+/// Splits response frames into packets for sending to the host.
+///
+/// The payload of a response is sent from the [`GreatResponse`] it was
+/// started with, which must not change until the response is sent.
+pub struct FrameTransmitter {
+    header: FrameHeader,
+    header_sent: usize,
+    /// The frame has a payload still to be sent.
+    payload: bool,
+    /// Length of the last packet sent, if the frame has been started.
+    last_packet_length: Option<usize>,
+    active: bool,
+    /// Header of a response without payload to send after the current one.
+    queued: Option<FrameHeader>,
+}
+
+impl FrameTransmitter {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            header: FrameHeader {
+                magic: FRAME_MAGIC,
+                kind: FrameKind::Response as u8,
+                tag: U16::ZERO,
+                status: U32::ZERO,
+                length: U32::ZERO,
+            },
+            header_sent: 0,
+            payload: false,
+            last_packet_length: None,
+            active: false,
+            queued: None,
+        }
+    }
+
+    /// Discard the current and any queued response.
+    pub fn reset(&mut self) {
+        self.payload = false;
+        self.active = false;
+        self.queued = None;
+    }
+
+    /// Returns `true` if there is no response left to send.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        !self.active && self.queued.is_none()
+    }
+
+    /// Start sending the response to the command with the given tag.
+    ///
+    /// Any response still being sent is discarded.
+    pub fn start(&mut self, tag: u16, result: GreatResult<&GreatResponse>) {
+        #[allow(clippy::cast_possible_truncation)]
+        match result {
+            Ok(response) => {
+                let length = response.len() as u32;
+                self.start_frame(FrameHeader::new(FrameKind::Response, tag, 0, length));
+                self.payload = true;
+            }
+            Err(error) => {
+                let header = FrameHeader::new(FrameKind::Response, tag, error as u32, 0);
+                self.start_frame(header);
+            }
+        }
+    }
+
+    /// Cancel the response to the command with the given tag and queue
+    /// the response to the cancel request.
+    pub fn cancel(&mut self, tag: u16) {
+        let status = if self.active && self.header.tag.get() == tag {
+            // end the transfer with the next packet
+            self.payload = false;
+            self.header_sent = FRAME_HEADER_SIZE;
+            if self.last_packet_length.is_none() {
+                self.active = false;
+            }
+            GreatError::OperationCanceled as u32
+        } else {
+            0
+        };
+
+        let header = FrameHeader::new(FrameKind::Response, tag, status, 0);
+        if self.active {
+            self.queued = Some(header);
+        } else {
+            self.start_frame(header);
+        }
+    }
+
+    /// Returns the next packet to send, or `None` once the last packet
+    /// of the response has been sent.
+    ///
+    /// Call again after each packet has been sent, with the response
+    /// the frame was started with.
+    pub fn next_packet<'a>(
+        &'a mut self,
+        max_packet_size: usize,
+        response: &'a mut GreatResponse,
+    ) -> Option<impl Iterator<Item = u8> + 'a> {
+        // the last packet was short, start the queued response if any
+        if matches!(self.last_packet_length, Some(length) if length < max_packet_size) {
+            self.active = false;
+            let header = self.queued.take()?;
+            self.start_frame(header);
+        }
+        if !self.active {
+            return None;
+        }
+
+        let start = self.header_sent;
+        self.header_sent = FRAME_HEADER_SIZE.min(start + max_packet_size);
+        let header = &self.header.as_bytes()[start..self.header_sent];
+        let payload = if self.payload {
+            response.next_chunk(max_packet_size - header.len())
+        } else {
+            &[]
+        };
+        self.last_packet_length = Some(header.len() + payload.len());
+
+        Some(header.iter().chain(payload).copied())
+    }
+
+    fn start_frame(&mut self, header: FrameHeader) {
+        self.header = header;
+        self.header_sent = 0;
+        self.payload = false;
+        self.last_packet_length = None;
+        self.active = true;
+    }
+}
+
+impl Default for FrameTransmitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcp::iter_to_response;
+
+    fn encode_frame(kind: FrameKind, tag: u16, payload: &[u8]) -> Vec<u8> {
+        let header = FrameHeader::new(kind, tag, 0, payload.len() as u32);
+        let mut frame = header.as_bytes().to_vec();
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn collect_packets(
+        transmitter: &mut FrameTransmitter,
+        response: &mut GreatResponse,
+        max_packet_size: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        while let Some(packet) = transmitter.next_packet(max_packet_size, response) {
+            packets.push(packet.collect());
+        }
+        packets
+    }
+
+    #[test]
+    fn test_receive_command() {
+        let mut receiver = FrameReceiver::new();
+        let command: Vec<u8> = (0..100).collect();
+        let frame = encode_frame(FrameKind::Command, 7, &command);
+
+        let mut chunks = frame.chunks(64);
+        assert!(receiver.receive(chunks.next().unwrap(), 64).is_none());
+        let received = receiver.receive(chunks.next().unwrap(), 64);
+        assert!(matches!(received, Some(Received::Command { tag: 7 })));
+        assert_eq!(receiver.command(), &command[..]);
+
+        // a frame ending on a packet boundary needs no zlp
+        let frame = encode_frame(FrameKind::Cancel, 8, &[]);
+        let received = receiver.receive(&frame, frame.len());
+        assert!(matches!(received, Some(Received::Cancel { tag: 8 })));
+        assert_eq!(receiver.command(), &[]);
+    }
+
+    #[test]
+    fn test_receive_errors() {
+        let mut receiver = FrameReceiver::new();
+
+        // truncated frame
+        let frame = encode_frame(FrameKind::Command, 1, &[0; 100]);
+        assert!(receiver.receive(&frame[..64], 64).is_none());
+        let received = receiver.receive(&[], 64);
+        assert!(matches!(
+            received,
+            Some(Received::Error {
+                tag: 1,
+                error: GreatError::BadMessage
+            })
+        ));
+
+        // bad header
+        let mut frame = encode_frame(FrameKind::Command, 2, &[]);
+        frame[0] = 0;
+        let received = receiver.receive(&frame, 64);
+        assert!(matches!(received, Some(Received::Error { tag: 2, .. })));
+
+        // command too large
+        let frame = encode_frame(FrameKind::Command, 3, &[0; 2000]);
+        let mut chunks = frame.chunks(512);
+        let received = chunks.find_map(|chunk| receiver.receive(chunk, 512));
+        assert!(chunks.next().is_none());
+        assert!(matches!(
+            received,
+            Some(Received::Error {
+                tag: 3,
+                error: GreatError::NoBufferSpaceAvailable
+            })
+        ));
+
+        // receiver recovers for the next frame
+        let frame = encode_frame(FrameKind::Command, 4, &[1, 2, 3]);
+        let received = receiver.receive(&frame, 64);
+        assert!(matches!(received, Some(Received::Command { tag: 4 })));
+        assert_eq!(receiver.command(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_transmit_response() {
+        let mut transmitter = FrameTransmitter::new();
+        assert!(transmitter.is_idle());
+
+        let mut response = GreatResponse::new();
+        let result = response.dispatch(|buffer| iter_to_response(0..116_u8, buffer));
+        transmitter.start(5, result.map(|()| &response));
+        let packets = collect_packets(&mut transmitter, &mut response, 64);
+        assert!(transmitter.is_idle());
+
+        // 12 + 116 bytes end on a packet boundary and need a zlp
+        assert_eq!(
+            packets.iter().map(Vec::len).collect::<Vec<_>>(),
+            [64, 64, 0]
+        );
+        let frame = packets.concat();
+        assert_eq!(
+            frame[..FRAME_HEADER_SIZE],
+            *FrameHeader::new(FrameKind::Response, 5, 0, 116).as_bytes()
+        );
+        assert!(frame[FRAME_HEADER_SIZE..].iter().copied().eq(0..116));
+
+        transmitter.start(6, Err(GreatError::InvalidArgument));
+        let packets = collect_packets(&mut transmitter, &mut response, 64);
+        assert_eq!(
+            packets,
+            [FrameHeader::new(FrameKind::Response, 6, 22, 0).as_bytes()]
+        );
+    }
+
+    #[test]
+    fn test_transmit_cancel() {
+        let mut transmitter = FrameTransmitter::new();
+        let mut response = GreatResponse::new();
+        let result = response.dispatch(|buffer| iter_to_response(0..255_u8, buffer));
+        transmitter.start(5, result.map(|()| &response));
+
+        let first: Vec<u8> = transmitter
+            .next_packet(64, &mut response)
+            .unwrap()
+            .collect();
+        assert_eq!(first.len(), 64);
+        transmitter.cancel(5);
+        let packets = collect_packets(&mut transmitter, &mut response, 64);
+        assert_eq!(
+            packets,
+            [
+                &[][..],
+                FrameHeader::new(FrameKind::Response, 5, 140, 0).as_bytes()
+            ]
+        );
+
+        // nothing to cancel
+        transmitter.cancel(9);
+        let packets = collect_packets(&mut transmitter, &mut response, 64);
+        assert_eq!(
+            packets,
+            [FrameHeader::new(FrameKind::Response, 9, 0, 0).as_bytes()]
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! Great Communications Protocol Jobs
+//!
+//! Verbs are dispatched from the firmware's main loop, so a verb that
+//! takes a long time to run would hold up everything else the firmware
+//! does. Such verbs can instead start a [`Job`] that is advanced a step
+//! at a time from the main loop while the host polls for its progress
+//! and result:
+//!
+//! ```ignore
+//! #[verbs(jobs = "jobs")]
+//! impl Firmware {
+//!     #[verb(id = 0x1, out_param_names = "job_id")]
+//!     pub fn full_erase(&mut self) -> GreatResult<u32> {
+//!         self.jobs.start(FullErase::new())
+//!     }
+//! }
+//!
+//! // ...and in the firmware's main loop
+//! classes.poll_jobs();
+//! ```
+//!
+//! The `jobs` of a class are reached through its
+//! [`GreatDispatch::jobs`](super::GreatDispatch::jobs), so that a
+//! [`registry!`](crate::registry) can advance them and the host can
+//! follow any job with the `job_status`, `job_result` and `job_cancel`
+//! verbs of the `core` class. Job ids are unique across all classes.
+//!
+//! A job is cancelled by the host with `job_cancel` or, along with the
+//! command in progress, with the libgreat cancel request.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::error::{GreatError, GreatResult};
+
+// - Job ----------------------------------------------------------------------
+
+/// The outcome of advancing a [`Job`] by a step
+pub enum JobPoll {
+    /// The job is still running and has done `done` of `total` units of work.
+    Pending { done: u32, total: u32 },
+    /// The job has finished, successfully or with the given error.
+    Ready(GreatResult<()>),
+}
+
+/// A long-running operation started by a verb
+pub trait Job {
+    /// Do the next step of the job.
+    ///
+    /// Steps should return quickly so that the firmware can continue
+    /// handling other requests while the job runs.
+    fn poll(&mut self) -> JobPoll;
+
+    /// Write the result of a successfully finished job to the start of
+    /// `response`, returning its length.
+    ///
+    /// The job keeps whatever its result is made from until the host
+    /// collects it, so that there is only ever the one response buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::ResultTooLarge`] if the result does not fit.
+    fn result(&mut self, response: &mut [u8]) -> GreatResult<usize>;
+
+    /// Stop the job before it has finished.
+    ///
+    /// The job is dropped afterwards and not polled again.
+    fn cancel(&mut self) {}
+}
+
+// - JobState -----------------------------------------------------------------
+
+/// State of a job as reported to the host
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum JobState {
+    Running = 0,
+    Complete = 1,
+    Failed = 2,
+    Canceled = 3,
+}
+
+// - JobControl ---------------------------------------------------------------
+
+/// The jobs of a class, as seen by the `core` class and the firmware's
+/// main loop
+pub trait JobControl {
+    /// Returns `true` if `job_id` is the current or last job.
+    fn owns(&self, job_id: u32) -> bool;
+
+    /// Advance the running job by a step, if there is one.
+    fn poll(&mut self);
+
+    /// Cancel the running job, if there is one.
+    fn cancel_all(&mut self);
+
+    /// Cancel the job with the given id if it is still running.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if `job_id` is not the
+    /// current or last job.
+    fn cancel(&mut self, job_id: u32) -> GreatResult<()>;
+
+    /// Returns the state of the job with the given id and how many of
+    /// its total units of work have been done.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if `job_id` is not the
+    /// current or last job.
+    fn status(&self, job_id: u32) -> GreatResult<(u32, u32, u32)>;
+
+    /// Take the result of the job with the given id once it has finished,
+    /// writing it to the start of `response` and returning its length.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the job failed with,
+    /// [`GreatError::OperationCanceled`] if it was cancelled,
+    /// [`GreatError::OperationWouldBlock`] if it is still running,
+    /// [`GreatError::NoData`] if the result has already been taken and
+    /// [`GreatError::InvalidArgument`] if `job_id` is not the current or
+    /// last job.
+    fn take_result(&mut self, job_id: u32, response: &mut [u8]) -> GreatResult<usize>;
+}
+
+// - Jobs ---------------------------------------------------------------------
+
+/// Id of the next job started by any class.
+static NEXT_JOB_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Runs one job of type `J` at a time and keeps the last job until the
+/// host has collected its result.
+pub struct Jobs<J: Job> {
+    /// Id of the current or last job, zero before the first job.
+    id: u32,
+    /// The running job, or the finished one until its result is taken.
+    job: Option<J>,
+    state: JobState,
+    done: u32,
+    total: u32,
+    /// Outcome of the last job until its result is taken.
+    outcome: Option<GreatResult<()>>,
+}
+
+impl<J: Job> Jobs<J> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            id: 0,
+            job: None,
+            state: JobState::Running,
+            done: 0,
+            total: 0,
+            outcome: None,
+        }
+    }
+
+    /// Returns `true` if a job is running.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.job.is_some() && self.outcome.is_none()
+    }
+
+    /// Start a job, returning its id.
+    ///
+    /// The result of the previous job is discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::DeviceOrResourceBusy`] if a job is already running.
+    pub fn start(&mut self, job: J) -> GreatResult<u32> {
+        if self.is_running() {
+            return Err(GreatError::DeviceOrResourceBusy);
+        }
+        self.id = 0;
+        while self.id == 0 {
+            self.id = NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+        }
+        self.job = Some(job);
+        self.state = JobState::Running;
+        self.done = 0;
+        self.total = 0;
+        self.outcome = None;
+        Ok(self.id)
+    }
+
+    fn finish(&mut self, outcome: GreatResult<()>) {
+        self.state = match &outcome {
+            Ok(()) => JobState::Complete,
+            Err(GreatError::OperationCanceled) => JobState::Canceled,
+            Err(_) => JobState::Failed,
+        };
+        self.outcome = Some(outcome);
+    }
+
+    fn check_id(&self, job_id: u32) -> GreatResult<()> {
+        if job_id == 0 || job_id != self.id {
+            return Err(GreatError::InvalidArgument);
+        }
+        Ok(())
+    }
+}
+
+impl<J: Job> JobControl for Jobs<J> {
+    fn owns(&self, job_id: u32) -> bool {
+        self.check_id(job_id).is_ok()
+    }
+
+    fn poll(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        let Some(job) = &mut self.job else {
+            return;
+        };
+        match job.poll() {
+            JobPoll::Pending { done, total } => {
+                self.done = done;
+                self.total = total;
+            }
+            JobPoll::Ready(outcome) => self.finish(outcome),
+        }
+    }
+
+    fn cancel_all(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        if let Some(mut job) = self.job.take() {
+            job.cancel();
+            self.finish(Err(GreatError::OperationCanceled));
+        }
+    }
+
+    fn cancel(&mut self, job_id: u32) -> GreatResult<()> {
+        self.check_id(job_id)?;
+        self.cancel_all();
+        Ok(())
+    }
+
+    fn status(&self, job_id: u32) -> GreatResult<(u32, u32, u32)> {
+        self.check_id(job_id)?;
+        Ok((self.state as u32, self.done, self.total))
+    }
+
+    fn take_result(&mut self, job_id: u32, response: &mut [u8]) -> GreatResult<usize> {
+        self.check_id(job_id)?;
+        if self.is_running() {
+            return Err(GreatError::OperationWouldBlock);
+        }
+        self.outcome.take().ok_or(GreatError::NoData)??;
+        let mut job = self.job.take().ok_or(GreatError::NoData)?;
+        job.result(response)
+    }
+}
+
+impl<J: Job> Default for Jobs<J> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcp::iter_to_response;
+
+    struct Countdown {
+        remaining: u32,
+        total: u32,
+        canceled: bool,
+    }
+
+    impl Countdown {
+        fn new(total: u32) -> Self {
+            Self {
+                remaining: total,
+                total,
+                canceled: false,
+            }
+        }
+    }
+
+    impl Job for Countdown {
+        fn poll(&mut self) -> JobPoll {
+            if self.remaining == 0 {
+                return JobPoll::Ready(Ok(()));
+            }
+            self.remaining -= 1;
+            JobPoll::Pending {
+                done: self.total - self.remaining,
+                total: self.total,
+            }
+        }
+
+        fn result(&mut self, response: &mut [u8]) -> GreatResult<usize> {
+            iter_to_response(self.total.to_le_bytes().into_iter(), response)
+        }
+
+        fn cancel(&mut self) {
+            self.canceled = true;
+        }
+    }
+
+    #[test]
+    fn test_job_complete() {
+        let mut jobs = Jobs::new();
+        let mut response = [0; 16];
+        let id = jobs.start(Countdown::new(3)).unwrap();
+        assert!(matches!(
+            jobs.start(Countdown::new(1)),
+            Err(GreatError::DeviceOrResourceBusy)
+        ));
+
+        jobs.poll();
+        assert_eq!(jobs.status(id).ok(), Some((JobState::Running as u32, 1, 3)));
+        assert!(matches!(
+            jobs.take_result(id, &mut response),
+            Err(GreatError::OperationWouldBlock)
+        ));
+
+        while jobs.is_running() {
+            jobs.poll();
+        }
+        assert_eq!(
+            jobs.status(id).ok(),
+            Some((JobState::Complete as u32, 3, 3))
+        );
+        let length = jobs.take_result(id, &mut response).unwrap();
+        assert_eq!(response[..length], [3, 0, 0, 0]);
+        assert!(matches!(
+            jobs.take_result(id, &mut response),
+            Err(GreatError::NoData)
+        ));
+        assert!(matches!(
+            jobs.status(id + 1),
+            Err(GreatError::InvalidArgument)
+        ));
+
+        // the next job gets a new id, unique across all classes
+        let next = jobs.start(Countdown::new(1)).unwrap();
+        let other = Jobs::new().start(Countdown::new(1)).unwrap();
+        assert!(next != id && other != id && other != next);
+        assert!(jobs.owns(next) && !jobs.owns(id) && !jobs.owns(other));
+    }
+
+    #[test]
+    fn test_job_cancel() {
+        let mut jobs = Jobs::new();
+        let mut response = [0; 16];
+        let id = jobs.start(Countdown::new(3)).unwrap();
+        jobs.poll();
+
+        assert!(jobs.cancel(id + 1).is_err());
+        jobs.cancel(id).unwrap();
+        assert!(!jobs.is_running());
+        assert_eq!(
+            jobs.status(id).ok(),
+            Some((JobState::Canceled as u32, 1, 3))
+        );
+        assert!(matches!(
+            jobs.take_result(id, &mut response),
+            Err(GreatError::OperationCanceled)
+        ));
+
+        // cancelling a finished job does nothing
+        jobs.cancel(id).unwrap();
+        jobs.cancel_all();
+        assert_eq!(
+            jobs.status(id).ok(),
+            Some((JobState::Canceled as u32, 1, 3))
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Great Communications Protocol Class Registry
+//!
+//! A registry owns the [`GreatDispatch`] implementing the verbs of each
+//! class, pairs it with the [`Class`] metadata and serves the `core`
+//! class itself, so introspection and dispatch share a single source.
+//! Registries are declared with [`registry!`](crate::registry).
+//!
+//! The registry also reaches the [`jobs`](GreatDispatch::jobs) of its
+//! classes, for the job verbs of the `core` class and for advancing and
+//! cancelling them from the firmware's main loop.
+
+use crate::error::{GreatError, GreatResult};
+use crate::firmware::BoardInformation;
+
+use super::class_core::Core;
+use super::job::JobControl;
+use super::{Class, ClassId, Classes, GreatDispatch};
+
+// - registry! ----------------------------------------------------------------
+
+/// Declare a registry owning the implementations of a set of classes.
+///
+/// Each field registers a class with the type implementing its verbs and
+/// the static holding its metadata. The registry is created once with the
+/// class implementations in field order:
+///
+/// ```ignore
+/// libgreat::registry! {
+///     /// The libgreat classes supported by the firmware
+///     pub struct Classes {
+///         firmware: Firmware => firmware::CLASS,
+///         moondancer: Moondancer => moondancer::CLASS,
+///     }
+/// }
+///
+/// let mut classes = Classes::new(BOARD_INFORMATION, Firmware::new(), Moondancer::new(usb0));
+/// let length = classes.dispatch(class_id, verb_number, arguments, &mut buffer)?;
+///
+/// // ...and in the firmware's main loop
+/// classes.poll_jobs();
+/// ```
+#[macro_export]
+macro_rules! registry {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $Registry:ident {
+            $($field:ident: $Class:ty => $class:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $Registry {
+            $(pub $field: $Class,)+
+            __classes: [$crate::gcp::Class; 1 + [$(stringify!($field)),+].len()],
+            __board_information: $crate::firmware::BoardInformation,
+        }
+
+        impl $Registry {
+            /// Create a new registry of the given class implementations.
+            ///
+            /// # Panics
+            ///
+            /// Panics if a class is registered more than once.
+            #[must_use]
+            pub fn new(
+                board_information: $crate::firmware::BoardInformation,
+                $($field: $Class),+
+            ) -> Self {
+                let classes = [$crate::gcp::class_core::CLASS, $($class),+];
+                $crate::gcp::registry::assert_unique(&classes);
+                Self {
+                    $($field,)+
+                    __classes: classes,
+                    __board_information: board_information,
+                }
+            }
+
+            /// Returns the registered classes.
+            #[must_use]
+            pub fn classes(&self) -> $crate::gcp::Classes<'_> {
+                $crate::gcp::Classes(&self.__classes)
+            }
+
+            /// Returns the board information served by the `core` class.
+            #[must_use]
+            pub fn board_information(&self) -> $crate::firmware::BoardInformation {
+                self.__board_information
+            }
+
+            /// Dispatches a GCP verb to its class, writing its response to
+            /// the start of `response` and returning the length of the
+            /// response.
+            ///
+            /// # Errors
+            ///
+            /// Returns `GreatError::InvalidArgument` if the class is not
+            /// registered, otherwise the result of the verb.
+            pub fn dispatch(
+                &mut self,
+                class_id: $crate::gcp::ClassId,
+                verb_number: u32,
+                arguments: &[u8],
+                response: &mut [u8],
+            ) -> $crate::GreatResult<usize> {
+                $(
+                    if class_id == $class.id {
+                        return $crate::gcp::GreatDispatch::dispatch(
+                            &mut self.$field,
+                            verb_number,
+                            arguments,
+                            response,
+                        );
+                    }
+                )+
+                $crate::gcp::registry::dispatch_core(
+                    $crate::gcp::Classes(&self.__classes),
+                    self.__board_information,
+                    &mut [$($crate::gcp::GreatDispatch::jobs(&mut self.$field)),+],
+                    class_id,
+                    verb_number,
+                    arguments,
+                    response,
+                )
+            }
+
+            /// Advance the running job of each class by a step.
+            pub fn poll_jobs(&mut self) {
+                $(
+                    if let Some(jobs) = $crate::gcp::GreatDispatch::jobs(&mut self.$field) {
+                        $crate::gcp::job::JobControl::poll(jobs);
+                    }
+                )+
+            }
+
+            /// Cancel the running job of each class.
+            pub fn cancel_jobs(&mut self) {
+                $(
+                    if let Some(jobs) = $crate::gcp::GreatDispatch::jobs(&mut self.$field) {
+                        $crate::gcp::job::JobControl::cancel_all(jobs);
+                    }
+                )+
+            }
+        }
+    };
+}
+
+// - helpers ------------------------------------------------------------------
+
+#[doc(hidden)]
+pub fn assert_unique(classes: &[Class]) {
+    for (index, class) in classes.iter().enumerate() {
+        assert!(
+            classes[..index].iter().all(|other| other.id != class.id),
+            "class registered more than once"
+        );
+    }
+}
+
+/// Dispatches a verb of the `core` class, the only class a registry
+/// doesn't hold an implementation of.
+#[doc(hidden)]
+pub fn dispatch_core<'a>(
+    classes: Classes<'a>,
+    board_information: BoardInformation,
+    jobs: &'a mut [Option<&'a mut dyn JobControl>],
+    class_id: ClassId,
+    verb_number: u32,
+    arguments: &[u8],
+    response: &mut [u8],
+) -> GreatResult<usize> {
+    if class_id != ClassId::core {
+        return Err(GreatError::InvalidArgument);
+    }
+    let mut core = Core::new(classes, board_information).with_jobs(jobs);
+    core.dispatch(verb_number, arguments, response)
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcp::job::{Job, JobPoll, Jobs};
+    use crate::gcp::verbs;
+
+    const BOARD_INFORMATION: BoardInformation = BoardInformation {
+        board_id: [0x00, 0x00, 0x00, 0x00],
+        version_string: "v2023.0.1\0",
+        part_id: [0x30, 0xa, 0x00, 0xa0, 0x5e, 0x4f, 0x60, 0x00],
+        serial_number: [0; 16],
+    };
+
+    static CLASS_SELFTEST: Class = Class {
+        id: ClassId::selftest,
+        name: "selftest",
+        docs: "\0",
+        verbs: &Selftest::VERBS,
+    };
+
+    struct Selftest {
+        calls: u32,
+    }
+
+    #[verbs]
+    impl Selftest {
+        #[verb(id = 0x10)]
+        fn echo(&mut self, value: u32) -> GreatResult<u32> {
+            self.calls += 1;
+            Ok(value)
+        }
+    }
+
+    crate::registry! {
+        struct Classes {
+            selftest: Selftest => CLASS_SELFTEST,
+        }
+    }
+
+    static CLASS_FIRMWARE: Class = Class {
+        id: ClassId::firmware,
+        name: "firmware",
+        docs: "\0",
+        verbs: &Firmware::VERBS,
+    };
+
+    struct Steps {
+        remaining: u32,
+    }
+
+    impl Job for Steps {
+        fn poll(&mut self) -> JobPoll {
+            if self.remaining == 0 {
+                return JobPoll::Ready(Ok(()));
+            }
+            self.remaining -= 1;
+            JobPoll::Pending {
+                done: 2 - self.remaining,
+                total: 2,
+            }
+        }
+
+        fn result(&mut self, response: &mut [u8]) -> GreatResult<usize> {
+            response[0] = 7;
+            Ok(1)
+        }
+    }
+
+    struct Firmware {
+        jobs: Jobs<Steps>,
+    }
+
+    #[verbs(jobs = "jobs")]
+    impl Firmware {
+        #[verb(id = 0x1)]
+        fn full_erase(&mut self) -> GreatResult<u32> {
+            self.jobs.start(Steps { remaining: 2 })
+        }
+    }
+
+    crate::registry! {
+        struct WithJobs {
+            selftest: Selftest => CLASS_SELFTEST,
+            firmware: Firmware => CLASS_FIRMWARE,
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut classes = Classes::new(BOARD_INFORMATION, Selftest { calls: 0 });
+        let mut buffer = [0; 16];
+
+        let length = classes
+            .dispatch(ClassId::selftest, 0x10, &[1, 2, 3, 4], &mut buffer)
+            .unwrap();
+        assert_eq!(buffer[..length], [1, 2, 3, 4]);
+
+        // core: get_available_classes
+        let length = classes
+            .dispatch(ClassId::core, 0x4, &[], &mut buffer)
+            .unwrap();
+        assert_eq!(
+            buffer[..length],
+            [0x00, 0x00, 0x00, 0x00, 0x11, 0x00, 0x00, 0x00]
+        );
+
+        // core: get_available_verbs(selftest)
+        let length = classes
+            .dispatch(ClassId::core, 0x5, &[0x11, 0x00, 0x00, 0x00], &mut buffer)
+            .unwrap();
+        assert_eq!(buffer[..length], [0x10, 0x00, 0x00, 0x00]);
+
+        // core: read_serial_number
+        let length = classes
+            .dispatch(ClassId::core, 0x3, &[], &mut buffer)
+            .unwrap();
+        assert_eq!(buffer[..length], classes.board_information().serial_number);
+
+        assert!(classes
+            .dispatch(ClassId::firmware, 0x0, &[], &mut buffer)
+            .is_err());
+        assert_eq!(classes.selftest.calls, 1);
+
+        // classes without jobs are left alone
+        classes.poll_jobs();
+        classes.cancel_jobs();
+        assert_eq!(classes.classes().0.len(), 2);
+    }
+
+    #[test]
+    fn test_jobs() {
+        let mut classes = WithJobs::new(
+            BOARD_INFORMATION,
+            Selftest { calls: 0 },
+            Firmware { jobs: Jobs::new() },
+        );
+        assert_eq!(classes.classes().0.len(), 3);
+        assert_eq!(
+            classes.board_information().version_string,
+            BOARD_INFORMATION.version_string
+        );
+        let mut buffer = [0; 16];
+        let mut dispatch = |classes: &mut WithJobs, class_id, verb_number, arguments: &[u8]| {
+            let length = classes.dispatch(class_id, verb_number, arguments, &mut buffer)?;
+            GreatResult::Ok(buffer[..length].to_vec())
+        };
+
+        let job_id = dispatch(&mut classes, ClassId::firmware, 0x1, &[]).unwrap();
+        classes.poll_jobs();
+
+        // core: job_status, job_result
+        let status = dispatch(&mut classes, ClassId::core, 0x30, &job_id).unwrap();
+        assert_eq!(status, [0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+        assert!(matches!(
+            dispatch(&mut classes, ClassId::core, 0x31, &job_id),
+            Err(GreatError::OperationWouldBlock)
+        ));
+        classes.poll_jobs();
+        classes.poll_jobs();
+        let result = dispatch(&mut classes, ClassId::core, 0x31, &job_id).unwrap();
+        assert_eq!(result, [7]);
+
+        // core: job_cancel, of a job that isn't running and of an unknown job
+        assert!(dispatch(&mut classes, ClassId::core, 0x32, &job_id).is_ok());
+        let unknown = (u32::from_le_bytes(job_id[..].try_into().unwrap()) + 1).to_le_bytes();
+        assert!(matches!(
+            dispatch(&mut classes, ClassId::core, 0x32, &unknown),
+            Err(GreatError::InvalidArgument)
+        ));
+
+        // the cancel request cancels the jobs of all classes
+        let job_id = dispatch(&mut classes, ClassId::firmware, 0x1, &[]).unwrap();
+        classes.cancel_jobs();
+        let status = dispatch(&mut classes, ClassId::core, 0x30, &job_id).unwrap();
+        assert_eq!(status[..4], [3, 0, 0, 0]);
+    }
+
+    // only ever created to check that it panics
+    #[allow(dead_code)]
+    mod twice {
+        use super::{Selftest, BOARD_INFORMATION, CLASS_SELFTEST};
+
+        crate::registry! {
+            struct Twice {
+                first: Selftest => CLASS_SELFTEST,
+                second: Selftest => CLASS_SELFTEST,
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "class registered more than once")]
+        fn test_register_twice() {
+            let _classes = Twice::new(
+                BOARD_INFORMATION,
+                Selftest { calls: 0 },
+                Selftest { calls: 0 },
+            );
+        }
+    }
+}
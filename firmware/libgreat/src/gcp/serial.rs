@@ -0,0 +1,444 @@
+//! Great Communications Protocol over Serial Ports
+//!
+//! Serial ports carry the same [`frame`](super::frame) format as the
+//! bulk endpoints, followed by a CRC to detect corrupted frames:
+//!
+//! * A [`FrameHeader`].
+//! * `length` bytes of payload.
+//! * The little-endian CRC-32 of the header and payload, see [`crc32`].
+//!
+//! Only `Command` frames are accepted from the host and each one is
+//! answered with a `Response` frame. Responses always run to completion
+//! so there are no `Cancel` frames.
+//!
+//! A byte stream has no packet boundaries to find the start of a frame
+//! by, so the receiver skips ahead to the next [`FRAME_MAGIC`] whenever
+//! it sees something that is not a valid command header. Commands that
+//! fail the CRC check are answered with [`GreatError::BadMessage`] and
+//! should be resent by the host.
+//!
+//! A corrupted length in an otherwise valid header would leave the
+//! receiver waiting for bytes the host never sends, so a frame that
+//! stops arriving for [`FRAME_TIMEOUT_MICROS`] is discarded and answered
+//! with [`GreatError::ConnectionTimedOut`].
+
+use zerocopy::byteorder::{U16, U32};
+use zerocopy::{AsBytes, FromBytes};
+
+use crate::error::{GreatError, GreatResult};
+
+use super::frame::{FrameHeader, FrameKind, Received, FRAME_HEADER_SIZE, FRAME_MAGIC};
+use super::{GreatResponse, LIBGREAT_MAX_COMMAND_SIZE};
+
+// - constants ----------------------------------------------------------------
+
+/// Length of the CRC following the payload
+pub const FRAME_CRC_SIZE: usize = 4;
+
+/// How long the stream may be idle in the middle of a frame before the
+/// frame is discarded, in microseconds
+pub const FRAME_TIMEOUT_MICROS: u64 = 100_000;
+
+// - crc32 --------------------------------------------------------------------
+
+/// Update a running CRC-32 with the given bytes.
+///
+/// Start with `0` and pass the result back in for each following block
+/// of bytes.
+#[must_use]
+pub fn crc32(crc: u32, bytes: &[u8]) -> u32 {
+    // CRC-32/ISO-HDLC, as used by zlib and Ethernet
+    let mut crc = !crc;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+// - SerialReceiver -----------------------------------------------------------
+
+/// Reassembles the command frames sent by the host from a byte stream.
+pub struct SerialReceiver {
+    buffer: [u8; FRAME_HEADER_SIZE + LIBGREAT_MAX_COMMAND_SIZE + FRAME_CRC_SIZE],
+    received: usize,
+    complete: bool,
+    /// When the stream was first seen idle since the last byte.
+    idle_since: Option<u64>,
+}
+
+impl SerialReceiver {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; FRAME_HEADER_SIZE + LIBGREAT_MAX_COMMAND_SIZE + FRAME_CRC_SIZE],
+            received: 0,
+            complete: false,
+            idle_since: None,
+        }
+    }
+
+    /// Discard any partially received frame.
+    pub fn reset(&mut self) {
+        self.received = 0;
+        self.complete = false;
+        self.idle_since = None;
+    }
+
+    /// Receive the next byte of the stream.
+    ///
+    /// Returns [`Received::Command`] once a whole command frame has been
+    /// received, or [`Received::Error`] if it failed the CRC check.
+    pub fn receive(&mut self, byte: u8) -> Option<Received> {
+        if self.complete {
+            self.reset();
+        }
+        self.idle_since = None;
+        if self.received == 0 && byte != FRAME_MAGIC {
+            return None;
+        }
+
+        self.buffer[self.received] = byte;
+        self.received += 1;
+        if self.received < FRAME_HEADER_SIZE {
+            return None;
+        }
+
+        let Some(length) = self.command_length() else {
+            self.resync();
+            return None;
+        };
+        let frame_length = FRAME_HEADER_SIZE + length;
+        if self.received < frame_length + FRAME_CRC_SIZE {
+            return None;
+        }
+
+        self.complete = true;
+        let tag = FrameHeader::read_from_prefix(&self.buffer[..])?.tag.get();
+        let expected = crc32(0, &self.buffer[..frame_length]);
+        let crc = &self.buffer[frame_length..self.received];
+        if crc == expected.to_le_bytes() {
+            Some(Received::Command { tag })
+        } else {
+            Some(Received::Error {
+                tag,
+                error: GreatError::BadMessage,
+            })
+        }
+    }
+
+    /// Note that there was no byte to receive at `now`, a time in
+    /// microseconds.
+    ///
+    /// Discards a partially received frame once the stream has been idle
+    /// for [`FRAME_TIMEOUT_MICROS`], returning [`Received::Error`] if its
+    /// header had been received.
+    pub fn idle(&mut self, now: u64) -> Option<Received> {
+        if self.complete || self.received == 0 {
+            return None;
+        }
+        let idle_since = *self.idle_since.get_or_insert(now);
+        if now.wrapping_sub(idle_since) < FRAME_TIMEOUT_MICROS {
+            return None;
+        }
+
+        let header = FrameHeader::read_from_prefix(&self.buffer[..self.received]);
+        self.reset();
+        header.map(|header| Received::Error {
+            tag: header.tag.get(),
+            error: GreatError::ConnectionTimedOut,
+        })
+    }
+
+    /// Returns the arguments of the last [`Received::Command`] frame,
+    /// starting with its [`CommandPrelude`](super::CommandPrelude).
+    #[must_use]
+    pub fn command(&self) -> &[u8] {
+        if self.complete {
+            &self.buffer[FRAME_HEADER_SIZE..self.received - FRAME_CRC_SIZE]
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns the payload length of the received header if it is a
+    /// valid command header.
+    fn command_length(&self) -> Option<usize> {
+        let header = FrameHeader::read_from_prefix(&self.buffer[..])?;
+        let length = header.length.get() as usize;
+        match header.kind() {
+            Ok(FrameKind::Command) if length <= LIBGREAT_MAX_COMMAND_SIZE => Some(length),
+            _ => None,
+        }
+    }
+
+    /// Drop the bytes received so far up to the next frame magic.
+    fn resync(&mut self) {
+        let start = self.buffer[1..self.received]
+            .iter()
+            .position(|&byte| byte == FRAME_MAGIC)
+            .map_or(self.received, |position| position + 1);
+        self.buffer.copy_within(start..self.received, 0);
+        self.received -= start;
+    }
+}
+
+impl Default for SerialReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - SerialTransmitter --------------------------------------------------------
+
+/// Serializes response frames for sending to the host.
+///
+/// The payload of a response is sent from the [`GreatResponse`] it was
+/// started with, which must not change until the response is sent.
+pub struct SerialTransmitter {
+    header: FrameHeader,
+    /// The frame has a payload.
+    payload: bool,
+    crc: [u8; FRAME_CRC_SIZE],
+    /// Bytes of the current frame sent so far.
+    sent: usize,
+    /// Total length of the current frame, zero when idle.
+    length: usize,
+}
+
+impl SerialTransmitter {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            header: FrameHeader {
+                magic: FRAME_MAGIC,
+                kind: FrameKind::Response as u8,
+                tag: U16::ZERO,
+                status: U32::ZERO,
+                length: U32::ZERO,
+            },
+            payload: false,
+            crc: [0; FRAME_CRC_SIZE],
+            sent: 0,
+            length: 0,
+        }
+    }
+
+    /// Discard the current response.
+    pub fn reset(&mut self) {
+        self.payload = false;
+        self.sent = 0;
+        self.length = 0;
+    }
+
+    /// Returns `true` if there is no response left to send.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.sent == self.length
+    }
+
+    /// Start sending the response to the command with the given tag.
+    ///
+    /// Any response still being sent is discarded.
+    pub fn start(&mut self, tag: u16, result: GreatResult<&GreatResponse>) {
+        #[allow(clippy::cast_possible_truncation)]
+        let (header, payload) = match result {
+            Ok(response) => {
+                let length = response.len() as u32;
+                let header = FrameHeader::new(FrameKind::Response, tag, 0, length);
+                (header, response.as_slice())
+            }
+            Err(error) => {
+                let header = FrameHeader::new(FrameKind::Response, tag, error as u32, 0);
+                (header, &[][..])
+            }
+        };
+
+        let crc = crc32(crc32(0, header.as_bytes()), payload);
+        self.length = FRAME_HEADER_SIZE + payload.len() + FRAME_CRC_SIZE;
+        self.header = header;
+        self.payload = !payload.is_empty();
+        self.crc = crc.to_le_bytes();
+        self.sent = 0;
+    }
+
+    /// Returns the next bytes of the response to send, or an empty slice
+    /// once the whole response has been sent.
+    ///
+    /// Call [`consume`](Self::consume) with the number of bytes sent and
+    /// the response the frame was started with.
+    #[must_use]
+    pub fn pending<'a>(&'a self, response: &'a GreatResponse) -> &'a [u8] {
+        let payload = if self.payload {
+            response.as_slice()
+        } else {
+            &[]
+        };
+        if self.is_idle() {
+            &[]
+        } else if self.sent < FRAME_HEADER_SIZE {
+            &self.header.as_bytes()[self.sent..]
+        } else if !payload.is_empty() {
+            payload
+        } else {
+            &self.crc[FRAME_CRC_SIZE - (self.length - self.sent)..]
+        }
+    }
+
+    /// Advance past the first `count` bytes returned by
+    /// [`pending`](Self::pending).
+    pub fn consume(&mut self, count: usize, response: &mut GreatResponse) {
+        let count = count.min(self.pending(response).len());
+        if self.sent >= FRAME_HEADER_SIZE && self.payload {
+            response.next_chunk(count);
+        }
+        self.sent += count;
+        if self.is_idle() {
+            self.reset();
+        }
+    }
+}
+
+impl Default for SerialTransmitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcp::iter_to_response;
+
+    fn encode_frame(kind: FrameKind, tag: u16, status: u32, payload: &[u8]) -> Vec<u8> {
+        let header = FrameHeader::new(kind, tag, status, payload.len() as u32);
+        let mut frame = header.as_bytes().to_vec();
+        frame.extend_from_slice(payload);
+        frame.extend_from_slice(&crc32(0, &frame).to_le_bytes());
+        frame
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(0, b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(crc32(0, b"1234"), b"56789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_receive_command() {
+        let mut receiver = SerialReceiver::new();
+        let command: Vec<u8> = (0..100).collect();
+
+        // skips line noise and headers that are not commands
+        let mut stream = b"xyG\x81".to_vec();
+        stream.extend(encode_frame(FrameKind::Response, 1, 0, &[]));
+        stream.extend(encode_frame(FrameKind::Command, 7, 0, &command));
+
+        let received: Vec<Received> = stream
+            .iter()
+            .filter_map(|&byte| receiver.receive(byte))
+            .collect();
+        assert!(matches!(received[..], [Received::Command { tag: 7 }]));
+        assert_eq!(receiver.command(), &command[..]);
+    }
+
+    #[test]
+    fn test_receive_errors() {
+        let mut receiver = SerialReceiver::new();
+
+        // corrupted frame
+        let mut frame = encode_frame(FrameKind::Command, 2, 0, &[1, 2, 3]);
+        frame[FRAME_HEADER_SIZE] ^= 0xff;
+        let received = frame.iter().find_map(|&byte| receiver.receive(byte));
+        assert!(matches!(
+            received,
+            Some(Received::Error {
+                tag: 2,
+                error: GreatError::BadMessage
+            })
+        ));
+
+        // command too large
+        let frame = encode_frame(FrameKind::Command, 3, 0, &[0; 2000]);
+        assert!(frame.iter().all(|&byte| receiver.receive(byte).is_none()));
+
+        // receiver recovers for the next frame
+        let frame = encode_frame(FrameKind::Command, 4, 0, &[1, 2, 3]);
+        let received = frame.iter().find_map(|&byte| receiver.receive(byte));
+        assert!(matches!(received, Some(Received::Command { tag: 4 })));
+        assert_eq!(receiver.command(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_receive_timeout() {
+        let mut receiver = SerialReceiver::new();
+
+        // a corrupted length leaves the frame waiting for more bytes
+        let mut frame = encode_frame(FrameKind::Command, 5, 0, &[1, 2, 3]);
+        frame[8] = 100;
+        assert!(frame.iter().all(|&byte| receiver.receive(byte).is_none()));
+
+        // the timeout runs from when the stream was first seen idle
+        assert!(receiver.idle(1_000).is_none());
+        assert!(receiver.receive(0).is_none());
+        assert!(receiver.idle(FRAME_TIMEOUT_MICROS).is_none());
+        assert!(receiver.idle(2 * FRAME_TIMEOUT_MICROS - 1).is_none());
+        let received = receiver.idle(2 * FRAME_TIMEOUT_MICROS);
+        assert!(matches!(
+            received,
+            Some(Received::Error {
+                tag: 5,
+                error: GreatError::ConnectionTimedOut
+            })
+        ));
+
+        // a partial header is dropped without a response
+        let frame = encode_frame(FrameKind::Command, 6, 0, &[1, 2, 3]);
+        assert!(receiver.receive(frame[0]).is_none());
+        assert!(receiver.idle(0).is_none());
+        assert!(receiver.idle(FRAME_TIMEOUT_MICROS).is_none());
+
+        // receiver recovers for the next frame
+        let received = frame.iter().find_map(|&byte| receiver.receive(byte));
+        assert!(matches!(received, Some(Received::Command { tag: 6 })));
+        assert_eq!(receiver.command(), &[1, 2, 3]);
+        assert!(receiver.idle(10 * FRAME_TIMEOUT_MICROS).is_none());
+    }
+
+    #[test]
+    fn test_transmit_response() {
+        let mut transmitter = SerialTransmitter::new();
+        assert!(transmitter.is_idle());
+
+        let payload: Vec<u8> = (0..100).collect();
+        let mut response = GreatResponse::new();
+        let result = response.dispatch(|buffer| iter_to_response(payload.iter().copied(), buffer));
+        transmitter.start(5, result.map(|()| &response));
+
+        // send a few bytes at a time
+        let mut sent = Vec::new();
+        while !transmitter.is_idle() {
+            let pending = transmitter.pending(&response);
+            let count = pending.len().min(3);
+            sent.extend_from_slice(&pending[..count]);
+            transmitter.consume(count, &mut response);
+        }
+        assert_eq!(sent, encode_frame(FrameKind::Response, 5, 0, &payload));
+        assert_eq!(transmitter.pending(&response), &[]);
+
+        transmitter.start(6, Err(GreatError::InvalidArgument));
+        let mut sent = Vec::new();
+        while !transmitter.is_idle() {
+            let pending = transmitter.pending(&response);
+            let count = pending.len();
+            sent.extend_from_slice(pending);
+            transmitter.consume(count, &mut response);
+        }
+        let status = GreatError::InvalidArgument as u32;
+        assert_eq!(sent, encode_frame(FrameKind::Response, 6, status, &[]));
+    }
+}
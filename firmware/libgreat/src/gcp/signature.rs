@@ -0,0 +1,884 @@
+//! Great Communications Protocol verb signatures
+//!
+//! Verb arguments and responses are described by the pygreat format
+//! strings of a [`Verb`](super::Verb), which extend those of Python's
+//! `struct` module:
+//!
+//! | format                                  | Rust type                          |
+//! |-----------------------------------------|------------------------------------|
+//! | `<`, `=` / `>`, `!` prefix              | little endian (default) / big endian |
+//! | `b`, `h`, `i` or `l`, `q`               | `i8`, `i16`, `i32`, `i64`          |
+//! | `B`, `H`, `I` or `L`, `Q`               | `u8`, `u16`, `u32`, `u64`          |
+//! | `?`                                     | `bool`                             |
+//! | `X` / `NX`                              | `u8` / `[u8; N]`                   |
+//! | `S`                                     | `&str`, `NUL` terminated           |
+//! | `Nc`                                    | `N` values of code `c`             |
+//! | `(...)`                                 | tuple                              |
+//! | `*X`, `*B`                              | `&[u8]`                            |
+//! | `*c`, `*(...)`                          | `&[T]` / [`Repeated<T>`]           |
+//!
+//! Repeated fields take the remainder of the data and must come last.
+//! Tuples match a group or, when there is none, consecutive fields.
+//!
+//! [`Signature`] encodes and decodes values without allocating:
+//!
+//! ```
+//! use libgreat::gcp::signature::{Repeated, Signature};
+//!
+//! let signature = Signature::parse("<B*(BHB)").unwrap();
+//!
+//! let mut buffer = [0; 16];
+//! let endpoints = [(0x01_u8, 512_u16, 2_u8), (0x82, 512, 2)];
+//! let length = signature.encode(&(2_u8, &endpoints[..]), &mut buffer).unwrap();
+//! assert_eq!(length, 9);
+//!
+//! let (count, endpoints): (u8, Repeated<(u8, u16, u8)>) =
+//!     signature.decode(&buffer[..length]).unwrap();
+//! assert_eq!(count, 2);
+//! assert!(endpoints.eq([(0x01, 512, 2), (0x82, 512, 2)]));
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::error::{GreatError, GreatResult};
+
+// - Signature ----------------------------------------------------------------
+
+/// A parsed verb signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature<'a> {
+    format: Format<'a>,
+}
+
+impl<'a> Signature<'a> {
+    /// Parse a signature.
+    ///
+    /// Trailing `NUL` characters are ignored so the signatures of a
+    /// [`Verb`](super::Verb) can be parsed as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the signature is
+    /// malformed, this includes the `"*"` of verbs without a signature.
+    pub fn parse(signature: &'a str) -> GreatResult<Self> {
+        let signature = signature.trim_end_matches('\0');
+        let (big_endian, rest) = match signature.as_bytes().first() {
+            Some(b'<' | b'=') => (false, &signature[1..]),
+            Some(b'>' | b'!') => (true, &signature[1..]),
+            _ => (false, signature),
+        };
+        let format = Format {
+            rest,
+            pending: None,
+            big_endian,
+        };
+        format.validate()?;
+        Ok(Self { format })
+    }
+
+    /// Returns the fields of the signature.
+    #[must_use]
+    pub fn format(&self) -> Format<'a> {
+        self.format
+    }
+
+    /// Decode a value from `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the type of the value
+    /// does not match the signature, [`GreatError::BadMessage`] if
+    /// `bytes` are too short or too long for it and
+    /// [`GreatError::IllegalByteSequence`] for strings that aren't UTF-8.
+    pub fn decode<'b, T: Decode<'b>>(&self, bytes: &'b [u8]) -> GreatResult<T>
+    where
+        'a: 'b,
+    {
+        let mut format: Format<'b> = self.format;
+        let mut reader = Reader::new(bytes);
+        let value = T::decode(&mut format, &mut reader)?;
+        format.finish()?;
+        if !reader.is_empty() {
+            return Err(GreatError::BadMessage);
+        }
+        Ok(value)
+    }
+
+    /// Encode a value into `buffer`, returning the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the type of the value
+    /// does not match the signature and
+    /// [`GreatError::NoBufferSpaceAvailable`] if it doesn't fit `buffer`.
+    pub fn encode<T: Encode + ?Sized>(&self, value: &T, buffer: &mut [u8]) -> GreatResult<usize> {
+        let mut format = self.format;
+        let mut writer = Writer::new(buffer);
+        value.encode(&mut format, &mut writer)?;
+        format.finish()?;
+        Ok(writer.position())
+    }
+}
+
+// - Format -------------------------------------------------------------------
+
+/// Cursor over the fields of a signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Format<'a> {
+    rest: &'a str,
+    /// Remaining repetitions of the last value.
+    pending: Option<(Code, usize)>,
+    big_endian: bool,
+}
+
+/// A field of a signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field<'a> {
+    /// A single value.
+    Value(Code),
+    /// An array of bytes.
+    Bytes(usize),
+    /// A `NUL` terminated string.
+    Str,
+    /// A group of fields.
+    Group(Format<'a>),
+    /// Fields repeated until the end of the data.
+    Repeat(Format<'a>),
+}
+
+/// Codes of single values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Code {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    Bool,
+}
+
+impl Code {
+    fn from_ascii(code: u8) -> Option<Self> {
+        match code {
+            b'b' => Some(Self::I8),
+            b'B' => Some(Self::U8),
+            b'h' => Some(Self::I16),
+            b'H' => Some(Self::U16),
+            b'i' | b'l' => Some(Self::I32),
+            b'I' | b'L' => Some(Self::U32),
+            b'q' => Some(Self::I64),
+            b'Q' => Some(Self::U64),
+            b'?' => Some(Self::Bool),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Format<'a> {
+    /// Returns `true` if values are big endian.
+    #[must_use]
+    pub fn big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    /// Returns the next field without consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the field is malformed.
+    pub fn peek(&self) -> GreatResult<Option<Field<'a>>> {
+        let mut format = *self;
+        format.next_field()
+    }
+
+    /// Consumes the next field.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the field is malformed.
+    pub fn next_field(&mut self) -> GreatResult<Option<Field<'a>>> {
+        if let Some((code, count)) = self.pending.take() {
+            if count > 1 {
+                self.pending = Some((code, count - 1));
+            }
+            return Ok(Some(Field::Value(code)));
+        }
+
+        let (count, rest) = split_count(self.rest.trim_start())?;
+        let Some(&code) = rest.as_bytes().first() else {
+            return match count {
+                Some(_) => Err(GreatError::InvalidArgument),
+                None => Ok(None),
+            };
+        };
+        if !code.is_ascii() {
+            return Err(GreatError::InvalidArgument);
+        }
+        let rest = &rest[1..];
+
+        let field = match (code, count) {
+            (b'*', None) => {
+                let (element, rest) = split_element(rest)?;
+                // repeated fields take the remainder of the data
+                if !rest.trim().is_empty() {
+                    return Err(GreatError::InvalidArgument);
+                }
+                self.rest = rest;
+                Field::Repeat(self.nested(element))
+            }
+            (b'(', None) => {
+                let (group, rest) = split_group(rest)?;
+                self.rest = rest;
+                Field::Group(self.nested(group))
+            }
+            (b'X', count) => {
+                self.rest = rest;
+                Field::Bytes(count.unwrap_or(1))
+            }
+            (b'S', None) => {
+                self.rest = rest;
+                Field::Str
+            }
+            (code, count) => {
+                let code = Code::from_ascii(code).ok_or(GreatError::InvalidArgument)?;
+                self.rest = rest;
+                match count.unwrap_or(1) {
+                    0 => return self.next_field(),
+                    1 => (),
+                    count => self.pending = Some((code, count - 1)),
+                }
+                Field::Value(code)
+            }
+        };
+
+        Ok(Some(field))
+    }
+
+    /// Consumes the next field, which must exist.
+    fn expect_field(&mut self) -> GreatResult<Field<'a>> {
+        self.next_field()?.ok_or(GreatError::InvalidArgument)
+    }
+
+    /// Consumes the next field, which must be a value of `code`.
+    fn expect_value(&mut self, code: Code) -> GreatResult<()> {
+        match self.expect_field()? {
+            Field::Value(value) if value == code => Ok(()),
+            _ => Err(GreatError::InvalidArgument),
+        }
+    }
+
+    /// Checks all fields have been consumed.
+    fn finish(&self) -> GreatResult<()> {
+        match self.peek()? {
+            Some(_) => Err(GreatError::InvalidArgument),
+            None => Ok(()),
+        }
+    }
+
+    fn nested(&self, rest: &'a str) -> Self {
+        Self {
+            rest,
+            pending: None,
+            big_endian: self.big_endian,
+        }
+    }
+
+    fn validate(mut self) -> GreatResult<()> {
+        while let Some(field) = self.next_field()? {
+            match field {
+                // an empty element would repeat forever
+                Field::Repeat(element) if element.peek()?.is_none() => {
+                    return Err(GreatError::InvalidArgument);
+                }
+                Field::Group(format) | Field::Repeat(format) => format.validate()?,
+                Field::Value(_) | Field::Bytes(_) | Field::Str => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits the count prefix of a field.
+fn split_count(format: &str) -> GreatResult<(Option<usize>, &str)> {
+    let digits = format.bytes().take_while(u8::is_ascii_digit).count();
+    let (count, rest) = format.split_at(digits);
+    if count.is_empty() {
+        return Ok((None, rest));
+    }
+    let count = count.parse().map_err(|_| GreatError::InvalidArgument)?;
+    Ok((Some(count), rest.trim_start()))
+}
+
+/// Splits the element of a repeated field.
+fn split_element(format: &str) -> GreatResult<(&str, &str)> {
+    let format = format.trim_start();
+    if let Some(rest) = format.strip_prefix('(') {
+        return split_group(rest);
+    }
+    let (_, rest) = split_count(format)?;
+    let length = format.len() - rest.len() + rest.chars().next().map_or(0, char::len_utf8);
+    Ok(format.split_at(length))
+}
+
+/// Splits the fields of a group from the remainder of the format.
+fn split_group(format: &str) -> GreatResult<(&str, &str)> {
+    let mut depth = 1_usize;
+    for (index, code) in format.bytes().enumerate() {
+        match code {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return Ok((&format[..index], &format[index + 1..]));
+        }
+    }
+    Err(GreatError::InvalidArgument)
+}
+
+// - Reader -------------------------------------------------------------------
+
+/// Source of encoded values.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    #[must_use]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns `true` if all bytes have been read.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the unread bytes without consuming them.
+    #[must_use]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Reads `length` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::BadMessage`] if fewer bytes remain.
+    pub fn take(&mut self, length: usize) -> GreatResult<&'a [u8]> {
+        if length > self.bytes.len() {
+            return Err(GreatError::BadMessage);
+        }
+        let (bytes, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    /// Reads all remaining bytes.
+    pub fn take_rest(&mut self) -> &'a [u8] {
+        core::mem::take(&mut self.bytes)
+    }
+}
+
+// - Writer -------------------------------------------------------------------
+
+/// Destination of encoded values.
+#[derive(Debug)]
+pub struct Writer<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    #[must_use]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of bytes written.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Writes `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::NoBufferSpaceAvailable`] if they don't fit.
+    pub fn write(&mut self, bytes: &[u8]) -> GreatResult<()> {
+        let end = self.position + bytes.len();
+        let destination = self
+            .buffer
+            .get_mut(self.position..end)
+            .ok_or(GreatError::NoBufferSpaceAvailable)?;
+        destination.copy_from_slice(bytes);
+        self.position = end;
+        Ok(())
+    }
+}
+
+// - traits -------------------------------------------------------------------
+
+/// Values that can be encoded against a signature.
+pub trait Encode {
+    /// Encode the value as the next fields of `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the value does not match
+    /// the fields and [`GreatError::NoBufferSpaceAvailable`] if it doesn't
+    /// fit `writer`.
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()>;
+}
+
+/// Values that can be decoded against a signature.
+pub trait Decode<'a>: Sized {
+    /// Decode the value from the next fields of `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::InvalidArgument`] if the value does not match
+    /// the fields and [`GreatError::BadMessage`] if `reader` is too short.
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self>;
+}
+
+// - values -------------------------------------------------------------------
+
+macro_rules! impl_integer {
+    ($($ty:ty => $code:ident),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+                format.expect_value(Code::$code)?;
+                if format.big_endian {
+                    writer.write(&self.to_be_bytes())
+                } else {
+                    writer.write(&self.to_le_bytes())
+                }
+            }
+        }
+
+        impl<'a> Decode<'a> for $ty {
+            fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+                format.expect_value(Code::$code)?;
+                let mut bytes = [0; core::mem::size_of::<$ty>()];
+                bytes.copy_from_slice(reader.take(core::mem::size_of::<$ty>())?);
+                if format.big_endian {
+                    Ok(<$ty>::from_be_bytes(bytes))
+                } else {
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        }
+    )*};
+}
+
+impl_integer! {
+    i8 => I8, i16 => I16, u16 => U16, i32 => I32, u32 => U32, i64 => I64, u64 => U64
+}
+
+/// `u8` also matches a single `X`.
+fn expect_byte(format: &mut Format<'_>) -> GreatResult<()> {
+    match format.expect_field()? {
+        Field::Value(Code::U8) | Field::Bytes(1) => Ok(()),
+        _ => Err(GreatError::InvalidArgument),
+    }
+}
+
+impl Encode for u8 {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        expect_byte(format)?;
+        writer.write(&[*self])
+    }
+}
+
+impl<'a> Decode<'a> for u8 {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        expect_byte(format)?;
+        Ok(reader.take(1)?[0])
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        format.expect_value(Code::Bool)?;
+        writer.write(&[u8::from(*self)])
+    }
+}
+
+impl<'a> Decode<'a> for bool {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        format.expect_value(Code::Bool)?;
+        Ok(reader.take(1)?[0] != 0)
+    }
+}
+
+impl<const N: usize> Encode for [u8; N] {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        match format.expect_field()? {
+            Field::Bytes(length) if length == N => writer.write(self),
+            _ => Err(GreatError::InvalidArgument),
+        }
+    }
+}
+
+impl<'a, const N: usize> Decode<'a> for [u8; N] {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        match format.expect_field()? {
+            Field::Bytes(length) if length == N => {
+                let mut bytes = [0; N];
+                bytes.copy_from_slice(reader.take(N)?);
+                Ok(bytes)
+            }
+            _ => Err(GreatError::InvalidArgument),
+        }
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        match format.expect_field()? {
+            Field::Str if !self.contains('\0') => {
+                writer.write(self.as_bytes())?;
+                writer.write(&[0])
+            }
+            _ => Err(GreatError::InvalidArgument),
+        }
+    }
+}
+
+impl<'a> Decode<'a> for &'a str {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        if format.expect_field()? != Field::Str {
+            return Err(GreatError::InvalidArgument);
+        }
+        // the terminator is optional at the end of the data
+        let bytes = match reader.remaining().iter().position(|&byte| byte == 0) {
+            Some(length) => {
+                let bytes = reader.take(length)?;
+                reader.take(1)?;
+                bytes
+            }
+            None => reader.take_rest(),
+        };
+        core::str::from_utf8(bytes).map_err(|_| GreatError::IllegalByteSequence)
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        let Field::Repeat(element) = format.expect_field()? else {
+            return Err(GreatError::InvalidArgument);
+        };
+        for value in self {
+            let mut format = element;
+            value.encode(&mut format, writer)?;
+            format.finish()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for &'a [u8] {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        let Field::Repeat(mut element) = format.expect_field()? else {
+            return Err(GreatError::InvalidArgument);
+        };
+        expect_byte(&mut element)?;
+        element.finish()?;
+        Ok(reader.take_rest())
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+        (**self).encode(format, writer)
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _format: &mut Format<'_>, _writer: &mut Writer<'_>) -> GreatResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for () {
+    fn decode(_format: &mut Format<'a>, _reader: &mut Reader<'a>) -> GreatResult<Self> {
+        Ok(())
+    }
+}
+
+/// Tuples consume a group if there is one, otherwise consecutive fields.
+fn with_group<'a, T>(
+    format: &mut Format<'a>,
+    f: impl FnOnce(&mut Format<'a>) -> GreatResult<T>,
+) -> GreatResult<T> {
+    if let Some(Field::Group(mut group)) = format.peek()? {
+        format.next_field()?;
+        let value = f(&mut group)?;
+        group.finish()?;
+        Ok(value)
+    } else {
+        f(format)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, format: &mut Format<'_>, writer: &mut Writer<'_>) -> GreatResult<()> {
+                let ($($name,)+) = self;
+                with_group(format, |format| {
+                    $($name.encode(format, writer)?;)+
+                    Ok(())
+                })
+            }
+        }
+
+        impl<'a, $($name: Decode<'a>),+> Decode<'a> for ($($name,)+) {
+            fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+                with_group(format, |format| Ok(($($name::decode(format, reader)?,)+)))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+
+// - Repeated -----------------------------------------------------------------
+
+/// Values of a repeated field, decoded on demand.
+///
+/// All values are checked when the field is decoded.
+pub struct Repeated<'a, T> {
+    element: Format<'a>,
+    reader: Reader<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for Repeated<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            element: self.element,
+            reader: self.reader,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Decode<'a>> Repeated<'a, T> {
+    fn next_value(&mut self) -> GreatResult<T> {
+        let mut format = self.element;
+        let value = T::decode(&mut format, &mut self.reader)?;
+        format.finish()?;
+        Ok(value)
+    }
+}
+
+impl<'a, T: Decode<'a>> Decode<'a> for Repeated<'a, T> {
+    fn decode(format: &mut Format<'a>, reader: &mut Reader<'a>) -> GreatResult<Self> {
+        let Field::Repeat(element) = format.expect_field()? else {
+            return Err(GreatError::InvalidArgument);
+        };
+        let repeated = Self {
+            element,
+            reader: Reader::new(reader.take_rest()),
+            _marker: PhantomData,
+        };
+
+        let mut values = repeated.clone();
+        while !values.reader.is_empty() {
+            values.next_value()?;
+        }
+
+        Ok(repeated)
+    }
+}
+
+impl<'a, T: Decode<'a>> Iterator for Repeated<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.is_empty() {
+            return None;
+        }
+        // values were checked when the field was decoded
+        self.next_value().ok()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::gcp::class_core::Core;
+
+    fn encode<T: Encode + ?Sized>(signature: &str, value: &T) -> GreatResult<std::vec::Vec<u8>> {
+        let mut buffer = [0; 1024];
+        let length = Signature::parse(signature)?.encode(value, &mut buffer)?;
+        Ok(buffer[..length].to_vec())
+    }
+
+    #[test]
+    fn test_parse() {
+        for signature in [
+            "", "<HBH", "<*(BHB)", "<I*X", "<S", "<8X", ">2I(?S)", "<B*4X",
+        ] {
+            assert!(Signature::parse(signature).is_ok(), "{signature}");
+        }
+        for signature in [
+            "*", "<*", "<*X*B", "<*XB", "<(BB", "<BB)", "<Z", "<2S", "<*()",
+        ] {
+            assert!(
+                matches!(
+                    Signature::parse(signature),
+                    Err(GreatError::InvalidArgument)
+                ),
+                "{signature}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_verbs() {
+        for verb in &Core::VERBS {
+            for signature in [verb.in_signature, verb.out_signature] {
+                if signature != "*\0" {
+                    assert!(Signature::parse(signature).is_ok(), "{signature}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode("<HBH\0", &(64_u16, 3_u8, 1_u16)).unwrap(),
+            [64, 0, 3, 1, 0]
+        );
+        assert_eq!(
+            encode(">I*X", &(1_u32, &b"ab"[..])).unwrap(),
+            [0, 0, 0, 1, b'a', b'b']
+        );
+        assert_eq!(encode("<S", "ok").unwrap(), *b"ok\0");
+        assert_eq!(encode("<3B", &(1_u8, 2_u8, 3_u8)).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        assert!(matches!(
+            encode("<HBH", &(64_u16, 3_u8)),
+            Err(GreatError::InvalidArgument)
+        ));
+        assert!(matches!(
+            encode("<HBH", &(64_u16, 3_u16, 1_u16)),
+            Err(GreatError::InvalidArgument)
+        ));
+        assert!(matches!(
+            encode("<S", "o\0k"),
+            Err(GreatError::InvalidArgument)
+        ));
+
+        let signature = Signature::parse("<I").unwrap();
+        assert!(matches!(
+            signature.encode(&1_u32, &mut [0; 3]),
+            Err(GreatError::NoBufferSpaceAvailable)
+        ));
+    }
+
+    #[test]
+    fn test_decode() {
+        let signature = Signature::parse("<HBH").unwrap();
+        assert_eq!(
+            signature
+                .decode::<(u16, u8, u16)>(&[64, 0, 3, 1, 0])
+                .unwrap(),
+            (64, 3, 1)
+        );
+        assert!(matches!(
+            signature.decode::<(u16, u8, u16)>(&[64, 0, 3, 1]),
+            Err(GreatError::BadMessage)
+        ));
+        assert!(matches!(
+            signature.decode::<(u16, u8, u16)>(&[64, 0, 3, 1, 0, 0]),
+            Err(GreatError::BadMessage)
+        ));
+        assert!(matches!(
+            signature.decode::<(u16, u16, u16)>(&[64, 0, 3, 1, 0]),
+            Err(GreatError::InvalidArgument)
+        ));
+
+        // strings at the end of the data don't need a terminator
+        let signature = Signature::parse("<S").unwrap();
+        assert_eq!(signature.decode::<&str>(b"ok").unwrap(), "ok");
+        assert!(matches!(
+            signature.decode::<&str>(&[0xff]),
+            Err(GreatError::IllegalByteSequence)
+        ));
+
+        // repeated fields are checked up front
+        let signature = Signature::parse("<*(BHB)").unwrap();
+        assert!(matches!(
+            signature.decode::<Repeated<(u8, u16, u8)>>(&[1, 2, 3, 4, 5]),
+            Err(GreatError::BadMessage)
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip_values(value: (u16, u8, u16, i8, i16, i32, i64, u64)) {
+            for signature in ["<HBHbhiqQ", ">HBHbhiqQ"] {
+                let bytes = encode(signature, &value).unwrap();
+                let signature = Signature::parse(signature).unwrap();
+                prop_assert_eq!(signature.decode::<(u16, u8, u16, i8, i16, i32, i64, u64)>(&bytes).unwrap(), value);
+            }
+        }
+
+        #[test]
+        fn test_round_trip_bytes(address: u32, flag: bool, id: [u8; 8], data: std::vec::Vec<u8>) {
+            let bytes = encode("<I?8X*X", &(address, flag, id, &data[..])).unwrap();
+            let signature = Signature::parse("<I?8X*X").unwrap();
+            let value: (u32, bool, [u8; 8], &[u8]) = signature.decode(&bytes).unwrap();
+            prop_assert_eq!(value, (address, flag, id, &data[..]));
+        }
+
+        #[test]
+        fn test_round_trip_groups(
+            name in "[^\0]*",
+            endpoints: std::vec::Vec<(u8, u16, u8)>,
+        ) {
+            let bytes = encode("<S*(BHB)", &(name.as_str(), &endpoints[..])).unwrap();
+            let signature = Signature::parse("<S*(BHB)").unwrap();
+            let (decoded, repeated): (&str, Repeated<(u8, u16, u8)>) = signature.decode(&bytes).unwrap();
+            prop_assert_eq!(decoded, name.as_str());
+            prop_assert_eq!(repeated.collect::<std::vec::Vec<_>>(), endpoints);
+        }
+
+        #[test]
+        fn test_round_trip_nested(values: std::vec::Vec<(bool, (u32, u8))>) {
+            let bytes = encode(">*(?(IB))", &values[..]).unwrap();
+            let signature = Signature::parse(">*(?(IB))").unwrap();
+            let repeated: Repeated<(bool, (u32, u8))> = signature.decode(&bytes).unwrap();
+            prop_assert_eq!(repeated.collect::<std::vec::Vec<_>>(), values);
+        }
+    }
+}
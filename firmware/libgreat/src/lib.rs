@@ -2,6 +2,9 @@
 #![cfg_attr(feature = "nightly", feature(panic_info_message))]
 #![cfg_attr(not(test), no_std)]
 
+// allow the generated code of `gcp::verbs` to be used in this crate
+extern crate self as libgreat;
+
 pub mod error;
 pub mod firmware;
 pub mod gcp;
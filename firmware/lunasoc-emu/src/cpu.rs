@@ -0,0 +1,786 @@
+//! RV32IMAC hart
+//!
+//! Implements the unprivileged RV32I base integer instruction set along
+//! with the `M`, `A`, `C` and `Zicsr` extensions and the subset of the
+//! machine-mode privileged architecture used by `riscv-rt` firmware:
+//! traps, `mret` and `wfi`.
+//!
+//! Vendor defined CSRs, such as the `VexRiscv` Machine IRQ Mask and
+//! Pending registers, are forwarded to the [`Bus`].
+
+mod compressed;
+
+/// Width of a memory access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    Byte = 1,
+    Half = 2,
+    Word = 4,
+}
+
+/// The hart's view of the system it is connected to.
+pub trait Bus {
+    /// Load a value of the given width.
+    ///
+    /// Returns `None` if nothing responds at the given address.
+    fn load(&mut self, address: u32, width: Width) -> Option<u32>;
+
+    /// Store a value of the given width.
+    ///
+    /// Returns `None` if nothing responds at the given address.
+    fn store(&mut self, address: u32, width: Width, value: u32) -> Option<()>;
+
+    /// Read a vendor defined CSR.
+    fn read_csr(&mut self, _csr: u16) -> Option<u32> {
+        None
+    }
+
+    /// Write a vendor defined CSR.
+    fn write_csr(&mut self, _csr: u16, _value: u32) -> Option<()> {
+        None
+    }
+
+    /// Returns `true` if the machine external interrupt line is asserted.
+    fn external_interrupt(&self) -> bool;
+}
+
+/// Exception causes.
+#[allow(non_snake_case, non_upper_case_globals)]
+pub mod Cause {
+    pub const InstructionAccessFault: u32 = 1;
+    pub const IllegalInstruction: u32 = 2;
+    pub const Breakpoint: u32 = 3;
+    pub const LoadAddressMisaligned: u32 = 4;
+    pub const LoadAccessFault: u32 = 5;
+    pub const StoreAddressMisaligned: u32 = 6;
+    pub const StoreAccessFault: u32 = 7;
+    pub const MachineEcall: u32 = 11;
+
+    /// Set in `mcause` for interrupts.
+    pub const Interrupt: u32 = 1 << 31;
+    pub const MachineExternalInterrupt: u32 = Interrupt | 11;
+}
+
+/// An exception taken by the hart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exception {
+    /// Exception cause, see [`Cause`].
+    pub cause: u32,
+    /// Address of the instruction which raised the exception.
+    pub pc: u32,
+    /// Faulting address or instruction.
+    pub tval: u32,
+}
+
+impl core::fmt::Display for Exception {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let cause = match self.cause {
+            Cause::InstructionAccessFault => "instruction access fault",
+            Cause::IllegalInstruction => "illegal instruction",
+            Cause::Breakpoint => "breakpoint",
+            Cause::LoadAddressMisaligned => "misaligned load",
+            Cause::LoadAccessFault => "load access fault",
+            Cause::StoreAddressMisaligned => "misaligned store",
+            Cause::StoreAccessFault => "store access fault",
+            Cause::MachineEcall => "environment call",
+            _ => "unknown exception",
+        };
+        write!(
+            f,
+            "{} at pc {:#010x} (mtval {:#010x})",
+            cause, self.pc, self.tval
+        )
+    }
+}
+
+/// Result of stepping the hart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// An instruction was executed.
+    Executed,
+    /// An exception was raised and the hart has entered its trap handler.
+    Exception(Exception),
+    /// The hart is waiting for an interrupt.
+    Waiting,
+}
+
+// - csrs ---------------------------------------------------------------------
+
+mod csr {
+    pub const MSTATUS: u16 = 0x300;
+    pub const MISA: u16 = 0x301;
+    pub const MIE: u16 = 0x304;
+    pub const MTVEC: u16 = 0x305;
+    pub const MSCRATCH: u16 = 0x340;
+    pub const MEPC: u16 = 0x341;
+    pub const MCAUSE: u16 = 0x342;
+    pub const MTVAL: u16 = 0x343;
+    pub const MIP: u16 = 0x344;
+    pub const MCYCLE: u16 = 0xb00;
+    pub const MINSTRET: u16 = 0xb02;
+    pub const MCYCLEH: u16 = 0xb80;
+    pub const MINSTRETH: u16 = 0xb82;
+    pub const CYCLE: u16 = 0xc00;
+    pub const TIME: u16 = 0xc01;
+    pub const INSTRET: u16 = 0xc02;
+    pub const CYCLEH: u16 = 0xc80;
+    pub const TIMEH: u16 = 0xc81;
+    pub const INSTRETH: u16 = 0xc82;
+    pub const MVENDORID: u16 = 0xf11;
+    pub const MARCHID: u16 = 0xf12;
+    pub const MIMPID: u16 = 0xf13;
+    pub const MHARTID: u16 = 0xf14;
+}
+
+const MSTATUS_MIE: u32 = 1 << 3;
+const MSTATUS_MPIE: u32 = 1 << 7;
+const MSTATUS_MPP: u32 = 0b11 << 11;
+
+const MIP_MSIP: u32 = 1 << 3;
+const MIP_MTIP: u32 = 1 << 7;
+const MIP_MEIP: u32 = 1 << 11;
+
+/// RV32 I, M, A and C
+const MISA: u32 = (1 << 30) | (1 << 0) | (1 << 2) | (1 << 8) | (1 << 12);
+
+// - Hart ---------------------------------------------------------------------
+
+/// A RISC-V hart running in machine mode.
+#[derive(Debug)]
+pub struct Hart {
+    /// Integer registers, `x[0]` is always zero.
+    pub x: [u32; 32],
+    /// Program counter.
+    pub pc: u32,
+    /// Clock cycles elapsed since reset.
+    pub cycle: u64,
+    /// Instructions retired since reset.
+    pub instret: u64,
+
+    mstatus: u32,
+    mie: u32,
+    mtvec: u32,
+    mscratch: u32,
+    mepc: u32,
+    mcause: u32,
+    mtval: u32,
+
+    /// Address reserved by `lr.w`.
+    reservation: Option<u32>,
+    /// Set by `wfi` until an interrupt becomes pending.
+    waiting: bool,
+}
+
+impl Default for Hart {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Hart {
+    /// Returns a hart which starts execution at the given address.
+    #[must_use]
+    pub fn new(reset_vector: u32) -> Self {
+        Self {
+            x: [0; 32],
+            pc: reset_vector,
+            cycle: 0,
+            instret: 0,
+            mstatus: MSTATUS_MPP,
+            mie: 0,
+            mtvec: 0,
+            mscratch: 0,
+            mepc: 0,
+            mcause: 0,
+            mtval: 0,
+            reservation: None,
+            waiting: false,
+        }
+    }
+
+    /// Returns `true` if the hart is waiting for an interrupt.
+    pub fn is_waiting(&self) -> bool {
+        self.waiting
+    }
+
+    /// Returns `true` if machine interrupts are globally enabled.
+    pub fn interrupts_enabled(&self) -> bool {
+        self.mstatus & MSTATUS_MIE != 0
+    }
+
+    /// Take any pending interrupt and execute the next instruction.
+    pub fn step(&mut self, bus: &mut impl Bus) -> Step {
+        self.cycle += 1;
+
+        let pending = self.mip(bus) & self.mie;
+        if pending != 0 {
+            self.waiting = false;
+            if self.interrupts_enabled() && pending & MIP_MEIP != 0 {
+                self.trap(Cause::MachineExternalInterrupt, self.pc, 0);
+            }
+        }
+        if self.waiting {
+            return Step::Waiting;
+        }
+
+        let pc = self.pc;
+        let result = self.fetch(bus).and_then(|(instruction, length)| {
+            match self.execute(bus, instruction, length) {
+                Err((cause, tval)) if cause == Cause::IllegalInstruction && tval == 0 => {
+                    Err((cause, instruction))
+                }
+                result => result,
+            }
+        });
+
+        match result {
+            Ok(()) => {
+                self.x[0] = 0;
+                self.instret += 1;
+                Step::Executed
+            }
+            Err((cause, tval)) => {
+                self.trap(cause, pc, tval);
+                Step::Exception(Exception { cause, pc, tval })
+            }
+        }
+    }
+
+    fn mip(&self, bus: &impl Bus) -> u32 {
+        if bus.external_interrupt() {
+            MIP_MEIP
+        } else {
+            0
+        }
+    }
+
+    fn trap(&mut self, cause: u32, pc: u32, tval: u32) {
+        self.mepc = pc;
+        self.mcause = cause;
+        self.mtval = tval;
+
+        // stack the interrupt enable
+        let mie = self.mstatus & MSTATUS_MIE != 0;
+        self.mstatus &= !(MSTATUS_MIE | MSTATUS_MPIE);
+        if mie {
+            self.mstatus |= MSTATUS_MPIE;
+        }
+
+        let base = self.mtvec & !0b11;
+        self.pc = if self.mtvec & 0b11 == 1 && cause & Cause::Interrupt != 0 {
+            base.wrapping_add(4 * (cause & !Cause::Interrupt))
+        } else {
+            base
+        };
+    }
+
+    fn fetch(&mut self, bus: &mut impl Bus) -> Result<(u32, u32)> {
+        let fault = (Cause::InstructionAccessFault, self.pc);
+        let low = bus.load(self.pc, Width::Half).ok_or(fault)?;
+        if low & 0b11 != 0b11 {
+            return Ok((compressed::expand(low as u16), 2));
+        }
+        let high = bus
+            .load(self.pc.wrapping_add(2), Width::Half)
+            .ok_or(fault)?;
+        Ok((low | (high << 16), 4))
+    }
+}
+
+// - execute ------------------------------------------------------------------
+
+/// Either the result of an instruction or an exception `(cause, tval)`.
+///
+/// A `tval` of zero for an illegal instruction is replaced by the
+/// instruction itself.
+type Result<T> = core::result::Result<T, (u32, u32)>;
+
+const ILLEGAL: (u32, u32) = (Cause::IllegalInstruction, 0);
+
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn sext(value: u32, bits: u32) -> u32 {
+    let shift = 32 - bits;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+impl Hart {
+    #[allow(clippy::too_many_lines)]
+    fn execute(&mut self, bus: &mut impl Bus, instruction: u32, length: u32) -> Result<()> {
+        let opcode = instruction & 0x7f;
+        let rd = ((instruction >> 7) & 0x1f) as usize;
+        let funct3 = (instruction >> 12) & 0x7;
+        let rs1 = ((instruction >> 15) & 0x1f) as usize;
+        let rs2 = ((instruction >> 20) & 0x1f) as usize;
+        let funct7 = instruction >> 25;
+
+        let imm_i = sext(instruction >> 20, 12);
+        let imm_s = sext(((instruction >> 25) << 5) | ((instruction >> 7) & 0x1f), 12);
+        let imm_b = sext(
+            ((instruction >> 31) << 12)
+                | (((instruction >> 7) & 0x1) << 11)
+                | (((instruction >> 25) & 0x3f) << 5)
+                | (((instruction >> 8) & 0xf) << 1),
+            13,
+        );
+        let imm_u = instruction & 0xffff_f000;
+        let imm_j = sext(
+            ((instruction >> 31) << 20)
+                | (((instruction >> 12) & 0xff) << 12)
+                | (((instruction >> 20) & 0x1) << 11)
+                | (((instruction >> 21) & 0x3ff) << 1),
+            21,
+        );
+
+        let a = self.x[rs1];
+        let b = self.x[rs2];
+        let next = self.pc.wrapping_add(length);
+
+        match opcode {
+            // LUI
+            0x37 => self.x[rd] = imm_u,
+
+            // AUIPC
+            0x17 => self.x[rd] = self.pc.wrapping_add(imm_u),
+
+            // JAL
+            0x6f => {
+                self.x[rd] = next;
+                self.pc = self.pc.wrapping_add(imm_j);
+                return Ok(());
+            }
+
+            // JALR
+            0x67 if funct3 == 0 => {
+                self.x[rd] = next;
+                self.pc = a.wrapping_add(imm_i) & !1;
+                return Ok(());
+            }
+
+            // BRANCH
+            0x63 => {
+                #[allow(clippy::cast_possible_wrap)]
+                let taken = match funct3 {
+                    0b000 => a == b,
+                    0b001 => a != b,
+                    0b100 => (a as i32) < (b as i32),
+                    0b101 => (a as i32) >= (b as i32),
+                    0b110 => a < b,
+                    0b111 => a >= b,
+                    _ => return Err(ILLEGAL),
+                };
+                self.pc = if taken {
+                    self.pc.wrapping_add(imm_b)
+                } else {
+                    next
+                };
+                return Ok(());
+            }
+
+            // LOAD
+            0x03 => {
+                let address = a.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0b000 => sext(self.load(bus, address, Width::Byte)?, 8),
+                    0b001 => sext(self.load(bus, address, Width::Half)?, 16),
+                    0b010 => self.load(bus, address, Width::Word)?,
+                    0b100 => self.load(bus, address, Width::Byte)?,
+                    0b101 => self.load(bus, address, Width::Half)?,
+                    _ => return Err(ILLEGAL),
+                };
+                self.x[rd] = value;
+            }
+
+            // STORE
+            0x23 => {
+                let address = a.wrapping_add(imm_s);
+                let width = match funct3 {
+                    0b000 => Width::Byte,
+                    0b001 => Width::Half,
+                    0b010 => Width::Word,
+                    _ => return Err(ILLEGAL),
+                };
+                self.store(bus, address, width, b)?;
+            }
+
+            // OP-IMM
+            0x13 => {
+                let shamt = (instruction >> 20) & 0x1f;
+                #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+                let value = match (funct3, funct7) {
+                    (0b000, _) => a.wrapping_add(imm_i),
+                    (0b010, _) => u32::from((a as i32) < (imm_i as i32)),
+                    (0b011, _) => u32::from(a < imm_i),
+                    (0b100, _) => a ^ imm_i,
+                    (0b110, _) => a | imm_i,
+                    (0b111, _) => a & imm_i,
+                    (0b001, 0x00) => a << shamt,
+                    (0b101, 0x00) => a >> shamt,
+                    (0b101, 0x20) => ((a as i32) >> shamt) as u32,
+                    _ => return Err(ILLEGAL),
+                };
+                self.x[rd] = value;
+            }
+
+            // OP
+            0x33 => {
+                #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+                let value = match (funct7, funct3) {
+                    (0x00, 0b000) => a.wrapping_add(b),
+                    (0x20, 0b000) => a.wrapping_sub(b),
+                    (0x00, 0b001) => a << (b & 0x1f),
+                    (0x00, 0b010) => u32::from((a as i32) < (b as i32)),
+                    (0x00, 0b011) => u32::from(a < b),
+                    (0x00, 0b100) => a ^ b,
+                    (0x00, 0b101) => a >> (b & 0x1f),
+                    (0x20, 0b101) => ((a as i32) >> (b & 0x1f)) as u32,
+                    (0x00, 0b110) => a | b,
+                    (0x00, 0b111) => a & b,
+                    (0x01, funct3) => Self::muldiv(funct3, a, b),
+                    _ => return Err(ILLEGAL),
+                };
+                self.x[rd] = value;
+            }
+
+            // MISC-MEM: fences and cache management are no-ops
+            0x0f => (),
+
+            // AMO
+            0x2f if funct3 == 0b010 => self.x[rd] = self.amo(bus, instruction >> 27, a, b)?,
+
+            // SYSTEM
+            0x73 => return self.system(bus, instruction, next),
+
+            _ => return Err(ILLEGAL),
+        }
+
+        self.pc = next;
+        Ok(())
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    fn muldiv(funct3: u32, a: u32, b: u32) -> u32 {
+        let (sa, sb) = (a as i32, b as i32);
+        match funct3 {
+            0b000 => a.wrapping_mul(b),
+            0b001 => ((i64::from(sa) * i64::from(sb)) >> 32) as u32,
+            0b010 => ((i64::from(sa) * i64::from(b)) >> 32) as u32,
+            0b011 => ((u64::from(a) * u64::from(b)) >> 32) as u32,
+            0b100 if b == 0 => u32::MAX,
+            0b100 => sa.wrapping_div(sb) as u32,
+            0b101 if b == 0 => u32::MAX,
+            0b101 => a / b,
+            0b110 if b == 0 => a,
+            0b110 => sa.wrapping_rem(sb) as u32,
+            0b111 if b == 0 => a,
+            _ => a % b,
+        }
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn amo(&mut self, bus: &mut impl Bus, funct5: u32, address: u32, value: u32) -> Result<u32> {
+        match funct5 {
+            // LR.W
+            0b00010 => {
+                let loaded = self.load(bus, address, Width::Word)?;
+                self.reservation = Some(address);
+                Ok(loaded)
+            }
+            // SC.W
+            0b00011 => {
+                if self.reservation.take() == Some(address) {
+                    self.store(bus, address, Width::Word, value)?;
+                    Ok(0)
+                } else {
+                    Ok(1)
+                }
+            }
+            _ => {
+                if address & 0b11 != 0 {
+                    return Err((Cause::StoreAddressMisaligned, address));
+                }
+                let loaded = bus
+                    .load(address, Width::Word)
+                    .ok_or((Cause::StoreAccessFault, address))?;
+                let stored = match funct5 {
+                    0b00001 => value,
+                    0b00000 => loaded.wrapping_add(value),
+                    0b00100 => loaded ^ value,
+                    0b01100 => loaded & value,
+                    0b01000 => loaded | value,
+                    0b10000 => (loaded as i32).min(value as i32) as u32,
+                    0b10100 => (loaded as i32).max(value as i32) as u32,
+                    0b11000 => loaded.min(value),
+                    0b11100 => loaded.max(value),
+                    _ => return Err(ILLEGAL),
+                };
+                self.store(bus, address, Width::Word, stored)?;
+                Ok(loaded)
+            }
+        }
+    }
+
+    fn system(&mut self, bus: &mut impl Bus, instruction: u32, next: u32) -> Result<()> {
+        let rd = ((instruction >> 7) & 0x1f) as usize;
+        let funct3 = (instruction >> 12) & 0x7;
+        let rs1 = ((instruction >> 15) & 0x1f) as usize;
+        #[allow(clippy::cast_possible_truncation)]
+        let csr = (instruction >> 20) as u16;
+
+        if funct3 == 0 {
+            match instruction {
+                // ECALL
+                0x0000_0073 => return Err((Cause::MachineEcall, 0)),
+                // EBREAK
+                0x0010_0073 => return Err((Cause::Breakpoint, self.pc)),
+                // MRET
+                0x3020_0073 => {
+                    let mpie = self.mstatus & MSTATUS_MPIE != 0;
+                    self.mstatus = (self.mstatus & !MSTATUS_MIE) | MSTATUS_MPIE;
+                    if mpie {
+                        self.mstatus |= MSTATUS_MIE;
+                    }
+                    self.pc = self.mepc;
+                    return Ok(());
+                }
+                // WFI
+                0x1050_0073 => self.waiting = true,
+                _ => return Err(ILLEGAL),
+            }
+            self.pc = next;
+            return Ok(());
+        }
+
+        // CSRRW, CSRRS, CSRRC and their immediate forms
+        let operand = if funct3 & 0b100 == 0 {
+            self.x[rs1]
+        } else {
+            rs1 as u32
+        };
+        let writes = funct3 & 0b011 == 0b01 || rs1 != 0;
+        let reads = funct3 & 0b011 != 0b01 || rd != 0;
+        if writes && csr >> 10 == 0b11 {
+            return Err(ILLEGAL);
+        }
+
+        let old = if reads { self.read_csr(bus, csr)? } else { 0 };
+        if writes {
+            let new = match funct3 & 0b011 {
+                0b01 => operand,
+                0b10 => old | operand,
+                _ => old & !operand,
+            };
+            self.write_csr(bus, csr, new)?;
+        }
+        self.x[rd] = old;
+        self.pc = next;
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn read_csr(&mut self, bus: &mut impl Bus, csr: u16) -> Result<u32> {
+        let value = match csr {
+            csr::MSTATUS => self.mstatus,
+            csr::MISA => MISA,
+            csr::MIE => self.mie,
+            csr::MTVEC => self.mtvec,
+            csr::MSCRATCH => self.mscratch,
+            csr::MEPC => self.mepc,
+            csr::MCAUSE => self.mcause,
+            csr::MTVAL => self.mtval,
+            csr::MIP => self.mip(bus),
+            csr::MCYCLE | csr::CYCLE | csr::TIME => self.cycle as u32,
+            csr::MCYCLEH | csr::CYCLEH | csr::TIMEH => (self.cycle >> 32) as u32,
+            csr::MINSTRET | csr::INSTRET => self.instret as u32,
+            csr::MINSTRETH | csr::INSTRETH => (self.instret >> 32) as u32,
+            csr::MVENDORID | csr::MARCHID | csr::MIMPID | csr::MHARTID => 0,
+            csr => bus.read_csr(csr).ok_or(ILLEGAL)?,
+        };
+        Ok(value)
+    }
+
+    fn write_csr(&mut self, bus: &mut impl Bus, csr: u16, value: u32) -> Result<()> {
+        match csr {
+            csr::MSTATUS => self.mstatus = (value & (MSTATUS_MIE | MSTATUS_MPIE)) | MSTATUS_MPP,
+            csr::MIE => self.mie = value & (MIP_MSIP | MIP_MTIP | MIP_MEIP),
+            csr::MTVEC => self.mtvec = value & !0b10,
+            csr::MSCRATCH => self.mscratch = value,
+            csr::MEPC => self.mepc = value & !1,
+            csr::MCAUSE => self.mcause = value,
+            csr::MTVAL => self.mtval = value,
+            csr::MCYCLE => self.cycle = (self.cycle & !0xffff_ffff) | u64::from(value),
+            csr::MCYCLEH => self.cycle = (self.cycle & 0xffff_ffff) | (u64::from(value) << 32),
+            csr::MINSTRET => self.instret = (self.instret & !0xffff_ffff) | u64::from(value),
+            csr::MINSTRETH => {
+                self.instret = (self.instret & 0xffff_ffff) | (u64::from(value) << 32);
+            }
+            // software and timer interrupts are not implemented
+            csr::MISA | csr::MIP => (),
+            csr => bus.write_csr(csr, value).ok_or(ILLEGAL)?,
+        }
+        Ok(())
+    }
+
+    fn load(&mut self, bus: &mut impl Bus, address: u32, width: Width) -> Result<u32> {
+        if address % width as u32 != 0 {
+            return Err((Cause::LoadAddressMisaligned, address));
+        }
+        bus.load(address, width)
+            .ok_or((Cause::LoadAccessFault, address))
+    }
+
+    fn store(&mut self, bus: &mut impl Bus, address: u32, width: Width, value: u32) -> Result<()> {
+        if address % width as u32 != 0 {
+            return Err((Cause::StoreAddressMisaligned, address));
+        }
+        if self.reservation == Some(address & !0b11) {
+            self.reservation = None;
+        }
+        bus.store(address, width, value)
+            .ok_or((Cause::StoreAccessFault, address))
+    }
+}
+
+// - tests ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Memory {
+        bytes: Vec<u8>,
+        irq: bool,
+    }
+
+    impl Memory {
+        fn with_program(program: &[u8]) -> Self {
+            let mut bytes = vec![0; 0x1000];
+            bytes[..program.len()].copy_from_slice(program);
+            Self { bytes, irq: false }
+        }
+    }
+
+    impl Bus for Memory {
+        fn load(&mut self, address: u32, width: Width) -> Option<u32> {
+            let address = address as usize;
+            let bytes = self.bytes.get(address..address + width as usize)?;
+            Some(
+                bytes
+                    .iter()
+                    .rev()
+                    .fold(0, |value, byte| (value << 8) | u32::from(*byte)),
+            )
+        }
+
+        fn store(&mut self, address: u32, width: Width, value: u32) -> Option<()> {
+            let address = address as usize;
+            let bytes = self.bytes.get_mut(address..address + width as usize)?;
+            bytes.copy_from_slice(&value.to_le_bytes()[..width as usize]);
+            Some(())
+        }
+
+        fn external_interrupt(&self) -> bool {
+            self.irq
+        }
+    }
+
+    fn run(hart: &mut Hart, memory: &mut Memory, steps: usize) {
+        for _ in 0..steps {
+            assert_eq!(hart.step(memory), Step::Executed);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        #[rustfmt::skip]
+        let mut memory = Memory::with_program(&[
+            0xfd, 0x50,             // li   ra, -1
+            0x13, 0xd1, 0x10, 0x00, // srli sp, ra, 1
+            0x93, 0xd1, 0x10, 0x40, // srai gp, ra, 1
+            0x33, 0x82, 0x30, 0x02, // mul  tp, ra, gp
+            0xb3, 0x42, 0x40, 0x02, // div  t0, zero, tp
+            0x33, 0x43, 0x00, 0x02, // div  t1, zero, zero
+        ]);
+        let mut hart = Hart::new(0);
+        run(&mut hart, &mut memory, 6);
+
+        assert_eq!(hart.x[1], 0xffff_ffff);
+        assert_eq!(hart.x[2], 0x7fff_ffff);
+        assert_eq!(hart.x[3], 0xffff_ffff);
+        assert_eq!(hart.x[4], 1);
+        assert_eq!(hart.x[5], 0);
+        assert_eq!(hart.x[6], 0xffff_ffff);
+    }
+
+    #[test]
+    fn test_load_store() {
+        #[rustfmt::skip]
+        let mut memory = Memory::with_program(&[
+            0x93, 0x00, 0x00, 0x80, // li  ra, -2048
+            0x13, 0x01, 0x00, 0x10, // li  sp, 256
+            0x06, 0xc0,             // sw  ra, 0(sp)
+            0x83, 0x01, 0x21, 0x00, // lb  gp, 2(sp)
+            0x03, 0x42, 0x21, 0x00, // lbu tp, 2(sp)
+            0x02, 0x85,             // jr  a0
+        ]);
+        let mut hart = Hart::new(0);
+        run(&mut hart, &mut memory, 6);
+
+        assert_eq!(&memory.bytes[0x100..0x104], &[0x00, 0xf8, 0xff, 0xff]);
+        assert_eq!(hart.x[3], 0xffff_ffff);
+        assert_eq!(hart.x[4], 0xff);
+        assert_eq!(hart.pc, 0);
+    }
+
+    #[test]
+    fn test_exception() {
+        #[rustfmt::skip]
+        let mut memory = Memory::with_program(&[
+            0x93, 0x00, 0x00, 0x10, // li   ra, 256
+            0x73, 0x90, 0x50, 0x30, // csrw mtvec, ra
+            0x00, 0x00,             // illegal
+        ]);
+        let mut hart = Hart::new(0);
+        run(&mut hart, &mut memory, 2);
+
+        assert_eq!(
+            hart.step(&mut memory),
+            Step::Exception(Exception {
+                cause: Cause::IllegalInstruction,
+                pc: 8,
+                tval: 0,
+            })
+        );
+        assert_eq!(hart.pc, 0x100);
+        assert_eq!(hart.mepc, 8);
+    }
+
+    #[test]
+    fn test_external_interrupt() {
+        #[rustfmt::skip]
+        let mut memory = Memory::with_program(&[
+            0x93, 0x00, 0x00, 0x20, // li    ra, 512
+            0x73, 0x90, 0x50, 0x30, // csrw  mtvec, ra
+            0x85, 0x60,             // lui   ra, 1
+            0x93, 0x80, 0x00, 0x80, // addi  ra, ra, -2048
+            0x73, 0x90, 0x40, 0x30, // csrw  mie, ra
+            0x73, 0x60, 0x04, 0x30, // csrsi mstatus, 8
+            0x73, 0x00, 0x50, 0x10, // wfi
+        ]);
+        memory.bytes[0x200..0x204].copy_from_slice(&[0x13, 0x00, 0x00, 0x00]); // nop
+        let mut hart = Hart::new(0);
+        run(&mut hart, &mut memory, 7);
+        assert!(hart.is_waiting());
+        assert_eq!(hart.step(&mut memory), Step::Waiting);
+
+        memory.irq = true;
+        assert_eq!(hart.step(&mut memory), Step::Executed);
+        assert_eq!(hart.mcause, Cause::MachineExternalInterrupt);
+        assert_eq!(hart.mepc, 26);
+        assert_eq!(hart.pc, 0x204);
+        assert!(!hart.interrupts_enabled());
+    }
+}
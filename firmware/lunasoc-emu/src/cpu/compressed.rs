@@ -0,0 +1,244 @@
+//! Expansion of `C` extension instructions
+//!
+//! Each 16-bit instruction is expanded into the equivalent 32-bit
+//! instruction before execution. Reserved and floating-point encodings
+//! expand to `0`, which is an illegal instruction.
+
+const ILLEGAL: u32 = 0;
+
+const OP_IMM: u32 = 0x13;
+const OP: u32 = 0x33;
+const LUI: u32 = 0x37;
+const LOAD: u32 = 0x03;
+const STORE: u32 = 0x23;
+const BRANCH: u32 = 0x63;
+const JAL: u32 = 0x6f;
+const JALR: u32 = 0x67;
+
+fn bit(value: u32, bit: u32) -> u32 {
+    (value >> bit) & 1
+}
+
+fn bits(value: u32, high: u32, low: u32) -> u32 {
+    (value >> low) & ((1 << (high - low + 1)) - 1)
+}
+
+/// Sign extend the low `width` bits of `value`.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn sext(value: u32, width: u32) -> u32 {
+    let shift = 32 - width;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+fn i_type(imm: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+fn s_type(imm: u32, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> u32 {
+    (bits(imm, 11, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 0) << 7)
+        | opcode
+}
+
+fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OP
+}
+
+fn b_type(imm: u32, rs1: u32, funct3: u32) -> u32 {
+    (bit(imm, 12) << 31)
+        | (bits(imm, 10, 5) << 25)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 1) << 8)
+        | (bit(imm, 11) << 7)
+        | BRANCH
+}
+
+fn j_type(imm: u32, rd: u32) -> u32 {
+    (bit(imm, 20) << 31)
+        | (bits(imm, 10, 1) << 21)
+        | (bit(imm, 11) << 20)
+        | (bits(imm, 19, 12) << 12)
+        | (rd << 7)
+        | JAL
+}
+
+/// Expand a compressed instruction into its 32-bit equivalent.
+#[allow(clippy::too_many_lines)]
+pub fn expand(instruction: u16) -> u32 {
+    let c = u32::from(instruction);
+    let funct3 = bits(c, 15, 13);
+
+    // registers
+    let rd = bits(c, 11, 7);
+    let rs2 = bits(c, 6, 2);
+    let rd_ = bits(c, 4, 2) + 8;
+    let rs1_ = bits(c, 9, 7) + 8;
+
+    // immediates
+    let imm6 = sext((bit(c, 12) << 5) | bits(c, 6, 2), 6);
+    let uimm_w = (bits(c, 12, 10) << 3) | (bit(c, 6) << 2) | (bit(c, 5) << 6);
+    let imm_j = sext(
+        (bit(c, 12) << 11)
+            | (bit(c, 11) << 4)
+            | (bits(c, 10, 9) << 8)
+            | (bit(c, 8) << 10)
+            | (bit(c, 7) << 6)
+            | (bit(c, 6) << 7)
+            | (bits(c, 5, 3) << 1)
+            | (bit(c, 2) << 5),
+        12,
+    );
+    let imm_b = sext(
+        (bit(c, 12) << 8)
+            | (bits(c, 11, 10) << 3)
+            | (bits(c, 6, 5) << 6)
+            | (bits(c, 4, 3) << 1)
+            | (bit(c, 2) << 5),
+        9,
+    );
+
+    match (c & 0b11, funct3) {
+        // - quadrant 0 --
+
+        // C.ADDI4SPN
+        (0b00, 0b000) => {
+            let nzuimm = (bits(c, 12, 11) << 4)
+                | (bits(c, 10, 7) << 6)
+                | (bit(c, 6) << 2)
+                | (bit(c, 5) << 3);
+            if nzuimm == 0 {
+                return ILLEGAL;
+            }
+            i_type(nzuimm, 2, 0b000, rd_, OP_IMM)
+        }
+        // C.LW
+        (0b00, 0b010) => i_type(uimm_w, rs1_, 0b010, rd_, LOAD),
+        // C.SW
+        (0b00, 0b110) => s_type(uimm_w, rd_, rs1_, 0b010, STORE),
+
+        // - quadrant 1 --
+
+        // C.ADDI, C.NOP
+        (0b01, 0b000) => i_type(imm6, rd, 0b000, rd, OP_IMM),
+        // C.JAL
+        (0b01, 0b001) => j_type(imm_j, 1),
+        // C.LI
+        (0b01, 0b010) => i_type(imm6, 0, 0b000, rd, OP_IMM),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == 2 => {
+            let nzimm = sext(
+                (bit(c, 12) << 9)
+                    | (bit(c, 6) << 4)
+                    | (bit(c, 5) << 6)
+                    | (bits(c, 4, 3) << 7)
+                    | (bit(c, 2) << 5),
+                10,
+            );
+            if nzimm == 0 {
+                return ILLEGAL;
+            }
+            i_type(nzimm, 2, 0b000, 2, OP_IMM)
+        }
+        // C.LUI
+        (0b01, 0b011) => {
+            if imm6 == 0 {
+                return ILLEGAL;
+            }
+            (imm6 << 12) | (rd << 7) | LUI
+        }
+        // C.SRLI, C.SRAI, C.ANDI, C.SUB, C.XOR, C.OR, C.AND
+        (0b01, 0b100) => match (bits(c, 11, 10), bit(c, 12), bits(c, 6, 5)) {
+            (0b00, 0, _) => i_type(rs2, rs1_, 0b101, rs1_, OP_IMM),
+            (0b01, 0, _) => i_type(0x400 | rs2, rs1_, 0b101, rs1_, OP_IMM),
+            (0b10, _, _) => i_type(imm6, rs1_, 0b111, rs1_, OP_IMM),
+            (0b11, 0, 0b00) => r_type(0x20, rd_, rs1_, 0b000, rs1_),
+            (0b11, 0, 0b01) => r_type(0x00, rd_, rs1_, 0b100, rs1_),
+            (0b11, 0, 0b10) => r_type(0x00, rd_, rs1_, 0b110, rs1_),
+            (0b11, 0, 0b11) => r_type(0x00, rd_, rs1_, 0b111, rs1_),
+            _ => ILLEGAL,
+        },
+        // C.J
+        (0b01, 0b101) => j_type(imm_j, 0),
+        // C.BEQZ
+        (0b01, 0b110) => b_type(imm_b, rs1_, 0b000),
+        // C.BNEZ
+        (0b01, 0b111) => b_type(imm_b, rs1_, 0b001),
+
+        // - quadrant 2 --
+
+        // C.SLLI
+        (0b10, 0b000) if bit(c, 12) == 0 => i_type(rs2, rd, 0b001, rd, OP_IMM),
+        // C.LWSP
+        (0b10, 0b010) if rd != 0 => {
+            let uimm = (bit(c, 12) << 5) | (bits(c, 6, 4) << 2) | (bits(c, 3, 2) << 6);
+            i_type(uimm, 2, 0b010, rd, LOAD)
+        }
+        // C.JR, C.MV, C.EBREAK, C.JALR, C.ADD
+        (0b10, 0b100) => match (bit(c, 12), rd, rs2) {
+            (0, 0, 0) => ILLEGAL,
+            (0, rs1, 0) => i_type(0, rs1, 0b000, 0, JALR),
+            (0, rd, rs2) => r_type(0x00, rs2, 0, 0b000, rd),
+            (1, 0, 0) => 0x0010_0073,
+            (1, rs1, 0) => i_type(0, rs1, 0b000, 1, JALR),
+            (_, rd, rs2) => r_type(0x00, rs2, rd, 0b000, rd),
+        },
+        // C.SWSP
+        (0b10, 0b110) => {
+            let uimm = (bits(c, 12, 9) << 2) | (bits(c, 8, 7) << 6);
+            s_type(uimm, rs2, 2, 0b010, STORE)
+        }
+
+        _ => ILLEGAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let vectors = [
+            (0x0028, 0x0081_0513), // addi a0, sp, 8
+            (0x414c, 0x0045_2583), // lw a1, 4(a0)
+            (0xc14c, 0x00b5_2223), // sw a1, 4(a0)
+            (0x1575, 0xffd5_0513), // addi a0, a0, -3
+            (0x3fc5, 0xff1f_f0ef), // jal ra, -16
+            (0x4515, 0x0050_0513), // addi a0, zero, 5
+            (0x7139, 0xfc01_0113), // addi sp, sp, -64
+            (0x75fd, 0xffff_f5b7), // lui a1, 0xfffff
+            (0x810d, 0x0035_5513), // srli a0, a0, 3
+            (0x850d, 0x4035_5513), // srai a0, a0, 3
+            (0x997d, 0xfff5_7513), // andi a0, a0, -1
+            (0x8d0d, 0x40b5_0533), // sub a0, a0, a1
+            (0x8d2d, 0x00b5_4533), // xor a0, a0, a1
+            (0x8d4d, 0x00b5_6533), // or a0, a0, a1
+            (0x8d6d, 0x00b5_7533), // and a0, a0, a1
+            (0xaffd, 0x7fe0_006f), // jal zero, 2046
+            (0xd101, 0xf005_00e3), // beq a0, zero, -256
+            (0xed7d, 0x0e05_1f63), // bne a0, zero, 254
+            (0x057e, 0x01f5_1513), // slli a0, a0, 31
+            (0x55fe, 0x0fc1_2583), // lw a1, 252(sp)
+            (0x8502, 0x0005_0067), // jalr zero, 0(a0)
+            (0x852e, 0x00b0_0533), // add a0, zero, a1
+            (0x9002, 0x0010_0073), // ebreak
+            (0x9502, 0x0005_00e7), // jalr ra, 0(a0)
+            (0x952e, 0x00b5_0533), // add a0, a0, a1
+            (0xdfae, 0x0eb1_2e23), // sw a1, 252(sp)
+        ];
+        for (compressed, expanded) in vectors {
+            assert_eq!(expand(compressed), expanded, "expanding {compressed:#06x}");
+        }
+    }
+
+    #[test]
+    fn test_illegal() {
+        assert_eq!(expand(0x0000), ILLEGAL);
+        assert_eq!(expand(0x2000), ILLEGAL); // c.fld
+        assert_eq!(expand(0x6101), ILLEGAL); // c.addi16sp with nzimm = 0
+    }
+}
@@ -0,0 +1,172 @@
+//! Minimal ELF loader
+//!
+//! Only 32-bit little-endian RISC-V executables are supported. Loadable
+//! segments are placed at their physical address so initialised data is
+//! found where the `riscv-rt` startup code expects to copy it from.
+
+/// ELF loader error type
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The file is not an ELF file.
+    InvalidMagic,
+    /// The file is not a 32-bit little-endian RISC-V executable.
+    Unsupported,
+    /// The file is truncated or a header points outside the file.
+    Truncated,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidMagic => write!(f, "not an ELF file"),
+            Error::Unsupported => write!(f, "not a 32-bit little-endian RISC-V executable"),
+            Error::Truncated => write!(f, "truncated ELF file"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A loadable segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment<'a> {
+    /// Physical address of the segment.
+    pub address: u32,
+    /// Contents of the segment.
+    pub data: &'a [u8],
+    /// Size of the segment in memory, any bytes beyond `data` are zero.
+    pub size: u32,
+}
+
+/// A symbol from the symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub value: u32,
+}
+
+/// A parsed ELF executable.
+#[derive(Debug, Clone)]
+pub struct Elf<'a> {
+    /// Entry point.
+    pub entry: u32,
+    /// Loadable segments.
+    pub segments: Vec<Segment<'a>>,
+    /// Named symbols, empty if the executable has been stripped.
+    pub symbols: Vec<Symbol<'a>>,
+}
+
+const ELFCLASS32: u8 = 1;
+const ELFDATA2LSB: u8 = 1;
+const ET_EXEC: u16 = 2;
+const EM_RISCV: u16 = 243;
+const PT_LOAD: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SYMBOL_SIZE: usize = 16;
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, Error> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or(Error::Truncated)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, Error> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Error::Truncated)
+}
+
+impl<'a> Elf<'a> {
+    /// Parse the given ELF file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not a valid RISC-V executable.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        if bytes.get(0..4) != Some(b"\x7fELF") {
+            return Err(Error::InvalidMagic);
+        }
+        if bytes.get(4) != Some(&ELFCLASS32)
+            || bytes.get(5) != Some(&ELFDATA2LSB)
+            || u16_at(bytes, 16)? != ET_EXEC
+            || u16_at(bytes, 18)? != EM_RISCV
+        {
+            return Err(Error::Unsupported);
+        }
+
+        let entry = u32_at(bytes, 24)?;
+        let phoff = u32_at(bytes, 28)? as usize;
+        let phentsize = usize::from(u16_at(bytes, 42)?);
+        let phnum = usize::from(u16_at(bytes, 44)?);
+
+        let mut segments = Vec::new();
+        for index in 0..phnum {
+            let header = phoff + index * phentsize;
+            if u32_at(bytes, header)? != PT_LOAD {
+                continue;
+            }
+            let offset = u32_at(bytes, header + 4)? as usize;
+            let address = u32_at(bytes, header + 12)?;
+            let filesz = u32_at(bytes, header + 16)? as usize;
+            let size = u32_at(bytes, header + 20)?;
+            let data = bytes.get(offset..offset + filesz).ok_or(Error::Truncated)?;
+            segments.push(Segment {
+                address,
+                data,
+                size,
+            });
+        }
+
+        Ok(Self {
+            entry,
+            segments,
+            symbols: symbols(bytes)?,
+        })
+    }
+
+    /// Returns the value of the symbol with the given name.
+    pub fn symbol(&self, name: &str) -> Option<u32> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .map(|symbol| symbol.value)
+    }
+}
+
+fn symbols(bytes: &[u8]) -> Result<Vec<Symbol<'_>>, Error> {
+    let shoff = u32_at(bytes, 32)? as usize;
+    let shentsize = usize::from(u16_at(bytes, 46)?);
+    let shnum = usize::from(u16_at(bytes, 48)?);
+    let section = |index: usize| shoff + index * shentsize;
+
+    let mut symbols = Vec::new();
+    for index in 0..shnum {
+        let header = section(index);
+        if u32_at(bytes, header + 4)? != SHT_SYMTAB {
+            continue;
+        }
+        let offset = u32_at(bytes, header + 16)? as usize;
+        let size = u32_at(bytes, header + 20)? as usize;
+        let strtab = section(u32_at(bytes, header + 24)? as usize);
+        let names_offset = u32_at(bytes, strtab + 16)? as usize;
+        let names_size = u32_at(bytes, strtab + 20)? as usize;
+        let names = bytes
+            .get(names_offset..names_offset + names_size)
+            .ok_or(Error::Truncated)?;
+
+        for symbol in (offset..offset + size).step_by(SYMBOL_SIZE) {
+            let name = names
+                .get(u32_at(bytes, symbol)? as usize..)
+                .and_then(|name| name.split(|&byte| byte == 0).next())
+                .and_then(|name| core::str::from_utf8(name).ok())
+                .ok_or(Error::Truncated)?;
+            if !name.is_empty() {
+                let value = u32_at(bytes, symbol + 4)?;
+                symbols.push(Symbol { name, value });
+            }
+        }
+    }
+    Ok(symbols)
+}
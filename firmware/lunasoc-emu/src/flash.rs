@@ -0,0 +1,311 @@
+//! SPI NOR flash model
+//!
+//! The flash is shared between the `SPI0` controller, where it responds
+//! to the JEDEC commands used by `lunasoc_hal::spiflash`, and the SoC's
+//! memory-mapped flash window which firmware is executed from.
+//!
+//! Program and erase operations complete immediately.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lunasoc_hal::sim::spi::Device;
+use lunasoc_hal::spiflash::{Command, Status};
+
+/// Default flash capacity, 16 MiB.
+pub const DEFAULT_CAPACITY: usize = 16 * 1024 * 1024;
+
+/// Default 64-bit unique ID.
+pub const DEFAULT_UNIQUE_ID: [u8; 8] = [0xe4, 0x63, 0x4c, 0x27, 0x1b, 0x5e, 0x2a, 0x2d];
+
+/// Page size in bytes.
+const PAGE_SIZE: usize = 256;
+
+/// Winbond
+const MANUFACTURER_ID: u8 = 0xef;
+const MEMORY_TYPE: u8 = 0x40;
+
+#[derive(Debug)]
+struct State {
+    memory: Vec<u8>,
+    unique_id: [u8; 8],
+    write_enable_latch: bool,
+    /// Bytes received since chip select was asserted.
+    transaction: Vec<u8>,
+}
+
+/// A handle to a simulated SPI NOR flash device.
+///
+/// Clones of the handle refer to the same device.
+#[derive(Clone, Debug)]
+pub struct Flash {
+    state: Rc<RefCell<State>>,
+}
+
+impl Default for Flash {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Flash {
+    /// Returns an erased flash device of the given capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is not a power of two.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity.is_power_of_two(),
+            "flash capacity must be a power of two"
+        );
+        Self {
+            state: Rc::new(RefCell::new(State {
+                memory: vec![0xff; capacity],
+                unique_id: DEFAULT_UNIQUE_ID,
+                write_enable_latch: false,
+                transaction: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns the capacity of the device in bytes.
+    pub fn capacity(&self) -> usize {
+        self.state.borrow().memory.len()
+    }
+
+    /// Set the device's unique ID.
+    pub fn set_unique_id(&self, unique_id: [u8; 8]) {
+        self.state.borrow_mut().unique_id = unique_id;
+    }
+
+    /// Read `buffer.len()` bytes starting at `offset`.
+    ///
+    /// Bytes beyond the end of the device read as `0xff`.
+    pub fn read(&self, offset: usize, buffer: &mut [u8]) {
+        let state = self.state.borrow();
+        for (index, byte) in buffer.iter_mut().enumerate() {
+            *byte = state.memory.get(offset + index).copied().unwrap_or(0xff);
+        }
+    }
+
+    /// Write `data` starting at `offset`, bypassing the SPI interface.
+    ///
+    /// Returns `false` if the data does not fit in the device.
+    pub fn write(&self, offset: usize, data: &[u8]) -> bool {
+        let mut state = self.state.borrow_mut();
+        match state.memory.get_mut(offset..offset + data.len()) {
+            Some(memory) => {
+                memory.copy_from_slice(data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the contents of the device.
+    pub fn contents(&self) -> Vec<u8> {
+        self.state.borrow().memory.clone()
+    }
+}
+
+// - SPI interface ------------------------------------------------------------
+
+impl State {
+    fn address(&self) -> usize {
+        let t = &self.transaction;
+        (usize::from(t[1]) << 16) | (usize::from(t[2]) << 8) | usize::from(t[3])
+    }
+
+    fn status(&self) -> u8 {
+        if self.write_enable_latch {
+            Status::WriteEnableLatch
+        } else {
+            0
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn jedec_id(&self) -> [u8; 3] {
+        let capacity = self.memory.len().trailing_zeros() as u8;
+        [MANUFACTURER_ID, MEMORY_TYPE, capacity]
+    }
+
+    /// Serial Flash Discoverable Parameters with a Basic Flash Parameter Table.
+    #[allow(clippy::cast_possible_truncation)]
+    fn sfdp(&self) -> Vec<u8> {
+        let density = (self.memory.len() * 8 - 1) as u32;
+        let erase_types = [
+            (u32::from(Command::BlockErase32K) << 24)
+                | (15 << 16)
+                | (u32::from(Command::SectorErase) << 8)
+                | 12,
+            (u32::from(Command::BlockErase64K) << 8) | 16,
+        ];
+        let mut table = vec![
+            b'S', b'F', b'D', b'P', 0x06, 0x01, 0x00, 0xff, // header
+            0x00, 0x06, 0x01, 11, 0x10, 0x00, 0x00, 0xff, // basic flash parameter header
+        ];
+        let dwords = [
+            0xfff1_20e5,
+            density,
+            0,
+            0,
+            0,
+            0,
+            0,
+            erase_types[0],
+            erase_types[1],
+            0,
+            PAGE_SIZE.trailing_zeros() << 4,
+        ];
+        for dword in dwords {
+            table.extend(dword.to_le_bytes());
+        }
+        table
+    }
+
+    fn respond(&mut self) -> u8 {
+        let index = self.transaction.len() - 1;
+        let byte_at = |bytes: &[u8], index: usize| bytes.get(index).copied().unwrap_or(0xff);
+
+        match self.transaction[0] {
+            Command::ReadJedecId if index >= 1 => byte_at(&self.jedec_id(), index - 1),
+            Command::ReadStatus1 if index >= 1 => self.status(),
+            Command::ReadUniqueId if index >= 5 => byte_at(&self.unique_id, index - 5),
+            Command::Read if index >= 4 => {
+                let address = self.address() + index - 4;
+                byte_at(&self.memory, address % self.memory.len())
+            }
+            Command::FastRead if index >= 5 => {
+                let address = self.address() + index - 5;
+                byte_at(&self.memory, address % self.memory.len())
+            }
+            Command::ReadSfdp if index >= 5 => byte_at(&self.sfdp(), self.address() + index - 5),
+            _ => 0xff,
+        }
+    }
+
+    fn execute(&mut self) {
+        let Some(&opcode) = self.transaction.first() else {
+            return;
+        };
+        match opcode {
+            Command::WriteEnable => self.write_enable_latch = true,
+            0x04 => self.write_enable_latch = false,
+            Command::PageProgram if self.write_enable_latch && self.transaction.len() > 4 => {
+                let address = self.address() % self.memory.len();
+                let page = address & !(PAGE_SIZE - 1);
+                for (index, byte) in self.transaction[4..].iter().enumerate() {
+                    // programming can only clear bits and wraps within the page
+                    let offset = page + (address + index) % PAGE_SIZE;
+                    self.memory[offset] &= byte;
+                }
+                self.write_enable_latch = false;
+            }
+            Command::SectorErase | Command::BlockErase32K | Command::BlockErase64K
+                if self.write_enable_latch && self.transaction.len() >= 4 =>
+            {
+                let size = match opcode {
+                    Command::SectorErase => 4 * 1024,
+                    Command::BlockErase32K => 32 * 1024,
+                    _ => 64 * 1024,
+                };
+                let start = (self.address() % self.memory.len()) & !(size - 1);
+                self.memory[start..start + size].fill(0xff);
+                self.write_enable_latch = false;
+            }
+            Command::ChipErase | 0x60 if self.write_enable_latch => {
+                self.memory.fill(0xff);
+                self.write_enable_latch = false;
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Device for Flash {
+    fn select(&mut self) {
+        self.state.borrow_mut().transaction.clear();
+    }
+
+    fn deselect(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.execute();
+        state.transaction.clear();
+    }
+
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        let mut state = self.state.borrow_mut();
+        state.transaction.push(mosi);
+        state.respond()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(flash: &mut Flash, command: &[u8], response: usize) -> Vec<u8> {
+        flash.select();
+        for byte in command {
+            flash.exchange(*byte);
+        }
+        let bytes = (0..response).map(|_| flash.exchange(0)).collect();
+        flash.deselect();
+        bytes
+    }
+
+    #[test]
+    fn test_identification() {
+        let mut flash = Flash::new(4 * 1024 * 1024);
+        assert_eq!(
+            transfer(&mut flash, &[Command::ReadJedecId], 3),
+            [0xef, 0x40, 22]
+        );
+        assert_eq!(
+            transfer(&mut flash, &[Command::ReadUniqueId, 0, 0, 0, 0], 8),
+            DEFAULT_UNIQUE_ID
+        );
+        assert_eq!(
+            transfer(&mut flash, &[Command::ReadSfdp, 0, 0, 0, 0], 4),
+            b"SFDP"
+        );
+    }
+
+    #[test]
+    fn test_program_erase() {
+        let mut flash = Flash::new(64 * 1024);
+
+        // program without write enable is ignored
+        transfer(&mut flash, &[Command::PageProgram, 0, 0x10, 0x00, 0x12], 0);
+        assert_eq!(
+            transfer(&mut flash, &[Command::Read, 0, 0x10, 0x00], 1),
+            [0xff]
+        );
+
+        transfer(&mut flash, &[Command::WriteEnable], 0);
+        assert_eq!(
+            transfer(&mut flash, &[Command::ReadStatus1], 1),
+            [Status::WriteEnableLatch]
+        );
+        transfer(
+            &mut flash,
+            &[Command::PageProgram, 0, 0x10, 0x00, 0x12, 0x34],
+            0,
+        );
+        assert_eq!(transfer(&mut flash, &[Command::ReadStatus1], 1), [0]);
+
+        let mut buffer = [0; 3];
+        flash.read(0x1000, &mut buffer);
+        assert_eq!(buffer, [0x12, 0x34, 0xff]);
+
+        transfer(&mut flash, &[Command::WriteEnable], 0);
+        transfer(&mut flash, &[Command::SectorErase, 0, 0x10, 0x80], 0);
+        flash.read(0x1000, &mut buffer);
+        assert_eq!(buffer, [0xff; 3]);
+    }
+}
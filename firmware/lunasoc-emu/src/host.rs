@@ -0,0 +1,297 @@
+//! USB host
+//!
+//! Drives the host side of the SoC's USB ports, performing control
+//! transfers against the running firmware.
+
+use lunasoc_hal::sim::usb::{Eptri, Handshake};
+use smolusb::device::Speed;
+use smolusb::setup::{Direction, SetupPacket};
+
+use crate::machine::{self, Machine};
+use crate::soc::Soc;
+
+/// `libgreat` vendor request used to execute GCP commands.
+pub const LIBGREAT_REQUEST_NUMBER: u8 = 0x65;
+/// Maximum size of a GCP response.
+pub const LIBGREAT_MAX_RESPONSE_SIZE: u16 = 1024;
+
+/// System clock frequency of the SoC.
+const CLOCK_FREQUENCY: u64 = 60_000_000;
+
+/// Clock cycles the machine runs between retries of a NAK'd transaction.
+const RETRY_INTERVAL: u64 = 256;
+
+/// Time allowed for a device to settle after it connects, 100ms.
+const CONNECT_DEBOUNCE: u64 = CLOCK_FREQUENCY / 10;
+
+/// Time allowed for a device to recover from a bus reset, 10ms.
+const RESET_RECOVERY: u64 = CLOCK_FREQUENCY / 100;
+
+/// The Cynthion USB ports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Port {
+    /// `USB0`
+    Target,
+    /// `USB1`
+    Aux,
+    /// `USB2`
+    Control,
+}
+
+impl Port {
+    /// Returns the port's controller.
+    pub fn controller(self, soc: &mut Soc) -> &mut Eptri {
+        match self {
+            Port::Target => &mut soc.usb0,
+            Port::Aux => &mut soc.usb1,
+            Port::Control => &mut soc.usb2,
+        }
+    }
+}
+
+/// USB host error type
+#[derive(Debug)]
+pub enum Error {
+    /// The device stalled the transfer.
+    Stall,
+    /// The device did not respond in time.
+    Timeout,
+    /// The machine stopped.
+    Machine(machine::Error),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Stall => write!(f, "device stalled the transfer"),
+            Error::Timeout => write!(f, "device did not respond"),
+            Error::Machine(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<machine::Error> for Error {
+    fn from(error: machine::Error) -> Self {
+        Error::Machine(error)
+    }
+}
+
+/// A USB host connected to one of the SoC's ports.
+#[derive(Clone, Debug)]
+pub struct UsbHost {
+    pub port: Port,
+    /// Maximum packet size of the device's control endpoint.
+    pub max_packet_size: usize,
+    /// Clock cycles to wait for the device before giving up.
+    pub timeout: u64,
+}
+
+impl UsbHost {
+    /// Returns a host for the given port with a timeout of one second.
+    #[must_use]
+    pub fn new(port: Port) -> Self {
+        Self {
+            port,
+            max_packet_size: 64,
+            timeout: CLOCK_FREQUENCY,
+        }
+    }
+
+    /// Wait for the device to connect and settle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if the device does not connect.
+    pub fn wait_connect(&self, machine: &mut Machine) -> Result<(), Error> {
+        let port = self.port;
+        if !machine.run_until(self.timeout, |soc| port.controller(soc).is_connected())? {
+            return Err(Error::Timeout);
+        }
+        machine.run(CONNECT_DEBOUNCE)?;
+        Ok(())
+    }
+
+    /// Reset the bus and wait for the device to recover.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if the device is not connected.
+    pub fn bus_reset(&self, machine: &mut Machine) -> Result<(), Error> {
+        handshake(self.port.controller(&mut machine.soc).host_bus_reset())?;
+        machine.run(RESET_RECOVERY)?;
+        Ok(())
+    }
+
+    /// Perform a control transfer on endpoint `0`.
+    ///
+    /// For device to host transfers returns up to `setup.length` bytes
+    /// of data, otherwise `data` is sent to the device and an empty
+    /// vector returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device stalls or does not respond.
+    pub fn control(
+        &self,
+        machine: &mut Machine,
+        setup: SetupPacket,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let packet = SetupPacket::as_bytes(setup);
+        handshake(self.port.controller(&mut machine.soc).host_setup(0, packet))?;
+
+        let length = usize::from(setup.length);
+        let mut received = Vec::new();
+
+        match setup.direction() {
+            Direction::DeviceToHost => {
+                while received.len() < length {
+                    let packet = self.retry(machine, |usb| usb.host_in(0))?;
+                    let short = packet.len() < self.max_packet_size;
+                    received.extend(packet);
+                    if short {
+                        break;
+                    }
+                }
+                received.truncate(length);
+                self.retry(machine, |usb| handshake(usb.host_out(0, &[])))?;
+            }
+            Direction::HostToDevice => {
+                let data = &data[..length.min(data.len())];
+                for chunk in data.chunks(self.max_packet_size) {
+                    self.retry(machine, |usb| handshake(usb.host_out(0, chunk)))?;
+                }
+                self.retry(machine, |usb| usb.host_in(0))?;
+            }
+        }
+
+        Ok(received)
+    }
+
+    /// Perform a bulk OUT transfer.
+    ///
+    /// `data` is split into packets of the maximum packet size for the
+    /// bus speed. An empty transfer is sent as a zero length packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device stalls or does not respond.
+    pub fn bulk_out(
+        &self,
+        machine: &mut Machine,
+        endpoint_number: u8,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let max_packet_size = self.bulk_max_packet_size(machine);
+        if data.is_empty() {
+            self.retry(machine, |usb| handshake(usb.host_out(endpoint_number, &[])))?;
+        }
+        for chunk in data.chunks(max_packet_size) {
+            self.retry(machine, |usb| {
+                handshake(usb.host_out(endpoint_number, chunk))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Perform a bulk IN transfer, returning the data received up to
+    /// and including the first short packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device stalls or does not respond.
+    pub fn bulk_in(&self, machine: &mut Machine, endpoint_number: u8) -> Result<Vec<u8>, Error> {
+        let max_packet_size = self.bulk_max_packet_size(machine);
+        let mut received = Vec::new();
+        loop {
+            let packet = self.retry(machine, |usb| usb.host_in(endpoint_number))?;
+            let short = packet.len() < max_packet_size;
+            received.extend(packet);
+            if short {
+                return Ok(received);
+            }
+        }
+    }
+
+    /// Execute a GCP command and return its response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Stall`] if the firmware failed to execute the
+    /// command.
+    pub fn gcp(
+        &self,
+        machine: &mut Machine,
+        class: u32,
+        verb: u32,
+        arguments: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let mut command = Vec::with_capacity(8 + arguments.len());
+        command.extend(class.to_le_bytes());
+        command.extend(verb.to_le_bytes());
+        command.extend(arguments);
+
+        let setup = SetupPacket {
+            request_type: 0x40,
+            request: LIBGREAT_REQUEST_NUMBER,
+            value: 0,
+            index: 0,
+            length: command.len() as u16,
+        };
+        self.control(machine, setup, &command)?;
+
+        let setup = SetupPacket {
+            request_type: 0xc0,
+            request: LIBGREAT_REQUEST_NUMBER,
+            value: 0,
+            index: 0,
+            length: LIBGREAT_MAX_RESPONSE_SIZE,
+        };
+        self.control(machine, setup, &[])
+    }
+
+    /// Returns the maximum bulk packet size for the bus speed.
+    fn bulk_max_packet_size(&self, machine: &mut Machine) -> usize {
+        match self.port.controller(&mut machine.soc).speed() {
+            Speed::High => 512,
+            _ => 64,
+        }
+    }
+
+    /// Retry a transaction, running the machine while the device NAKs.
+    fn retry<T>(
+        &self,
+        machine: &mut Machine,
+        mut transaction: impl FnMut(&mut Eptri) -> Result<T, Handshake>,
+    ) -> Result<T, Error> {
+        let deadline = machine.cycles() + self.timeout;
+        loop {
+            match transaction(self.port.controller(&mut machine.soc)) {
+                Ok(value) => return Ok(value),
+                Err(Handshake::Nak) if machine.cycles() < deadline => {
+                    machine.run(RETRY_INTERVAL)?;
+                }
+                Err(Handshake::Stall) => return Err(Error::Stall),
+                Err(_) => return Err(Error::Timeout),
+            }
+        }
+    }
+}
+
+fn handshake(handshake: Handshake) -> Result<(), Handshake> {
+    match handshake {
+        Handshake::Ack => Ok(()),
+        other => Err(other),
+    }
+}
+
+impl From<Handshake> for Error {
+    fn from(handshake: Handshake) -> Self {
+        match handshake {
+            Handshake::Stall => Error::Stall,
+            _ => Error::Timeout,
+        }
+    }
+}
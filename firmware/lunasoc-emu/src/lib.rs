@@ -0,0 +1,19 @@
+//! Emulator for running Moondancer firmware images on the host.
+//!
+//! A [`machine::Machine`] combines an RV32IMAC [`cpu::Hart`] with a
+//! model of the Moondancer SoC built from the `lunasoc_hal::sim`
+//! peripherals. The USB ports are driven by a [`host::UsbHost`], either
+//! directly or from a [`script`].
+
+#![allow(clippy::must_use_candidate)]
+
+// modules
+pub mod cpu;
+pub mod elf;
+pub mod flash;
+pub mod host;
+pub mod machine;
+pub mod script;
+pub mod soc;
+
+pub use machine::{Config, Machine};
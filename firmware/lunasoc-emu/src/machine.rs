@@ -0,0 +1,251 @@
+//! A Moondancer SoC with a hart attached
+
+use std::io::Write;
+
+use crate::cpu::{Exception, Hart, Step};
+use crate::elf::{self, Elf};
+use crate::flash::{self, Flash};
+use crate::soc::Soc;
+
+/// Clock cycles skipped at a time while the hart waits for an interrupt.
+const WAIT_QUANTUM: u64 = 64;
+
+/// Machine configuration.
+pub struct Config {
+    /// Hardware revision reported by the `INFO` peripheral as `(major, minor)`.
+    pub revision: (u8, u8),
+    /// Capacity of the SPI flash in bytes, must be a power of two.
+    pub flash_capacity: usize,
+    /// Initial contents of the SPI flash, starting at offset `0`.
+    pub flash_image: Option<Vec<u8>>,
+    /// Unique ID of the SPI flash.
+    pub unique_id: [u8; 8],
+    /// Receives the bytes transmitted by `UART`.
+    pub uart: Box<dyn Write>,
+    /// Receives the bytes transmitted by `UART1`.
+    pub uart1: Box<dyn Write>,
+    /// Stop the machine when the hart raises an exception.
+    pub halt_on_exception: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            revision: (1, 4),
+            flash_capacity: flash::DEFAULT_CAPACITY,
+            flash_image: None,
+            unique_id: flash::DEFAULT_UNIQUE_ID,
+            uart: Box::new(std::io::stdout()),
+            uart1: Box::new(std::io::stdout()),
+            halt_on_exception: true,
+        }
+    }
+}
+
+/// Machine error type
+#[derive(Debug)]
+pub enum Error {
+    /// The firmware image could not be parsed.
+    Elf(elf::Error),
+    /// A segment of the firmware image does not fit in RAM or flash.
+    Load { address: u32 },
+    /// The flash image is larger than the flash.
+    FlashImage,
+    /// The hart raised an exception.
+    Exception(Exception),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Elf(error) => write!(f, "invalid firmware image: {error}"),
+            Error::Load { address } => {
+                write!(f, "segment at {address:#010x} does not fit in memory")
+            }
+            Error::FlashImage => write!(f, "flash image is larger than the flash"),
+            Error::Exception(exception) => write!(f, "{exception}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<elf::Error> for Error {
+    fn from(error: elf::Error) -> Self {
+        Error::Elf(error)
+    }
+}
+
+/// The emulated machine.
+pub struct Machine {
+    pub hart: Hart,
+    pub soc: Soc,
+    uart: Box<dyn Write>,
+    uart1: Box<dyn Write>,
+    halt_on_exception: bool,
+    /// Top of the stack, if the firmware image defines `_stack_start`.
+    stack_start: Option<u32>,
+    /// Lowest stack pointer seen since the firmware image was loaded.
+    stack_low: u32,
+}
+
+impl Machine {
+    /// Returns a machine in its reset state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flash image does not fit in the flash.
+    pub fn new(config: Config) -> Result<Self, Error> {
+        let flash = Flash::new(config.flash_capacity);
+        flash.set_unique_id(config.unique_id);
+        if let Some(image) = &config.flash_image {
+            if !flash.write(0, image) {
+                return Err(Error::FlashImage);
+            }
+        }
+
+        let mut soc = Soc::new(flash);
+        soc.info.revision = config.revision;
+
+        Ok(Self {
+            hart: Hart::default(),
+            soc,
+            uart: config.uart,
+            uart1: config.uart1,
+            halt_on_exception: config.halt_on_exception,
+            stack_start: None,
+            stack_low: u32::MAX,
+        })
+    }
+
+    /// Load a firmware image and reset the hart to its entry point.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image is invalid or does not fit in memory.
+    pub fn load_elf(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let elf = Elf::parse(bytes)?;
+        for segment in &elf.segments {
+            if !self.soc.load(segment.address, segment.data) {
+                return Err(Error::Load {
+                    address: segment.address,
+                });
+            }
+            let filesz = segment.data.len() as u32;
+            if segment.size > filesz {
+                let zeros = vec![0; (segment.size - filesz) as usize];
+                let address = segment.address + filesz;
+                if !self.soc.load(address, &zeros) {
+                    return Err(Error::Load { address });
+                }
+            }
+        }
+        self.hart = Hart::new(elf.entry);
+        self.stack_start = elf.symbol("_stack_start");
+        self.stack_low = u32::MAX;
+        Ok(())
+    }
+
+    /// Returns the clock cycles elapsed since reset.
+    pub fn cycles(&self) -> u64 {
+        self.hart.cycle
+    }
+
+    /// Returns the most stack the firmware has used since it was loaded,
+    /// or `None` if the firmware image does not define `_stack_start`.
+    pub fn stack_usage(&self) -> Option<u32> {
+        let stack_start = self.stack_start?;
+        Some(stack_start.saturating_sub(self.stack_low))
+    }
+
+    /// Execute a single instruction.
+    ///
+    /// While the hart is waiting for an interrupt the machine skips ahead
+    /// until one becomes pending.
+    ///
+    /// # Errors
+    ///
+    /// Returns the exception raised by the hart if the machine is
+    /// configured to halt on exceptions.
+    pub fn step(&mut self) -> Result<(), Error> {
+        match self.hart.step(&mut self.soc) {
+            Step::Executed => {
+                // start tracking once the startup code has set up the stack
+                let sp = self.hart.x[2];
+                if self.stack_low != u32::MAX || Some(sp) == self.stack_start {
+                    self.stack_low = self.stack_low.min(sp);
+                }
+                if self.soc.tick(1) {
+                    self.drain_consoles();
+                }
+            }
+            Step::Waiting => {
+                self.hart.cycle += WAIT_QUANTUM - 1;
+                self.soc.tick(WAIT_QUANTUM);
+                self.soc.sync();
+                self.drain_consoles();
+            }
+            Step::Exception(exception) => {
+                self.soc.tick(1);
+                if self.halt_on_exception {
+                    self.drain_consoles();
+                    return Err(Error::Exception(exception));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the machine for the given number of clock cycles.
+    ///
+    /// # Errors
+    ///
+    /// See [`Machine::step`].
+    pub fn run(&mut self, cycles: u64) -> Result<(), Error> {
+        let deadline = self.hart.cycle + cycles;
+        while self.hart.cycle < deadline {
+            self.step()?;
+        }
+        self.drain_consoles();
+        Ok(())
+    }
+
+    /// Run the machine until `condition` returns `true` or `timeout`
+    /// clock cycles have elapsed.
+    ///
+    /// Returns `false` on timeout.
+    ///
+    /// # Errors
+    ///
+    /// See [`Machine::step`].
+    pub fn run_until(
+        &mut self,
+        timeout: u64,
+        mut condition: impl FnMut(&mut Soc) -> bool,
+    ) -> Result<bool, Error> {
+        let deadline = self.hart.cycle + timeout;
+        while self.hart.cycle < deadline {
+            self.step()?;
+            if condition(&mut self.soc) {
+                self.drain_consoles();
+                return Ok(true);
+            }
+        }
+        self.drain_consoles();
+        Ok(false)
+    }
+
+    fn drain_consoles(&mut self) {
+        // console output is best-effort, a closed pipe should not stop the machine
+        for (uart, console) in [
+            (&mut self.soc.uart, &mut self.uart),
+            (&mut self.soc.uart1, &mut self.uart1),
+        ] {
+            let bytes = uart.host_read();
+            if !bytes.is_empty() {
+                let _ = console.write_all(&bytes);
+                let _ = console.flush();
+            }
+        }
+    }
+}
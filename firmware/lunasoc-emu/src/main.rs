@@ -0,0 +1,98 @@
+//! Run a Moondancer firmware image.
+//!
+//! ```text
+//! lunasoc-emu [--flash FILE] [--script FILE] [--cycles N] FIRMWARE.elf
+//! ```
+//!
+//! Without a script the machine runs for `--cycles` clock cycles, or
+//! until it is interrupted. With a script the machine stops once the
+//! script completes.
+
+use std::process::ExitCode;
+
+use lunasoc_emu::{script, Config, Machine};
+
+const USAGE: &str = "usage: lunasoc-emu [--flash FILE] [--script FILE] [--cycles N] FIRMWARE.elf";
+
+struct Arguments {
+    firmware: String,
+    flash: Option<String>,
+    script: Option<String>,
+    cycles: Option<u64>,
+}
+
+fn parse_arguments() -> Result<Arguments, String> {
+    let mut firmware = None;
+    let mut flash = None;
+    let mut script = None;
+    let mut cycles = None;
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .ok_or(format!("missing value for {argument}"))
+        };
+        match argument.as_str() {
+            "--flash" => flash = Some(value()?),
+            "--script" => script = Some(value()?),
+            "--cycles" => {
+                let value = value()?;
+                cycles = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid cycles '{value}'"))?,
+                );
+            }
+            "-h" | "--help" => return Err(USAGE.into()),
+            _ if firmware.is_none() && !argument.starts_with('-') => firmware = Some(argument),
+            _ => return Err(format!("unexpected argument '{argument}'\n{USAGE}")),
+        }
+    }
+
+    Ok(Arguments {
+        firmware: firmware.ok_or(USAGE)?,
+        flash,
+        script,
+        cycles,
+    })
+}
+
+fn run(arguments: Arguments) -> Result<(), Box<dyn std::error::Error>> {
+    let read = |path: &str| std::fs::read(path).map_err(|e| format!("{path}: {e}"));
+
+    let config = Config {
+        flash_image: arguments.flash.as_deref().map(read).transpose()?,
+        ..Config::default()
+    };
+    let mut machine = Machine::new(config)?;
+    machine.load_elf(&read(&arguments.firmware)?)?;
+
+    if let Some(path) = &arguments.script {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        script::run(&mut machine, &text)?;
+    } else {
+        machine.run(arguments.cycles.unwrap_or(u64::MAX))?;
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let arguments = match parse_arguments() {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(arguments) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("lunasoc-emu: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
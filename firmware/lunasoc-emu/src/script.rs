@@ -0,0 +1,251 @@
+//! USB host scripts
+//!
+//! Scripts drive the [`UsbHost`] from a text file, one command per line.
+//! Numbers may be given in decimal or as `0x` prefixed hex and byte
+//! strings as hex without separators. Everything after a `#` is ignored.
+//!
+//! * `run <cycles>` runs the machine;
+//! * `connect <port>` waits for the device to connect;
+//! * `reset <port>` resets the bus;
+//! * `control <port> <type> <request> <value> <index> <length> [data]`
+//!   performs a control transfer;
+//! * `gcp <class> <verb> [arguments]` executes a GCP command on the
+//!   Control port;
+//! * `bulk-out <port> <endpoint> [data]` performs a bulk OUT transfer;
+//! * `bulk-in <port> <endpoint>` performs a bulk IN transfer;
+//! * `expect [data]` compares the response to the last transfer, which
+//!   must be empty if no data is given;
+//! * `expect-stall` checks the last transfer was stalled.
+//!
+//! Ports are named `target`, `aux` or `control`.
+
+use smolusb::setup::SetupPacket;
+
+use crate::host::{self, Port, UsbHost};
+use crate::machine::Machine;
+
+/// Script error type
+#[derive(Debug)]
+pub enum Error {
+    /// The line could not be parsed.
+    Syntax { line: usize, message: String },
+    /// A command failed.
+    Host { line: usize, error: host::Error },
+    /// A response did not match the expected value.
+    Mismatch {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            Error::Host { line, error } => write!(f, "line {line}: {error}"),
+            Error::Mismatch {
+                line,
+                expected,
+                actual,
+            } => write!(f, "line {line}: expected {expected}, got {actual}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Result of the previous transfer.
+type Response = Result<Vec<u8>, host::Error>;
+
+/// Run a script against the machine, printing each response.
+///
+/// # Errors
+///
+/// Returns the first error encountered.
+pub fn run(machine: &mut Machine, script: &str) -> Result<(), Error> {
+    let mut last: Option<Response> = None;
+
+    for (index, text) in script.lines().enumerate() {
+        let line = index + 1;
+        let text = text.split('#').next().unwrap_or_default();
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let Some((&command, arguments)) = words.split_first() else {
+            continue;
+        };
+        let syntax = |message: String| Error::Syntax { line, message };
+        let host_error = |error: host::Error| Error::Host { line, error };
+
+        match (command, arguments) {
+            ("run", [cycles]) => {
+                let cycles = number(cycles).map_err(syntax)?;
+                machine
+                    .run(cycles)
+                    .map_err(|e| host_error(host::Error::Machine(e)))?;
+            }
+            ("connect", [port]) => {
+                let host = UsbHost::new(parse_port(port).map_err(syntax)?);
+                host.wait_connect(machine).map_err(host_error)?;
+            }
+            ("reset", [port]) => {
+                let host = UsbHost::new(parse_port(port).map_err(syntax)?);
+                host.bus_reset(machine).map_err(host_error)?;
+            }
+            ("control", [port, request_type, request, value, index, length, data @ ..])
+                if data.len() <= 1 =>
+            {
+                let host = UsbHost::new(parse_port(port).map_err(syntax)?);
+                let setup = SetupPacket {
+                    request_type: number(request_type).map_err(syntax)?,
+                    request: number(request).map_err(syntax)?,
+                    value: number(value).map_err(syntax)?,
+                    index: number(index).map_err(syntax)?,
+                    length: number(length).map_err(syntax)?,
+                };
+                let data = match data {
+                    [data] => bytes(data).map_err(syntax)?,
+                    _ => Vec::new(),
+                };
+                last = Some(report(line, host.control(machine, setup, &data))?);
+            }
+            ("gcp", [class, verb, arguments @ ..]) if arguments.len() <= 1 => {
+                let host = UsbHost::new(Port::Control);
+                let class = number(class).map_err(syntax)?;
+                let verb = number(verb).map_err(syntax)?;
+                let arguments = match arguments {
+                    [arguments] => bytes(arguments).map_err(syntax)?,
+                    _ => Vec::new(),
+                };
+                last = Some(report(line, host.gcp(machine, class, verb, &arguments))?);
+            }
+            ("bulk-out", [port, endpoint, data @ ..]) if data.len() <= 1 => {
+                let host = UsbHost::new(parse_port(port).map_err(syntax)?);
+                let endpoint = number(endpoint).map_err(syntax)?;
+                let data = match data {
+                    [data] => bytes(data).map_err(syntax)?,
+                    _ => Vec::new(),
+                };
+                let response = host.bulk_out(machine, endpoint, &data).map(|()| Vec::new());
+                last = Some(report(line, response)?);
+            }
+            ("bulk-in", [port, endpoint]) => {
+                let host = UsbHost::new(parse_port(port).map_err(syntax)?);
+                let endpoint = number(endpoint).map_err(syntax)?;
+                last = Some(report(line, host.bulk_in(machine, endpoint))?);
+            }
+            ("expect", expected) if expected.len() <= 1 => {
+                let expected = match expected {
+                    [expected] => bytes(expected).map_err(syntax)?,
+                    _ => Vec::new(),
+                };
+                match last.take() {
+                    Some(Ok(actual)) if actual == expected => (),
+                    Some(Ok(actual)) => {
+                        return Err(Error::Mismatch {
+                            line,
+                            expected: hex(&expected),
+                            actual: hex(&actual),
+                        })
+                    }
+                    Some(Err(error)) => return Err(host_error(error)),
+                    None => return Err(syntax("no response to compare".into())),
+                }
+            }
+            ("expect-stall", []) => match last.take() {
+                Some(Err(host::Error::Stall)) => (),
+                Some(Ok(actual)) => {
+                    return Err(Error::Mismatch {
+                        line,
+                        expected: "stall".into(),
+                        actual: hex(&actual),
+                    })
+                }
+                Some(Err(error)) => return Err(host_error(error)),
+                None => return Err(syntax("no response to compare".into())),
+            },
+            _ => return Err(syntax(format!("invalid command '{}'", text.trim()))),
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the response to a transfer.
+///
+/// Stalls are returned for checking by `expect-stall`, any other failure
+/// stops the script.
+fn report(line: usize, response: Response) -> Result<Response, Error> {
+    match response {
+        Ok(data) => {
+            println!("<< {}", hex(&data));
+            Ok(Ok(data))
+        }
+        Err(host::Error::Stall) => {
+            println!("<< stall");
+            Ok(Err(host::Error::Stall))
+        }
+        Err(error) => Err(Error::Host { line, error }),
+    }
+}
+
+fn parse_port(word: &str) -> Result<Port, String> {
+    match word {
+        "target" => Ok(Port::Target),
+        "aux" => Ok(Port::Aux),
+        "control" => Ok(Port::Control),
+        _ => Err(format!("invalid port '{word}'")),
+    }
+}
+
+fn number<T: TryFrom<u64>>(word: &str) -> Result<T, String> {
+    let value = match word.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => word.parse(),
+    };
+    value
+        .ok()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| format!("invalid number '{word}'"))
+}
+
+fn bytes(word: &str) -> Result<Vec<u8>, String> {
+    if word.len() % 2 != 0 {
+        return Err(format!("invalid byte string '{word}'"));
+    }
+    (0..word.len())
+        .step_by(2)
+        .map(|i| {
+            word.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("invalid byte string '{word}'"))
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(number::<u8>("0x65"), Ok(0x65));
+        assert_eq!(number::<u16>("1024"), Ok(1024));
+        assert!(number::<u8>("256").is_err());
+        assert_eq!(bytes("00ff10"), Ok(vec![0x00, 0xff, 0x10]));
+        assert!(bytes("0").is_err());
+        assert_eq!(parse_port("control"), Ok(Port::Control));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let mut machine = Machine::new(crate::machine::Config::default()).unwrap();
+        let result = run(&mut machine, "# comment\n\nfrobnicate 1 2\n");
+        assert!(matches!(result, Err(Error::Syntax { line: 3, .. })));
+    }
+}
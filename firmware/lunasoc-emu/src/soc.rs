@@ -0,0 +1,354 @@
+//! The Moondancer SoC
+//!
+//! Implements the memory map of `moondancer-pac` using the peripheral
+//! models from `lunasoc_hal::sim` along with models of the SoC's
+//! `LEDS`, `ADVERTISER` and `INFO` peripherals.
+
+use lunasoc_hal::sim::gpio::Gpio;
+use lunasoc_hal::sim::spi::Spi;
+use lunasoc_hal::sim::timer::Timer;
+use lunasoc_hal::sim::uart::Uart;
+use lunasoc_hal::sim::usb::Eptri;
+use lunasoc_hal::sim::Peripheral;
+
+use crate::cpu::{Bus, Width};
+use crate::flash::Flash;
+
+// - memory map ---------------------------------------------------------------
+
+/// Base address of the memory-mapped SPI flash.
+pub const SPIFLASH_BASE: u32 = 0x1000_0000;
+/// Base address of main RAM.
+pub const MAINRAM_BASE: u32 = 0x4000_0000;
+/// Size of main RAM in bytes.
+pub const MAINRAM_SIZE: usize = 0x0001_0000;
+
+/// Firmware images are located at this offset in the SPI flash.
+pub const FIRMWARE_OFFSET: usize = 0x000b_0000;
+
+/// Clock cycles between updates of the peripherals.
+///
+/// Peripherals are always brought up to date before their registers
+/// are accessed.
+const TICK_QUANTUM: u64 = 16;
+
+/// Machine IRQ Mask and Pending CSRs for each of the supported softcores.
+mod csr {
+    pub const VEXRISCV_MIM: u16 = 0xbc0;
+    pub const VEXRISCV_MIP: u16 = 0xfc0;
+    pub const MINERVA_MIM: u16 = 0x330;
+    pub const MINERVA_MIP: u16 = 0x360;
+}
+
+// - peripherals --------------------------------------------------------------
+
+/// Model of the `LEDS` peripheral.
+#[derive(Debug, Default)]
+pub struct Leds {
+    output: u8,
+}
+
+impl Leds {
+    /// Returns the state of the LEDs, one bit per LED.
+    pub fn output(&self) -> u8 {
+        self.output
+    }
+}
+
+impl Peripheral for Leds {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.output.into(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write(&mut self, offset: usize, value: u32) {
+        if offset == 0x00 {
+            self.output = (value & 0x3f) as u8;
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        0
+    }
+}
+
+/// Model of the `ADVERTISER` peripheral.
+///
+/// While enabled Apollo releases the Control port to the SoC.
+#[derive(Debug, Default)]
+pub struct Advertiser {
+    enable: bool,
+}
+
+impl Advertiser {
+    /// Returns `true` if the advertiser is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enable
+    }
+}
+
+impl Peripheral for Advertiser {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.enable.into(),
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: usize, value: u32) {
+        if offset == 0x00 {
+            self.enable = value & 1 != 0;
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        0
+    }
+}
+
+/// Model of the `INFO` peripheral.
+#[derive(Debug)]
+pub struct Info {
+    /// Hardware revision as `(major, minor)`.
+    pub revision: (u8, u8),
+}
+
+impl Default for Info {
+    fn default() -> Self {
+        Self { revision: (1, 4) }
+    }
+}
+
+impl Peripheral for Info {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.revision.0.into(),
+            0x04 => self.revision.1.into(),
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, _offset: usize, _value: u32) {}
+
+    fn irq(&self) -> u32 {
+        0
+    }
+}
+
+// - Soc ----------------------------------------------------------------------
+
+/// The SoC's memories and peripherals.
+pub struct Soc {
+    pub timer: Timer,
+    pub uart: Uart,
+    pub leds: Leds,
+    pub gpioa: Gpio,
+    pub gpiob: Gpio,
+    pub usb0: Eptri,
+    pub usb1: Eptri,
+    pub usb2: Eptri,
+    pub uart1: Uart,
+    pub advertiser: Advertiser,
+    pub info: Info,
+    pub spi0: Spi,
+    pub flash: Flash,
+    ram: Vec<u8>,
+    /// Machine IRQ Mask
+    mim: u32,
+    /// Clock cycles not yet applied to the peripherals.
+    pending: u64,
+}
+
+impl Soc {
+    /// Returns the SoC in its reset state with the given flash attached
+    /// to `SPI0`.
+    #[must_use]
+    pub fn new(flash: Flash) -> Self {
+        let mut spi0 = Spi::new();
+        spi0.attach(flash.clone());
+        Self {
+            timer: Timer::new(),
+            uart: Uart::new(),
+            leds: Leds::default(),
+            gpioa: Gpio::new(),
+            gpiob: Gpio::new(),
+            usb0: Eptri::new(),
+            usb1: Eptri::new(),
+            usb2: Eptri::new(),
+            uart1: Uart::new(),
+            advertiser: Advertiser::default(),
+            info: Info::default(),
+            spi0,
+            flash,
+            ram: vec![0; MAINRAM_SIZE],
+            mim: 0,
+            pending: 0,
+        }
+    }
+
+    /// Returns the contents of the Machine IRQ Pending register.
+    ///
+    /// Like the hardware register only unmasked interrupts are pending.
+    pub fn mip(&self) -> u32 {
+        let lines = self.timer.irq()
+            | self.uart.irq() << 1
+            | self.gpioa.irq() << 2
+            | self.gpiob.irq() << 3
+            | self.usb0.irq() << 4
+            | self.usb1.irq() << 8
+            | self.usb2.irq() << 12
+            | self.uart1.irq() << 16;
+        lines & self.mim
+    }
+
+    /// Advance the peripherals by the given number of clock cycles.
+    ///
+    /// Returns `true` if the peripherals were updated.
+    pub fn tick(&mut self, cycles: u64) -> bool {
+        self.pending += cycles;
+        if self.pending < TICK_QUANTUM {
+            return false;
+        }
+        self.sync();
+        true
+    }
+
+    /// Apply any outstanding clock cycles to the peripherals.
+    pub fn sync(&mut self) {
+        let cycles = core::mem::take(&mut self.pending);
+        if cycles == 0 {
+            return;
+        }
+        for peripheral in self.peripherals() {
+            peripheral.tick(cycles);
+        }
+    }
+
+    /// Copy `data` into memory at `address`, flash is writable.
+    ///
+    /// Returns `false` if the data does not fit in RAM or flash.
+    pub fn load(&mut self, address: u32, data: &[u8]) -> bool {
+        if let Some(range) = Self::ram_range(address, data.len()) {
+            self.ram[range].copy_from_slice(data);
+            true
+        } else if let Some(offset) = address.checked_sub(SPIFLASH_BASE) {
+            self.flash.write(offset as usize, data)
+        } else {
+            false
+        }
+    }
+
+    fn ram_range(address: u32, length: usize) -> Option<core::ops::Range<usize>> {
+        let offset = address.checked_sub(MAINRAM_BASE)? as usize;
+        let end = offset.checked_add(length)?;
+        (end <= MAINRAM_SIZE).then_some(offset..end)
+    }
+
+    fn decode(&mut self, address: u32) -> Option<(&mut dyn Peripheral, usize)> {
+        let (peripheral, base): (&mut dyn Peripheral, u32) = match address {
+            0xf000_0100..=0xf000_01ff => (&mut self.timer, 0xf000_0100),
+            0xf000_0200..=0xf000_02ff => (&mut self.uart, 0xf000_0200),
+            0xf000_1000..=0xf000_10ff => (&mut self.leds, 0xf000_1000),
+            0xf000_2000..=0xf000_20ff => (&mut self.gpioa, 0xf000_2000),
+            0xf000_2100..=0xf000_21ff => (&mut self.gpiob, 0xf000_2100),
+            0xf000_3000..=0xf000_31ff => (&mut self.usb0, 0xf000_3000),
+            0xf000_4000..=0xf000_41ff => (&mut self.usb1, 0xf000_4000),
+            0xf000_5000..=0xf000_51ff => (&mut self.usb2, 0xf000_5000),
+            0xf000_6000..=0xf000_60ff => (&mut self.uart1, 0xf000_6000),
+            0xf000_7000..=0xf000_70ff => (&mut self.advertiser, 0xf000_7000),
+            0xf000_7100..=0xf000_71ff => (&mut self.info, 0xf000_7100),
+            0xf000_8000..=0xf000_80ff => (&mut self.spi0, 0xf000_8000),
+            _ => return None,
+        };
+        Some((peripheral, (address - base) as usize))
+    }
+
+    fn peripherals(&mut self) -> [&mut dyn Peripheral; 12] {
+        [
+            &mut self.timer,
+            &mut self.uart,
+            &mut self.leds,
+            &mut self.gpioa,
+            &mut self.gpiob,
+            &mut self.usb0,
+            &mut self.usb1,
+            &mut self.usb2,
+            &mut self.uart1,
+            &mut self.advertiser,
+            &mut self.info,
+            &mut self.spi0,
+        ]
+    }
+}
+
+fn mask(width: Width) -> u32 {
+    match width {
+        Width::Byte => 0xff,
+        Width::Half => 0xffff,
+        Width::Word => 0xffff_ffff,
+    }
+}
+
+impl Bus for Soc {
+    fn load(&mut self, address: u32, width: Width) -> Option<u32> {
+        let length = width as usize;
+        if let Some(range) = Self::ram_range(address, length) {
+            let mut bytes = [0; 4];
+            bytes[..length].copy_from_slice(&self.ram[range]);
+            return Some(u32::from_le_bytes(bytes));
+        }
+        if let Some(offset) = address.checked_sub(SPIFLASH_BASE) {
+            let offset = offset as usize;
+            if offset + length <= self.flash.capacity() {
+                let mut bytes = [0; 4];
+                self.flash.read(offset, &mut bytes[..length]);
+                return Some(u32::from_le_bytes(bytes));
+            }
+        }
+
+        self.sync();
+        let (peripheral, offset) = self.decode(address)?;
+        Some(peripheral.read(offset) & mask(width))
+    }
+
+    fn store(&mut self, address: u32, width: Width, value: u32) -> Option<()> {
+        let length = width as usize;
+        if let Some(range) = Self::ram_range(address, length) {
+            self.ram[range].copy_from_slice(&value.to_le_bytes()[..length]);
+            return Some(());
+        }
+
+        self.sync();
+        let (peripheral, offset) = self.decode(address)?;
+        peripheral.write(offset, value & mask(width));
+        Some(())
+    }
+
+    fn read_csr(&mut self, csr: u16) -> Option<u32> {
+        match csr {
+            csr::VEXRISCV_MIM | csr::MINERVA_MIM => Some(self.mim),
+            csr::VEXRISCV_MIP | csr::MINERVA_MIP => {
+                self.sync();
+                Some(self.mip())
+            }
+            _ => None,
+        }
+    }
+
+    fn write_csr(&mut self, csr: u16, value: u32) -> Option<()> {
+        match csr {
+            csr::VEXRISCV_MIM | csr::MINERVA_MIM => {
+                self.mim = value;
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn external_interrupt(&self) -> bool {
+        self.mip() != 0
+    }
+}
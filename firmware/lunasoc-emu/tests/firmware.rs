@@ -0,0 +1,106 @@
+//! Boot the Moondancer firmware and exercise it over GCP.
+//!
+//! These tests need a firmware image built for the SoC, run them with
+//! `make test-emu` or set `MOONDANCER_FIRMWARE` to the path of an image
+//! and pass `--ignored`.
+
+use std::path::PathBuf;
+
+use lunasoc_emu::{script, Config, Machine};
+
+/// Most stack the firmware may use while handling the scripts below.
+///
+/// The firmware has no stack guard, so running out of stack silently
+/// corrupts its statics instead of faulting.
+const STACK_BUDGET: u32 = 16 * 1024;
+
+fn firmware() -> Vec<u8> {
+    let path = std::env::var_os("MOONDANCER_FIRMWARE").map_or_else(
+        || {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../target/riscv32imac-unknown-none-elf/release/moondancer")
+        },
+        PathBuf::from,
+    );
+    std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}, build the firmware with `make build` first",
+            path.display()
+        )
+    })
+}
+
+fn run(text: &str) {
+    let mut machine = Machine::new(Config::default()).unwrap();
+    machine.load_elf(&firmware()).unwrap();
+    if let Err(error) = script::run(&mut machine, text) {
+        panic!("{error}");
+    }
+
+    let stack_usage = machine
+        .stack_usage()
+        .expect("firmware image defines `_stack_start`");
+    assert!(
+        stack_usage <= STACK_BUDGET,
+        "firmware used {stack_usage} bytes of stack, more than its budget of {STACK_BUDGET} bytes"
+    );
+}
+
+#[test]
+#[ignore = "requires a firmware image, run with `make test-emu`"]
+fn test_gcp_control() {
+    run("
+        connect control
+        reset control
+        control control 0x80 6 0x0100 0 18   # device descriptor
+        gcp 0 0                              # core: read_board_id
+        expect 10000000
+        gcp 0 4                              # core: get_available_classes
+        expect 00000000010000001100000020010000
+        gcp 0 7 000000000700000001000000     # core: get_verb_descriptor
+        expect 3c49494900
+        gcp 1 0                              # firmware: initialize
+        expect 0001000000002000
+        gcp 0x11 0x10 00000000               # selftest: test_error_return_code(0)
+        expect 6f6b
+        gcp 0x1234 0                         # unknown class
+        expect-stall
+    ");
+}
+
+#[test]
+#[ignore = "requires a firmware image, run with `make test-emu`"]
+fn test_gcp_control_chunked() {
+    run("
+        connect control
+        reset control
+        control control 0x40 0x65 0 0 8 0000000000000000   # core: read_board_id
+        control control 0xc0 0x65 0 0 2
+        expect 1000
+        control control 0xc0 0x65 0 0 2
+        expect 0000
+        control control 0xc0 0x65 0 0 2                     # terminated with a ZLP
+        expect
+        gcp 0 0                                             # next command
+        expect 10000000
+        control control 0xc0 0x65 0 0 2                     # response was consumed
+        expect-stall
+    ");
+}
+
+#[test]
+#[ignore = "requires a firmware image, run with `make test-emu`"]
+fn test_gcp_bulk() {
+    run("
+        connect control
+        reset control
+        bulk-out control 2 4701010000000000080000000000000000000000   # core: read_board_id
+        bulk-in control 1
+        expect 47810100000000000400000010000000
+        bulk-out control 2 470102000000000008000000ffffffff00000000   # unknown class
+        bulk-in control 1
+        expect 478102001600000000000000
+        gcp 0 0                                                       # control transport still works
+        expect 10000000
+    ");
+}
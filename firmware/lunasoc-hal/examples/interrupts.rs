@@ -9,6 +9,8 @@ use riscv_rt::entry;
 use lunasoc_hal as hal;
 use moondancer_pac as pac;
 
+use hal::shared::Shared;
+
 lunasoc_hal::impl_serial! {
     Serial: pac::UART,
 }
@@ -17,10 +19,16 @@ lunasoc_hal::impl_timer! {
     Timer: pac::TIMER,
 }
 
+// peripherals shared with the interrupt handler
+static SERIAL: Shared<Serial> = Shared::new();
+static TIMER: Shared<Timer> = Shared::new();
+static LEDS: Shared<(pac::LEDS, bool)> = Shared::new();
+
 #[entry]
 fn main() -> ! {
     let peripherals = pac::Peripherals::take().unwrap();
-    let mut serial = Serial::new(peripherals.UART);
+    SERIAL.init(Serial::new(peripherals.UART)).ok();
+    LEDS.init((peripherals.LEDS, true)).ok();
 
     // configure and enable timer
     let one_second = pac::clock::sysclk();
@@ -30,6 +38,7 @@ fn main() -> ! {
 
     // enable timer events
     timer.listen(hal::timer::Event::TimeOut);
+    TIMER.init(timer).ok();
 
     // enable interrupts
     unsafe {
@@ -43,11 +52,17 @@ fn main() -> ! {
         pac::csr::interrupt::enable(pac::Interrupt::TIMER)
     }
 
-    writeln!(serial, "Peripherals initialized, entering main loop.").unwrap();
+    SERIAL
+        .with(|serial| writeln!(serial, "Peripherals initialized, entering main loop."))
+        .unwrap()
+        .unwrap();
 
     let mut uptime = 1;
     loop {
-        writeln!(serial, "Uptime: {} seconds", uptime).unwrap();
+        SERIAL
+            .with(|serial| writeln!(serial, "Uptime: {} seconds", uptime))
+            .unwrap()
+            .unwrap();
 
         unsafe {
             riscv::asm::delay(pac::clock::sysclk());
@@ -60,27 +75,28 @@ fn main() -> ! {
 #[allow(non_snake_case)]
 #[no_mangle]
 fn MachineExternal() {
-    static mut TOGGLE: bool = true;
-
-    let mut serial = unsafe { Serial::summon() };
-
     if pac::csr::interrupt::is_pending(pac::Interrupt::TIMER) {
-        let timer = unsafe { Timer::summon() };
-        timer.clear_pending();
+        TIMER.with(|timer| timer.clear_pending()).unwrap();
 
-        writeln!(serial, "MachineExternal - timer interrupt").unwrap();
+        SERIAL
+            .with(|serial| writeln!(serial, "MachineExternal - timer interrupt"))
+            .unwrap()
+            .unwrap();
 
         // blinkenlights
-        let peripherals = unsafe { pac::Peripherals::steal() };
-        let leds = &peripherals.LEDS;
-
-        if unsafe { TOGGLE } {
-            leds.output().write(|w| unsafe { w.output().bits(255) });
-        } else {
-            leds.output().write(|w| unsafe { w.output().bits(0) });
-        }
-        unsafe { TOGGLE = !TOGGLE };
+        LEDS.with(|(leds, toggle)| {
+            if *toggle {
+                leds.output().write(|w| unsafe { w.output().bits(255) });
+            } else {
+                leds.output().write(|w| unsafe { w.output().bits(0) });
+            }
+            *toggle = !*toggle;
+        })
+        .unwrap();
     } else {
-        writeln!(serial, "MachineExternal - unknown interrupt").unwrap();
+        SERIAL
+            .with(|serial| writeln!(serial, "MachineExternal - unknown interrupt"))
+            .unwrap()
+            .unwrap();
     }
 }
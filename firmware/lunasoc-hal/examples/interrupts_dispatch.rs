@@ -0,0 +1,118 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+use panic_halt as _;
+use riscv_rt::entry;
+
+use lunasoc_hal as hal;
+use moondancer_pac::{self as pac, interrupt};
+
+use hal::hal_nb::serial::Read;
+use hal::serial::Event;
+use hal::shared::Shared;
+use pac::dispatch::Dispatcher;
+
+lunasoc_hal::impl_serial! {
+    Serial: pac::UART,
+}
+
+lunasoc_hal::impl_timer! {
+    Timer: pac::TIMER,
+}
+
+// peripherals shared with the interrupt handlers
+static SERIAL: Shared<Serial> = Shared::new();
+static TIMER: Shared<Timer> = Shared::new();
+static LEDS: Shared<(pac::LEDS, bool)> = Shared::new();
+
+// received characters are echoed even while the timer handler is running
+static DISPATCHER: Dispatcher<2> =
+    Dispatcher::new([pac::Interrupt::UART, pac::Interrupt::TIMER]).nested();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = pac::Peripherals::take().unwrap();
+    LEDS.init((peripherals.LEDS, true)).ok();
+
+    // configure serial and enable receive events
+    let mut serial = Serial::new(peripherals.UART);
+    serial.listen(Event::RxReady);
+    SERIAL.init(serial).ok();
+
+    // configure and enable timer
+    let one_second = pac::clock::sysclk();
+    let mut timer = Timer::new(peripherals.TIMER, one_second);
+    timer.set_timeout_ticks(one_second / 2);
+    timer.enable();
+
+    // enable timer events
+    timer.listen(hal::timer::Event::TimeOut);
+    TIMER.init(timer).ok();
+
+    // enable interrupts
+    unsafe {
+        // set mstatus register: interrupt enable
+        riscv::interrupt::enable();
+
+        // set mie register: machine external interrupts enable
+        riscv::register::mie::set_mext();
+
+        // write csr: enable timer and uart interrupts
+        pac::csr::interrupt::enable(pac::Interrupt::TIMER);
+        pac::csr::interrupt::enable(pac::Interrupt::UART);
+    }
+
+    SERIAL
+        .with(|serial| writeln!(serial, "Peripherals initialized, entering main loop."))
+        .unwrap()
+        .unwrap();
+
+    loop {
+        unsafe {
+            riscv::asm::wfi();
+        }
+    }
+}
+
+// - interrupt handlers -------------------------------------------------------
+
+#[allow(non_snake_case)]
+#[no_mangle]
+extern "C" fn MachineExternal() {
+    unsafe { DISPATCHER.dispatch() };
+}
+
+#[interrupt]
+fn TIMER() {
+    TIMER.with(|timer| timer.clear_pending()).unwrap();
+
+    SERIAL
+        .with(|serial| writeln!(serial, "TIMER interrupt"))
+        .unwrap()
+        .unwrap();
+
+    // blinkenlights
+    LEDS.with(|(leds, toggle)| {
+        if *toggle {
+            leds.output().write(|w| unsafe { w.output().bits(255) });
+        } else {
+            leds.output().write(|w| unsafe { w.output().bits(0) });
+        }
+        *toggle = !*toggle;
+    })
+    .unwrap();
+}
+
+#[interrupt]
+fn UART() {
+    SERIAL
+        .with(|serial| {
+            serial.clear_pending(Event::RxReady);
+            while let Ok(byte) = serial.read() {
+                hal::hal::serial::Write::write(serial, &[byte]).ok();
+            }
+        })
+        .unwrap();
+}
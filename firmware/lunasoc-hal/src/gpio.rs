@@ -1,64 +1,494 @@
+//! GPIO driver for luna-soc `GpioPeripheral` ports
+//!
+//! Ports are generated for each `pac::GPIOx` peripheral using the
+//! [`impl_gpio!`](crate::impl_gpio) macro and can be split into
+//! individually typed pins:
+//!
+//!     let gpioa = hal::GpioA::new(peripherals.GPIOA).split();
+//!
+//!     let mut led = gpioa.p7.into_output();
+//!     let mut button = gpioa.p0.into_input();
+//!
+//!     button.listen(Edge::Falling);
+//!     led.set_high()?;
+//!
+//! The peripheral raises a single event whenever any of its inputs
+//! change, edge detection is therefore performed in software by
+//! comparing the current port state with the state seen by the previous
+//! call to `Events::take_pending()`. Pulses shorter than the interrupt
+//! latency may be missed.
+
+use core::cell::UnsafeCell;
+
+/// Typestate for pins configured as inputs.
+#[derive(Debug)]
+pub struct Input;
+
+/// Typestate for pins configured as outputs.
+#[derive(Debug)]
+pub struct Output;
+
+/// Input pin edges that can trigger an interrupt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Both,
+}
+
+// - State --------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+struct Shadow {
+    /// Shadow copy of the write-only `odr` register.
+    odr: u8,
+    /// Pins listening for rising edges.
+    rising: u8,
+    /// Pins listening for falling edges.
+    falling: u8,
+    /// Input state seen by the previous call to `take_pending`.
+    last: u8,
+}
+
+/// Port state shared between the pins of a split port.
+pub struct State {
+    inner: UnsafeCell<Shadow>,
+}
+
+// Safety: all accesses happen inside `interrupt::free`
+unsafe impl Sync for State {}
+
+impl State {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: UnsafeCell::new(Shadow {
+                odr: 0,
+                rising: 0,
+                falling: 0,
+                last: 0,
+            }),
+        }
+    }
+
+    /// Update the output shadow register, returning the new value.
+    pub fn set_odr(&self, mask: u8, high: bool) -> u8 {
+        self.with(|state| {
+            if high {
+                state.odr |= mask;
+            } else {
+                state.odr &= !mask;
+            }
+            state.odr
+        })
+    }
+
+    /// Toggle the output shadow register, returning the new value.
+    pub fn toggle_odr(&self, mask: u8) -> u8 {
+        self.with(|state| {
+            state.odr ^= mask;
+            state.odr
+        })
+    }
+
+    /// Returns the output shadow register.
+    pub fn odr(&self) -> u8 {
+        self.with(|state| state.odr)
+    }
+
+    /// Configure edge detection for the given pins, returning `true` if
+    /// any pin of the port is still listening.
+    ///
+    /// `idr` is the current input state, used as the reference for the
+    /// next call to [`State::take_pending`].
+    pub fn listen(&self, mask: u8, edge: Option<Edge>, idr: u8) -> bool {
+        self.with(|state| {
+            state.rising &= !mask;
+            state.falling &= !mask;
+            match edge {
+                Some(Edge::Rising) => state.rising |= mask,
+                Some(Edge::Falling) => state.falling |= mask,
+                Some(Edge::Both) => {
+                    state.rising |= mask;
+                    state.falling |= mask;
+                }
+                None => (),
+            }
+            state.last = (state.last & !mask) | (idr & mask);
+            (state.rising | state.falling) != 0
+        })
+    }
+
+    /// Returns a bitmask of the pins that saw a configured edge since
+    /// the previous call, given the current input state `idr`.
+    pub fn take_pending(&self, idr: u8) -> u8 {
+        self.with(|state| {
+            let changed = state.last ^ idr;
+            state.last = idr;
+            (changed & idr & state.rising) | (changed & !idr & state.falling)
+        })
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Shadow) -> R) -> R {
+        crate::interrupt::free(|| f(unsafe { &mut *self.inner.get() }))
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - impl_gpio ----------------------------------------------------------------
+
+/// Macro to generate GPIO ports for `pac::GPIOx` peripherals
+///
+/// For example:
+///
+///     impl_gpio! {
+///         GpioA: gpioa, pac::GPIOA,
+///         GpioB: gpiob, pac::GPIOB,
+///     }
+///
+/// generates a `GpioA` port which can be split into the pins of
+/// module `gpioa`.
 #[macro_export]
 macro_rules! impl_gpio {
     ($(
-        $GPIOX:ident: $PACGPIOX:ty,
+        $GPIOX:ident: $gpiox:ident, $PACGPIOX:ty,
     )+) => {
         $(
+            pub mod $gpiox {
+                use super::*;
+
+                use core::marker::PhantomData;
+
+                use $crate::gpio::{Edge, Input, Output};
+
+                static STATE: $crate::gpio::State = $crate::gpio::State::new();
+
+                #[inline(always)]
+                fn registers() -> &'static <$PACGPIOX as core::ops::Deref>::Target {
+                    unsafe { &*<$PACGPIOX>::ptr() }
+                }
+
+                #[inline(always)]
+                fn idr() -> u8 {
+                    registers().idr().read().idr().bits()
+                }
+
+                /// The individual pins of a split port.
+                pub struct Parts {
+                    pub p0: Pin<0, Input>,
+                    pub p1: Pin<1, Input>,
+                    pub p2: Pin<2, Input>,
+                    pub p3: Pin<3, Input>,
+                    pub p4: Pin<4, Input>,
+                    pub p5: Pin<5, Input>,
+                    pub p6: Pin<6, Input>,
+                    pub p7: Pin<7, Input>,
+                    pub events: Events,
+                }
+
+                impl Parts {
+                    pub(super) fn new() -> Self {
+                        Self {
+                            p0: Pin::new(),
+                            p1: Pin::new(),
+                            p2: Pin::new(),
+                            p3: Pin::new(),
+                            p4: Pin::new(),
+                            p5: Pin::new(),
+                            p6: Pin::new(),
+                            p7: Pin::new(),
+                            events: Events { _private: () },
+                        }
+                    }
+                }
+
+                /// A single pin `I` of the port in mode `MODE`.
+                #[derive(Debug)]
+                pub struct Pin<const I: u8, MODE> {
+                    _mode: PhantomData<MODE>,
+                }
+
+                impl<const I: u8, MODE> Pin<I, MODE> {
+                    const MASK: u8 = 1 << I;
+
+                    fn new() -> Self {
+                        Self { _mode: PhantomData }
+                    }
+
+                    /// Pin number within the port.
+                    pub fn index(&self) -> u8 {
+                        I
+                    }
+
+                    /// Configure the pin as an input.
+                    pub fn into_input(self) -> Pin<I, Input> {
+                        $crate::interrupt::free(|| {
+                            registers().moder().modify(|r, w| unsafe {
+                                w.moder().bits(r.moder().bits() | Self::MASK)
+                            });
+                        });
+                        Pin::new()
+                    }
+
+                    /// Configure the pin as an output, retaining its last output state.
+                    pub fn into_output(self) -> Pin<I, Output> {
+                        let odr = STATE.odr();
+                        $crate::interrupt::free(|| {
+                            registers().odr().write(|w| unsafe { w.odr().bits(odr) });
+                            registers().moder().modify(|r, w| unsafe {
+                                w.moder().bits(r.moder().bits() & !Self::MASK)
+                            });
+                        });
+                        Pin::new()
+                    }
+
+                    /// Configure the pin as an output driving the given state.
+                    pub fn into_output_in_state(self, state: $crate::hal::digital::PinState) -> Pin<I, Output> {
+                        STATE.set_odr(Self::MASK, state == $crate::hal::digital::PinState::High);
+                        self.into_output()
+                    }
+                }
+
+                impl<const I: u8> Pin<I, Input> {
+                    /// Raise an event when the pin sees the given edge.
+                    pub fn listen(&mut self, edge: Edge) {
+                        let enable = STATE.listen(Self::MASK, Some(edge), idr());
+                        registers().ev_enable().write(|w| w.enable().bit(enable));
+                    }
+
+                    /// Stop raising events for the pin.
+                    pub fn unlisten(&mut self) {
+                        let enable = STATE.listen(Self::MASK, None, idr());
+                        registers().ev_enable().write(|w| w.enable().bit(enable));
+                    }
+
+                    fn is_high_(&self) -> bool {
+                        idr() & Self::MASK != 0
+                    }
+                }
+
+                impl<const I: u8> Pin<I, Output> {
+                    fn set_state_(&mut self, high: bool) {
+                        $crate::interrupt::free(|| {
+                            let odr = STATE.set_odr(Self::MASK, high);
+                            registers().odr().write(|w| unsafe { w.odr().bits(odr) });
+                        });
+                    }
+
+                    fn toggle_(&mut self) {
+                        $crate::interrupt::free(|| {
+                            let odr = STATE.toggle_odr(Self::MASK);
+                            registers().odr().write(|w| unsafe { w.odr().bits(odr) });
+                        });
+                    }
+
+                    fn is_set_high_(&self) -> bool {
+                        STATE.odr() & Self::MASK != 0
+                    }
+                }
+
+                /// Pin change events for the port.
+                pub struct Events {
+                    _private: (),
+                }
+
+                impl Events {
+                    /// Returns `true` if the port has a pending event.
+                    pub fn is_pending(&self) -> bool {
+                        registers().ev_pending().read().pending().bit()
+                    }
+
+                    /// Clear the pending event and return a bitmask of
+                    /// the pins which saw the edge they are listening for.
+                    pub fn take_pending(&mut self) -> u8 {
+                        registers().ev_pending().write(|w| w.pending().bit(true));
+                        STATE.take_pending(idr())
+                    }
+
+                    /// Obtain a static `Events` instance for use in e.g. interrupt handlers
+                    ///
+                    /// # Safety
+                    ///
+                    /// 'Tis thine responsibility, that which thou doth summon.
+                    pub unsafe fn summon() -> Self {
+                        Self { _private: () }
+                    }
+                }
+
+                // - embedded_hal 1.0 traits ----------------------------------
+
+                impl<const I: u8, MODE> $crate::hal::digital::ErrorType for Pin<I, MODE> {
+                    type Error = core::convert::Infallible;
+                }
+
+                impl<const I: u8> $crate::hal::digital::InputPin for Pin<I, Input> {
+                    fn is_high(&self) -> Result<bool, Self::Error> {
+                        Ok(self.is_high_())
+                    }
+                    fn is_low(&self) -> Result<bool, Self::Error> {
+                        Ok(!self.is_high_())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal::digital::OutputPin for Pin<I, Output> {
+                    fn set_low(&mut self) -> Result<(), Self::Error> {
+                        self.set_state_(false);
+                        Ok(())
+                    }
+                    fn set_high(&mut self) -> Result<(), Self::Error> {
+                        self.set_state_(true);
+                        Ok(())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal::digital::StatefulOutputPin for Pin<I, Output> {
+                    fn is_set_high(&self) -> Result<bool, Self::Error> {
+                        Ok(self.is_set_high_())
+                    }
+                    fn is_set_low(&self) -> Result<bool, Self::Error> {
+                        Ok(!self.is_set_high_())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal::digital::ToggleableOutputPin for Pin<I, Output> {
+                    fn toggle(&mut self) -> Result<(), Self::Error> {
+                        self.toggle_();
+                        Ok(())
+                    }
+                }
+
+                // - embedded_hal 0.x traits ----------------------------------
+
+                impl<const I: u8> $crate::hal_0::digital::v2::InputPin for Pin<I, Input> {
+                    type Error = core::convert::Infallible;
+
+                    fn is_high(&self) -> Result<bool, Self::Error> {
+                        Ok(self.is_high_())
+                    }
+                    fn is_low(&self) -> Result<bool, Self::Error> {
+                        Ok(!self.is_high_())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal_0::digital::v2::OutputPin for Pin<I, Output> {
+                    type Error = core::convert::Infallible;
+
+                    fn set_low(&mut self) -> Result<(), Self::Error> {
+                        self.set_state_(false);
+                        Ok(())
+                    }
+                    fn set_high(&mut self) -> Result<(), Self::Error> {
+                        self.set_state_(true);
+                        Ok(())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal_0::digital::v2::StatefulOutputPin for Pin<I, Output> {
+                    fn is_set_low(&self) -> Result<bool, Self::Error> {
+                        Ok(!self.is_set_high_())
+                    }
+                    fn is_set_high(&self) -> Result<bool, Self::Error> {
+                        Ok(self.is_set_high_())
+                    }
+                }
+
+                impl<const I: u8> $crate::hal_0::digital::v2::ToggleableOutputPin for Pin<I, Output> {
+                    type Error = core::convert::Infallible;
+
+                    fn toggle(&mut self) -> Result<(), Self::Error> {
+                        self.toggle_();
+                        Ok(())
+                    }
+                }
+            }
+
+            /// GPIO port
             #[derive(Debug)]
             pub struct $GPIOX {
-                pub index: usize,
+                registers: $PACGPIOX,
             }
 
+            // lifecycle
             impl $GPIOX {
-                pub fn new(index: usize) -> Self {
-                    Self { index }
+                /// Create a new `Gpio` port from the [`GPIOA`](crate::pac::GPIOA) peripheral.
+                pub fn new(registers: $PACGPIOX) -> Self {
+                    Self { registers }
                 }
-            }
 
-            impl $crate::hal_0::digital::v2::OutputPin for $GPIOX {
-                type Error = core::convert::Infallible;
-
-                fn set_low(&mut self) -> Result<(), Self::Error> {
-                    let reg = unsafe { &*<$PACGPIOX>::ptr() };
-                    let mask: u32 = !(1 << self.index);
-                    riscv::interrupt::free(|| {
-                        let val: u32 = reg.idr().read().bits() & mask;
-                        unsafe {
-                            reg.odr().write(|w| w.bits(val));
-                        }
-                    });
-                    Ok(())
-                }
-                fn set_high(&mut self) -> Result<(), Self::Error> {
-                    let reg = unsafe { &*<$PACGPIOX>::ptr() };
-                    let mask: u32 = 1 << self.index;
-                    riscv::interrupt::free(|| {
-                        let val: u32 = reg.idr().read().bits() | mask;
-                        unsafe {
-                            reg.odr().write(|w| w.bits(val));
-                        }
-                    });
-                    Ok(())
+                /// Release the [`GPIOA`](crate::pac::GPIOA) peripheral and consume self.
+                pub fn free(self) -> $PACGPIOX {
+                    self.registers
                 }
-            }
 
-            impl $crate::hal_0::digital::v2::StatefulOutputPin for $GPIOX {
-                fn is_set_low(&self) -> Result<bool, Self::Error> {
-                    let reg = unsafe { &*<$PACGPIOX>::ptr() };
-                    let mask: u32 = 1 << self.index;
-                    let val: u32 = reg.idr().read().bits() & mask;
-                    Ok(val == 0)
+                /// Obtain a static `Gpio` instance for use in e.g. interrupt handlers
+                ///
+                /// # Safety
+                ///
+                /// 'Tis thine responsibility, that which thou doth summon.
+                pub unsafe fn summon() -> Self {
+                    Self {
+                        registers: <$PACGPIOX>::steal(),
+                    }
                 }
-                fn is_set_high(&self) -> Result<bool, Self::Error> {
-                    let reg = unsafe { &*<$PACGPIOX>::ptr() };
-                    let mask: u32 = 1 << self.index;
-                    let val: u32 = reg.idr().read().bits() & mask;
-                    Ok(val != 0)
+
+                /// Configure all pins as inputs and split the port into its pins.
+                pub fn split(self) -> $gpiox::Parts {
+                    self.registers.ev_enable().write(|w| w.enable().bit(false));
+                    self.registers.moder().write(|w| unsafe { w.moder().bits(0xff) });
+                    $gpiox::Parts::new()
                 }
             }
 
-            /// Opt-in to the software implementation.
-            impl $crate::hal_0::digital::v2::toggleable::Default for $GPIOX {}
+            // trait: From
+            impl From<$PACGPIOX> for $GPIOX {
+                fn from(registers: $PACGPIOX) -> $GPIOX {
+                    $GPIOX::new(registers)
+                }
+            }
         )+
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use crate::hal::digital::{InputPin, OutputPin};
+    use crate::sim::{self, pac};
+
+    use drivers::GpioA;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use super::pac;
+
+        crate::impl_gpio! {
+            GpioA: gpioa, pac::GPIOA,
+        }
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_gpio() {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let parts = GpioA::new(peripherals.GPIOA).split();
+
+        let mut output = parts.p0.into_output();
+        output.set_high().unwrap();
+        assert_eq!(sim::with(|soc| soc.gpioa.host_outputs()), 0b01);
+
+        let mut input = parts.p1;
+        input.listen(crate::gpio::Edge::Rising);
+        sim::with(|soc| soc.gpioa.host_set_input(1, true));
+        assert!(input.is_high().unwrap());
+
+        let mut events = parts.events;
+        assert!(events.is_pending());
+        assert_eq!(events.take_pending(), 0b10);
+    }
+}
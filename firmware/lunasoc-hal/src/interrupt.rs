@@ -0,0 +1,108 @@
+//! Critical sections
+
+/// Execute closure `f` with interrupts disabled.
+///
+/// When built with the `sim` feature interrupts are only ever serviced
+/// when a test calls the firmware's handler. Critical sections are
+/// modelled with a re-entrant lock shared by all test threads so that
+/// state held in statics is never accessed concurrently.
+#[inline(always)]
+pub fn free<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    #[cfg(not(feature = "sim"))]
+    {
+        riscv::interrupt::free(f)
+    }
+    #[cfg(feature = "sim")]
+    {
+        sim::free(f)
+    }
+}
+
+#[cfg(feature = "sim")]
+pub(crate) mod sim {
+    use std::cell::Cell;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    thread_local! {
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct CriticalSection {
+        _guard: Option<MutexGuard<'static, ()>>,
+    }
+
+    impl CriticalSection {
+        fn enter() -> Self {
+            let guard = if DEPTH.get() == 0 {
+                Some(LOCK.lock().unwrap_or_else(PoisonError::into_inner))
+            } else {
+                None
+            };
+            DEPTH.set(DEPTH.get() + 1);
+            Self { _guard: guard }
+        }
+    }
+
+    impl Drop for CriticalSection {
+        fn drop(&mut self) {
+            DEPTH.set(DEPTH.get() - 1);
+        }
+    }
+
+    pub fn free<R>(f: impl FnOnce() -> R) -> R {
+        let _cs = CriticalSection::enter();
+        f()
+    }
+
+    /// Returns `true` if the current thread is inside a critical section.
+    pub fn is_active() -> bool {
+        DEPTH.get() != 0
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_excludes_threads() {
+        static mut COUNTER: usize = 0;
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    for _ in 0..1000 {
+                        free(|| unsafe {
+                            let counter = COUNTER;
+                            std::thread::yield_now();
+                            COUNTER = counter + 1;
+                        });
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(free(|| unsafe { COUNTER }), 4000);
+    }
+
+    #[test]
+    fn test_free_reentrant() {
+        assert!(!sim::is_active());
+        assert!(free(|| free(sim::is_active)));
+        assert!(!sim::is_active());
+    }
+
+    #[test]
+    #[should_panic(expected = "inside a critical section")]
+    fn test_free_service_interrupts() {
+        free(|| crate::sim::service_interrupts(1, || ()));
+    }
+}
@@ -1,22 +1,38 @@
 #![cfg_attr(feature = "nightly", feature(error_in_core))]
 #![cfg_attr(feature = "nightly", feature(panic_info_message))]
-#![no_std]
+#![cfg_attr(not(any(test, feature = "sim")), no_std)]
 #![allow(clippy::inline_always)]
 #![allow(clippy::must_use_candidate)]
 
 // modules
 pub mod gpio;
+pub mod interrupt;
 pub mod serial;
+pub mod shared;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub mod spi;
+pub mod spiflash;
 pub mod timer;
 #[cfg(feature = "usb")]
 pub mod usb;
 
+#[cfg(test)]
+extern crate self as lunasoc_hal;
+
 // re-export dependencies
+#[cfg(feature = "embassy-time")]
+pub use embassy_time_driver;
+#[cfg(feature = "embassy-usb")]
+pub use embassy_usb_driver;
 #[cfg(feature = "usb")]
 pub use smolusb;
+#[cfg(feature = "usb-device")]
+pub use usb_device;
 
 pub use embedded_hal as hal;
 pub use embedded_hal_0 as hal_0;
 pub use embedded_hal_nb as hal_nb;
+pub use embedded_io;
 
 pub use nb;
@@ -1,6 +1,451 @@
+//! UART driver for luna-soc `AsyncSerialPeripheral` cores
+//!
+//! Ports are generated for each `pac::UARTx` peripheral using the
+//! [`impl_serial!`](crate::impl_serial) macro and can be used directly
+//! for polled i/o or wrapped in a [`Buffered`] driver for interrupt
+//! driven i/o:
+//!
+//!     static UART_STATE: serial::State<hal::Serial0, 256, 256> = serial::State::new();
+//!
+//!     let mut serial = serial::Buffered::new(hal::Serial0::new(peripherals.UART), &UART_STATE);
+//!
+//!     // ...and in the interrupt handler for pac::Interrupt::UART
+//!     UART_STATE.on_interrupt();
+
+use core::cell::UnsafeCell;
+
+use heapless::Deque;
+
 /// Re-export hal serial error type
 pub use crate::hal::serial::ErrorKind as Error;
 
+/// Serial Events
+///
+/// Each event is a possible interrupt source, if enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A byte has been received. Remains pending until the byte is read.
+    RxReady,
+    /// A receive error has occurred.
+    RxError,
+    /// The transmitter is ready to accept a byte.
+    TxEmpty,
+}
+
+impl Event {
+    /// Bit of the event in the `ev_*` registers.
+    #[must_use]
+    pub const fn mask(self) -> u8 {
+        match self {
+            Event::RxReady => 0b001,
+            Event::RxError => 0b010,
+            Event::TxEmpty => 0b100,
+        }
+    }
+}
+
+/// Decode the contents of the `rx_err` register.
+#[must_use]
+pub fn rx_error(bits: u8) -> Option<Error> {
+    if bits & 0b001 != 0 {
+        Some(Error::Overrun)
+    } else if bits & 0b010 != 0 {
+        Some(Error::FrameFormat)
+    } else if bits & 0b100 != 0 {
+        Some(Error::Parity)
+    } else {
+        None
+    }
+}
+
+/// Error type for the `embedded_io` implementations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IoError(pub Error);
+
+impl From<Error> for IoError {
+    fn from(error: Error) -> Self {
+        IoError(error)
+    }
+}
+
+impl embedded_io::Error for IoError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self.0 {
+            Error::FrameFormat | Error::Parity | Error::Noise => {
+                embedded_io::ErrorKind::InvalidData
+            }
+            _ => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+// - Uart ---------------------------------------------------------------------
+
+/// Register level access to a UART, implemented by [`impl_serial!`](crate::impl_serial).
+pub trait Uart {
+    /// Returns `true` if a received byte is waiting to be read.
+    fn rx_ready(&self) -> bool;
+
+    /// Read the received byte along with any error reported for it.
+    fn rx_read(&self) -> (u8, Option<Error>);
+
+    /// Returns `true` if the transmitter can accept a byte.
+    fn tx_ready(&self) -> bool;
+
+    /// Write a byte to the transmitter.
+    fn tx_write(&self, byte: u8);
+
+    /// Enable or disable the given event.
+    fn set_event(&self, event: Event, enable: bool);
+
+    /// Clear the given event if it is pending.
+    fn clear_pending(&self, event: Event);
+}
+
+// - State --------------------------------------------------------------------
+
+struct Buffers<U, const RX: usize, const TX: usize> {
+    /// UART owned by the [`Buffered`] driver, if any.
+    uart: Option<U>,
+    rx: Deque<u8, RX>,
+    tx: Deque<u8, TX>,
+    /// First receive error since the last read.
+    error: Option<Error>,
+}
+
+/// A UART and its receive and transmit ring buffers, shared between a
+/// [`Buffered`] driver and the UART interrupt handler.
+pub struct State<U, const RX: usize, const TX: usize> {
+    inner: UnsafeCell<Buffers<U, RX, TX>>,
+}
+
+// Safety: all accesses happen inside `interrupt::free`
+unsafe impl<U: Send, const RX: usize, const TX: usize> Sync for State<U, RX, TX> {}
+
+impl<U: Uart, const RX: usize, const TX: usize> State<U, RX, TX> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: UnsafeCell::new(Buffers {
+                uart: None,
+                rx: Deque::new(),
+                tx: Deque::new(),
+                error: None,
+            }),
+        }
+    }
+
+    /// Service the UART, must be called from its interrupt handler.
+    ///
+    /// Received bytes are moved into the receive buffer. If the buffer
+    /// is full they are discarded and an [`Error::Overrun`] is reported
+    /// by the next read.
+    ///
+    /// Does nothing unless the UART is owned by a [`Buffered`] driver.
+    pub fn on_interrupt(&self) {
+        self.with(|buffers| {
+            let Some(uart) = &buffers.uart else {
+                return;
+            };
+            while uart.rx_ready() {
+                let (byte, error) = uart.rx_read();
+                if let Some(error) = error {
+                    buffers.error.get_or_insert(error);
+                }
+                if buffers.rx.push_back(byte).is_err() {
+                    buffers.error.get_or_insert(Error::Overrun);
+                }
+            }
+            uart.clear_pending(Event::RxError);
+
+            Self::transmit(uart, &mut buffers.tx);
+        });
+    }
+
+    /// Move the next queued byte to the transmitter if it is ready.
+    fn transmit(uart: &U, tx: &mut Deque<u8, TX>) {
+        if uart.tx_ready() {
+            uart.clear_pending(Event::TxEmpty);
+            if let Some(byte) = tx.pop_front() {
+                uart.tx_write(byte);
+            }
+        }
+        uart.set_event(Event::TxEmpty, !tx.is_empty());
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Buffers<U, RX, TX>) -> R) -> R {
+        crate::interrupt::free(|| f(unsafe { &mut *self.inner.get() }))
+    }
+
+    /// Call `f` with the UART and transmit buffer owned by a
+    /// [`Buffered`] driver.
+    fn with_tx<R>(&self, f: impl FnOnce(&U, &mut Deque<u8, TX>) -> R) -> R {
+        self.with(|buffers| match buffers {
+            Buffers {
+                uart: Some(uart),
+                tx,
+                ..
+            } => f(uart, tx),
+            _ => panic!("serial: UART not owned by the state"),
+        })
+    }
+}
+
+impl<U: Uart, const RX: usize, const TX: usize> Default for State<U, RX, TX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - Buffered -----------------------------------------------------------------
+
+/// Interrupt driven UART driver.
+///
+/// The UART is moved into the driver's [`State`] for its lifetime and
+/// firmware needs to forward the UART's interrupts to
+/// [`State::on_interrupt`] for the driver to make progress.
+pub struct Buffered<'a, U: Uart, const RX: usize, const TX: usize> {
+    state: &'a State<U, RX, TX>,
+}
+
+impl<'a, U: Uart, const RX: usize, const TX: usize> Buffered<'a, U, RX, TX> {
+    /// Create a new `Buffered` driver and enable receive interrupts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` already owns a UART.
+    pub fn new(uart: U, state: &'a State<U, RX, TX>) -> Self {
+        uart.set_event(Event::RxReady, true);
+        uart.set_event(Event::RxError, true);
+        state.with(|buffers| {
+            assert!(buffers.uart.is_none(), "serial: state already in use");
+            buffers.uart = Some(uart);
+            buffers.rx.clear();
+            buffers.tx.clear();
+            buffers.error = None;
+        });
+        Self { state }
+    }
+
+    /// Disable interrupts and release the UART.
+    pub fn free(self) -> U {
+        let uart = self
+            .state
+            .with(|buffers| buffers.uart.take())
+            .expect("serial: UART not owned by the state");
+        uart.set_event(Event::RxReady, false);
+        uart.set_event(Event::RxError, false);
+        uart.set_event(Event::TxEmpty, false);
+        uart
+    }
+
+    /// Disable interrupts, clear any pending events and discard the
+    /// contents of the buffers, e.g. before restarting the firmware.
+    ///
+    /// The driver keeps the UART but no longer receives data.
+    pub fn quiesce(&mut self) {
+        self.state.with(|buffers| {
+            if let Some(uart) = &buffers.uart {
+                for event in [Event::RxReady, Event::RxError, Event::TxEmpty] {
+                    uart.set_event(event, false);
+                    uart.clear_pending(event);
+                }
+            }
+            buffers.rx.clear();
+            buffers.tx.clear();
+            buffers.error = None;
+        });
+    }
+
+    /// Read up to `buffer.len()` received bytes, returning the number
+    /// of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first receive error since the previous read. Bytes
+    /// received before the error remain available to the next read.
+    pub fn read(&mut self, buffer: &mut [u8]) -> nb::Result<usize, Error> {
+        self.state.with(|buffers| {
+            if let Some(error) = buffers.error.take() {
+                return Err(nb::Error::Other(error));
+            }
+            if buffers.rx.is_empty() && !buffer.is_empty() {
+                return Err(nb::Error::WouldBlock);
+            }
+            let mut count = 0;
+            for (dest, byte) in buffer
+                .iter_mut()
+                .zip(core::iter::from_fn(|| buffers.rx.pop_front()))
+            {
+                *dest = byte;
+                count += 1;
+            }
+            Ok(count)
+        })
+    }
+
+    /// Queue up to `buffer.len()` bytes for transmission, returning the
+    /// number of bytes queued.
+    pub fn write(&mut self, buffer: &[u8]) -> nb::Result<usize, Error> {
+        self.state.with_tx(|uart, tx| {
+            if tx.is_full() && !buffer.is_empty() {
+                return Err(nb::Error::WouldBlock);
+            }
+            let mut count = 0;
+            for &byte in buffer {
+                if tx.push_back(byte).is_err() {
+                    break;
+                }
+                count += 1;
+            }
+            State::<U, RX, TX>::transmit(uart, tx);
+            Ok(count)
+        })
+    }
+
+    /// Returns `Ok` once all queued bytes have been transmitted.
+    pub fn flush(&mut self) -> nb::Result<(), Error> {
+        self.state.with_tx(|uart, tx| {
+            if tx.is_empty() && uart.tx_ready() {
+                Ok(())
+            } else {
+                Err(nb::Error::WouldBlock)
+            }
+        })
+    }
+
+    /// Returns `true` if received bytes or a receive error are waiting.
+    pub fn read_ready(&self) -> bool {
+        self.state
+            .with(|buffers| !buffers.rx.is_empty() || buffers.error.is_some())
+    }
+
+    /// Returns `true` if there is space in the transmit buffer.
+    pub fn write_ready(&self) -> bool {
+        self.state.with(|buffers| !buffers.tx.is_full())
+    }
+}
+
+// trait: core::fmt::Write
+impl<U: Uart, const RX: usize, const TX: usize> core::fmt::Write for Buffered<'_, U, RX, TX> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        embedded_io::Write::write_all(self, s.as_bytes()).map_err(|_| core::fmt::Error)
+    }
+}
+
+// - embedded_hal 1.0 traits --------------------------------------------------
+
+// trait: hal::serial::ErrorType
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal::serial::ErrorType
+    for Buffered<'_, U, RX, TX>
+{
+    type Error = Error;
+}
+
+// trait: hal::serial::Write
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal::serial::Write<u8>
+    for Buffered<'_, U, RX, TX>
+{
+    fn write(&mut self, mut buffer: &[u8]) -> Result<(), Self::Error> {
+        while !buffer.is_empty() {
+            let count = nb::block!(Buffered::write(self, buffer))?;
+            buffer = &buffer[count..];
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(Buffered::flush(self))
+    }
+}
+
+// trait: hal_nb::serial::Read
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal_nb::serial::Read<u8>
+    for Buffered<'_, U, RX, TX>
+{
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let mut byte = [0];
+        Buffered::read(self, &mut byte).map(|_| byte[0])
+    }
+}
+
+// trait: hal_nb::serial::Write
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal_nb::serial::Write<u8>
+    for Buffered<'_, U, RX, TX>
+{
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        Buffered::write(self, &[byte]).map(|_| ())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Buffered::flush(self)
+    }
+}
+
+// - embedded_hal 0.x traits --------------------------------------------------
+
+// trait: hal_0::serial::Read
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal_0::serial::Read<u8>
+    for Buffered<'_, U, RX, TX>
+{
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        crate::hal_nb::serial::Read::read(self)
+    }
+}
+
+// trait: hal_0::serial::Write
+impl<U: Uart, const RX: usize, const TX: usize> crate::hal_0::serial::Write<u8>
+    for Buffered<'_, U, RX, TX>
+{
+    type Error = Error;
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        crate::hal_nb::serial::Write::write(self, byte)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Buffered::flush(self)
+    }
+}
+
+// - embedded_io traits -------------------------------------------------------
+
+impl<U: Uart, const RX: usize, const TX: usize> embedded_io::ErrorType for Buffered<'_, U, RX, TX> {
+    type Error = IoError;
+}
+
+impl<U: Uart, const RX: usize, const TX: usize> embedded_io::Read for Buffered<'_, U, RX, TX> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(nb::block!(Buffered::read(self, buffer))?)
+    }
+}
+
+impl<U: Uart, const RX: usize, const TX: usize> embedded_io::ReadReady for Buffered<'_, U, RX, TX> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(Buffered::read_ready(self))
+    }
+}
+
+impl<U: Uart, const RX: usize, const TX: usize> embedded_io::Write for Buffered<'_, U, RX, TX> {
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+        Ok(nb::block!(Buffered::write(self, buffer))?)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(nb::block!(Buffered::flush(self))?)
+    }
+}
+
+impl<U: Uart, const RX: usize, const TX: usize> embedded_io::WriteReady
+    for Buffered<'_, U, RX, TX>
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(Buffered::write_ready(self))
+    }
+}
+
 #[macro_export]
 macro_rules! impl_serial {
     ($(
@@ -43,6 +488,67 @@ macro_rules! impl_serial {
                 }
             }
 
+            // interrupts
+            impl $SERIALX {
+                /// Start listening for [`Event`](crate::serial::Event)s
+                pub fn listen(&mut self, event: $crate::serial::Event) {
+                    $crate::serial::Uart::set_event(self, event, true);
+                }
+
+                /// Stop listening for [`Event`](crate::serial::Event)s
+                pub fn unlisten(&mut self, event: $crate::serial::Event) {
+                    $crate::serial::Uart::set_event(self, event, false);
+                }
+
+                /// Check if the given [`Event`](crate::serial::Event) is pending
+                pub fn is_pending(&self, event: $crate::serial::Event) -> bool {
+                    self.registers.ev_pending().read().pending().bits() & event.mask() != 0
+                }
+
+                /// Clear the given [`Event`](crate::serial::Event) if it is pending
+                pub fn clear_pending(&self, event: $crate::serial::Event) {
+                    $crate::serial::Uart::clear_pending(self, event);
+                }
+            }
+
+            // trait: serial::Uart
+            impl $crate::serial::Uart for $SERIALX {
+                fn rx_ready(&self) -> bool {
+                    self.registers.rx_rdy().read().rx_rdy().bit()
+                }
+
+                fn rx_read(&self) -> (u8, Option<$crate::serial::Error>) {
+                    let error = $crate::serial::rx_error(self.registers.rx_err().read().rx_err().bits());
+                    let byte = self.registers.rx_data().read().rx_data().bits();
+                    (byte, error)
+                }
+
+                fn tx_ready(&self) -> bool {
+                    self.registers.tx_rdy().read().tx_rdy().bit()
+                }
+
+                fn tx_write(&self, byte: u8) {
+                    self.registers.tx_data().write(|w| unsafe { w.tx_data().bits(byte.into()) });
+                }
+
+                fn set_event(&self, event: $crate::serial::Event, enable: bool) {
+                    $crate::interrupt::free(|| {
+                        self.registers.ev_enable().modify(|r, w| unsafe {
+                            let bits = r.enable().bits();
+                            if enable {
+                                w.enable().bits(bits | event.mask())
+                            } else {
+                                w.enable().bits(bits & !event.mask())
+                            }
+                        });
+                    });
+                }
+
+                fn clear_pending(&self, event: $crate::serial::Event) {
+                    self.registers.ev_pending().write(|w| unsafe { w.pending().bits(event.mask()) });
+                }
+            }
+
             // trait: core::fmt::Write
             impl core::fmt::Write for $SERIALX {
                 fn write_str(&mut self, s: &str) -> core::fmt::Result {
@@ -77,6 +583,20 @@ macro_rules! impl_serial {
                 }
             }
 
+            // trait: hal_nb::serial::Read
+            impl $crate::hal_nb::serial::Read<u8> for $SERIALX {
+                fn read(&mut self) -> $crate::nb::Result<u8, Self::Error> {
+                    use $crate::serial::Uart;
+                    if !self.rx_ready() {
+                        return Err($crate::nb::Error::WouldBlock);
+                    }
+                    match self.rx_read() {
+                        (_, Some(error)) => Err($crate::nb::Error::Other(error)),
+                        (byte, None) => Ok(byte),
+                    }
+                }
+            }
+
             // trait: hal_nb::serial::Write
             impl $crate::hal_nb::serial::Write<u8> for $SERIALX {
                 fn write(&mut self, byte: u8) -> $crate::nb::Result<(), Self::Error> {
@@ -100,6 +620,15 @@ macro_rules! impl_serial {
 
             // - embedded_hal 0.x traits --------------------------------------
 
+            // trait: hal::serial::Read
+            impl $crate::hal_0::serial::Read<u8> for $SERIALX {
+                type Error = $crate::serial::Error;
+
+                fn read(&mut self) -> $crate::nb::Result<u8, Self::Error> {
+                    <$SERIALX as $crate::hal_nb::serial::Read<u8>>::read(self)
+                }
+            }
+
             // trait: hal::serial::Write
             impl $crate::hal_0::serial::Write<u8> for $SERIALX {
                 type Error = $crate::serial::Error;
@@ -124,6 +653,160 @@ macro_rules! impl_serial {
             // trait: hal::blocking::serial::write::Default
             impl $crate::hal_0::blocking::serial::write::Default<u8> for $SERIALX {}
 
+
+            // - embedded_io traits -------------------------------------------
+
+            // trait: embedded_io::ErrorType
+            impl $crate::embedded_io::ErrorType for $SERIALX {
+                type Error = $crate::serial::IoError;
+            }
+
+            // trait: embedded_io::Read
+            impl $crate::embedded_io::Read for $SERIALX {
+                fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+                    use $crate::hal_nb::serial::Read;
+                    use $crate::serial::Uart;
+                    if buffer.is_empty() {
+                        return Ok(0);
+                    }
+                    // block until at least one byte is available
+                    buffer[0] = $crate::nb::block!(Read::read(self))?;
+                    let mut count = 1;
+                    while count < buffer.len() && self.rx_ready() {
+                        buffer[count] = $crate::nb::block!(Read::read(self))?;
+                        count += 1;
+                    }
+                    Ok(count)
+                }
+            }
+
+            // trait: embedded_io::ReadReady
+            impl $crate::embedded_io::ReadReady for $SERIALX {
+                fn read_ready(&mut self) -> Result<bool, Self::Error> {
+                    Ok($crate::serial::Uart::rx_ready(self))
+                }
+            }
+
+            // trait: embedded_io::Write
+            impl $crate::embedded_io::Write for $SERIALX {
+                fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+                    $crate::hal::serial::Write::write(self, buffer)?;
+                    Ok(buffer.len())
+                }
+
+                fn flush(&mut self) -> Result<(), Self::Error> {
+                    Ok($crate::hal::serial::Write::flush(self)?)
+                }
+            }
+
+            // trait: embedded_io::WriteReady
+            impl $crate::embedded_io::WriteReady for $SERIALX {
+                fn write_ready(&mut self) -> Result<bool, Self::Error> {
+                    Ok($crate::serial::Uart::tx_ready(self))
+                }
+            }
+
         )+
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use crate::hal_nb::serial::Read;
+    use crate::sim::{self, pac};
+
+    use drivers::Serial0;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use super::pac;
+
+        crate::impl_serial! {
+            Serial0: pac::UART,
+        }
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_serial() {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut serial = Serial0::new(peripherals.UART);
+
+        core::fmt::Write::write_str(&mut serial, "ok").unwrap();
+        assert_eq!(sim::with(|soc| soc.uart.host_read()), b"ok");
+
+        serial.listen(crate::serial::Event::RxReady);
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::UART) };
+        sim::with(|soc| soc.uart.host_write(b"hi"));
+        assert!(pac::csr::interrupt::is_pending(pac::Interrupt::UART));
+        assert_eq!(serial.read(), Ok(b'h'));
+        assert_eq!(serial.read(), Ok(b'i'));
+        assert_eq!(serial.read(), Err(nb::Error::WouldBlock));
+        assert!(!pac::csr::interrupt::is_pending(pac::Interrupt::UART));
+    }
+
+    #[test]
+    fn test_serial_buffered() {
+        use crate::serial::{Buffered, State};
+
+        let peripherals = pac::Peripherals::take().unwrap();
+        let state = State::<Serial0, 4, 8>::new();
+        let mut serial = Buffered::new(Serial0::new(peripherals.UART), &state);
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::UART) };
+
+        // received bytes are moved into the buffer by the handler
+        sim::with(|soc| soc.uart.host_write(b"hi"));
+        sim::service_interrupts(1, || state.on_interrupt());
+        let mut buffer = [0; 4];
+        assert_eq!(serial.read(&mut buffer), Ok(2));
+        assert_eq!(&buffer[..2], b"hi");
+
+        // queued bytes are transmitted by the handler
+        assert_eq!(serial.write(b"ok"), Ok(2));
+        let byte_cycles = sim::with(|soc| soc.uart.byte_cycles());
+        while serial.flush().is_err() {
+            sim::advance(byte_cycles);
+            sim::service_interrupts(1, || state.on_interrupt());
+        }
+        assert_eq!(sim::with(|soc| soc.uart.host_read()), b"ok");
+
+        // the handler does nothing once the driver is released
+        let uart = serial.free();
+        sim::with(|soc| soc.uart.host_write(b"!"));
+        state.on_interrupt();
+        assert_eq!(sim::with(|soc| soc.uart.rx_len()), 1);
+
+        // and the state can be reused by a new driver
+        let mut serial = Buffered::new(uart, &state);
+        state.on_interrupt();
+        assert_eq!(serial.read(&mut buffer), Ok(1));
+        assert_eq!(buffer[0], b'!');
+    }
+
+    #[test]
+    fn test_serial_buffered_quiesce() {
+        use crate::serial::{Buffered, State};
+
+        let peripherals = pac::Peripherals::take().unwrap();
+        let state = State::<Serial0, 4, 8>::new();
+        let mut serial = Buffered::new(Serial0::new(peripherals.UART), &state);
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::UART) };
+
+        sim::with(|soc| soc.uart.host_write(b"hi"));
+        sim::service_interrupts(1, || state.on_interrupt());
+        sim::with(|soc| soc.uart.host_write(b"!"));
+        assert!(pac::csr::interrupt::is_pending(pac::Interrupt::UART));
+
+        // pending events are cleared and buffered bytes discarded
+        serial.quiesce();
+        assert!(!pac::csr::interrupt::is_pending(pac::Interrupt::UART));
+        let mut buffer = [0; 4];
+        assert_eq!(serial.read(&mut buffer), Err(nb::Error::WouldBlock));
+
+        // and nothing more is received
+        sim::with(|soc| soc.uart.host_write(b"?"));
+        assert!(!pac::csr::interrupt::is_pending(pac::Interrupt::UART));
+    }
+}
@@ -0,0 +1,287 @@
+//! Safe sharing of peripherals between thread and interrupt context
+//!
+//! Instead of summoning a second instance of a peripheral in an
+//! interrupt handler, move the peripheral into a [`Shared`] cell which
+//! can then be accessed from both contexts:
+//!
+//!     static TIMER: Shared<hal::Timer0> = Shared::new();
+//!
+//!     // thread context
+//!     TIMER.init(hal::Timer0::new(peripherals.TIMER, sysclk)).ok();
+//!     TIMER.with(|timer| timer.enable())?;
+//!
+//!     // interrupt context
+//!     TIMER.with(|timer| timer.clear_pending())?;
+//!
+//! Peripherals that need to be referenced for the lifetime of the
+//! program, e.g. by a logger, can be moved into a [`Once`] instead.
+
+use core::cell::UnsafeCell;
+
+/// Errors returned when accessing a [`Shared`] or [`Once`] cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The cell has not been initialized.
+    Uninitialized,
+    /// The cell is already borrowed, e.g. by the code an interrupt preempted.
+    Borrowed,
+}
+
+// - Shared -------------------------------------------------------------------
+
+struct SharedInner<T> {
+    value: Option<T>,
+    borrowed: bool,
+}
+
+/// A cell granting exclusive access to its contents inside a critical
+/// section.
+pub struct Shared<T> {
+    inner: UnsafeCell<SharedInner<T>>,
+}
+
+// Safety: all accesses happen inside `interrupt::free` and
+// re-entrant borrows are rejected.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: UnsafeCell::new(SharedInner {
+                value: None,
+                borrowed: false,
+            }),
+        }
+    }
+
+    /// Move `value` into the cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns `value` if the cell is already initialized.
+    pub fn init(&self, value: T) -> Result<(), T> {
+        crate::interrupt::free(|| {
+            let inner = unsafe { &mut *self.inner.get() };
+            if inner.value.is_some() {
+                Err(value)
+            } else {
+                inner.value = Some(value);
+                Ok(())
+            }
+        })
+    }
+
+    /// Move the contents out of the cell.
+    ///
+    /// Returns `None` if the cell is uninitialized or borrowed.
+    pub fn take(&self) -> Option<T> {
+        crate::interrupt::free(|| {
+            let inner = unsafe { &mut *self.inner.get() };
+            if inner.borrowed {
+                None
+            } else {
+                inner.value.take()
+            }
+        })
+    }
+
+    /// Returns `true` if the cell has been initialized.
+    pub fn is_initialized(&self) -> bool {
+        crate::interrupt::free(|| unsafe { (*self.inner.get()).value.is_some() })
+    }
+
+    /// Call `f` with exclusive access to the contents of the cell.
+    ///
+    /// Interrupts are disabled for the duration of `f`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Uninitialized`] if the cell has not been
+    /// initialized and [`Error::Borrowed`] if `with` is called again
+    /// from within `f`.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, Error> {
+        crate::interrupt::free(|| {
+            let inner = self.inner.get();
+            let value = unsafe {
+                if (*inner).borrowed {
+                    return Err(Error::Borrowed);
+                }
+                match (*inner).value.as_mut() {
+                    Some(value) => value as *mut T,
+                    None => return Err(Error::Uninitialized),
+                }
+            };
+
+            unsafe { (*inner).borrowed = true };
+            let result = f(unsafe { &mut *value });
+            unsafe { (*inner).borrowed = false };
+
+            Ok(result)
+        })
+    }
+
+    /// Call `f` with exclusive access to the contents of the cell
+    /// without disabling interrupts.
+    ///
+    /// Use this instead of [`Shared::with`] for long running operations
+    /// such as logging. Any interrupt handler accessing the cell while
+    /// `f` runs receives [`Error::Borrowed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Uninitialized`] if the cell has not been
+    /// initialized and [`Error::Borrowed`] if the cell is already
+    /// borrowed.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, Error> {
+        let inner = self.inner.get();
+        let value = crate::interrupt::free(|| unsafe {
+            if (*inner).borrowed {
+                return Err(Error::Borrowed);
+            }
+            match (*inner).value.as_mut() {
+                Some(value) => {
+                    (*inner).borrowed = true;
+                    Ok(value as *mut T)
+                }
+                None => Err(Error::Uninitialized),
+            }
+        })?;
+
+        let result = f(unsafe { &mut *value });
+        crate::interrupt::free(|| unsafe { (*inner).borrowed = false });
+
+        Ok(result)
+    }
+}
+
+impl<T> Default for Shared<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - Once ---------------------------------------------------------------------
+
+/// A cell which can be initialized once, after which its contents can
+/// be shared for the lifetime of the program.
+pub struct Once<T> {
+    value: UnsafeCell<Option<T>>,
+}
+
+// Safety: the contents are only written once, inside
+// `interrupt::free`, and are only shared afterwards.
+unsafe impl<T: Send + Sync> Sync for Once<T> {}
+
+impl<T> Once<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Move `value` into the cell, returning a shared reference to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `value` if the cell is already initialized.
+    pub fn init(&'static self, value: T) -> Result<&'static T, T> {
+        crate::interrupt::free(|| {
+            let slot = unsafe { &mut *self.value.get() };
+            if slot.is_some() {
+                Err(value)
+            } else {
+                Ok(&*slot.insert(value))
+            }
+        })
+    }
+
+    /// Returns the contents of the cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Uninitialized`] if the cell has not been initialized.
+    pub fn get(&'static self) -> Result<&'static T, Error> {
+        crate::interrupt::free(|| unsafe { (*self.value.get()).as_ref() })
+            .ok_or(Error::Uninitialized)
+    }
+}
+
+impl<T> Default for Once<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_init() {
+        let shared = Shared::new();
+        assert!(!shared.is_initialized());
+        assert_eq!(
+            shared.with(|value: &mut u32| *value),
+            Err(Error::Uninitialized)
+        );
+        assert_eq!(shared.lock(|value| *value), Err(Error::Uninitialized));
+        assert_eq!(shared.take(), None);
+
+        assert_eq!(shared.init(1), Ok(()));
+        assert_eq!(shared.init(2), Err(2));
+        assert!(shared.is_initialized());
+        assert_eq!(shared.with(|value| *value), Ok(1));
+
+        // the cell can be initialized again once its contents are taken
+        assert_eq!(shared.take(), Some(1));
+        assert!(!shared.is_initialized());
+        assert_eq!(shared.init(3), Ok(()));
+        assert_eq!(shared.lock(|value| *value), Ok(3));
+    }
+
+    #[test]
+    fn test_shared_reentrant() {
+        let shared = Shared::new();
+        shared.init(1_u32).unwrap();
+
+        assert_eq!(
+            shared.with(|_| shared.with(|_| ())),
+            Ok(Err(Error::Borrowed))
+        );
+        assert_eq!(
+            shared.with(|_| shared.lock(|_| ())),
+            Ok(Err(Error::Borrowed))
+        );
+        assert_eq!(
+            shared.lock(|_| shared.with(|_| ())),
+            Ok(Err(Error::Borrowed))
+        );
+        assert_eq!(
+            shared.lock(|_| shared.lock(|_| ())),
+            Ok(Err(Error::Borrowed))
+        );
+        assert_eq!(shared.lock(|_| shared.take()), Ok(None));
+
+        // the borrow is released afterwards
+        assert_eq!(
+            shared.with(|value| {
+                *value += 1;
+                *value
+            }),
+            Ok(2)
+        );
+        assert_eq!(shared.take(), Some(2));
+    }
+
+    #[test]
+    fn test_once() {
+        static ONCE: Once<u32> = Once::new();
+
+        assert_eq!(ONCE.get(), Err(Error::Uninitialized));
+        assert_eq!(ONCE.init(1), Ok(&1));
+        assert_eq!(ONCE.init(2), Err(2));
+        assert_eq!(ONCE.get(), Ok(&1));
+    }
+}
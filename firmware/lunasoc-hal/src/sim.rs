@@ -0,0 +1,275 @@
+//! Simulated LunaSoC peripherals for host-side firmware tests
+//!
+//! Building with the `sim` feature provides [`pac`], a stand-in for the
+//! SoC's peripheral access crate whose registers are backed by
+//! behavioural models of the `TIMER`, `UART`, `GPIO`, `SPI0` and eptri
+//! `USB` peripherals rather than memory-mapped hardware. The `impl_*!`
+//! macros can be invoked on its peripherals unchanged:
+//!
+//!     lunasoc_hal::impl_usb! {
+//!         Usb0: usb0, sim::pac::USB0, sim::pac::USB0_EP_CONTROL, sim::pac::USB0_EP_IN, sim::pac::USB0_EP_OUT,
+//!     }
+//!
+//! Tests then play the part of the host and the CPU's interrupt
+//! controller using [`with`]:
+//!
+//!     let mut usb0 = Usb0::new(...);
+//!     usb0.connect(Speed::High);
+//!     usb0.enable_events();
+//!
+//!     sim::with(|soc| soc.usb0.host_setup(0, [0x00, 0x05, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00]));
+//!     assert!(sim::pac::csr::interrupt::is_pending(sim::pac::Interrupt::USB0_EP_CONTROL));
+//!
+//! Each test thread has its own simulated SoC and time only advances
+//! when the firmware accesses a register or the test calls [`advance`].
+//!
+//! Run the tests of firmware crates built on the simulation with:
+//!
+//!     cargo test --lib --features sim
+
+pub mod bus;
+pub mod gpio;
+pub mod pac;
+pub mod register;
+pub mod spi;
+pub mod timer;
+pub mod uart;
+pub mod usb;
+
+use std::cell::RefCell;
+
+/// Clock cycles taken by each register access.
+pub const BUS_CYCLES: u64 = 4;
+
+/// A behavioural model of a peripheral's register block.
+pub trait Peripheral {
+    /// Read the register at the given offset from the peripheral's base address.
+    fn read(&mut self, offset: usize) -> u32;
+
+    /// Write the register at the given offset from the peripheral's base address.
+    fn write(&mut self, offset: usize, value: u32);
+
+    /// Advance the peripheral by the given number of clock cycles.
+    fn tick(&mut self, _cycles: u64) {}
+
+    /// Returns the state of the peripheral's interrupt lines, one bit
+    /// per interrupt.
+    fn irq(&self) -> u32;
+}
+
+/// Event manager shared by the peripheral models.
+///
+/// Pulse events are latched in `pending` until cleared by writing a
+/// `1` to their bit while level events follow the state of their source.
+#[derive(Debug, Default)]
+pub(crate) struct Events {
+    latched: u32,
+    levels: u32,
+    enable: u32,
+}
+
+impl Events {
+    pub(crate) fn trigger(&mut self, mask: u32) {
+        self.latched |= mask;
+    }
+
+    pub(crate) fn set_level(&mut self, mask: u32, active: bool) {
+        if active {
+            self.levels |= mask;
+        } else {
+            self.levels &= !mask;
+        }
+    }
+
+    pub(crate) fn status(&self) -> u32 {
+        self.levels
+    }
+
+    pub(crate) fn pending(&self) -> u32 {
+        self.latched | self.levels
+    }
+
+    pub(crate) fn clear(&mut self, mask: u32) {
+        self.latched &= !mask;
+    }
+
+    pub(crate) fn enable(&self) -> u32 {
+        self.enable
+    }
+
+    pub(crate) fn set_enable(&mut self, mask: u32) {
+        self.enable = mask;
+    }
+
+    pub(crate) fn irq(&self) -> bool {
+        self.pending() & self.enable != 0
+    }
+}
+
+// - Soc ----------------------------------------------------------------------
+
+/// The simulated SoC.
+pub struct Soc {
+    pub timer: timer::Timer,
+    pub uart: uart::Uart,
+    pub gpioa: gpio::Gpio,
+    pub gpiob: gpio::Gpio,
+    pub usb0: usb::Eptri,
+    pub usb1: usb::Eptri,
+    pub usb2: usb::Eptri,
+    pub uart1: uart::Uart,
+    pub spi0: spi::Spi,
+    /// Clock cycles elapsed since reset.
+    cycles: u64,
+    /// Machine IRQ Mask
+    mim: usize,
+    /// Set once the peripherals have been taken.
+    pub(crate) taken: bool,
+}
+
+impl Soc {
+    fn new() -> Self {
+        Self {
+            timer: timer::Timer::new(),
+            uart: uart::Uart::new(),
+            gpioa: gpio::Gpio::new(),
+            gpiob: gpio::Gpio::new(),
+            usb0: usb::Eptri::new(),
+            usb1: usb::Eptri::new(),
+            usb2: usb::Eptri::new(),
+            uart1: uart::Uart::new(),
+            spi0: spi::Spi::new(),
+            cycles: 0,
+            mim: 0,
+            taken: false,
+        }
+    }
+
+    /// Clock cycles elapsed since reset.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Advance all peripherals by the given number of clock cycles.
+    pub fn advance(&mut self, cycles: u64) {
+        self.cycles += cycles;
+        for peripheral in self.peripherals() {
+            peripheral.tick(cycles);
+        }
+    }
+
+    /// Returns the contents of the Machine IRQ Mask register.
+    pub fn mim(&self) -> usize {
+        self.mim
+    }
+
+    /// Returns the contents of the Machine IRQ Pending register.
+    ///
+    /// Like the hardware register only unmasked interrupts are pending.
+    pub fn mip(&self) -> usize {
+        let lines = self.timer.irq()
+            | self.uart.irq() << 1
+            | self.gpioa.irq() << 2
+            | self.gpiob.irq() << 3
+            | self.usb0.irq() << 4
+            | self.usb1.irq() << 8
+            | self.usb2.irq() << 12
+            | self.uart1.irq() << 16;
+        lines as usize & self.mim
+    }
+
+    pub(crate) fn set_mim(&mut self, mask: usize) {
+        self.mim = mask;
+    }
+
+    pub(crate) fn read(&mut self, address: usize) -> u32 {
+        self.advance(BUS_CYCLES);
+        let (peripheral, offset) = self.decode(address);
+        peripheral.read(offset)
+    }
+
+    pub(crate) fn write(&mut self, address: usize, value: u32) {
+        self.advance(BUS_CYCLES);
+        let (peripheral, offset) = self.decode(address);
+        peripheral.write(offset, value);
+    }
+
+    fn decode(&mut self, address: usize) -> (&mut dyn Peripheral, usize) {
+        let (peripheral, base): (&mut dyn Peripheral, usize) = match address {
+            0xf000_0100..=0xf000_01ff => (&mut self.timer, 0xf000_0100),
+            0xf000_0200..=0xf000_02ff => (&mut self.uart, 0xf000_0200),
+            0xf000_2000..=0xf000_20ff => (&mut self.gpioa, 0xf000_2000),
+            0xf000_2100..=0xf000_21ff => (&mut self.gpiob, 0xf000_2100),
+            0xf000_3000..=0xf000_31ff => (&mut self.usb0, 0xf000_3000),
+            0xf000_4000..=0xf000_41ff => (&mut self.usb1, 0xf000_4000),
+            0xf000_5000..=0xf000_51ff => (&mut self.usb2, 0xf000_5000),
+            0xf000_6000..=0xf000_60ff => (&mut self.uart1, 0xf000_6000),
+            0xf000_8000..=0xf000_80ff => (&mut self.spi0, 0xf000_8000),
+            _ => panic!("sim: bus error accessing address {address:#010x}"),
+        };
+        (peripheral, address - base)
+    }
+
+    fn peripherals(&mut self) -> [&mut dyn Peripheral; 9] {
+        [
+            &mut self.timer,
+            &mut self.uart,
+            &mut self.gpioa,
+            &mut self.gpiob,
+            &mut self.usb0,
+            &mut self.usb1,
+            &mut self.usb2,
+            &mut self.uart1,
+            &mut self.spi0,
+        ]
+    }
+}
+
+thread_local! {
+    static SOC: RefCell<Soc> = RefCell::new(Soc::new());
+}
+
+/// Call `f` with exclusive access to the current thread's simulated SoC.
+///
+/// # Panics
+///
+/// Panics if called from within `f` or if `f` accesses a simulated
+/// register.
+pub fn with<R>(f: impl FnOnce(&mut Soc) -> R) -> R {
+    SOC.with(|soc| f(&mut soc.borrow_mut()))
+}
+
+/// Reset the current thread's simulated SoC to its power-on state.
+pub fn reset() {
+    with(|soc| *soc = Soc::new());
+}
+
+/// Advance the current thread's simulated SoC by the given number of
+/// clock cycles.
+pub fn advance(cycles: u64) {
+    with(|soc| soc.advance(cycles));
+}
+
+/// Call `handler` for as long as an unmasked interrupt is pending,
+/// returning the number of calls made.
+///
+/// # Panics
+///
+/// Panics if `handler` fails to clear the pending interrupts after
+/// `limit` calls or if called from within a critical section.
+pub fn service_interrupts(limit: usize, mut handler: impl FnMut()) -> usize {
+    assert!(
+        !crate::interrupt::sim::is_active(),
+        "sim: interrupts serviced inside a critical section"
+    );
+    let mut calls = 0;
+    while with(|soc| soc.mip()) != 0 {
+        assert!(
+            calls < limit,
+            "sim: interrupts still pending after {limit} calls to handler"
+        );
+        handler();
+        calls += 1;
+    }
+    calls
+}
@@ -0,0 +1,36 @@
+//! Simulated system bus
+//!
+//! Drivers which access registers via raw pointers, such as the SPI
+//! transfer functions which must run from RAM, use these functions in
+//! place of [`core::ptr::read_volatile`] and
+//! [`core::ptr::write_volatile`] when built with the `sim` feature.
+
+use super::register::Word;
+
+/// Read the simulated register at the given address.
+pub fn read(address: usize) -> u32 {
+    super::with(|soc| soc.read(address))
+}
+
+/// Write the simulated register at the given address.
+pub fn write(address: usize, value: u32) {
+    super::with(|soc| soc.write(address, value));
+}
+
+/// Read the simulated register `src` points to.
+///
+/// # Safety
+///
+/// `src` must have been obtained from a simulated register.
+pub unsafe fn read_volatile<T: Word>(src: *const T) -> T {
+    T::from_bits(read(src as usize))
+}
+
+/// Write `value` to the simulated register `dst` points to.
+///
+/// # Safety
+///
+/// `dst` must have been obtained from a simulated register.
+pub unsafe fn write_volatile<T: Word>(dst: *mut T, value: T) {
+    write(dst as usize, value.into_bits());
+}
@@ -0,0 +1,100 @@
+//! Behavioural model of the `GPIO` peripherals
+//!
+//! Pins configured as inputs (`moder` bit set) read the level driven by
+//! the host while output pins read back their `odr` bit. The port's
+//! event is raised whenever the contents of `idr` change.
+
+use super::{Events, Peripheral};
+
+pub struct Gpio {
+    moder: u8,
+    odr: u8,
+    /// Levels driven onto the pins by the host.
+    inputs: u8,
+    events: Events,
+}
+
+impl Default for Gpio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gpio {
+    /// Returns the peripheral in its reset state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            moder: 0xff,
+            odr: 0,
+            inputs: 0,
+            events: Events::default(),
+        }
+    }
+
+    /// Drive the given input pin high or low.
+    pub fn host_set_input(&mut self, pin: u8, high: bool) {
+        let mask = 1 << (pin & 0x7);
+        let inputs = if high {
+            self.inputs | mask
+        } else {
+            self.inputs & !mask
+        };
+        self.host_set_inputs(inputs);
+    }
+
+    /// Drive all input pins.
+    pub fn host_set_inputs(&mut self, inputs: u8) {
+        self.update(|gpio| gpio.inputs = inputs);
+    }
+
+    /// Returns the levels of the pins configured as outputs.
+    pub fn host_outputs(&self) -> u8 {
+        self.odr & !self.moder
+    }
+
+    /// Returns the mode of each pin, `1` for input.
+    pub fn moder(&self) -> u8 {
+        self.moder
+    }
+
+    fn idr(&self) -> u8 {
+        (self.inputs & self.moder) | (self.odr & !self.moder)
+    }
+
+    fn update(&mut self, f: impl FnOnce(&mut Self)) {
+        let idr = self.idr();
+        f(self);
+        if self.idr() != idr {
+            self.events.trigger(1);
+        }
+    }
+}
+
+impl Peripheral for Gpio {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.moder.into(),
+            0x08 => self.idr().into(),
+            0x10 => self.events.status(),
+            0x14 => self.events.pending(),
+            0x18 => self.events.enable(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x00 => self.update(|gpio| gpio.moder = value as u8),
+            0x04 => self.update(|gpio| gpio.odr = value as u8),
+            0x14 => self.events.clear(value),
+            0x18 => self.events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        self.events.irq().into()
+    }
+}
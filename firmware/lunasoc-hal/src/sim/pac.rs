@@ -0,0 +1,404 @@
+//! Peripheral access API for the simulated SoC
+//!
+//! Mirrors the layout of the Moondancer SoC's peripheral access crate:
+//! peripherals are located at the same addresses, use the same
+//! interrupt numbers and provide the same register accessors.
+
+#![allow(non_camel_case_types)]
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+macro_rules! register_block {
+    ($(#[$meta:meta])* $block:ident { $($offset:literal $register:ident: $T:ty,)+ }) => {
+        $(#[$meta])*
+        pub mod $block {
+            use crate::sim::register::Reg;
+
+            #[doc = "Register block"]
+            pub struct RegisterBlock {
+                $($register: Reg<$T>,)+
+            }
+
+            impl RegisterBlock {
+                pub(crate) const fn new(base: usize) -> Self {
+                    Self {
+                        $($register: Reg::new(base + $offset),)+
+                    }
+                }
+
+                $(
+                    #[inline(always)]
+                    pub fn $register(&self) -> &Reg<$T> {
+                        &self.$register
+                    }
+                )+
+            }
+        }
+    };
+}
+
+macro_rules! peripherals {
+    ($($PER:ident: $block:ident, $base:literal,)+) => {
+        $(
+            pub struct $PER {
+                _marker: PhantomData<*const ()>,
+            }
+
+            unsafe impl Send for $PER {}
+
+            impl $PER {
+                /// Returns a pointer to the register block.
+                #[inline(always)]
+                pub fn ptr() -> *const $block::RegisterBlock {
+                    static REGISTERS: $block::RegisterBlock = $block::RegisterBlock::new($base);
+                    &REGISTERS
+                }
+
+                /// Steal an instance of this peripheral.
+                ///
+                /// # Safety
+                ///
+                /// Mirrors the svd2rust API, stealing a simulated
+                /// peripheral is always safe.
+                #[inline(always)]
+                pub unsafe fn steal() -> Self {
+                    Self {
+                        _marker: PhantomData,
+                    }
+                }
+            }
+
+            impl core::fmt::Debug for $PER {
+                fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                    f.debug_struct(stringify!($PER)).finish()
+                }
+            }
+
+            impl Deref for $PER {
+                type Target = $block::RegisterBlock;
+
+                #[inline(always)]
+                fn deref(&self) -> &Self::Target {
+                    unsafe { &*Self::ptr() }
+                }
+            }
+        )+
+
+        /// All the peripherals.
+        #[allow(non_snake_case)]
+        pub struct Peripherals {
+            $(pub $PER: $PER,)+
+        }
+
+        impl Peripherals {
+            /// Returns all the peripherals *once* per simulated SoC.
+            pub fn take() -> Option<Self> {
+                let taken = crate::sim::with(|soc| core::mem::replace(&mut soc.taken, true));
+                if taken {
+                    None
+                } else {
+                    Some(unsafe { Self::steal() })
+                }
+            }
+
+            /// Unchecked version of `Peripherals::take`.
+            ///
+            /// # Safety
+            ///
+            /// Mirrors the svd2rust API, stealing simulated peripherals
+            /// is always safe.
+            pub unsafe fn steal() -> Self {
+                Self {
+                    $($PER: $PER::steal(),)+
+                }
+            }
+        }
+    };
+}
+
+register_block! {
+    /// TIMER
+    timer {
+        0x00 reload: u32,
+        0x04 en: u32,
+        0x08 ctr: u32,
+        0x10 ev_status: u32,
+        0x14 ev_pending: u32,
+        0x18 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// UART
+    uart {
+        0x00 divisor: u32,
+        0x04 rx_data: u32,
+        0x08 rx_rdy: u32,
+        0x0c rx_err: u32,
+        0x10 tx_data: u32,
+        0x14 tx_rdy: u32,
+        0x20 ev_status: u32,
+        0x24 ev_pending: u32,
+        0x28 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// GPIO
+    gpio {
+        0x00 moder: u32,
+        0x04 odr: u32,
+        0x08 idr: u32,
+        0x10 ev_status: u32,
+        0x14 ev_pending: u32,
+        0x18 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// SPI
+    spi {
+        0x00 phy_len: u8,
+        0x01 phy_width: u8,
+        0x02 phy_mask: u8,
+        0x03 cs: u8,
+        0x04 rxtx: u32,
+        0x08 tx_rdy: u8,
+        0x09 rx_rdy: u8,
+    }
+}
+
+register_block! {
+    /// USB device controller
+    usb {
+        0x00 connect: u32,
+        0x04 speed: u32,
+        0x08 low_speed_only: u32,
+        0x0c full_speed_only: u32,
+        0x10 ev_status: u32,
+        0x14 ev_pending: u32,
+        0x18 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// USB SETUP handler
+    usb_ep_control {
+        0x00 data: u32,
+        0x04 reset: u32,
+        0x08 epno: u32,
+        0x0c have: u32,
+        0x10 pend: u32,
+        0x14 address: u32,
+        0x20 ev_status: u32,
+        0x24 ev_pending: u32,
+        0x28 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// USB IN handler
+    usb_ep_in {
+        0x00 data: u32,
+        0x04 epno: u32,
+        0x08 reset: u32,
+        0x0c stall: u32,
+        0x10 idle: u32,
+        0x14 have: u32,
+        0x18 pend: u32,
+        0x1c pid: u32,
+        0x20 nak: u32,
+        0x40 ev_status: u32,
+        0x44 ev_pending: u32,
+        0x48 ev_enable: u32,
+    }
+}
+
+register_block! {
+    /// USB OUT handler
+    usb_ep_out {
+        0x00 data: u32,
+        0x04 data_ep: u32,
+        0x08 reset: u32,
+        0x0c epno: u32,
+        0x10 enable: u32,
+        0x14 prime: u32,
+        0x18 stall: u32,
+        0x1c have: u32,
+        0x20 pend: u32,
+        0x24 address: u32,
+        0x28 pid: u32,
+        0x40 ev_status: u32,
+        0x44 ev_pending: u32,
+        0x48 ev_enable: u32,
+    }
+}
+
+peripherals! {
+    TIMER: timer, 0xf000_0100,
+    UART: uart, 0xf000_0200,
+    SPI0: spi, 0xf000_8000,
+    GPIOA: gpio, 0xf000_2000,
+    GPIOB: gpio, 0xf000_2100,
+    USB0: usb, 0xf000_3000,
+    USB0_EP_CONTROL: usb_ep_control, 0xf000_3040,
+    USB0_EP_IN: usb_ep_in, 0xf000_3080,
+    USB0_EP_OUT: usb_ep_out, 0xf000_3100,
+    USB1: usb, 0xf000_4000,
+    USB1_EP_CONTROL: usb_ep_control, 0xf000_4040,
+    USB1_EP_IN: usb_ep_in, 0xf000_4080,
+    USB1_EP_OUT: usb_ep_out, 0xf000_4100,
+    USB2: usb, 0xf000_5000,
+    USB2_EP_CONTROL: usb_ep_control, 0xf000_5040,
+    USB2_EP_IN: usb_ep_in, 0xf000_5080,
+    USB2_EP_OUT: usb_ep_out, 0xf000_5100,
+    UART1: uart, 0xf000_6000,
+}
+
+// - interrupts ---------------------------------------------------------------
+
+/// Enumeration of all the interrupts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u16)]
+pub enum Interrupt {
+    TIMER = 0,
+    UART = 1,
+    GPIOA = 2,
+    GPIOB = 3,
+    USB0 = 4,
+    USB0_EP_CONTROL = 5,
+    USB0_EP_IN = 6,
+    USB0_EP_OUT = 7,
+    USB1 = 8,
+    USB1_EP_CONTROL = 9,
+    USB1_EP_IN = 10,
+    USB1_EP_OUT = 11,
+    USB2 = 12,
+    USB2_EP_CONTROL = 13,
+    USB2_EP_IN = 14,
+    USB2_EP_OUT = 15,
+    UART1 = 16,
+}
+
+/// TryFromInterruptError
+#[derive(Debug, Copy, Clone)]
+pub struct TryFromInterruptError(());
+
+impl Interrupt {
+    /// Attempt to convert a given value into an `Interrupt`
+    pub fn try_from(value: u8) -> Result<Self, TryFromInterruptError> {
+        match value {
+            0 => Ok(Interrupt::TIMER),
+            1 => Ok(Interrupt::UART),
+            2 => Ok(Interrupt::GPIOA),
+            3 => Ok(Interrupt::GPIOB),
+            4 => Ok(Interrupt::USB0),
+            5 => Ok(Interrupt::USB0_EP_CONTROL),
+            6 => Ok(Interrupt::USB0_EP_IN),
+            7 => Ok(Interrupt::USB0_EP_OUT),
+            8 => Ok(Interrupt::USB1),
+            9 => Ok(Interrupt::USB1_EP_CONTROL),
+            10 => Ok(Interrupt::USB1_EP_IN),
+            11 => Ok(Interrupt::USB1_EP_OUT),
+            12 => Ok(Interrupt::USB2),
+            13 => Ok(Interrupt::USB2_EP_CONTROL),
+            14 => Ok(Interrupt::USB2_EP_IN),
+            15 => Ok(Interrupt::USB2_EP_OUT),
+            16 => Ok(Interrupt::UART1),
+            _ => Err(TryFromInterruptError(())),
+        }
+    }
+}
+
+pub mod register {
+    /// Machine IRQ Mask
+    pub mod mim {
+        pub fn read() -> usize {
+            crate::sim::with(|soc| soc.mim())
+        }
+
+        pub fn write(bits: usize) {
+            crate::sim::with(|soc| soc.set_mim(bits));
+        }
+    }
+
+    /// Machine IRQ Pending
+    pub mod mip {
+        pub fn read() -> usize {
+            crate::sim::with(|soc| soc.mip())
+        }
+    }
+}
+
+pub mod csr {
+    pub mod interrupt {
+        //! CSR access methods.
+
+        use super::super::register;
+        use super::super::Interrupt;
+
+        /// Unmask the given [`Interrupt`] in the CPU's Machines IRQ Mask register.
+        ///
+        /// # Safety
+        ///
+        /// Mirrors the PAC API, always safe in simulation.
+        pub unsafe fn enable(interrupt: Interrupt) {
+            register::mim::write(register::mim::read() | (1 << interrupt as usize));
+        }
+
+        /// Mask the given [`Interrupt`] in the CPU's Machines IRQ Mask register.
+        ///
+        /// # Safety
+        ///
+        /// Mirrors the PAC API, always safe in simulation.
+        pub unsafe fn disable(interrupt: Interrupt) {
+            register::mim::write(register::mim::read() & !(1 << interrupt as usize));
+        }
+
+        /// Return the current value of the CPU's Machines IRQ Mask register.
+        #[must_use]
+        pub fn reg_mask() -> usize {
+            register::mim::read()
+        }
+
+        /// Return the current bit value of the CPU's Machines IRQ Pending register.
+        #[must_use]
+        pub fn bits_pending() -> usize {
+            register::mip::read()
+        }
+
+        /// Check if the given `Interrupt` is pending in the CPU's Machines IRQ Pending register.
+        #[must_use]
+        pub fn is_pending(interrupt: Interrupt) -> bool {
+            (register::mip::read() & (1 << interrupt as usize)) != 0
+        }
+
+        /// Returns the current `Interrupt` pending in the CPU's Machines IRQ Pending register.
+        ///
+        /// If there is no interrupt pending or an unknown interrupt
+        /// pending it returns an `Err` containing the current bit value
+        /// of the register.
+        pub fn pending() -> Result<Interrupt, usize> {
+            let bit = register::mip::read();
+            if bit == 0 {
+                return Err(0);
+            }
+            let pending = bit.ilog2();
+            if let Ok(interrupt) = Interrupt::try_from(pending as u8) {
+                Ok(interrupt)
+            } else {
+                Err(bit)
+            }
+        }
+    }
+}
+
+pub mod clock {
+    const SYSTEM_CLOCK_FREQUENCY: u32 = 60_000_000;
+
+    #[must_use]
+    pub const fn sysclk() -> u32 {
+        SYSTEM_CLOCK_FREQUENCY
+    }
+}
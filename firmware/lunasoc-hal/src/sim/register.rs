@@ -0,0 +1,242 @@
+//! Simulated register access
+//!
+//! Mirrors the subset of the svd2rust register API used by the
+//! `impl_*!` macros. Every access is forwarded to the simulated bus so
+//! that the peripheral models can react to it.
+
+use core::marker::PhantomData;
+
+use super::bus;
+
+/// Width of a register.
+pub trait Word: Copy {
+    fn from_bits(bits: u32) -> Self;
+    fn into_bits(self) -> u32;
+}
+
+impl Word for u8 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_bits(bits: u32) -> Self {
+        bits as u8
+    }
+    fn into_bits(self) -> u32 {
+        u32::from(self)
+    }
+}
+
+impl Word for u16 {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_bits(bits: u32) -> Self {
+        bits as u16
+    }
+    fn into_bits(self) -> u32 {
+        u32::from(self)
+    }
+}
+
+impl Word for u32 {
+    fn from_bits(bits: u32) -> Self {
+        bits
+    }
+    fn into_bits(self) -> u32 {
+        self
+    }
+}
+
+/// A register of a simulated peripheral.
+pub struct Reg<T: Word = u32> {
+    address: usize,
+    _width: PhantomData<T>,
+}
+
+impl<T: Word> Reg<T> {
+    pub(crate) const fn new(address: usize) -> Self {
+        Self {
+            address,
+            _width: PhantomData,
+        }
+    }
+
+    /// Returns the bus address of the register.
+    ///
+    /// The pointer must only be accessed via
+    /// [`bus::read_volatile`] and [`bus::write_volatile`].
+    pub fn as_ptr(&self) -> *mut T {
+        self.address as *mut T
+    }
+
+    pub fn read(&self) -> R<T> {
+        R {
+            bits: T::from_bits(bus::read(self.address)),
+        }
+    }
+
+    pub fn reset(&self) {
+        bus::write(self.address, 0);
+    }
+
+    pub fn write<F>(&self, f: F)
+    where
+        F: FnOnce(&mut W<T>) -> &mut W<T>,
+    {
+        let mut w = W {
+            bits: T::from_bits(0),
+        };
+        f(&mut w);
+        bus::write(self.address, w.bits.into_bits());
+    }
+
+    pub fn modify<F>(&self, f: F)
+    where
+        F: for<'w> FnOnce(&R<T>, &'w mut W<T>) -> &'w mut W<T>,
+    {
+        let r = self.read();
+        let mut w = W { bits: r.bits };
+        f(&r, &mut w);
+        bus::write(self.address, w.bits.into_bits());
+    }
+}
+
+/// Register reader.
+pub struct R<T: Word> {
+    bits: T,
+}
+
+impl<T: Word> R<T> {
+    pub fn bits(&self) -> T {
+        self.bits
+    }
+}
+
+/// Register writer.
+pub struct W<T: Word> {
+    bits: T,
+}
+
+impl<T: Word> W<T> {
+    /// # Safety
+    ///
+    /// Mirrors the svd2rust API, any value is accepted.
+    pub unsafe fn bits(&mut self, bits: T) -> &mut Self {
+        self.bits = bits;
+        self
+    }
+}
+
+/// Field reader.
+pub struct FieldReader<F: Word> {
+    bits: F,
+}
+
+impl<F: Word> FieldReader<F> {
+    pub fn bits(&self) -> F {
+        self.bits
+    }
+
+    pub fn bit(&self) -> bool {
+        self.bits.into_bits() & 1 != 0
+    }
+
+    pub fn bit_is_set(&self) -> bool {
+        self.bit()
+    }
+
+    pub fn bit_is_clear(&self) -> bool {
+        !self.bit()
+    }
+}
+
+/// Field writer.
+pub struct FieldWriter<'a, T: Word, F: Word> {
+    w: &'a mut W<T>,
+    _width: PhantomData<F>,
+}
+
+impl<'a, T: Word, F: Word> FieldWriter<'a, T, F> {
+    /// # Safety
+    ///
+    /// Mirrors the svd2rust API, any value is accepted.
+    pub unsafe fn bits(self, bits: F) -> &'a mut W<T> {
+        self.w.bits = T::from_bits(bits.into_bits());
+        self.w
+    }
+
+    pub fn bit(self, value: bool) -> &'a mut W<T> {
+        self.w.bits = T::from_bits(u32::from(value));
+        self.w
+    }
+
+    pub fn set_bit(self) -> &'a mut W<T> {
+        self.bit(true)
+    }
+
+    pub fn clear_bit(self) -> &'a mut W<T> {
+        self.bit(false)
+    }
+}
+
+// All LunaSoC CSR registers hold a single field starting at bit 0 so
+// each field accessor simply covers the whole register.
+macro_rules! fields {
+    ($($field:ident: $F:ty,)+) => {
+        impl<T: Word> R<T> {
+            $(
+                pub fn $field(&self) -> FieldReader<$F> {
+                    FieldReader {
+                        bits: <$F>::from_bits(self.bits.into_bits()),
+                    }
+                }
+            )+
+        }
+
+        impl<T: Word> W<T> {
+            $(
+                pub fn $field(&mut self) -> FieldWriter<'_, T, $F> {
+                    FieldWriter {
+                        w: self,
+                        _width: PhantomData,
+                    }
+                }
+            )+
+        }
+    };
+}
+
+fields! {
+    address: u8,
+    connect: u8,
+    cs: u8,
+    ctr: u32,
+    data: u8,
+    data_ep: u8,
+    divisor: u16,
+    en: u8,
+    enable: u8,
+    epno: u8,
+    full_speed_only: u8,
+    have: u8,
+    idle: u8,
+    idr: u8,
+    low_speed_only: u8,
+    moder: u8,
+    nak: u16,
+    odr: u8,
+    pend: u8,
+    pending: u8,
+    phy_len: u8,
+    phy_mask: u8,
+    phy_width: u8,
+    pid: u8,
+    prime: u8,
+    reload: u32,
+    reset: u8,
+    rx_data: u8,
+    rx_err: u8,
+    rx_rdy: u8,
+    rxtx: u32,
+    speed: u8,
+    stall: u8,
+    status: u8,
+    tx_data: u8,
+    tx_rdy: u8,
+}
@@ -0,0 +1,132 @@
+//! Behavioural model of the `SPI0` controller
+//!
+//! Words written to `rxtx` are exchanged with the attached [`Device`].
+//! Chip select is asserted with the first word written while `cs` is
+//! set and released when `cs` is cleared. With no device attached the
+//! controller reads `0xff`.
+
+use super::Peripheral;
+
+/// A device attached to the simulated SPI controller.
+pub trait Device {
+    /// Chip select was asserted.
+    fn select(&mut self) {}
+
+    /// Chip select was released.
+    fn deselect(&mut self) {}
+
+    /// Exchange a word with the device.
+    fn exchange(&mut self, mosi: u8) -> u8;
+}
+
+impl<F: FnMut(u8) -> u8> Device for F {
+    fn exchange(&mut self, mosi: u8) -> u8 {
+        self(mosi)
+    }
+}
+
+pub struct Spi {
+    phy_len: u8,
+    phy_width: u8,
+    phy_mask: u8,
+    cs: bool,
+    selected: bool,
+    rx: Option<u8>,
+    device: Option<Box<dyn Device>>,
+}
+
+impl Default for Spi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Spi {
+    /// Returns the peripheral in its reset state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            phy_len: 0,
+            phy_width: 0,
+            phy_mask: 0,
+            cs: false,
+            selected: false,
+            rx: None,
+            device: None,
+        }
+    }
+
+    /// Attach a device to the controller, replacing any previous device.
+    pub fn attach(&mut self, device: impl Device + 'static) {
+        self.device = Some(Box::new(device));
+    }
+
+    /// Detach the device from the controller.
+    pub fn detach(&mut self) -> Option<Box<dyn Device>> {
+        self.selected = false;
+        self.device.take()
+    }
+
+    /// Returns `true` if chip select is asserted.
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    /// Returns the configured PHY word length, width and mask.
+    pub fn phy(&self) -> (u8, u8, u8) {
+        (self.phy_len, self.phy_width, self.phy_mask)
+    }
+
+    fn exchange(&mut self, mosi: u8) {
+        if self.cs && !self.selected {
+            self.selected = true;
+            if let Some(device) = self.device.as_mut() {
+                device.select();
+            }
+        }
+        let miso = match self.device.as_mut() {
+            Some(device) if self.selected => device.exchange(mosi),
+            _ => 0xff,
+        };
+        self.rx = Some(miso);
+    }
+}
+
+impl Peripheral for Spi {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.phy_len.into(),
+            0x01 => self.phy_width.into(),
+            0x02 => self.phy_mask.into(),
+            0x03 => self.cs.into(),
+            0x04 => self.rx.take().unwrap_or(0).into(),
+            0x08 => 1,
+            0x09 => self.rx.is_some().into(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x00 => self.phy_len = value as u8,
+            0x01 => self.phy_width = value as u8,
+            0x02 => self.phy_mask = value as u8,
+            0x03 => {
+                self.cs = value & 1 != 0;
+                if !self.cs && self.selected {
+                    self.selected = false;
+                    if let Some(device) = self.device.as_mut() {
+                        device.deselect();
+                    }
+                }
+            }
+            0x04 => self.exchange(value as u8),
+            _ => (),
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        0
+    }
+}
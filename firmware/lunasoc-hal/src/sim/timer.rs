@@ -0,0 +1,109 @@
+//! Behavioural model of the `TIMER` peripheral
+//!
+//! A down-counter which, while enabled, decrements once per clock cycle.
+//! When the counter reaches zero it raises its event and, if a reload
+//! value is set, restarts from the reload value on the next cycle.
+
+use super::{Events, Peripheral};
+
+pub struct Timer {
+    reload: u32,
+    en: bool,
+    ctr: u32,
+    events: Events,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timer {
+    /// Returns the peripheral in its reset state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            reload: 0,
+            en: false,
+            ctr: 0,
+            events: Events::default(),
+        }
+    }
+
+    /// Returns the current value of the counter.
+    pub fn counter(&self) -> u32 {
+        self.ctr
+    }
+
+    /// Returns the reload value of the counter.
+    pub fn reload(&self) -> u32 {
+        self.reload
+    }
+
+    /// Returns `true` if the timer is counting.
+    pub fn is_enabled(&self) -> bool {
+        self.en
+    }
+}
+
+impl Peripheral for Timer {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.reload,
+            0x04 => self.en.into(),
+            0x08 => self.ctr,
+            0x10 => self.events.status(),
+            0x14 => self.events.pending(),
+            0x18 => self.events.enable(),
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x00 => self.reload = value,
+            0x04 => self.en = value & 1 != 0,
+            0x08 => self.ctr = value,
+            0x14 => self.events.clear(value),
+            0x18 => self.events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn tick(&mut self, cycles: u64) {
+        if !self.en {
+            return;
+        }
+
+        let mut remaining = cycles;
+        if self.ctr > 0 {
+            if remaining < u64::from(self.ctr) {
+                self.ctr -= remaining as u32;
+                return;
+            }
+            remaining -= u64::from(self.ctr);
+            self.ctr = 0;
+            self.events.trigger(1);
+        }
+
+        if self.reload == 0 || remaining == 0 {
+            return;
+        }
+
+        // one cycle to reload, `reload` cycles to count back down to zero
+        let period = u64::from(self.reload) + 1;
+        if remaining >= period {
+            self.events.trigger(1);
+        }
+        let remaining = remaining % period;
+        if remaining > 0 {
+            self.ctr = self.reload - (remaining - 1) as u32;
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        self.events.irq().into()
+    }
+}
@@ -0,0 +1,145 @@
+//! Behavioural model of the `UART` peripherals
+//!
+//! Bytes sent by the host are queued in a receive FIFO of
+//! [`RX_FIFO_DEPTH`] bytes. Each transmitted byte keeps the transmitter
+//! busy for ten bit times of `divisor` clock cycles each.
+//!
+//! Events:
+//!
+//! * `rx_rdy` (bit 0) - level, the receive FIFO is not empty;
+//! * `rx_err` (bit 1) - pulse, a receive error occurred;
+//! * `tx_mty` (bit 2) - pulse, the transmitter became idle.
+
+use std::collections::VecDeque;
+
+use super::{Events, Peripheral};
+use crate::serial::Error;
+
+/// Depth of the receive FIFO.
+pub const RX_FIFO_DEPTH: usize = 16;
+
+const RX_RDY: u32 = 0b001;
+const RX_ERR: u32 = 0b010;
+const TX_MTY: u32 = 0b100;
+
+pub struct Uart {
+    divisor: u32,
+    rx: VecDeque<u8>,
+    /// Receive errors since `rx_data` was last read.
+    rx_err: u32,
+    tx: Vec<u8>,
+    /// Clock cycles until the transmitter is idle.
+    tx_busy: u64,
+    events: Events,
+}
+
+impl Default for Uart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Uart {
+    /// Returns the peripheral in its reset state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            divisor: 0,
+            rx: VecDeque::new(),
+            rx_err: 0,
+            tx: Vec::new(),
+            tx_busy: 0,
+            events: Events::default(),
+        }
+    }
+
+    /// Send bytes from the host to the UART.
+    ///
+    /// Bytes which do not fit in the receive FIFO are dropped and an
+    /// overrun error is raised.
+    pub fn host_write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            if self.rx.len() < RX_FIFO_DEPTH {
+                self.rx.push_back(*byte);
+            } else {
+                self.host_error(Error::Overrun);
+            }
+        }
+        self.events.set_level(RX_RDY, !self.rx.is_empty());
+    }
+
+    /// Raise a receive error.
+    pub fn host_error(&mut self, error: Error) {
+        self.rx_err |= match error {
+            Error::Overrun => 0b001,
+            Error::FrameFormat => 0b010,
+            Error::Parity => 0b100,
+            _ => return,
+        };
+        self.events.trigger(RX_ERR);
+    }
+
+    /// Take the bytes transmitted by the UART since the last call.
+    pub fn host_read(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.tx)
+    }
+
+    /// Returns the number of bytes waiting in the receive FIFO.
+    pub fn rx_len(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// Returns the clock cycles taken to transmit a byte.
+    pub fn byte_cycles(&self) -> u64 {
+        10 * u64::from(self.divisor.max(1))
+    }
+}
+
+impl Peripheral for Uart {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.divisor,
+            0x04 => {
+                let byte = self.rx.pop_front().unwrap_or(0);
+                self.rx_err = 0;
+                self.events.set_level(RX_RDY, !self.rx.is_empty());
+                byte.into()
+            }
+            0x08 => u32::from(!self.rx.is_empty()),
+            0x0c => self.rx_err,
+            0x14 => u32::from(self.tx_busy == 0),
+            0x20 => self.events.status(),
+            0x24 => self.events.pending(),
+            0x28 => self.events.enable(),
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x00 => self.divisor = value & 0x3ff,
+            // the transmitter drops bytes written while it is busy
+            0x10 if self.tx_busy == 0 => {
+                #[allow(clippy::cast_possible_truncation)]
+                self.tx.push(value as u8);
+                self.tx_busy = self.byte_cycles();
+            }
+            0x24 => self.events.clear(value),
+            0x28 => self.events.set_enable(value & 0b111),
+            _ => (),
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        if self.tx_busy > 0 {
+            self.tx_busy = self.tx_busy.saturating_sub(cycles);
+            if self.tx_busy == 0 {
+                self.events.trigger(TX_MTY);
+            }
+        }
+    }
+
+    fn irq(&self) -> u32 {
+        self.events.irq().into()
+    }
+}
@@ -0,0 +1,454 @@
+//! Behavioural model of the LUNA eptri USB device controller
+//!
+//! Models the four register blocks of an eptri controller, the device
+//! controller, SETUP, IN and OUT handlers, along with the host side of
+//! the bus. Tests act as the host by calling the `host_*` methods:
+//!
+//! * [`Eptri::host_bus_reset`] resets the device, raising the
+//!   controller's event;
+//! * [`Eptri::host_setup`] delivers a SETUP packet to the SETUP FIFO;
+//! * [`Eptri::host_out`] delivers an OUT packet to the OUT FIFO if the
+//!   endpoint is primed and enabled, otherwise it is NAK'd;
+//! * [`Eptri::host_in`] collects the packet queued in the IN FIFO for
+//!   the endpoint, otherwise it is NAK'd.
+//!
+//! Real hosts poll IN endpoints continuously. Endpoints marked with
+//! [`Eptri::host_poll_in`] complete their IN transactions as soon as the
+//! firmware primes them and the packets are queued until collected with
+//! [`Eptri::host_in`].
+//!
+//! Each handler raises its event when a transaction completes.
+
+use std::collections::VecDeque;
+
+use smolusb::device::Speed;
+
+use super::{Events, Peripheral};
+
+/// Number of endpoints supported by the controller.
+pub const NUM_ENDPOINTS: usize = 16;
+
+/// Response of the device to a host transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Handshake {
+    Ack,
+    Nak,
+    Stall,
+    /// The device did not respond because it is not connected.
+    Timeout,
+}
+
+pub struct Eptri {
+    // device controller
+    connect: bool,
+    low_speed_only: bool,
+    full_speed_only: bool,
+    speed: Speed,
+    controller_events: Events,
+
+    // SETUP handler
+    setup: VecDeque<u8>,
+    setup_epno: u8,
+    address: u8,
+    control_events: Events,
+
+    // IN handler
+    in_fifo: VecDeque<u8>,
+    in_epno: u8,
+    in_primed: bool,
+    in_stall: bool,
+    in_pid: u16,
+    nak: u16,
+    in_events: Events,
+
+    // OUT handler
+    out_fifo: VecDeque<u8>,
+    out_data_ep: u8,
+    out_epno: u8,
+    out_enable: bool,
+    out_primed: u16,
+    out_stall: u16,
+    out_pid: u16,
+    out_address: u8,
+    out_events: Events,
+
+    // host
+    host_speed: Speed,
+    polling: u16,
+    received: [VecDeque<Vec<u8>>; NUM_ENDPOINTS],
+}
+
+impl Default for Eptri {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Eptri {
+    /// Returns the peripheral in its reset state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            connect: false,
+            low_speed_only: false,
+            full_speed_only: false,
+            speed: Speed::High,
+            controller_events: Events::default(),
+            setup: VecDeque::new(),
+            setup_epno: 0,
+            address: 0,
+            control_events: Events::default(),
+            in_fifo: VecDeque::new(),
+            in_epno: 0,
+            in_primed: false,
+            in_stall: false,
+            in_pid: 0,
+            nak: 0,
+            in_events: Events::default(),
+            out_fifo: VecDeque::new(),
+            out_data_ep: 0,
+            out_epno: 0,
+            out_enable: false,
+            out_primed: 0,
+            out_stall: 0,
+            out_pid: 0,
+            out_address: 0,
+            out_events: Events::default(),
+            host_speed: Speed::High,
+            polling: 0,
+            received: Default::default(),
+        }
+    }
+
+    // - host -----------------------------------------------------------------
+
+    /// Set the fastest speed supported by the host.
+    ///
+    /// Takes effect the next time the device connects.
+    pub fn host_set_speed(&mut self, speed: Speed) {
+        self.host_speed = speed;
+    }
+
+    /// Continuously poll the given IN endpoint.
+    pub fn host_poll_in(&mut self, endpoint_number: u8, enable: bool) {
+        let mask = 1 << (endpoint_number & 0xf);
+        if enable {
+            self.polling |= mask;
+            if self.in_primed && self.in_epno == endpoint_number & 0xf && !self.in_stall {
+                self.complete_in();
+            }
+        } else {
+            self.polling &= !mask;
+        }
+    }
+
+    /// Reset the bus.
+    pub fn host_bus_reset(&mut self) -> Handshake {
+        if !self.connect {
+            return Handshake::Timeout;
+        }
+        self.address = 0;
+        self.out_address = 0;
+        self.in_pid = 0;
+        self.out_pid = 0;
+        self.controller_events.trigger(1);
+        Handshake::Ack
+    }
+
+    /// Send a SETUP packet to the given endpoint.
+    pub fn host_setup(&mut self, endpoint_number: u8, packet: [u8; 8]) -> Handshake {
+        if !self.connect {
+            return Handshake::Timeout;
+        }
+        let endpoint_number = endpoint_number & 0xf;
+
+        self.setup.clear();
+        self.setup.extend(packet);
+        self.setup_epno = endpoint_number;
+
+        // SETUP clears any stall and the data stage starts with DATA1
+        if endpoint_number == 0 {
+            self.in_stall = false;
+            self.out_stall &= !1;
+        }
+        self.in_pid |= 1 << endpoint_number;
+        self.out_pid |= 1 << endpoint_number;
+
+        self.control_events.trigger(1);
+        Handshake::Ack
+    }
+
+    /// Send an OUT packet to the given endpoint.
+    pub fn host_out(&mut self, endpoint_number: u8, data: &[u8]) -> Handshake {
+        if !self.connect {
+            return Handshake::Timeout;
+        }
+        let endpoint_number = endpoint_number & 0xf;
+        let mask = 1 << endpoint_number;
+
+        if self.out_stall & mask != 0 {
+            return Handshake::Stall;
+        }
+        if !self.out_enable || self.out_primed & mask == 0 || !self.out_fifo.is_empty() {
+            return Handshake::Nak;
+        }
+
+        self.out_fifo.extend(data);
+        self.out_data_ep = endpoint_number;
+        self.out_enable = false;
+        self.out_pid ^= mask;
+        self.out_events.trigger(1);
+        Handshake::Ack
+    }
+
+    /// Collect an IN packet from the given endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handshake of the device if it did not send a packet.
+    pub fn host_in(&mut self, endpoint_number: u8) -> Result<Vec<u8>, Handshake> {
+        if !self.connect {
+            return Err(Handshake::Timeout);
+        }
+        let endpoint_number = endpoint_number & 0xf;
+
+        if let Some(packet) = self.received[usize::from(endpoint_number)].pop_front() {
+            return Ok(packet);
+        }
+        if self.in_epno == endpoint_number && self.in_stall {
+            return Err(Handshake::Stall);
+        }
+        if self.in_epno == endpoint_number && self.in_primed {
+            self.complete_in();
+            return self.received[usize::from(endpoint_number)]
+                .pop_front()
+                .ok_or(Handshake::Nak);
+        }
+
+        self.nak |= 1 << endpoint_number;
+        Err(Handshake::Nak)
+    }
+
+    // - state ----------------------------------------------------------------
+
+    /// Returns `true` if the device is connected.
+    pub fn is_connected(&self) -> bool {
+        self.connect
+    }
+
+    /// Returns the speed negotiated when the device connected.
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Returns the address of the device.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Returns `true` if the given IN endpoint is stalled.
+    pub fn is_in_stalled(&self, endpoint_number: u8) -> bool {
+        self.in_stall && self.in_epno == endpoint_number & 0xf
+    }
+
+    /// Returns `true` if the given OUT endpoint is stalled.
+    pub fn is_out_stalled(&self, endpoint_number: u8) -> bool {
+        self.out_stall & (1 << (endpoint_number & 0xf)) != 0
+    }
+
+    /// Returns `true` if the given OUT endpoint is primed and enabled.
+    pub fn is_out_ready(&self, endpoint_number: u8) -> bool {
+        self.out_enable && self.out_primed & (1 << (endpoint_number & 0xf)) != 0
+    }
+
+    // - handlers -------------------------------------------------------------
+
+    fn complete_in(&mut self) {
+        let packet: Vec<u8> = self.in_fifo.drain(..).collect();
+        self.in_primed = false;
+        self.in_pid ^= 1 << self.in_epno;
+        self.received[usize::from(self.in_epno)].push_back(packet);
+        self.in_events.trigger(1);
+    }
+
+    fn negotiate_speed(&mut self) {
+        self.speed = if self.low_speed_only {
+            Speed::Low
+        } else if self.full_speed_only || self.host_speed == Speed::Full {
+            Speed::Full
+        } else if self.host_speed == Speed::Low {
+            Speed::Low
+        } else {
+            Speed::High
+        };
+    }
+
+    fn read_controller(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.connect.into(),
+            0x04 => self.speed as u32,
+            0x08 => self.low_speed_only.into(),
+            0x0c => self.full_speed_only.into(),
+            0x10 => self.controller_events.status(),
+            0x14 => self.controller_events.pending(),
+            0x18 => self.controller_events.enable(),
+            _ => 0,
+        }
+    }
+
+    fn write_controller(&mut self, offset: usize, value: u32) {
+        let bit = value & 1 != 0;
+        match offset {
+            0x00 => {
+                if bit && !self.connect {
+                    self.negotiate_speed();
+                }
+                self.connect = bit;
+            }
+            0x08 => self.low_speed_only = bit,
+            0x0c => self.full_speed_only = bit,
+            0x14 => self.controller_events.clear(value),
+            0x18 => self.controller_events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+
+    fn read_control(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.setup.pop_front().unwrap_or(0).into(),
+            0x08 => self.setup_epno.into(),
+            0x0c => u32::from(!self.setup.is_empty()),
+            0x10 => self.control_events.pending(),
+            0x14 => self.address.into(),
+            0x20 => self.control_events.status(),
+            0x24 => self.control_events.pending(),
+            0x28 => self.control_events.enable(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write_control(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x04 if value & 1 != 0 => self.setup.clear(),
+            0x14 => self.address = value as u8 & 0x7f,
+            0x24 => self.control_events.clear(value),
+            0x28 => self.control_events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+
+    fn read_in(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x04 => self.in_epno.into(),
+            0x0c => self.in_stall.into(),
+            0x10 => u32::from(!self.in_primed),
+            0x14 => u32::from(!self.in_fifo.is_empty()),
+            0x18 => self.in_events.pending(),
+            0x1c => u32::from(self.in_pid >> self.in_epno & 1),
+            0x20 => u32::from(core::mem::take(&mut self.nak)),
+            0x40 => self.in_events.status(),
+            0x44 => self.in_events.pending(),
+            0x48 => self.in_events.enable(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write_in(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x00 => self.in_fifo.push_back(value as u8),
+            0x04 => {
+                self.in_epno = value as u8 & 0xf;
+                self.in_primed = true;
+                if self.polling & (1 << self.in_epno) != 0 && !self.in_stall {
+                    self.complete_in();
+                }
+            }
+            0x08 if value & 1 != 0 => {
+                self.in_fifo.clear();
+                self.in_primed = false;
+            }
+            0x0c => self.in_stall = value & 1 != 0,
+            0x1c => {
+                let mask = 1 << self.in_epno;
+                if value & 1 != 0 {
+                    self.in_pid |= mask;
+                } else {
+                    self.in_pid &= !mask;
+                }
+            }
+            0x44 => self.in_events.clear(value),
+            0x48 => self.in_events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+
+    fn read_out(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x00 => self.out_fifo.pop_front().unwrap_or(0).into(),
+            0x04 => self.out_data_ep.into(),
+            0x0c => self.out_epno.into(),
+            0x10 => self.out_enable.into(),
+            0x14 => u32::from(self.out_primed >> self.out_epno & 1),
+            0x18 => u32::from(self.out_stall >> self.out_epno & 1),
+            0x1c => u32::from(!self.out_fifo.is_empty()),
+            0x20 => self.out_events.pending(),
+            0x24 => self.out_address.into(),
+            0x28 => u32::from(self.out_pid >> self.out_epno & 1),
+            0x40 => self.out_events.status(),
+            0x44 => self.out_events.pending(),
+            0x48 => self.out_events.enable(),
+            _ => 0,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write_out(&mut self, offset: usize, value: u32) {
+        let mask = 1 << self.out_epno;
+        let bit = value & 1 != 0;
+        match offset {
+            0x08 if bit => self.out_fifo.clear(),
+            0x0c => self.out_epno = value as u8 & 0xf,
+            0x10 => self.out_enable = bit,
+            0x14 if bit => self.out_primed |= mask,
+            0x14 => self.out_primed &= !mask,
+            0x18 if bit => self.out_stall |= mask,
+            0x18 => self.out_stall &= !mask,
+            0x24 => self.out_address = value as u8 & 0x7f,
+            0x28 if bit => self.out_pid |= mask,
+            0x28 => self.out_pid &= !mask,
+            0x44 => self.out_events.clear(value),
+            0x48 => self.out_events.set_enable(value & 1),
+            _ => (),
+        }
+    }
+}
+
+impl Peripheral for Eptri {
+    fn read(&mut self, offset: usize) -> u32 {
+        match offset {
+            0x000..=0x03f => self.read_controller(offset),
+            0x040..=0x07f => self.read_control(offset - 0x40),
+            0x080..=0x0ff => self.read_in(offset - 0x80),
+            _ => self.read_out(offset - 0x100),
+        }
+    }
+
+    fn write(&mut self, offset: usize, value: u32) {
+        match offset {
+            0x000..=0x03f => self.write_controller(offset, value),
+            0x040..=0x07f => self.write_control(offset - 0x40, value),
+            0x080..=0x0ff => self.write_in(offset - 0x80, value),
+            _ => self.write_out(offset - 0x100, value),
+        }
+    }
+
+    /// One interrupt line per register block: controller, SETUP, IN and OUT.
+    fn irq(&self) -> u32 {
+        u32::from(self.controller_events.irq())
+            | u32::from(self.control_events.irq()) << 1
+            | u32::from(self.in_events.irq()) << 2
+            | u32::from(self.out_events.irq()) << 3
+    }
+}
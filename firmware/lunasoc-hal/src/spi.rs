@@ -0,0 +1,295 @@
+#[cfg(not(feature = "sim"))]
+use core::ptr::{read_volatile, write_volatile};
+
+#[cfg(feature = "sim")]
+use crate::sim::bus::{read_volatile, write_volatile};
+
+/// Re-export hal spi error type
+pub use crate::hal::spi::ErrorKind as Error;
+
+/// Number of register polls before an SPI transfer times out
+pub const SPI_TIMEOUT: usize = 100_000;
+
+/// Raw pointers to the SPI controller registers used during a transfer.
+///
+/// On `SoC`s executing directly from SPI flash the controller shares its
+/// PHY with the memory-mapped flash interface. Instruction fetches from
+/// flash will therefore stall for as long as the controller holds chip
+/// select. To avoid deadlocking the CPU the functions performing the
+/// actual transfers are placed in RAM and only access the registers via
+/// these pointers.
+///
+/// [`SpiDevice::transaction`](crate::hal::spi::SpiDevice::transaction)
+/// is placed in RAM as well but the closure it is called with is only
+/// safe if it is inlined into the transaction or otherwise only calls
+/// functions placed in RAM.
+#[derive(Clone, Copy, Debug)]
+pub struct Registers {
+    pub rxtx: *mut u32,
+    pub tx_rdy: *mut u8,
+    pub rx_rdy: *mut u8,
+}
+
+#[inline(always)]
+unsafe fn wait_ready(register: *mut u8) -> Result<(), Error> {
+    let mut timeout = 0;
+    while read_volatile(register) & 1 == 0 {
+        timeout += 1;
+        if timeout > SPI_TIMEOUT {
+            return Err(Error::Other);
+        }
+    }
+    Ok(())
+}
+
+#[inline(always)]
+unsafe fn exchange(registers: &Registers, word: u8) -> Result<u8, Error> {
+    wait_ready(registers.tx_rdy)?;
+    write_volatile(registers.rxtx, u32::from(word));
+    wait_ready(registers.rx_rdy)?;
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(read_volatile(registers.rxtx) as u8)
+}
+
+/// Exchange `max(read.len(), write.len())` words with the device.
+///
+/// Words written beyond the end of `write` are zero and words read
+/// beyond the end of `read` are discarded.
+///
+/// # Safety
+///
+/// `registers` must point to the registers of an SPI controller.
+#[inline(never)]
+#[cfg_attr(not(feature = "sim"), link_section = ".data.lunasoc_hal.spi.transfer")]
+pub unsafe fn transfer(registers: &Registers, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+    let length = if read.len() > write.len() {
+        read.len()
+    } else {
+        write.len()
+    };
+    for index in 0..length {
+        let word = match write.get(index) {
+            Some(word) => *word,
+            None => 0,
+        };
+        let word = exchange(registers, word)?;
+        if let Some(dest) = read.get_mut(index) {
+            *dest = word;
+        }
+    }
+    Ok(())
+}
+
+/// Exchange `words.len()` words with the device, replacing the
+/// contents of `words` with the words read.
+///
+/// # Safety
+///
+/// `registers` must point to the registers of an SPI controller.
+#[inline(never)]
+#[cfg_attr(
+    not(feature = "sim"),
+    link_section = ".data.lunasoc_hal.spi.transfer_in_place"
+)]
+pub unsafe fn transfer_in_place(registers: &Registers, words: &mut [u8]) -> Result<(), Error> {
+    for word in words.iter_mut() {
+        *word = exchange(registers, *word)?;
+    }
+    Ok(())
+}
+
+/// Macro to generate SPI master wrappers for `pac::SPIx` peripherals
+///
+/// The controller drives a single device using its own chip select
+/// and implements [`SpiDevice`](crate::hal::spi::SpiDevice) as well as
+/// [`SpiBus`](crate::hal::spi::SpiBus).
+///
+/// For example:
+///
+///     impl_spi! {
+///         Spi0: pac::SPI0,
+///     }
+///
+#[macro_export]
+macro_rules! impl_spi {
+    ($(
+        $SPIX:ident: $PACSPIX:ty,
+    )+) => {
+        $(
+            #[derive(Debug)]
+            pub struct $SPIX {
+                registers: $PACSPIX,
+            }
+
+            // lifecycle
+            impl $SPIX {
+                /// Create a new `Spi` from the [`SPI0`](crate::pac::SPI0) peripheral.
+                pub fn new(registers: $PACSPIX) -> Self {
+                    let mut spi = Self { registers };
+                    spi.configure();
+                    spi
+                }
+
+                /// Release the [`SPI0`](crate::pac::SPI0) peripheral and consume self.
+                pub fn free(self) -> $PACSPIX {
+                    self.registers
+                }
+
+                /// Obtain a static `Spi` instance for use in e.g. interrupt handlers
+                ///
+                /// # Safety
+                ///
+                /// 'Tis thine responsibility, that which thou doth summon.
+                pub unsafe fn summon() -> Self {
+                    Self {
+                        registers: <$PACSPIX>::steal(),
+                    }
+                }
+            }
+
+            // trait: From
+            impl From<$PACSPIX> for $SPIX {
+                fn from(registers: $PACSPIX) -> $SPIX {
+                    $SPIX::new(registers)
+                }
+            }
+
+            // configuration
+            impl $SPIX {
+                /// Configure the phy for single-bit, 8-bit word transfers and release chip select.
+                pub fn configure(&mut self) {
+                    self.registers.phy_len().write(|w| unsafe { w.phy_len().bits(8) });
+                    self.registers.phy_width().write(|w| unsafe { w.phy_width().bits(1) });
+                    self.registers.phy_mask().write(|w| unsafe { w.phy_mask().bits(1) });
+                    self.registers.cs().write(|w| w.cs().bit(false));
+                }
+
+                /// Assert or release chip select.
+                ///
+                /// Chip select is only asserted once the first word is
+                /// written and is held until it is released and the
+                /// transmit FIFO has emptied.
+                #[inline(always)]
+                pub fn set_cs(&mut self, active: bool) {
+                    self.registers.cs().write(|w| w.cs().bit(active));
+                }
+
+                #[inline(always)]
+                fn raw_registers(&self) -> $crate::spi::Registers {
+                    $crate::spi::Registers {
+                        rxtx: self.registers.rxtx().as_ptr(),
+                        tx_rdy: self.registers.tx_rdy().as_ptr(),
+                        rx_rdy: self.registers.rx_rdy().as_ptr(),
+                    }
+                }
+            }
+
+            // - embedded_hal 1.0 traits --------------------------------------
+
+            // trait: hal::spi::ErrorType
+            impl $crate::hal::spi::ErrorType for $SPIX {
+                type Error = $crate::spi::Error;
+            }
+
+            // trait: hal::spi::SpiBusFlush
+            impl $crate::hal::spi::SpiBusFlush for $SPIX {
+                #[inline(always)]
+                fn flush(&mut self) -> Result<(), Self::Error> {
+                    // every transfer waits for its response so there's nothing to flush
+                    Ok(())
+                }
+            }
+
+            // trait: hal::spi::SpiBusRead
+            impl $crate::hal::spi::SpiBusRead<u8> for $SPIX {
+                #[inline(always)]
+                fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+                    let registers = self.raw_registers();
+                    unsafe { $crate::spi::transfer(&registers, words, &[]) }
+                }
+            }
+
+            // trait: hal::spi::SpiBusWrite
+            impl $crate::hal::spi::SpiBusWrite<u8> for $SPIX {
+                #[inline(always)]
+                fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+                    let registers = self.raw_registers();
+                    unsafe { $crate::spi::transfer(&registers, &mut [], words) }
+                }
+            }
+
+            // trait: hal::spi::SpiBus
+            impl $crate::hal::spi::SpiBus<u8> for $SPIX {
+                fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+                    let registers = self.raw_registers();
+                    unsafe { $crate::spi::transfer(&registers, read, write) }
+                }
+
+                fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+                    let registers = self.raw_registers();
+                    unsafe { $crate::spi::transfer_in_place(&registers, words) }
+                }
+            }
+
+            // trait: hal::spi::SpiDevice
+            impl $crate::hal::spi::SpiDevice for $SPIX {
+                type Bus = Self;
+
+                #[inline(never)]
+                #[cfg_attr(not(feature = "sim"), link_section = ".data.lunasoc_hal.spi.transaction")]
+                fn transaction<R>(
+                    &mut self,
+                    f: impl FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as $crate::hal::spi::ErrorType>::Error>,
+                ) -> Result<R, Self::Error> {
+                    use $crate::hal::spi::SpiBusFlush;
+
+                    self.set_cs(true);
+                    let result = f(self);
+                    let flush = self.flush();
+                    self.set_cs(false);
+
+                    let result = result?;
+                    flush?;
+
+                    Ok(result)
+                }
+            }
+        )+
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use crate::hal::spi::SpiBus;
+    use crate::sim::{self, pac};
+
+    use drivers::Spi0;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use super::pac;
+
+        crate::impl_spi! {
+            Spi0: pac::SPI0,
+        }
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_spi() {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut spi0 = Spi0::new(peripherals.SPI0);
+        sim::with(|soc| soc.spi0.attach(|mosi: u8| mosi.wrapping_add(1)));
+
+        spi0.set_cs(true);
+        let mut read = [0; 3];
+        spi0.transfer(&mut read, &[1, 2, 3]).unwrap();
+        assert!(sim::with(|soc| soc.spi0.is_selected()));
+        spi0.set_cs(false);
+
+        assert_eq!(read, [2, 3, 4]);
+        assert!(!sim::with(|soc| soc.spi0.is_selected()));
+    }
+}
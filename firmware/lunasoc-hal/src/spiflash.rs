@@ -0,0 +1,728 @@
+//! Driver for JEDEC compatible serial NOR flash devices
+//!
+//! The driver works with any [`SpiDevice`] and uses the Serial Flash
+//! Discoverable Parameters (SFDP) of the device, where available, to
+//! determine its geometry.
+//!
+//! For example:
+//!
+//!     let spi0 = hal::Spi0::new(peripherals.SPI0);
+//!     let mut flash = Flash::new(spi0);
+//!     let jedec_id = flash.jedec_id()?;
+//!     let unique_id = flash.unique_id()?;
+//!
+//! Program and erase operations run from RAM with interrupts disabled
+//! so that they can be used on the flash the firmware executes from.
+//! See [`crate::spi::Registers`] for details.
+
+use crate::hal::spi::{SpiBus, SpiBusRead, SpiBusWrite, SpiDevice};
+
+/// Number of status register polls before an operation times out
+pub const FLASH_TIMEOUT: usize = 10_000_000;
+
+/// Flash commands
+#[allow(non_snake_case, non_upper_case_globals)]
+pub mod Command {
+    pub const WriteEnable: u8 = 0x06;
+    pub const ReadStatus1: u8 = 0x05;
+    pub const PageProgram: u8 = 0x02;
+    pub const Read: u8 = 0x03;
+    pub const FastRead: u8 = 0x0b;
+    pub const SectorErase: u8 = 0x20;
+    pub const BlockErase32K: u8 = 0x52;
+    pub const BlockErase64K: u8 = 0xd8;
+    pub const ChipErase: u8 = 0xc7;
+    pub const ReadSfdp: u8 = 0x5a;
+    pub const ReadUniqueId: u8 = 0x4b;
+    pub const ReadJedecId: u8 = 0x9f;
+    pub const ReleasePowerDown: u8 = 0xab;
+}
+
+/// Status register 1 bits
+#[allow(non_snake_case, non_upper_case_globals)]
+pub mod Status {
+    /// Write in progress
+    pub const Busy: u8 = 0b0000_0001;
+    /// Write enable latch
+    pub const WriteEnableLatch: u8 = 0b0000_0010;
+}
+
+/// Flash driver error type
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// An error occurred on the SPI bus.
+    Spi(E),
+    /// The device did not finish an operation in time.
+    Timeout,
+    /// The address is not aligned or outside the addressable range.
+    InvalidAddress,
+    /// The address and length describe a range outside the device.
+    OutOfBounds,
+    /// The device did not accept the write enable command.
+    WriteEnable,
+    /// The device does not have a valid SFDP table.
+    InvalidSfdp,
+}
+
+/// Device identification returned by the JEDEC ID command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JedecId {
+    pub manufacturer: u8,
+    pub memory_type: u8,
+    pub capacity: u8,
+}
+
+impl JedecId {
+    /// Device capacity in bytes as encoded by most manufacturers.
+    #[must_use]
+    pub fn capacity_bytes(&self) -> Option<usize> {
+        1_usize.checked_shl(u32::from(self.capacity))
+    }
+}
+
+/// An erase operation supported by the device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EraseType {
+    /// Size of the region erased, in bytes.
+    pub size: usize,
+    /// Command opcode.
+    pub opcode: u8,
+}
+
+/// Device geometry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Geometry {
+    /// Device capacity in bytes.
+    pub capacity: usize,
+    /// Page size in bytes.
+    pub page_size: usize,
+    /// Supported erase operations, ordered by size.
+    pub erase_types: [Option<EraseType>; 4],
+}
+
+impl Geometry {
+    /// Conservative defaults for a 24-bit addressable device of the
+    /// given capacity.
+    #[must_use]
+    pub const fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            page_size: 256,
+            erase_types: [
+                Some(EraseType {
+                    size: 4 * 1024,
+                    opcode: Command::SectorErase,
+                }),
+                Some(EraseType {
+                    size: 32 * 1024,
+                    opcode: Command::BlockErase32K,
+                }),
+                Some(EraseType {
+                    size: 64 * 1024,
+                    opcode: Command::BlockErase64K,
+                }),
+                None,
+            ],
+        }
+    }
+
+    /// Returns the erase operation for the given size, if supported.
+    #[must_use]
+    pub fn erase_type(&self, size: usize) -> Option<EraseType> {
+        self.erase_types
+            .iter()
+            .flatten()
+            .find(|erase_type| erase_type.size == size)
+            .copied()
+    }
+}
+
+/// Serial NOR flash driver.
+pub struct Flash<SPI> {
+    spi: SPI,
+    geometry: Geometry,
+}
+
+impl<SPI, E> Flash<SPI>
+where
+    SPI: SpiDevice<Error = E>,
+    SPI::Bus: SpiBus<u8>,
+    E: crate::hal::spi::Error,
+{
+    /// Create a new `Flash` driver for a device on the given bus.
+    ///
+    /// The device geometry defaults to a 16 MiB device with 256 byte pages
+    /// and 4K/32K/64K erase sizes until [`Flash::discover`] is called.
+    pub fn new(spi: SPI) -> Self {
+        Self {
+            spi,
+            geometry: Geometry::with_capacity(16 * 1024 * 1024),
+        }
+    }
+
+    /// Release the underlying [`SpiDevice`] and consume self.
+    pub fn free(self) -> SPI {
+        self.spi
+    }
+
+    /// Returns the device geometry.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Identify the device and read its geometry from SFDP, falling
+    /// back to the capacity encoded in the JEDEC ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    pub fn discover(&mut self) -> Result<Geometry, Error<E>> {
+        self.release_power_down()?;
+        let jedec_id = self.jedec_id()?;
+        self.geometry = match self.read_sfdp_geometry() {
+            Ok(geometry) => geometry,
+            Err(Error::InvalidSfdp) => {
+                let capacity = jedec_id.capacity_bytes().ok_or(Error::InvalidSfdp)?;
+                Geometry::with_capacity(capacity)
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(self.geometry)
+    }
+
+    // - commands --
+    //
+    // Everything called while the device is selected or busy must be
+    // placed in RAM or inlined into a function placed in RAM.
+
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.command"
+    )]
+    fn command(&mut self, command: &[u8]) -> Result<(), Error<E>> {
+        self.spi
+            .transaction(|bus| SpiBusWrite::write(bus, command))
+            .map_err(Error::Spi)
+    }
+
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.command_read"
+    )]
+    fn command_read(&mut self, command: &[u8], buffer: &mut [u8]) -> Result<(), Error<E>> {
+        self.spi
+            .transaction(|bus| {
+                SpiBusWrite::write(bus, command)?;
+                SpiBusRead::read(bus, buffer)
+            })
+            .map_err(Error::Spi)
+    }
+
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.command_write"
+    )]
+    fn command_write(&mut self, command: &[u8], data: &[u8]) -> Result<(), Error<E>> {
+        self.spi
+            .transaction(|bus| {
+                SpiBusWrite::write(bus, command)?;
+                SpiBusWrite::write(bus, data)
+            })
+            .map_err(Error::Spi)
+    }
+
+    fn address_command(&self, opcode: u8, address: usize) -> Result<[u8; 4], Error<E>> {
+        if address >= self.geometry.capacity || address > 0x00ff_ffff {
+            return Err(Error::InvalidAddress);
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let [_, a2, a1, a0] = (address as u32).to_be_bytes();
+        Ok([opcode, a2, a1, a0])
+    }
+
+    fn check_bounds(&self, address: usize, length: usize) -> Result<(), Error<E>> {
+        match address.checked_add(length) {
+            Some(end) if end <= self.geometry.capacity => Ok(()),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+
+    /// Wake the device from deep power-down.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command could not be sent.
+    pub fn release_power_down(&mut self) -> Result<(), Error<E>> {
+        self.command(&[Command::ReleasePowerDown])
+    }
+
+    /// Read the JEDEC manufacturer and device ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    pub fn jedec_id(&mut self) -> Result<JedecId, Error<E>> {
+        let mut response = [0_u8; 3];
+        self.command_read(&[Command::ReadJedecId], &mut response)?;
+        Ok(JedecId {
+            manufacturer: response[0],
+            memory_type: response[1],
+            capacity: response[2],
+        })
+    }
+
+    /// Read the device's 64-bit unique ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    pub fn unique_id(&mut self) -> Result<[u8; 8], Error<E>> {
+        let mut response = [0_u8; 8];
+        self.command_read(&[Command::ReadUniqueId, 0, 0, 0, 0], &mut response)?;
+        Ok(response)
+    }
+
+    /// Read status register 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.read_status"
+    )]
+    pub fn read_status(&mut self) -> Result<u8, Error<E>> {
+        let mut status = [0_u8; 1];
+        self.command_read(&[Command::ReadStatus1], &mut status)?;
+        Ok(status[0])
+    }
+
+    /// Returns `true` if the device is busy with a program or erase operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    pub fn is_busy(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_status()? & Status::Busy != 0)
+    }
+
+    /// Poll the status register until the device is idle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if the device remains busy.
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.wait_idle"
+    )]
+    pub fn wait_idle(&mut self) -> Result<(), Error<E>> {
+        let mut timeout = 0;
+        while self.read_status()? & Status::Busy != 0 {
+            timeout += 1;
+            if timeout > FLASH_TIMEOUT {
+                return Err(Error::Timeout);
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.write_enable"
+    )]
+    fn write_enable(&mut self) -> Result<(), Error<E>> {
+        self.command(&[Command::WriteEnable])?;
+        if self.read_status()? & Status::WriteEnableLatch == 0 {
+            return Err(Error::WriteEnable);
+        }
+        Ok(())
+    }
+
+    /// Enable writes, send a program or erase command followed by
+    /// `data` and wait for the device to complete it.
+    ///
+    /// The device can not be read until the operation completes so
+    /// interrupts are disabled for its duration.
+    #[inline(never)]
+    #[cfg_attr(
+        not(feature = "sim"),
+        link_section = ".data.lunasoc_hal.spiflash.execute"
+    )]
+    fn execute(&mut self, command: &[u8], data: &[u8]) -> Result<(), Error<E>> {
+        crate::interrupt::free(|| {
+            self.write_enable()?;
+            self.command_write(command, data)?;
+            self.wait_idle()
+        })
+    }
+
+    // - read --
+
+    /// Read `buffer.len()` bytes starting at `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if the range is outside the device or an
+    /// error if it could not be read.
+    pub fn read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        self.check_bounds(address, buffer.len())?;
+        let command = self.address_command(Command::Read, address)?;
+        self.command_read(&command, buffer)
+    }
+
+    /// Read `buffer.len()` bytes starting at `address` using the fast read command.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if the range is outside the device or an
+    /// error if it could not be read.
+    pub fn fast_read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        self.check_bounds(address, buffer.len())?;
+        let [opcode, a2, a1, a0] = self.address_command(Command::FastRead, address)?;
+        self.command_read(&[opcode, a2, a1, a0, 0], buffer)
+    }
+
+    // - program --
+
+    /// Program a single page.
+    ///
+    /// The data must not cross a page boundary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data crosses a page boundary or the operation failed.
+    pub fn page_program(&mut self, address: usize, data: &[u8]) -> Result<(), Error<E>> {
+        let page_size = self.geometry.page_size;
+        if data.is_empty() {
+            return Ok(());
+        }
+        if (address % page_size) + data.len() > page_size {
+            return Err(Error::InvalidAddress);
+        }
+        let command = self.address_command(Command::PageProgram, address)?;
+        self.execute(&command, data)
+    }
+
+    /// Program `data` starting at `address`, splitting it into pages as needed.
+    ///
+    /// The region must have been erased beforehand.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::OutOfBounds`] if the range is outside the device or an
+    /// error if the operation failed.
+    pub fn write(&mut self, address: usize, data: &[u8]) -> Result<(), Error<E>> {
+        self.check_bounds(address, data.len())?;
+        let page_size = self.geometry.page_size;
+        let mut address = address;
+        let mut data = data;
+        while !data.is_empty() {
+            let length = (page_size - (address % page_size)).min(data.len());
+            self.page_program(address, &data[..length])?;
+            address += length;
+            data = &data[length..];
+        }
+        Ok(())
+    }
+
+    // - erase --
+
+    /// Erase the region of `size` bytes at `address`.
+    ///
+    /// `size` must be one of the device's erase sizes and `address`
+    /// must be aligned to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the size is unsupported, the address is
+    /// unaligned or the operation failed.
+    pub fn erase(&mut self, address: usize, size: usize) -> Result<(), Error<E>> {
+        let erase_type = self
+            .geometry
+            .erase_type(size)
+            .ok_or(Error::InvalidAddress)?;
+        if address % erase_type.size != 0 {
+            return Err(Error::InvalidAddress);
+        }
+        let command = self.address_command(erase_type.opcode, address)?;
+        self.execute(&command, &[])
+    }
+
+    /// Erase the 4 KiB sector at `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is unaligned or the operation failed.
+    pub fn erase_sector(&mut self, address: usize) -> Result<(), Error<E>> {
+        self.erase(address, 4 * 1024)
+    }
+
+    /// Erase the 64 KiB block at `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is unaligned or the operation failed.
+    pub fn erase_block(&mut self, address: usize) -> Result<(), Error<E>> {
+        self.erase(address, 64 * 1024)
+    }
+
+    /// Erase the entire device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operation failed.
+    pub fn erase_chip(&mut self) -> Result<(), Error<E>> {
+        self.execute(&[Command::ChipErase], &[])
+    }
+
+    // - sfdp --
+
+    /// Read `buffer.len()` bytes from the SFDP table starting at `address`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the device could not be read.
+    pub fn read_sfdp(&mut self, address: u32, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        let [_, a2, a1, a0] = address.to_be_bytes();
+        self.command_read(&[Command::ReadSfdp, a2, a1, a0, 0], buffer)
+    }
+
+    /// Read the device geometry from the JEDEC Basic Flash Parameter Table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSfdp`] if the device does not have a valid SFDP table.
+    pub fn read_sfdp_geometry(&mut self) -> Result<Geometry, Error<E>> {
+        // SFDP header
+        let mut header = [0_u8; 8];
+        self.read_sfdp(0, &mut header)?;
+        if &header[0..4] != b"SFDP" {
+            return Err(Error::InvalidSfdp);
+        }
+        let parameter_headers = usize::from(header[6]) + 1;
+
+        // find the basic flash parameter table
+        let mut table = None;
+        for index in 0..parameter_headers {
+            let mut parameter_header = [0_u8; 8];
+            #[allow(clippy::cast_possible_truncation)]
+            self.read_sfdp(8 + 8 * index as u32, &mut parameter_header)?;
+            let id = u16::from_le_bytes([parameter_header[0], parameter_header[7]]);
+            if id == 0xff00 {
+                let length = usize::from(parameter_header[3]);
+                let pointer = u32::from_le_bytes([
+                    parameter_header[4],
+                    parameter_header[5],
+                    parameter_header[6],
+                    0,
+                ]);
+                table = Some((pointer, length));
+                break;
+            }
+        }
+        let (pointer, length) = table.ok_or(Error::InvalidSfdp)?;
+        if length < 9 {
+            return Err(Error::InvalidSfdp);
+        }
+
+        let mut dwords = [0_u32; 11];
+        let count = length.min(dwords.len());
+        for (index, dword) in dwords.iter_mut().take(count).enumerate() {
+            let mut bytes = [0_u8; 4];
+            #[allow(clippy::cast_possible_truncation)]
+            self.read_sfdp(pointer + 4 * index as u32, &mut bytes)?;
+            *dword = u32::from_le_bytes(bytes);
+        }
+
+        // 2nd dword: flash memory density
+        let density = dwords[1];
+        let capacity = if density & 0x8000_0000 == 0 {
+            (density as usize + 1) / 8
+        } else {
+            (density & 0x7fff_ffff)
+                .checked_sub(3)
+                .and_then(|exponent| 1_usize.checked_shl(exponent))
+                .ok_or(Error::InvalidSfdp)?
+        };
+
+        // 8th and 9th dwords: erase types
+        let mut erase_types = [None; 4];
+        for (index, erase_type) in erase_types.iter_mut().enumerate() {
+            let dword = dwords[7 + index / 2] >> (16 * (index % 2));
+            let exponent = dword & 0xff;
+            #[allow(clippy::cast_possible_truncation)]
+            let opcode = (dword >> 8) as u8;
+            if exponent != 0 {
+                *erase_type = Some(EraseType {
+                    size: 1 << exponent,
+                    opcode,
+                });
+            }
+        }
+        erase_types.sort_unstable_by_key(|erase_type| erase_type.map_or(usize::MAX, |e| e.size));
+
+        // 11th dword: page size
+        let page_size = if count >= 11 {
+            1 << ((dwords[10] >> 4) & 0xf)
+        } else {
+            256
+        };
+
+        Ok(Geometry {
+            capacity,
+            page_size,
+            erase_types,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::{Command, Error, Flash, Status};
+
+    use crate::sim::{self, pac};
+
+    use drivers::Spi0;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use super::pac;
+
+        crate::impl_spi! {
+            Spi0: pac::SPI0,
+        }
+    }
+
+    /// Minimal SPI NOR flash which stays busy for a few status polls
+    /// after each program or erase.
+    #[derive(Clone, Default)]
+    struct TestFlash(std::rc::Rc<core::cell::RefCell<FlashState>>);
+
+    #[derive(Default)]
+    struct FlashState {
+        memory: std::collections::BTreeMap<usize, u8>,
+        transaction: Vec<u8>,
+        write_enable_latch: bool,
+        busy_polls: usize,
+        status_polls: usize,
+    }
+
+    impl FlashState {
+        fn address(&self) -> usize {
+            let t = &self.transaction;
+            (usize::from(t[1]) << 16) | (usize::from(t[2]) << 8) | usize::from(t[3])
+        }
+    }
+
+    impl sim::spi::Device for TestFlash {
+        fn deselect(&mut self) {
+            let mut flash = self.0.borrow_mut();
+            let operation = match flash.transaction.first().copied() {
+                Some(Command::WriteEnable) => {
+                    flash.write_enable_latch = true;
+                    false
+                }
+                Some(Command::PageProgram) if flash.write_enable_latch => {
+                    let address = flash.address();
+                    let data = flash.transaction[4..].to_vec();
+                    for (offset, byte) in data.into_iter().enumerate() {
+                        flash.memory.insert(address + offset, byte);
+                    }
+                    true
+                }
+                Some(Command::SectorErase) if flash.write_enable_latch => {
+                    let address = flash.address();
+                    flash.memory.retain(|&a, _| a / 4096 != address / 4096);
+                    true
+                }
+                _ => false,
+            };
+            if operation {
+                flash.write_enable_latch = false;
+                flash.busy_polls = 3;
+            }
+            flash.transaction.clear();
+        }
+
+        fn exchange(&mut self, mosi: u8) -> u8 {
+            let mut flash = self.0.borrow_mut();
+            flash.transaction.push(mosi);
+            let index = flash.transaction.len() - 1;
+            match flash.transaction[0] {
+                Command::ReadStatus1 if index > 0 => {
+                    flash.status_polls += 1;
+                    let busy = flash.busy_polls > 0;
+                    flash.busy_polls = flash.busy_polls.saturating_sub(1);
+                    let mut status = 0;
+                    if busy {
+                        status |= Status::Busy;
+                    }
+                    if flash.write_enable_latch {
+                        status |= Status::WriteEnableLatch;
+                    }
+                    status
+                }
+                Command::Read if index > 3 => {
+                    let address = flash.address() + index - 4;
+                    flash.memory.get(&address).copied().unwrap_or(0xff)
+                }
+                _ => 0xff,
+            }
+        }
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_spiflash_program_erase() {
+        let device = TestFlash::default();
+        sim::with(|soc| soc.spi0.attach(device.clone()));
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut flash = Flash::new(Spi0::new(peripherals.SPI0));
+
+        // writes are split at page boundaries and wait for the device
+        let data: Vec<u8> = (0..=255).cycle().skip(7).take(300).collect();
+        flash.write(0x10f0, &data).unwrap();
+        assert_eq!(device.0.borrow().memory.len(), 300);
+        assert!(device.0.borrow().status_polls >= 2 * 4);
+
+        let mut buffer = [0; 300];
+        flash.read(0x10f0, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], &data[..]);
+
+        // erase returns the sector to its erased state
+        flash.erase_sector(0x1000).unwrap();
+        flash.read(0x10f0, &mut buffer).unwrap();
+        assert!(buffer.iter().all(|&byte| byte == 0xff));
+
+        // operations fail if the device does not latch write enable
+        sim::with(|soc| soc.spi0.attach(|_| 0));
+        assert_eq!(flash.erase_sector(0), Err(Error::WriteEnable));
+    }
+
+    #[test]
+    fn test_spiflash_bounds() {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut flash = Flash::new(Spi0::new(peripherals.SPI0));
+        let capacity = flash.geometry().capacity;
+
+        let mut buffer = [0; 4];
+        assert_eq!(
+            flash.read(usize::MAX - 1, &mut buffer),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(
+            flash.fast_read(capacity - 2, &mut buffer),
+            Err(Error::OutOfBounds)
+        );
+        assert_eq!(flash.write(usize::MAX, &buffer), Err(Error::OutOfBounds));
+        assert_eq!(flash.erase_sector(0x123), Err(Error::InvalidAddress));
+    }
+}
@@ -1,3 +1,10 @@
+use core::cell::UnsafeCell;
+#[cfg(not(feature = "sim"))]
+use core::ptr::{read_volatile, write_volatile};
+
+#[cfg(feature = "sim")]
+use crate::sim::bus::{read_volatile, write_volatile};
+
 /// Timer Events
 ///
 /// Each event is a possible interrupt source, if enabled.
@@ -6,6 +13,394 @@ pub enum Event {
     TimeOut,
 }
 
+// - Monotonic ----------------------------------------------------------------
+
+/// Monotonic clock period for a free-running timer, the longest possible.
+pub const MONOTONIC_FREE_RUNNING: u32 = u32::MAX;
+
+/// Number of alarms supported by a [`Monotonic`] clock.
+pub const MONOTONIC_ALARMS: usize = 4;
+
+/// Number of ticks before the counter reaches zero below which it is
+/// left alone rather than reprogrammed for an alarm.
+const MONOTONIC_REPROGRAM_MARGIN: u32 = 16;
+
+/// Raw pointers to the timer registers used by the [`Monotonic`] clock.
+#[derive(Clone, Copy, Debug)]
+pub struct Registers {
+    pub ctr: *mut u32,
+    pub ev_pending: *mut u32,
+}
+
+/// Alarm callback and its context.
+type Callback = (fn(*mut ()), *mut ());
+
+#[derive(Clone, Copy)]
+struct Alarm {
+    /// Expiry time in timer ticks, `u64::MAX` if not set.
+    timestamp: u64,
+    callback: Option<Callback>,
+}
+
+impl Alarm {
+    const NONE: Alarm = Alarm {
+        timestamp: u64::MAX,
+        callback: None,
+    };
+}
+
+struct Inner {
+    registers: Option<Registers>,
+    /// Timer clock frequency.
+    clk: u32,
+    /// Reload value of the counter, one period is `period + 1` ticks.
+    period: u32,
+    /// Counter value at the start of the current segment, either
+    /// `period` or a shorter count to the nearest alarm.
+    segment: u32,
+    /// Ticks elapsed at the start of the current segment.
+    base: u64,
+    allocated: usize,
+    alarms: [Alarm; MONOTONIC_ALARMS],
+}
+
+impl Inner {
+    /// Ticks elapsed since the clock was started.
+    fn now(&self) -> u64 {
+        let Some(registers) = self.registers else {
+            return 0;
+        };
+        let (counter, pending) = unsafe {
+            (
+                read_volatile(registers.ctr),
+                read_volatile(registers.ev_pending) & 1 != 0,
+            )
+        };
+
+        // the counter reloaded but the interrupt has not been serviced yet
+        if pending && counter > self.period / 2 {
+            self.base + u64::from(self.segment) + 1 + u64::from(self.period - counter)
+        } else {
+            self.base + u64::from(self.segment - counter.min(self.segment))
+        }
+    }
+
+    /// Shorten the current segment so that the counter reaches zero,
+    /// and raises its interrupt, when the nearest alarm expires.
+    fn schedule(&mut self) {
+        let Some(registers) = self.registers else {
+            return;
+        };
+        let Some(next) = self
+            .alarms
+            .iter()
+            .map(|alarm| alarm.timestamp)
+            .min()
+            .filter(|timestamp| *timestamp != u64::MAX)
+        else {
+            return;
+        };
+
+        // read the counter first so a reload in between is seen as pending
+        let (counter, pending) = unsafe {
+            (
+                read_volatile(registers.ctr).min(self.segment),
+                read_volatile(registers.ev_pending) & 1 != 0,
+            )
+        };
+        if pending || counter < MONOTONIC_REPROGRAM_MARGIN {
+            // the interrupt handler will schedule the alarm
+            return;
+        }
+
+        let now = self.base + u64::from(self.segment - counter);
+        let ticks = next.saturating_sub(now).max(1);
+        if ticks < u64::from(counter) {
+            #[allow(clippy::cast_possible_truncation)]
+            let ticks = ticks as u32;
+            self.base = now;
+            self.segment = ticks;
+            unsafe { write_volatile(registers.ctr, ticks) };
+        }
+    }
+}
+
+/// A 64-bit monotonic clock derived from a timer peripheral.
+///
+/// The timer counts down from `period` and reloads, raising its
+/// `TimeOut` event. The clock extends the 32-bit counter by counting
+/// the reloads in [`Monotonic::on_interrupt`], which must therefore be
+/// called from the timer's interrupt handler at least once per period.
+///
+/// When an alarm expires before the counter next reaches zero the
+/// counter is reprogrammed to reach zero at the alarm's timestamp
+/// instead. The few ticks between reading and rewriting the counter
+/// are lost so setting alarms causes the clock to drift slightly.
+pub struct Monotonic {
+    inner: UnsafeCell<Inner>,
+}
+
+// Safety: all accesses happen inside `interrupt::free`
+unsafe impl Sync for Monotonic {}
+
+impl Monotonic {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: UnsafeCell::new(Inner {
+                registers: None,
+                clk: 0,
+                period: 0,
+                segment: 0,
+                base: 0,
+                allocated: 0,
+                alarms: [Alarm::NONE; MONOTONIC_ALARMS],
+            }),
+        }
+    }
+
+    /// Start the clock, called by the timer once it has been configured.
+    ///
+    /// # Safety
+    ///
+    /// `registers` must point to the registers of a timer counting
+    /// down from `period`.
+    pub unsafe fn start(&self, registers: Registers, clk: u32, period: u32) {
+        self.with(|inner| {
+            inner.registers = Some(registers);
+            inner.clk = clk;
+            inner.period = period;
+            inner.segment = period;
+            inner.base = 0;
+        });
+    }
+
+    /// Returns `true` if the clock has been started.
+    pub fn is_running(&self) -> bool {
+        self.with(|inner| inner.registers.is_some())
+    }
+
+    /// Timer clock frequency in Hz.
+    pub fn clk(&self) -> u32 {
+        self.with(|inner| inner.clk)
+    }
+
+    /// Ticks elapsed since the clock was started.
+    pub fn now(&self) -> u64 {
+        self.with(|inner| inner.now())
+    }
+
+    /// Microseconds elapsed since the clock was started.
+    pub fn now_micros(&self) -> u64 {
+        let (ticks, clk) = self.with(|inner| (inner.now(), inner.clk));
+        ticks_to_hz(ticks, clk, 1_000_000)
+    }
+
+    /// Convert a duration in microseconds to timer ticks.
+    pub fn micros_to_ticks(&self, micros: u64) -> u64 {
+        hz_to_ticks(micros, self.clk(), 1_000_000)
+    }
+
+    /// Busy-wait for the given number of microseconds.
+    ///
+    /// Returns immediately if the clock has not been started.
+    pub fn delay_us(&self, us: u32) {
+        self.delay_ticks(self.micros_to_ticks(u64::from(us)));
+    }
+
+    /// Busy-wait for the given number of milliseconds.
+    ///
+    /// Returns immediately if the clock has not been started.
+    pub fn delay_ms(&self, ms: u32) {
+        self.delay_ticks(self.micros_to_ticks(u64::from(ms) * 1_000));
+    }
+
+    fn delay_ticks(&self, ticks: u64) {
+        if !self.is_running() {
+            return;
+        }
+        let deadline = self.now() + ticks;
+        while self.now() < deadline {}
+    }
+
+    /// Allocate an alarm, returning its index.
+    pub fn allocate_alarm(&self) -> Option<usize> {
+        self.with(|inner| {
+            if inner.allocated < MONOTONIC_ALARMS {
+                inner.allocated += 1;
+                Some(inner.allocated - 1)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Set the function to call when the given alarm expires.
+    ///
+    /// The callback is invoked from the timer's interrupt handler.
+    pub fn set_alarm_callback(&self, alarm: usize, callback: fn(*mut ()), context: *mut ()) {
+        self.with(|inner| {
+            if let Some(alarm) = inner.alarms.get_mut(alarm) {
+                alarm.callback = Some((callback, context));
+            }
+        });
+    }
+
+    /// Set the given alarm to expire at `timestamp` ticks.
+    ///
+    /// Returns `false`, without setting the alarm, if `timestamp` has
+    /// already passed.
+    pub fn set_alarm(&self, alarm: usize, timestamp: u64) -> bool {
+        self.with(|inner| {
+            let now = inner.now();
+            let Some(alarm) = inner.alarms.get_mut(alarm) else {
+                return false;
+            };
+            if timestamp <= now {
+                alarm.timestamp = u64::MAX;
+                false
+            } else {
+                alarm.timestamp = timestamp;
+                inner.schedule();
+                true
+            }
+        })
+    }
+
+    /// Extend the clock and fire any expired alarms, must be called
+    /// from the timer's interrupt handler.
+    pub fn on_interrupt(&self) {
+        let mut expired: [Option<Callback>; MONOTONIC_ALARMS] = [None; MONOTONIC_ALARMS];
+
+        self.with(|inner| {
+            let Some(registers) = inner.registers else {
+                return;
+            };
+            unsafe {
+                // the counter reloads on the tick after it reaches zero,
+                // leave the event pending until it has
+                if read_volatile(registers.ev_pending) & 1 != 0 && read_volatile(registers.ctr) != 0
+                {
+                    write_volatile(registers.ev_pending, 1);
+                    inner.base += u64::from(inner.segment) + 1;
+                    inner.segment = inner.period;
+                }
+            }
+
+            let now = inner.now();
+            for (alarm, expired) in inner.alarms.iter_mut().zip(expired.iter_mut()) {
+                if alarm.timestamp <= now {
+                    alarm.timestamp = u64::MAX;
+                    *expired = alarm.callback;
+                }
+            }
+            inner.schedule();
+        });
+
+        // invoke callbacks outside the critical section so they can set new alarms
+        for (callback, context) in expired.into_iter().flatten() {
+            callback(context);
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Inner) -> R) -> R {
+        crate::interrupt::free(|| f(unsafe { &mut *self.inner.get() }))
+    }
+}
+
+impl Default for Monotonic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert `ticks` of a `clk` Hz clock to units of a `hz` Hz clock, rounding down.
+fn ticks_to_hz(ticks: u64, clk: u32, hz: u64) -> u64 {
+    let clk = u64::from(clk.max(1));
+    (ticks / clk) * hz + (ticks % clk) * hz / clk
+}
+
+/// Convert `units` of a `hz` Hz clock to ticks of a `clk` Hz clock, rounding up.
+fn hz_to_ticks(units: u64, clk: u32, hz: u64) -> u64 {
+    let clk = u64::from(clk);
+    (units / hz) * clk + ((units % hz) * clk).div_ceil(hz)
+}
+
+// - embassy-time driver ------------------------------------------------------
+
+/// `embassy_time_driver::Driver` implementation for a [`Monotonic`] clock.
+///
+/// Use the [`impl_embassy_time_driver!`](crate::impl_embassy_time_driver)
+/// macro to register a timer as the time driver.
+#[cfg(feature = "embassy-time")]
+pub struct EmbassyTimeDriver {
+    monotonic: fn() -> &'static Monotonic,
+}
+
+#[cfg(feature = "embassy-time")]
+impl EmbassyTimeDriver {
+    #[must_use]
+    pub const fn new(monotonic: fn() -> &'static Monotonic) -> Self {
+        Self { monotonic }
+    }
+}
+
+#[cfg(feature = "embassy-time")]
+impl embassy_time_driver::Driver for EmbassyTimeDriver {
+    fn now(&self) -> u64 {
+        let monotonic = (self.monotonic)();
+        ticks_to_hz(
+            monotonic.now(),
+            monotonic.clk(),
+            embassy_time_driver::TICK_HZ,
+        )
+    }
+
+    unsafe fn allocate_alarm(&self) -> Option<embassy_time_driver::AlarmHandle> {
+        #[allow(clippy::cast_possible_truncation)]
+        (self.monotonic)()
+            .allocate_alarm()
+            .map(|alarm| embassy_time_driver::AlarmHandle::new(alarm as u8))
+    }
+
+    fn set_alarm_callback(
+        &self,
+        alarm: embassy_time_driver::AlarmHandle,
+        callback: fn(*mut ()),
+        context: *mut (),
+    ) {
+        (self.monotonic)().set_alarm_callback(usize::from(alarm.id()), callback, context);
+    }
+
+    fn set_alarm(&self, alarm: embassy_time_driver::AlarmHandle, timestamp: u64) -> bool {
+        let monotonic = (self.monotonic)();
+        let timestamp = if timestamp == u64::MAX {
+            u64::MAX
+        } else {
+            hz_to_ticks(timestamp, monotonic.clk(), embassy_time_driver::TICK_HZ)
+        };
+        monotonic.set_alarm(usize::from(alarm.id()), timestamp)
+    }
+}
+
+/// Macro to register a timer's [`Monotonic`] clock as the `embassy-time` driver
+///
+/// The timer's monotonic clock must be started and its interrupts
+/// forwarded to [`Monotonic::on_interrupt`]. For example:
+///
+///     impl_embassy_time_driver!(Timer0);
+///
+#[cfg(feature = "embassy-time")]
+#[macro_export]
+macro_rules! impl_embassy_time_driver {
+    ($TIMERX:ident) => {
+        $crate::embassy_time_driver::time_driver_impl!(
+            static EMBASSY_TIME_DRIVER: $crate::timer::EmbassyTimeDriver =
+                $crate::timer::EmbassyTimeDriver::new($TIMERX::monotonic)
+        );
+    };
+}
+
 #[macro_export]
 macro_rules! impl_timer {
     ($(
@@ -74,7 +469,7 @@ macro_rules! impl_timer {
                     let ticks = u32::try_from(
                         clk * timeout.as_secs() +
                         clk * u64::from(timeout.subsec_nanos()) / NANOS_PER_SECOND,
-                    ).unwrap_or(u32::max_value());
+                    ).unwrap_or(u32::MAX);
 
                     self.set_timeout_ticks(ticks.max(1));
                 }
@@ -87,6 +482,44 @@ macro_rules! impl_timer {
                 }
             }
 
+            // monotonic clock
+            impl $TIMERX {
+                /// Returns the monotonic clock driven by this timer
+                pub fn monotonic() -> &'static $crate::timer::Monotonic {
+                    static MONOTONIC: $crate::timer::Monotonic = $crate::timer::Monotonic::new();
+                    &MONOTONIC
+                }
+
+                /// Start the timer as a free-running monotonic clock
+                ///
+                /// The counter reloads every `period + 1` ticks, usually
+                /// [`MONOTONIC_FREE_RUNNING`](crate::timer::MONOTONIC_FREE_RUNNING).
+                /// The timer interrupt must be forwarded to
+                /// `$TIMERX::monotonic().on_interrupt()` and serviced
+                /// at least once per period.
+                pub fn start_monotonic(&mut self, period: u32) -> &'static $crate::timer::Monotonic {
+                    self.disable();
+                    self.registers.reload().write(|w| unsafe { w.reload().bits(period) });
+                    self.registers.ctr().write(|w| unsafe { w.ctr().bits(period) });
+                    self.clear_pending();
+                    self.listen($crate::timer::Event::TimeOut);
+
+                    let monotonic = Self::monotonic();
+                    unsafe {
+                        monotonic.start(
+                            $crate::timer::Registers {
+                                ctr: self.registers.ctr().as_ptr(),
+                                ev_pending: self.registers.ev_pending().as_ptr(),
+                            },
+                            self.clk,
+                            period,
+                        );
+                    }
+                    self.enable();
+                    monotonic
+                }
+            }
+
             // interrupts
             impl $TIMERX {
                 /// Start listening for [`Event`]
@@ -143,3 +576,91 @@ macro_rules! impl_timer {
         )+
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use crate::hal::delay::DelayUs;
+    use crate::sim::{self, pac};
+
+    use drivers::Timer0;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use super::pac;
+
+        crate::impl_timer! {
+            Timer0: pac::TIMER,
+        }
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_timer() {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut timer = Timer0::new(peripherals.TIMER, pac::clock::sysclk());
+
+        let start = sim::with(|soc| soc.cycles());
+        timer.delay_us(10).unwrap();
+        assert!(sim::with(|soc| soc.cycles()) - start >= 600);
+
+        timer.set_timeout_ticks(1000);
+        timer.enable();
+        sim::advance(1000);
+        assert!(timer.is_pending());
+        timer.clear_pending();
+        assert!(!timer.is_pending());
+    }
+
+    #[test]
+    fn test_monotonic_alarm() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        fn on_alarm(context: *mut ()) {
+            unsafe { &*(context as *const AtomicUsize) }.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut timer = Timer0::new(peripherals.TIMER, pac::clock::sysclk());
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::TIMER) };
+        let monotonic = timer.start_monotonic(100_000);
+        let service = || sim::service_interrupts(2, || monotonic.on_interrupt());
+
+        let fired = AtomicUsize::new(0);
+        let alarm = monotonic.allocate_alarm().unwrap();
+        monotonic.set_alarm_callback(alarm, on_alarm, core::ptr::addr_of!(fired) as *mut ());
+
+        let offset = sim::with(|soc| soc.cycles()) - monotonic.now();
+
+        // alarms within the current period reprogram the counter
+        let timestamp = monotonic.now() + 1_000;
+        assert!(monotonic.set_alarm(alarm, timestamp));
+        assert!(!monotonic.set_alarm(alarm, 1));
+        assert!(monotonic.set_alarm(alarm, timestamp));
+        sim::advance(900);
+        assert_eq!(service(), 0);
+        sim::advance(200);
+        assert_eq!(service(), 1);
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+        assert!((timestamp..timestamp + 200).contains(&monotonic.now()));
+
+        // alarms beyond the current period are scheduled on reload
+        let timestamp = monotonic.now() + 250_000;
+        assert!(monotonic.set_alarm(alarm, timestamp));
+        for _ in 0..5 {
+            sim::advance(49_000);
+            service();
+        }
+        assert_eq!(fired.load(Ordering::Relaxed), 1);
+        sim::advance(10_000);
+        assert_eq!(service(), 1);
+        assert_eq!(fired.load(Ordering::Relaxed), 2);
+
+        // the clock only loses the ticks spent reprogramming the counter
+        let drift = monotonic
+            .now()
+            .abs_diff(sim::with(|soc| soc.cycles()) - offset);
+        assert!(drift < 64, "monotonic clock drifted by {drift} ticks");
+    }
+}
@@ -1,5 +1,13 @@
 //! smolusb hal implementation for luna eptri peripherals
 
+#[cfg(feature = "embassy-usb")]
+pub mod embassy;
+pub mod tx_queue;
+#[cfg(feature = "usb-device")]
+pub mod usb_device;
+
+pub use tx_queue::{TxFifo, TxQueue};
+
 /// Re-export smolusb error type
 pub use smolusb::error::ErrorKind as Error;
 
@@ -16,6 +24,15 @@ use pac::interrupt::Interrupt;*/
 /// Default timeout for USB operations
 pub const DEFAULT_TIMEOUT: usize = 1_000_000;
 
+/// Timeout for USB operations on low-speed devices
+///
+/// Low-speed transfers run at 1.5 Mbps and interrupt endpoints are
+/// polled no more often than every 10ms so we need to wait longer.
+pub const LOW_SPEED_TIMEOUT: usize = 10 * DEFAULT_TIMEOUT;
+
+/// Maximum packet size for all endpoints of a low-speed device
+pub const LOW_SPEED_MAX_PACKET_SIZE: usize = 8;
+
 /// Macro to generate smolusb hal wrappers for `pac::USBx` peripherals
 ///
 /// For example:
@@ -121,6 +138,26 @@ macro_rules! impl_usb {
                 pub fn ep_control_address(&self) -> u8 {
                     self.ep_control.address().read().address().bits()
                 }
+
+                /// Returns the timeout for blocking operations at the current device speed.
+                #[must_use]
+                pub fn timeout(&self) -> usize {
+                    match self.device_speed {
+                        Speed::Low => $crate::usb::LOW_SPEED_TIMEOUT,
+                        _ => DEFAULT_TIMEOUT,
+                    }
+                }
+
+                /// Take the handle used to service the controller's
+                /// events from its interrupt handler.
+                ///
+                /// Returns `None` if the handle has already been taken.
+                pub fn events(&self) -> Option<$IDX::Events> {
+                    $IDX::TAKEN.init(()).ok()?;
+                    Some($IDX::Events {
+                        usb: unsafe { Self::summon() },
+                    })
+                }
             }
 
             // - trait: UsbDriverOperations -----------------------------------
@@ -128,27 +165,13 @@ macro_rules! impl_usb {
             impl UsbDriverOperations for $USBX {
                 /// Connect the device.
                 fn connect(&mut self, device_speed: Speed) {
-                    // set the device speed
-                    self.device_speed = device_speed;
-                    match device_speed {
-                        Speed::High => {
-                            self.controller.full_speed_only().write(|w| w.full_speed_only().bit(false));
-                            self.controller.low_speed_only().write(|w| w.low_speed_only().bit(false));
-                        },
-                        Speed::Full => {
-                            self.controller.full_speed_only().write(|w| w.full_speed_only().bit(true));
-                            self.controller.low_speed_only().write(|w| w.low_speed_only().bit(false));
-                        },
-                        /*Speed::Low => {
-                            // FIXME still connects at full speed
-                            self.controller.full_speed_only.write(|w| w.full_speed_only().bit(false));
-                            self.controller.low_speed_only.write(|w| w.low_speed_only().bit(true));
-                        }*/
+                    self.device_speed = match device_speed {
+                        Speed::High | Speed::Full | Speed::Low => device_speed,
                         _ => {
                             log::warn!("Requested unsupported device speed '{:?}'. Ignoring request and setting device to 'Speed::High'.", device_speed);
-                            self.device_speed = Speed::High;
+                            Speed::High
                         }
-                    }
+                    };
 
                     // disconnect device controller
                     self.controller.connect().write(|w| w.connect().bit(false));
@@ -156,6 +179,20 @@ macro_rules! impl_usb {
                     // disable endpoint events
                     self.disable_events();
 
+                    // set the device speed
+                    //
+                    // The speed is only sampled by the controller when the
+                    // device connects so it must be set while disconnected.
+                    // Low-speed devices must also not perform the
+                    // high-speed chirp so we force full speed as well.
+                    let (full_speed_only, low_speed_only) = match self.device_speed {
+                        Speed::Full => (true, false),
+                        Speed::Low => (true, true),
+                        _ => (false, false),
+                    };
+                    self.controller.full_speed_only().write(|w| w.full_speed_only().bit(full_speed_only));
+                    self.controller.low_speed_only().write(|w| w.low_speed_only().bit(low_speed_only));
+
                     // reset FIFOs
                     self.ep_control.reset().write(|w| w.reset().bit(true));
                     self.ep_in.reset().write(|w| w.reset().bit(true));
@@ -270,8 +307,9 @@ macro_rules! impl_usb {
             // - trait: UnsafeUsbDriverOperations -----------------------------
 
             #[allow(non_snake_case)]
-            mod $IDX {
+            pub mod $IDX {
                 use lunasoc_hal::smolusb::EP_MAX_ENDPOINTS;
+                use lunasoc_hal::smolusb::traits::{ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriverOperations};
 
                 #[cfg(target_has_atomic)]
                 #[allow(clippy::declare_interior_mutable_const)]
@@ -283,6 +321,52 @@ macro_rules! impl_usb {
                 pub static TX_ACK_ACTIVE: [core::sync::atomic::AtomicBool; EP_MAX_ENDPOINTS] =
                     [ATOMIC_FALSE; EP_MAX_ENDPOINTS];
 
+                /// Set once the controller's [`Events`] handle has been taken.
+                pub(super) static TAKEN: $crate::shared::Once<()> = $crate::shared::Once::new();
+
+                /// Handle for servicing a controller's events from its
+                /// interrupt handler, taken with the controller's `events()`.
+                ///
+                /// Each method clears the event it services.
+                pub struct Events {
+                    pub(super) usb: super::$USBX,
+                }
+
+                impl Events {
+                    /// Reset the device after a bus reset.
+                    pub fn bus_reset(&mut self) {
+                        self.usb.controller.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        self.usb.bus_reset();
+                    }
+
+                    /// Read a setup packet received on the control endpoint.
+                    ///
+                    /// Returns the endpoint number and the number of bytes read.
+                    pub fn receive_setup_packet(&mut self, buffer: &mut [u8; 8]) -> (u8, usize) {
+                        self.usb.ep_control.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        let endpoint_number = self.usb.ep_control.epno().read().bits() as u8;
+                        (endpoint_number, self.usb.read_control(buffer))
+                    }
+
+                    /// Returns the number of the IN endpoint which finished sending a packet.
+                    pub fn send_complete(&mut self) -> u8 {
+                        self.usb.ep_in.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        let endpoint_number = self.usb.ep_in.epno().read().bits() as u8;
+                        unsafe { self.usb.clear_tx_ack_active(endpoint_number); }
+                        endpoint_number
+                    }
+
+                    /// Returns the number of the OUT endpoint which received a packet.
+                    pub fn receive_packet(&mut self) -> u8 {
+                        self.usb.ep_out.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        self.usb.ep_out.data_ep().read().bits() as u8
+                    }
+
+                    /// Read the packet received on the given OUT endpoint.
+                    pub fn read(&mut self, endpoint_number: u8, buffer: &mut [u8]) -> usize {
+                        self.usb.read(endpoint_number, buffer)
+                    }
+                }
             }
 
             impl UnsafeUsbDriverOperations for $USBX {
@@ -291,7 +375,7 @@ macro_rules! impl_usb {
                     #[cfg(not(target_has_atomic))]
                     {
                         let endpoint_number = endpoint_number as usize;
-                        riscv::interrupt::free(|| {
+                        $crate::interrupt::free(|| {
                             $IDX::TX_ACK_ACTIVE[endpoint_number] = true;
                         });
                     }
@@ -307,7 +391,7 @@ macro_rules! impl_usb {
                     #[cfg(not(target_has_atomic))]
                     {
                         let endpoint_number = endpoint_number as usize;
-                        riscv::interrupt::free(|| {
+                        $crate::interrupt::free(|| {
                             $IDX::TX_ACK_ACTIVE[endpoint_number] = false;
                         });
                     }
@@ -323,7 +407,7 @@ macro_rules! impl_usb {
                     #[cfg(not(target_has_atomic))]
                     {
                         let endpoint_number = endpoint_number as usize;
-                        let active = riscv::interrupt::free(|| {
+                        let active = $crate::interrupt::free(|| {
                             $IDX::TX_ACK_ACTIVE[endpoint_number]
                         });
                         active
@@ -434,6 +518,7 @@ macro_rules! impl_usb {
                     I: Iterator<Item = u8>
                 {
                     let max_packet_size = match (self.device_speed, endpoint_number) {
+                        (Speed::Low, _) => $crate::usb::LOW_SPEED_MAX_PACKET_SIZE,
                         (_, 0) => 64,
                         (Speed::High, _) => smolusb::EP_MAX_PACKET_SIZE,
                         (Speed::Full, _) => 64,
@@ -457,7 +542,7 @@ macro_rules! impl_usb {
                     while self.ep_in.have().read().have().bit() {
                         if timeout == 0 {
                             log::warn!("  {} clear tx", stringify!($USBX));
-                        } else if timeout > DEFAULT_TIMEOUT {
+                        } else if timeout > self.timeout() {
                             self.ep_in.reset().write(|w| w.reset().bit(true));
                             log::error!("  {} clear tx timeout", stringify!($USBX));
                         }
@@ -480,7 +565,7 @@ macro_rules! impl_usb {
                             let mut timeout = 0;
                             while self.ep_in.have().read().have().bit() {
                                 timeout += 1;
-                                if timeout > DEFAULT_TIMEOUT {
+                                if timeout > self.timeout() {
                                     log::error!(
                                         "{}::write timed out after {} bytes",
                                         stringify!($USBX),
@@ -505,8 +590,284 @@ macro_rules! impl_usb {
 
             }
 
+            // - trait: TxFifo ------------------------------------------------
+
+            impl $crate::usb::TxFifo for $USBX {
+                #[inline(always)]
+                fn tx_fifo_is_empty(&self) -> bool {
+                    !self.ep_in.have().read().have().bit()
+                }
+
+                fn tx_fifo_send<I>(&self, endpoint_number: u8, packet: I)
+                where
+                    I: Iterator<Item = u8>
+                {
+                    unsafe { self.set_tx_ack_active(endpoint_number); }
+
+                    for byte in packet {
+                        self.ep_in.data().write(|w| unsafe { w.data().bits(byte) });
+                    }
+
+                    // prime the IN endpoint to send the packet
+                    self.ep_in
+                        .epno()
+                        .write(|w| unsafe { w.epno().bits(endpoint_number) });
+                }
+            }
+
             // mark implementation as complete
             impl UsbDriver for $USBX {}
         )+
     }
 }
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use smolusb::device::Speed;
+    use smolusb::setup::Direction;
+    use smolusb::traits::{ReadControl, ReadEndpoint, UsbDriverOperations, WriteEndpoint};
+
+    use crate::sim::usb::Handshake;
+    use crate::sim::{self, pac};
+    use crate::usb::DEFAULT_TIMEOUT;
+
+    use drivers::Usb0;
+
+    // the tests only exercise part of the driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use crate::smolusb::device::Speed;
+        use crate::smolusb::setup::Direction;
+        use crate::smolusb::traits::{
+            ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriver, UsbDriverOperations,
+            WriteEndpoint,
+        };
+        use crate::usb::DEFAULT_TIMEOUT;
+
+        use super::pac;
+
+        crate::impl_usb! {
+            Usb0: usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+        }
+    }
+
+    fn usb0() -> Usb0 {
+        let peripherals = pac::Peripherals::take().unwrap();
+        Usb0::new(
+            peripherals.USB0,
+            peripherals.USB0_EP_CONTROL,
+            peripherals.USB0_EP_IN,
+            peripherals.USB0_EP_OUT,
+        )
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_usb_connect() {
+        let mut usb0 = usb0();
+        sim::with(|soc| soc.usb0.host_set_speed(Speed::Full));
+        usb0.connect(Speed::High);
+
+        assert_eq!(
+            Speed::from(usb0.controller.speed().read().speed().bits()),
+            Speed::Full
+        );
+        assert!(sim::with(|soc| soc.usb0.is_connected()));
+
+        usb0.disconnect();
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_bus_reset()),
+            Handshake::Timeout
+        );
+    }
+
+    #[test]
+    fn test_usb_connect_low_speed() {
+        let mut usb0 = usb0();
+        usb0.connect(Speed::Low);
+
+        assert!(usb0
+            .controller
+            .low_speed_only()
+            .read()
+            .low_speed_only()
+            .bit());
+        assert!(usb0
+            .controller
+            .full_speed_only()
+            .read()
+            .full_speed_only()
+            .bit());
+        assert_eq!(
+            Speed::from(usb0.controller.speed().read().speed().bits()),
+            Speed::Low
+        );
+        assert_eq!(usb0.timeout(), crate::usb::LOW_SPEED_TIMEOUT);
+
+        // all endpoints are limited to 8 byte packets
+        usb0.enable_events();
+        sim::with(|soc| soc.usb0.host_poll_in(1, true));
+        let report: [u8; 12] = core::array::from_fn(|n| n as u8);
+        assert_eq!(usb0.write(1, report.iter().copied()), 12);
+        let packets = sim::with(|soc| [soc.usb0.host_in(1), soc.usb0.host_in(1)]);
+        assert_eq!(packets[0].as_deref(), Ok(&report[..8]));
+        assert_eq!(packets[1].as_deref(), Ok(&report[8..]));
+
+        // reconnecting at a higher speed clears the low-speed configuration
+        usb0.connect(Speed::High);
+        assert!(!usb0
+            .controller
+            .low_speed_only()
+            .read()
+            .low_speed_only()
+            .bit());
+        assert_eq!(usb0.timeout(), DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_usb_control_in() {
+        let mut usb0 = usb0();
+        usb0.connect(Speed::High);
+        usb0.enable_events();
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0_EP_CONTROL) };
+
+        // GET_DESCRIPTOR(Device)
+        let setup = [0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00];
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_setup(0, setup)),
+            Handshake::Ack
+        );
+        assert_eq!(
+            pac::csr::interrupt::pending().ok(),
+            Some(pac::Interrupt::USB0_EP_CONTROL)
+        );
+
+        let mut buffer = [0; 8];
+        assert_eq!(usb0.read_control(&mut buffer), 8);
+        assert_eq!(buffer, setup);
+        usb0.ep_control
+            .ev_pending()
+            .modify(|r, w| w.pending().bit(r.pending().bit()));
+        assert_eq!(pac::csr::interrupt::bits_pending(), 0);
+
+        // data stage is split into packets of the control endpoint's size
+        sim::with(|soc| soc.usb0.host_poll_in(0, true));
+        let descriptor: [u8; 100] = core::array::from_fn(|n| n as u8);
+        assert_eq!(usb0.write(0, descriptor.iter().copied()), 100);
+        let packets = sim::with(|soc| {
+            [
+                soc.usb0.host_in(0),
+                soc.usb0.host_in(0),
+                soc.usb0.host_in(0),
+            ]
+        });
+        assert_eq!(packets[0].as_deref(), Ok(&descriptor[..64]));
+        assert_eq!(packets[1].as_deref(), Ok(&descriptor[64..]));
+        assert_eq!(packets[2], Err(Handshake::Nak));
+
+        // status stage
+        sim::with(|soc| soc.usb0.host_poll_in(0, false));
+        usb0.ack(0, Direction::DeviceToHost);
+        assert_eq!(sim::with(|soc| soc.usb0.host_out(0, &[])), Handshake::Ack);
+        assert_eq!(usb0.read(0, &mut buffer), 0);
+    }
+
+    #[test]
+    fn test_usb_out() {
+        let mut usb0 = usb0();
+        usb0.connect(Speed::High);
+        usb0.enable_events();
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0_EP_OUT) };
+
+        // unprimed endpoints NAK
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(2, b"hello")),
+            Handshake::Nak
+        );
+
+        usb0.ep_out_prime_receive(2);
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(2, b"hello")),
+            Handshake::Ack
+        );
+        assert!(pac::csr::interrupt::is_pending(pac::Interrupt::USB0_EP_OUT));
+        assert_eq!(usb0.ep_out.data_ep().read().bits(), 2);
+
+        // the endpoint must be re-enabled to receive the next packet
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(2, b"world")),
+            Handshake::Nak
+        );
+
+        let mut buffer = [0; 64];
+        assert_eq!(usb0.read(2, &mut buffer), 5);
+        assert_eq!(&buffer[..5], b"hello");
+
+        usb0.stall_endpoint_out(2);
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(2, b"world")),
+            Handshake::Stall
+        );
+    }
+
+    #[test]
+    fn test_usb_events() {
+        let mut usb0 = usb0();
+        usb0.connect(Speed::High);
+        usb0.enable_events();
+
+        let mut events = usb0.events().unwrap();
+        assert!(usb0.events().is_none());
+
+        // setup packets are read by the handle
+        let setup = [0x00, 0x05, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00];
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0_EP_CONTROL) };
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_setup(0, setup)),
+            Handshake::Ack
+        );
+        let mut buffer = [0; 8];
+        assert_eq!(events.receive_setup_packet(&mut buffer), (0, 8));
+        assert_eq!(buffer, setup);
+        assert_eq!(pac::csr::interrupt::bits_pending(), 0);
+
+        // as are packets received on OUT endpoints
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0_EP_OUT) };
+        usb0.ep_out_prime_receive(1);
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(1, b"hello")),
+            Handshake::Ack
+        );
+        assert_eq!(events.receive_packet(), 1);
+        let mut buffer = [0; 64];
+        assert_eq!(events.read(1, &mut buffer), 5);
+        assert_eq!(&buffer[..5], b"hello");
+        assert_eq!(pac::csr::interrupt::bits_pending(), 0);
+
+        // and bus resets
+        usb0.set_address(0x12);
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0) };
+        assert_eq!(sim::with(|soc| soc.usb0.host_bus_reset()), Handshake::Ack);
+        events.bus_reset();
+        assert_eq!(usb0.ep_control_address(), 0);
+        assert_eq!(pac::csr::interrupt::bits_pending(), 0);
+    }
+
+    #[test]
+    fn test_usb_bus_reset() {
+        let mut usb0 = usb0();
+        usb0.connect(Speed::High);
+        usb0.enable_events();
+        usb0.set_address(0x12);
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0x12);
+
+        unsafe { pac::csr::interrupt::enable(pac::Interrupt::USB0) };
+        assert_eq!(sim::with(|soc| soc.usb0.host_bus_reset()), Handshake::Ack);
+        assert!(pac::csr::interrupt::is_pending(pac::Interrupt::USB0));
+        assert_eq!(usb0.ep_control_address(), 0);
+
+        usb0.bus_reset();
+        assert_eq!(pac::csr::interrupt::bits_pending(), 0);
+    }
+}
@@ -0,0 +1,1146 @@
+//! `embassy-usb` driver implementation for luna eptri peripherals
+//!
+//! The driver is generated for each set of `pac::USBx` peripherals
+//! using the [`impl_embassy_usb!`](crate::impl_embassy_usb) macro.
+//!
+//! Unlike the smolusb backend, which leaves interrupt handling to the
+//! firmware, the embassy driver records controller events in a
+//! per-controller [`State`] and wakes any pending futures. Firmware
+//! therefore needs to forward the controller's interrupts to the
+//! generated `on_interrupt()` function:
+//!
+//!     #[allow(non_snake_case)]
+//!     #[no_mangle]
+//!     extern "C" fn MachineExternal() {
+//!         hal::Usb0Driver::on_interrupt();
+//!     }
+
+use core::cell::UnsafeCell;
+use core::task::Waker;
+
+use crate::smolusb::EP_MAX_ENDPOINTS;
+
+// - WakerCell ----------------------------------------------------------------
+
+/// A [`Waker`] slot that can be shared between thread and interrupt context.
+pub struct WakerCell {
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+// Safety: all accesses happen inside `interrupt::free`
+unsafe impl Sync for WakerCell {}
+
+impl WakerCell {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Register the given [`Waker`], replacing any previous registration.
+    pub fn register(&self, waker: &Waker) {
+        crate::interrupt::free(|| {
+            let slot = unsafe { &mut *self.waker.get() };
+            match slot {
+                Some(current) if current.will_wake(waker) => (),
+                _ => *slot = Some(waker.clone()),
+            }
+        });
+    }
+
+    /// Wake the registered [`Waker`], if any.
+    pub fn wake(&self) {
+        let waker = crate::interrupt::free(|| unsafe { (*self.waker.get()).take() });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for WakerCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - State --------------------------------------------------------------------
+
+/// Event flags recorded by the interrupt handler.
+#[derive(Clone, Copy)]
+struct Flags {
+    /// A bus reset was received.
+    bus_reset: bool,
+    /// A setup packet has been read into `State::setup`.
+    setup_ready: bool,
+    /// The first `Bus::poll` has reported `PowerDetected`.
+    power_reported: bool,
+    /// The shared `EP_IN` FIFO is owned by a writer.
+    ep_in_busy: bool,
+    /// Bitmask of IN endpoints that have completed a send.
+    ep_in_complete: u16,
+    /// Bitmask of OUT endpoints with a packet waiting in the `EP_OUT` FIFO.
+    ep_out_ready: u16,
+    /// Bitmask of enabled IN endpoints.
+    ep_in_enabled: u16,
+    /// Bitmask of enabled OUT endpoints.
+    ep_out_enabled: u16,
+    /// Bitmask of stalled IN endpoints.
+    ep_in_stalled: u16,
+    /// Bitmask of stalled OUT endpoints.
+    ep_out_stalled: u16,
+}
+
+impl Flags {
+    const fn new() -> Self {
+        Self {
+            bus_reset: false,
+            setup_ready: false,
+            power_reported: false,
+            ep_in_busy: false,
+            ep_in_complete: 0,
+            ep_out_ready: 0,
+            ep_in_enabled: 0,
+            ep_out_enabled: 0,
+            ep_in_stalled: 0,
+            ep_out_stalled: 0,
+        }
+    }
+}
+
+/// Interrupt-shared state for a single eptri controller.
+pub struct State {
+    flags: UnsafeCell<Flags>,
+    setup: UnsafeCell<[u8; 8]>,
+    pub bus_waker: WakerCell,
+    pub control_waker: WakerCell,
+    pub ep_in_wakers: [WakerCell; EP_MAX_ENDPOINTS],
+    pub ep_out_wakers: [WakerCell; EP_MAX_ENDPOINTS],
+}
+
+// Safety: all accesses happen inside `interrupt::free`
+unsafe impl Sync for State {}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const WAKER_CELL: WakerCell = WakerCell::new();
+
+impl State {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            flags: UnsafeCell::new(Flags::new()),
+            setup: UnsafeCell::new([0; 8]),
+            bus_waker: WakerCell::new(),
+            control_waker: WakerCell::new(),
+            ep_in_wakers: [WAKER_CELL; EP_MAX_ENDPOINTS],
+            ep_out_wakers: [WAKER_CELL; EP_MAX_ENDPOINTS],
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Flags) -> R) -> R {
+        crate::interrupt::free(|| f(unsafe { &mut *self.flags.get() }))
+    }
+
+    // - interrupt context --
+
+    /// Record a bus reset and wake everyone up.
+    pub fn on_bus_reset(&self) {
+        self.with(|flags| {
+            flags.bus_reset = true;
+            flags.setup_ready = false;
+            flags.ep_in_busy = false;
+            flags.ep_in_complete = 0;
+            flags.ep_out_ready = 0;
+            flags.ep_in_enabled = 1;
+            flags.ep_out_enabled = 1;
+            flags.ep_in_stalled = 0;
+            flags.ep_out_stalled = 0;
+        });
+        self.wake_all();
+    }
+
+    /// Record the arrival of a setup packet.
+    pub fn on_setup(&self, setup: [u8; 8]) {
+        crate::interrupt::free(|| unsafe {
+            *self.setup.get() = setup;
+            (*self.flags.get()).setup_ready = true;
+        });
+        self.control_waker.wake();
+    }
+
+    /// Record the completion of a send on the given IN endpoint.
+    pub fn on_send_complete(&self, endpoint_number: u8) {
+        let endpoint_number = usize::from(endpoint_number) % EP_MAX_ENDPOINTS;
+        self.with(|flags| flags.ep_in_complete |= 1 << endpoint_number);
+
+        // the FIFO is about to become available, so wake any other writers too
+        for waker in &self.ep_in_wakers {
+            waker.wake();
+        }
+        self.control_waker.wake();
+    }
+
+    /// Record the arrival of a packet for the given OUT endpoint.
+    pub fn on_receive_packet(&self, endpoint_number: u8) {
+        let endpoint_number = usize::from(endpoint_number) % EP_MAX_ENDPOINTS;
+        self.with(|flags| flags.ep_out_ready |= 1 << endpoint_number);
+        if endpoint_number == 0 {
+            self.control_waker.wake();
+        }
+        self.ep_out_wakers[endpoint_number].wake();
+    }
+
+    /// Wake every registered waker.
+    pub fn wake_all(&self) {
+        self.bus_waker.wake();
+        self.control_waker.wake();
+        for waker in &self.ep_in_wakers {
+            waker.wake();
+        }
+        for waker in &self.ep_out_wakers {
+            waker.wake();
+        }
+    }
+
+    // - thread context --
+
+    /// Take a pending bus reset flag.
+    pub fn take_bus_reset(&self) -> bool {
+        self.with(|flags| core::mem::take(&mut flags.bus_reset))
+    }
+
+    /// Returns `true` the first time it is called after a [`State::reset`].
+    pub fn take_power_detected(&self) -> bool {
+        self.with(|flags| !core::mem::replace(&mut flags.power_reported, true))
+    }
+
+    /// Take the pending setup packet, if any.
+    pub fn take_setup(&self) -> Option<[u8; 8]> {
+        crate::interrupt::free(|| unsafe {
+            let flags = &mut *self.flags.get();
+            if flags.setup_ready {
+                flags.setup_ready = false;
+                Some(*self.setup.get())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Try to claim the shared `EP_IN` FIFO.
+    pub fn try_claim_ep_in(&self) -> bool {
+        self.with(|flags| {
+            if flags.ep_in_busy {
+                false
+            } else {
+                flags.ep_in_busy = true;
+                true
+            }
+        })
+    }
+
+    /// Release the shared `EP_IN` FIFO.
+    pub fn release_ep_in(&self) {
+        self.with(|flags| flags.ep_in_busy = false);
+        for waker in &self.ep_in_wakers {
+            waker.wake();
+        }
+        self.control_waker.wake();
+    }
+
+    /// Clear the send complete flag for the given IN endpoint.
+    pub fn clear_send_complete(&self, endpoint_number: usize) {
+        self.with(|flags| flags.ep_in_complete &= !(1 << endpoint_number));
+    }
+
+    /// Take the send complete flag for the given IN endpoint.
+    pub fn take_send_complete(&self, endpoint_number: usize) -> bool {
+        self.with(|flags| {
+            let mask = 1 << endpoint_number;
+            let complete = flags.ep_in_complete & mask != 0;
+            flags.ep_in_complete &= !mask;
+            complete
+        })
+    }
+
+    /// Clear the packet received flag for the given OUT endpoint.
+    pub fn clear_receive_packet(&self, endpoint_number: usize) {
+        self.with(|flags| flags.ep_out_ready &= !(1 << endpoint_number));
+    }
+
+    /// Take the packet received flag for the given OUT endpoint.
+    pub fn take_receive_packet(&self, endpoint_number: usize) -> bool {
+        self.with(|flags| {
+            let mask = 1 << endpoint_number;
+            let ready = flags.ep_out_ready & mask != 0;
+            flags.ep_out_ready &= !mask;
+            ready
+        })
+    }
+
+    pub fn set_enabled(&self, endpoint_address: u8, enabled: bool) {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            let bits = if endpoint_address & 0x80 == 0 {
+                &mut flags.ep_out_enabled
+            } else {
+                &mut flags.ep_in_enabled
+            };
+            if enabled {
+                *bits |= mask;
+            } else {
+                *bits &= !mask;
+            }
+        });
+    }
+
+    pub fn is_enabled(&self, endpoint_address: u8) -> bool {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            if endpoint_address & 0x80 == 0 {
+                flags.ep_out_enabled & mask != 0
+            } else {
+                flags.ep_in_enabled & mask != 0
+            }
+        })
+    }
+
+    pub fn set_stalled(&self, endpoint_address: u8, stalled: bool) {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            let bits = if endpoint_address & 0x80 == 0 {
+                &mut flags.ep_out_stalled
+            } else {
+                &mut flags.ep_in_stalled
+            };
+            if stalled {
+                *bits |= mask;
+            } else {
+                *bits &= !mask;
+            }
+        });
+    }
+
+    pub fn is_stalled(&self, endpoint_address: u8) -> bool {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            if endpoint_address & 0x80 == 0 {
+                flags.ep_out_stalled & mask != 0
+            } else {
+                flags.ep_in_stalled & mask != 0
+            }
+        })
+    }
+
+    /// Reset all state, e.g. when the device is disconnected.
+    pub fn reset(&self) {
+        self.with(|flags| *flags = Flags::new());
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - impl_embassy_usb! --------------------------------------------------------
+
+/// Macro to generate `embassy-usb` driver wrappers for `pac::USBx` peripherals
+///
+/// The driver takes ownership of a smolusb hal instance generated by
+/// [`impl_usb!`](crate::impl_usb).
+///
+/// For example:
+///
+///     impl_embassy_usb! {
+///         Usb0Driver: usb0_embassy, Usb0, USB0, USB0_EP_CONTROL, USB0_EP_IN, USB0_EP_OUT,
+///     }
+///
+#[macro_export]
+macro_rules! impl_embassy_usb {
+    ($(
+        $DRIVER:ident: $IDX:ident, $USBX:ty, $USBX_CONTROLLER:ty, $USBX_EP_CONTROL:ty, $USBX_EP_IN:ty, $USBX_EP_OUT:ty,
+    )+) => {
+        $(
+            #[allow(non_snake_case)]
+            pub mod $IDX {
+                use super::*;
+
+                use $crate::embassy_usb_driver as driver;
+                use $crate::smolusb::device::Speed;
+                use $crate::smolusb::EP_MAX_ENDPOINTS;
+                use $crate::usb::embassy::State;
+
+                use core::future::poll_fn;
+                use core::marker::PhantomData;
+                use core::task::Poll;
+
+                pub static STATE: State = State::new();
+
+                // - register access --
+
+                #[inline(always)]
+                fn controller() -> &'static <$USBX_CONTROLLER as core::ops::Deref>::Target {
+                    unsafe { &*<$USBX_CONTROLLER>::ptr() }
+                }
+
+                #[inline(always)]
+                fn ep_control() -> &'static <$USBX_EP_CONTROL as core::ops::Deref>::Target {
+                    unsafe { &*<$USBX_EP_CONTROL>::ptr() }
+                }
+
+                #[inline(always)]
+                fn ep_in() -> &'static <$USBX_EP_IN as core::ops::Deref>::Target {
+                    unsafe { &*<$USBX_EP_IN>::ptr() }
+                }
+
+                #[inline(always)]
+                fn ep_out() -> &'static <$USBX_EP_OUT as core::ops::Deref>::Target {
+                    unsafe { &*<$USBX_EP_OUT>::ptr() }
+                }
+
+                fn reset_fifos() {
+                    ep_control().reset().write(|w| w.reset().bit(true));
+                    ep_in().reset().write(|w| w.reset().bit(true));
+                    ep_out().reset().write(|w| w.reset().bit(true));
+                }
+
+                fn set_address(address: u8) {
+                    ep_out().address().write(|w| unsafe { w.address().bits(address & 0x7f) });
+                    ep_control().address().write(|w| unsafe { w.address().bits(address & 0x7f) });
+                }
+
+                fn enable_events(enable: bool) {
+                    controller().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                    ep_control().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                    ep_in().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                    ep_out().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+
+                    controller().ev_enable().write(|w| w.enable().bit(enable));
+                    ep_control().ev_enable().write(|w| w.enable().bit(enable));
+                    ep_in().ev_enable().write(|w| w.enable().bit(enable));
+                    ep_out().ev_enable().write(|w| w.enable().bit(enable));
+                }
+
+                fn prime_receive(endpoint_number: u8) {
+                    ep_out().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                    ep_out().prime().write(|w| w.prime().bit(true));
+                    ep_out().enable().write(|w| w.enable().bit(true));
+                }
+
+                /// Drain the `EP_OUT` FIFO into the given buffer.
+                fn read_packet(buffer: &mut [u8]) -> Result<usize, driver::EndpointError> {
+                    let mut bytes_read = 0;
+                    let mut overflow = false;
+                    while ep_out().have().read().have().bit() {
+                        let byte = ep_out().data().read().data().bits();
+                        if let Some(dest) = buffer.get_mut(bytes_read) {
+                            *dest = byte;
+                            bytes_read += 1;
+                        } else {
+                            overflow = true;
+                        }
+                    }
+                    if overflow {
+                        Err(driver::EndpointError::BufferOverflow)
+                    } else {
+                        Ok(bytes_read)
+                    }
+                }
+
+                /// Exclusive access to the shared `EP_IN` FIFO.
+                ///
+                /// The claim is released when dropped so that a write
+                /// future which is cancelled, e.g. by a `select` or a
+                /// timeout, does not leave the FIFO claimed. Packets
+                /// the host has not collected are discarded.
+                struct EpInClaim {
+                    sent: bool,
+                }
+
+                impl Drop for EpInClaim {
+                    fn drop(&mut self) {
+                        if !self.sent {
+                            ep_in().reset().write(|w| w.reset().bit(true));
+                        }
+                        STATE.release_ep_in();
+                    }
+                }
+
+                /// Write a single packet to the shared `EP_IN` FIFO and
+                /// wait for the host to acknowledge it.
+                async fn write_packet(
+                    endpoint_number: u8,
+                    data: &[u8],
+                ) -> Result<(), driver::EndpointError> {
+                    let index = usize::from(endpoint_number);
+
+                    // wait for exclusive access to the FIFO
+                    poll_fn(|cx| {
+                        let waker = if index == 0 {
+                            &STATE.control_waker
+                        } else {
+                            &STATE.ep_in_wakers[index]
+                        };
+                        waker.register(cx.waker());
+                        if STATE.try_claim_ep_in() {
+                            Poll::Ready(())
+                        } else {
+                            Poll::Pending
+                        }
+                    })
+                    .await;
+                    let mut claim = EpInClaim { sent: false };
+
+                    // fill FIFO and prime endpoint
+                    STATE.clear_send_complete(index);
+                    for &byte in data {
+                        ep_in().data().write(|w| unsafe { w.data().bits(byte) });
+                    }
+                    ep_in().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+
+                    // wait for send to complete
+                    let result = poll_fn(|cx| {
+                        let waker = if index == 0 {
+                            &STATE.control_waker
+                        } else {
+                            &STATE.ep_in_wakers[index]
+                        };
+                        waker.register(cx.waker());
+                        if STATE.take_send_complete(index) {
+                            Poll::Ready(Ok(()))
+                        } else if index != 0 && !STATE.is_enabled(0x80 | endpoint_number) {
+                            Poll::Ready(Err(driver::EndpointError::Disabled))
+                        } else {
+                            Poll::Pending
+                        }
+                    })
+                    .await;
+
+                    claim.sent = result.is_ok();
+                    result
+                }
+
+                // - Driver --
+
+                /// `embassy-usb` driver for the controller.
+                pub struct $DRIVER<'d> {
+                    usb: $USBX,
+                    device_speed: Speed,
+                    ep_in_allocated: u16,
+                    ep_out_allocated: u16,
+                    _marker: PhantomData<&'d ()>,
+                }
+
+                impl<'d> $DRIVER<'d> {
+                    /// Create a new driver from the given smolusb hal instance.
+                    pub fn new(usb: $USBX, device_speed: Speed) -> Self {
+                        Self {
+                            usb,
+                            device_speed,
+                            // endpoint zero is reserved for the control pipe
+                            ep_in_allocated: 1,
+                            ep_out_allocated: 1,
+                            _marker: PhantomData,
+                        }
+                    }
+
+                    /// Release the smolusb hal instance and consume self.
+                    pub fn free(self) -> $USBX {
+                        self.usb
+                    }
+
+                    /// Handle any pending events for the controller.
+                    ///
+                    /// This must be called from the interrupt handler for
+                    /// each of the controller's four interrupts.
+                    pub fn on_interrupt() {
+                        // bus reset
+                        if controller().ev_pending().read().pending().bit() {
+                            controller().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                            set_address(0);
+                            reset_fifos();
+                            STATE.on_bus_reset();
+                        }
+
+                        // setup packet received
+                        if ep_control().ev_pending().read().pending().bit() {
+                            ep_control().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                            let mut setup = [0_u8; 8];
+                            let mut bytes_read = 0;
+                            while ep_control().have().read().have().bit() {
+                                let byte = ep_control().data().read().data().bits();
+                                if let Some(dest) = setup.get_mut(bytes_read) {
+                                    *dest = byte;
+                                }
+                                bytes_read += 1;
+                            }
+                            if bytes_read == setup.len() {
+                                STATE.on_setup(setup);
+                            }
+                        }
+
+                        // send complete
+                        if ep_in().ev_pending().read().pending().bit() {
+                            ep_in().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                            #[allow(clippy::cast_possible_truncation)]
+                            let endpoint_number = ep_in().epno().read().bits() as u8;
+                            STATE.on_send_complete(endpoint_number);
+                        }
+
+                        // packet received
+                        if ep_out().ev_pending().read().pending().bit() {
+                            ep_out().ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                            #[allow(clippy::cast_possible_truncation)]
+                            let endpoint_number = ep_out().data_ep().read().bits() as u8;
+                            STATE.on_receive_packet(endpoint_number);
+                        }
+                    }
+
+                    fn alloc_endpoint(
+                        allocated: &mut u16,
+                        direction: driver::Direction,
+                        ep_type: driver::EndpointType,
+                        max_packet_size: u16,
+                        interval_ms: u8,
+                    ) -> Result<driver::EndpointInfo, driver::EndpointAllocError> {
+                        if usize::from(max_packet_size) > $crate::smolusb::EP_MAX_PACKET_SIZE {
+                            return Err(driver::EndpointAllocError);
+                        }
+                        let index = (1..EP_MAX_ENDPOINTS)
+                            .find(|index| *allocated & (1 << index) == 0)
+                            .ok_or(driver::EndpointAllocError)?;
+                        *allocated |= 1 << index;
+                        Ok(driver::EndpointInfo {
+                            addr: driver::EndpointAddress::from_parts(index, direction),
+                            ep_type,
+                            max_packet_size,
+                            interval_ms,
+                        })
+                    }
+                }
+
+                impl<'d> driver::Driver<'d> for $DRIVER<'d> {
+                    type EndpointOut = EndpointOut<'d>;
+                    type EndpointIn = EndpointIn<'d>;
+                    type ControlPipe = ControlPipe<'d>;
+                    type Bus = Bus<'d>;
+
+                    fn alloc_endpoint_out(
+                        &mut self,
+                        ep_type: driver::EndpointType,
+                        max_packet_size: u16,
+                        interval_ms: u8,
+                    ) -> Result<Self::EndpointOut, driver::EndpointAllocError> {
+                        let info = Self::alloc_endpoint(
+                            &mut self.ep_out_allocated,
+                            driver::Direction::Out,
+                            ep_type,
+                            max_packet_size,
+                            interval_ms,
+                        )?;
+                        Ok(EndpointOut { info, primed: false, _marker: PhantomData })
+                    }
+
+                    fn alloc_endpoint_in(
+                        &mut self,
+                        ep_type: driver::EndpointType,
+                        max_packet_size: u16,
+                        interval_ms: u8,
+                    ) -> Result<Self::EndpointIn, driver::EndpointAllocError> {
+                        let info = Self::alloc_endpoint(
+                            &mut self.ep_in_allocated,
+                            driver::Direction::In,
+                            ep_type,
+                            max_packet_size,
+                            interval_ms,
+                        )?;
+                        Ok(EndpointIn { info, _marker: PhantomData })
+                    }
+
+                    fn start(self, control_max_packet_size: u16) -> (Self::Bus, Self::ControlPipe) {
+                        STATE.reset();
+                        (
+                            Bus {
+                                usb: self.usb,
+                                device_speed: self.device_speed,
+                                _marker: PhantomData,
+                            },
+                            ControlPipe {
+                                max_packet_size: control_max_packet_size,
+                                _marker: PhantomData,
+                            },
+                        )
+                    }
+                }
+
+                // - Bus --
+
+                /// `embassy-usb` bus for the controller.
+                pub struct Bus<'d> {
+                    usb: $USBX,
+                    device_speed: Speed,
+                    _marker: PhantomData<&'d ()>,
+                }
+
+                impl<'d> driver::Bus for Bus<'d> {
+                    async fn enable(&mut self) {
+                        use $crate::smolusb::traits::UsbDriverOperations;
+                        self.usb.connect(self.device_speed);
+                        STATE.set_enabled(0x00, true);
+                        STATE.set_enabled(0x80, true);
+                        enable_events(true);
+                    }
+
+                    async fn disable(&mut self) {
+                        use $crate::smolusb::traits::UsbDriverOperations;
+                        self.usb.disconnect();
+                        STATE.reset();
+                        STATE.wake_all();
+                    }
+
+                    async fn poll(&mut self) -> driver::Event {
+                        poll_fn(|cx| {
+                            STATE.bus_waker.register(cx.waker());
+                            if STATE.take_power_detected() {
+                                // eptri has no vbus detection, assume we're powered
+                                Poll::Ready(driver::Event::PowerDetected)
+                            } else if STATE.take_bus_reset() {
+                                Poll::Ready(driver::Event::Reset)
+                            } else {
+                                Poll::Pending
+                            }
+                        })
+                        .await
+                    }
+
+                    fn endpoint_set_enabled(&mut self, ep_addr: driver::EndpointAddress, enabled: bool) {
+                        let address = u8::from(ep_addr);
+                        #[allow(clippy::cast_possible_truncation)]
+                        let endpoint_number = ep_addr.index() as u8;
+
+                        // reset data toggle when (re-)enabling an endpoint
+                        if enabled {
+                            if ep_addr.is_in() {
+                                ep_in().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                                ep_in().pid().write(|w| w.pid().bit(false));
+                            } else {
+                                ep_out().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                                ep_out().pid().write(|w| w.pid().bit(false));
+                            }
+                        }
+
+                        STATE.set_enabled(address, enabled);
+                        if ep_addr.is_in() {
+                            STATE.ep_in_wakers[ep_addr.index()].wake();
+                        } else {
+                            STATE.ep_out_wakers[ep_addr.index()].wake();
+                        }
+                    }
+
+                    fn endpoint_set_stalled(&mut self, ep_addr: driver::EndpointAddress, stalled: bool) {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let endpoint_number = ep_addr.index() as u8;
+                        if ep_addr.is_in() {
+                            ep_in().stall().write(|w| w.stall().bit(stalled));
+                            ep_in().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                            if !stalled {
+                                ep_in().pid().write(|w| w.pid().bit(false));
+                            }
+                        } else {
+                            ep_out().epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                            ep_out().stall().write(|w| w.stall().bit(stalled));
+                            if !stalled {
+                                ep_out().pid().write(|w| w.pid().bit(false));
+                            }
+                        }
+                        STATE.set_stalled(u8::from(ep_addr), stalled);
+                    }
+
+                    fn endpoint_is_stalled(&mut self, ep_addr: driver::EndpointAddress) -> bool {
+                        STATE.is_stalled(u8::from(ep_addr))
+                    }
+
+                    async fn remote_wakeup(&mut self) -> Result<(), driver::Unsupported> {
+                        Err(driver::Unsupported)
+                    }
+                }
+
+                // - ControlPipe --
+
+                /// `embassy-usb` control pipe for the controller.
+                pub struct ControlPipe<'d> {
+                    max_packet_size: u16,
+                    _marker: PhantomData<&'d ()>,
+                }
+
+                impl<'d> driver::ControlPipe for ControlPipe<'d> {
+                    fn max_packet_size(&self) -> usize {
+                        usize::from(self.max_packet_size)
+                    }
+
+                    async fn setup(&mut self) -> [u8; 8] {
+                        poll_fn(|cx| {
+                            STATE.control_waker.register(cx.waker());
+                            match STATE.take_setup() {
+                                Some(setup) => Poll::Ready(setup),
+                                None => Poll::Pending,
+                            }
+                        })
+                        .await
+                    }
+
+                    async fn data_out(
+                        &mut self,
+                        buf: &mut [u8],
+                        _first: bool,
+                        _last: bool,
+                    ) -> Result<usize, driver::EndpointError> {
+                        STATE.clear_receive_packet(0);
+                        prime_receive(0);
+                        poll_fn(|cx| {
+                            STATE.control_waker.register(cx.waker());
+                            if STATE.take_receive_packet(0) {
+                                Poll::Ready(read_packet(buf))
+                            } else {
+                                Poll::Pending
+                            }
+                        })
+                        .await
+                    }
+
+                    async fn data_in(
+                        &mut self,
+                        data: &[u8],
+                        _first: bool,
+                        last: bool,
+                    ) -> Result<(), driver::EndpointError> {
+                        if data.len() > usize::from(self.max_packet_size) {
+                            return Err(driver::EndpointError::BufferOverflow);
+                        }
+                        write_packet(0, data).await?;
+
+                        // prime the endpoint so we can receive the status stage zlp from the host
+                        if last {
+                            prime_receive(0);
+                        }
+
+                        Ok(())
+                    }
+
+                    async fn accept(&mut self) {
+                        // send a zlp to complete the status stage
+                        write_packet(0, &[]).await.ok();
+                    }
+
+                    async fn reject(&mut self) {
+                        ep_in().stall().write(|w| w.stall().bit(true));
+                        ep_in().epno().write(|w| unsafe { w.epno().bits(0) });
+                        ep_out().epno().write(|w| unsafe { w.epno().bits(0) });
+                        ep_out().stall().write(|w| w.stall().bit(true));
+                    }
+
+                    async fn accept_set_address(&mut self, addr: u8) {
+                        // the new address only takes effect after the status stage completes
+                        write_packet(0, &[]).await.ok();
+                        set_address(addr);
+                    }
+                }
+
+                // - EndpointIn --
+
+                /// `embassy-usb` IN endpoint for the controller.
+                pub struct EndpointIn<'d> {
+                    info: driver::EndpointInfo,
+                    _marker: PhantomData<&'d ()>,
+                }
+
+                impl<'d> driver::Endpoint for EndpointIn<'d> {
+                    fn info(&self) -> &driver::EndpointInfo {
+                        &self.info
+                    }
+
+                    async fn wait_enabled(&mut self) {
+                        let address = u8::from(self.info.addr);
+                        poll_fn(|cx| {
+                            STATE.ep_in_wakers[self.info.addr.index()].register(cx.waker());
+                            if STATE.is_enabled(address) {
+                                Poll::Ready(())
+                            } else {
+                                Poll::Pending
+                            }
+                        })
+                        .await
+                    }
+                }
+
+                impl<'d> driver::EndpointIn for EndpointIn<'d> {
+                    async fn write(&mut self, buf: &[u8]) -> Result<(), driver::EndpointError> {
+                        if !STATE.is_enabled(u8::from(self.info.addr)) {
+                            return Err(driver::EndpointError::Disabled);
+                        }
+                        if buf.len() > usize::from(self.info.max_packet_size) {
+                            return Err(driver::EndpointError::BufferOverflow);
+                        }
+                        #[allow(clippy::cast_possible_truncation)]
+                        let endpoint_number = self.info.addr.index() as u8;
+                        write_packet(endpoint_number, buf).await
+                    }
+                }
+
+                // - EndpointOut --
+
+                /// `embassy-usb` OUT endpoint for the controller.
+                pub struct EndpointOut<'d> {
+                    info: driver::EndpointInfo,
+                    primed: bool,
+                    _marker: PhantomData<&'d ()>,
+                }
+
+                impl<'d> driver::Endpoint for EndpointOut<'d> {
+                    fn info(&self) -> &driver::EndpointInfo {
+                        &self.info
+                    }
+
+                    async fn wait_enabled(&mut self) {
+                        let address = u8::from(self.info.addr);
+                        poll_fn(|cx| {
+                            STATE.ep_out_wakers[self.info.addr.index()].register(cx.waker());
+                            if STATE.is_enabled(address) {
+                                Poll::Ready(())
+                            } else {
+                                Poll::Pending
+                            }
+                        })
+                        .await
+                    }
+                }
+
+                impl<'d> driver::EndpointOut for EndpointOut<'d> {
+                    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, driver::EndpointError> {
+                        let address = u8::from(self.info.addr);
+                        let index = self.info.addr.index();
+                        #[allow(clippy::cast_possible_truncation)]
+                        let endpoint_number = index as u8;
+
+                        if !STATE.is_enabled(address) {
+                            self.primed = false;
+                            return Err(driver::EndpointError::Disabled);
+                        }
+
+                        // prime the endpoint once per packet
+                        if !self.primed {
+                            STATE.clear_receive_packet(index);
+                            prime_receive(endpoint_number);
+                            self.primed = true;
+                        }
+
+                        let result = poll_fn(|cx| {
+                            STATE.ep_out_wakers[index].register(cx.waker());
+                            if STATE.take_receive_packet(index) {
+                                Poll::Ready(read_packet(buf))
+                            } else if !STATE.is_enabled(address) {
+                                Poll::Ready(Err(driver::EndpointError::Disabled))
+                            } else {
+                                Poll::Pending
+                            }
+                        })
+                        .await;
+
+                        self.primed = false;
+                        result
+                    }
+                }
+            }
+
+            pub use $IDX::$DRIVER;
+        )+
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use embassy_usb_driver::{
+        Bus as _, ControlPipe as _, Driver as _, Endpoint as _, EndpointError, EndpointIn as _,
+        EndpointOut as _, EndpointType, Event,
+    };
+
+    use crate::sim::usb::Handshake;
+    use crate::sim::{self, pac};
+    use crate::smolusb::device::Speed;
+
+    use drivers::{DriverA, DriverB, DriverC, Usb0};
+
+    // each test uses its own driver as their states are static, the
+    // tests only exercise part of each driver's api
+    #[allow(dead_code)]
+    mod drivers {
+        use crate::smolusb::device::Speed;
+        use crate::smolusb::setup::Direction;
+        use crate::smolusb::traits::{
+            ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriver, UsbDriverOperations,
+            WriteEndpoint,
+        };
+        use crate::usb::DEFAULT_TIMEOUT;
+
+        use super::pac;
+
+        crate::impl_usb! {
+            Usb0: usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+        }
+
+        crate::impl_embassy_usb! {
+            DriverA: driver_a, Usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+            DriverB: driver_b, Usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+            DriverC: driver_c, Usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+        }
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Poll `future` once.
+    fn poll<F: Future>(future: core::pin::Pin<&mut F>) -> Poll<F::Output> {
+        let waker = Arc::new(NoopWaker).into();
+        future.poll(&mut Context::from_waker(&waker))
+    }
+
+    /// Poll `future`, which must not wait on the host, to completion.
+    fn ready<F: Future>(future: F) -> F::Output {
+        match poll(pin!(future)) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future is waiting"),
+        }
+    }
+
+    fn usb0() -> Usb0 {
+        let peripherals = pac::Peripherals::take().unwrap();
+        Usb0::new(
+            peripherals.USB0,
+            peripherals.USB0_EP_CONTROL,
+            peripherals.USB0_EP_IN,
+            peripherals.USB0_EP_OUT,
+        )
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_control_in() {
+        let driver = DriverA::new(usb0(), Speed::High);
+        let (mut bus, mut control) = driver.start(64);
+        ready(bus.enable());
+        assert!(sim::with(|soc| soc.usb0.is_connected()));
+        assert_eq!(ready(bus.poll()), Event::PowerDetected);
+
+        // bus reset
+        assert_eq!(sim::with(|soc| soc.usb0.host_bus_reset()), Handshake::Ack);
+        DriverA::on_interrupt();
+        assert_eq!(ready(bus.poll()), Event::Reset);
+
+        // setup stage
+        let setup = [0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00];
+        {
+            let mut setup_future = pin!(control.setup());
+            assert!(poll(setup_future.as_mut()).is_pending());
+            assert_eq!(
+                sim::with(|soc| soc.usb0.host_setup(0, setup)),
+                Handshake::Ack
+            );
+            DriverA::on_interrupt();
+            assert_eq!(poll(setup_future), Poll::Ready(setup));
+        }
+
+        // data stage completes once the host has collected the packet
+        let descriptor = [0x12, 0x01, 0x00, 0x02];
+        let mut data_in = pin!(control.data_in(&descriptor, true, true));
+        assert!(poll(data_in.as_mut()).is_pending());
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_in(0)),
+            Ok(descriptor.to_vec())
+        );
+        DriverA::on_interrupt();
+        assert_eq!(poll(data_in), Poll::Ready(Ok(())));
+
+        // status stage
+        assert_eq!(sim::with(|soc| soc.usb0.host_out(0, &[])), Handshake::Ack);
+    }
+
+    #[test]
+    fn test_write_cancelled() {
+        let mut driver = DriverB::new(usb0(), Speed::High);
+        let mut ep1 = driver.alloc_endpoint_in(EndpointType::Bulk, 64, 0).unwrap();
+        let mut ep2 = driver.alloc_endpoint_in(EndpointType::Bulk, 64, 0).unwrap();
+        let (mut bus, _control) = driver.start(64);
+        ready(bus.enable());
+        bus.endpoint_set_enabled(ep1.info().addr, true);
+        bus.endpoint_set_enabled(ep2.info().addr, true);
+
+        // the host never collects the packet and the write is dropped
+        {
+            let mut write = pin!(ep1.write(b"lost"));
+            assert!(poll(write.as_mut()).is_pending());
+        }
+        assert!(drivers::driver_b::STATE.try_claim_ep_in());
+        drivers::driver_b::STATE.release_ep_in();
+
+        // which releases the FIFO for the next writer without sending the packet
+        let mut write = pin!(ep2.write(b"sent"));
+        assert!(poll(write.as_mut()).is_pending());
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(2)), Ok(b"sent".to_vec()));
+        DriverB::on_interrupt();
+        assert_eq!(poll(write), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn test_endpoint_out() {
+        let mut driver = DriverC::new(usb0(), Speed::High);
+        let mut ep1 = driver
+            .alloc_endpoint_out(EndpointType::Bulk, 64, 0)
+            .unwrap();
+        let (mut bus, _control) = driver.start(64);
+        ready(bus.enable());
+
+        let mut buffer = [0; 64];
+        assert_eq!(ready(ep1.read(&mut buffer)), Err(EndpointError::Disabled));
+        bus.endpoint_set_enabled(ep1.info().addr, true);
+
+        // the endpoint is primed by the first poll of the read
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(1, b"early")),
+            Handshake::Nak
+        );
+        {
+            let mut read = pin!(ep1.read(&mut buffer));
+            assert!(poll(read.as_mut()).is_pending());
+            assert_eq!(
+                sim::with(|soc| soc.usb0.host_out(1, b"hello")),
+                Handshake::Ack
+            );
+            DriverC::on_interrupt();
+            assert_eq!(poll(read), Poll::Ready(Ok(5)));
+        }
+        assert_eq!(&buffer[..5], b"hello");
+
+        // stalled endpoints are reported to the stack
+        bus.endpoint_set_stalled(ep1.info().addr, true);
+        assert!(bus.endpoint_is_stalled(ep1.info().addr));
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(1, b"world")),
+            Handshake::Stall
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Per-endpoint IN transmit queue for luna eptri peripherals
+//!
+//! Eptri only has a single `EP_IN` FIFO which is shared by all IN
+//! endpoints. Writing directly to the FIFO means a slow endpoint will
+//! block every other endpoint until the host has collected its data.
+//!
+//! [`TxQueue`] instead holds a software queue for each IN endpoint and
+//! feeds the FIFO one packet at a time, round-robin across endpoints:
+//!
+//!     // queue transfers without blocking
+//!     tx_queue.enqueue(1, &data, 512, true)?;
+//!     tx_queue.enqueue(3, &more_data, 64, true)?;
+//!     tx_queue.service(&usb0);
+//!
+//!     // ...and in the handler for UsbEvent::SendComplete(endpoint_number)
+//!     tx_queue.on_send_complete(endpoint_number);
+//!     tx_queue.service(&usb0);
+
+use heapless::Deque;
+
+use super::Error;
+use crate::smolusb::EP_MAX_ENDPOINTS;
+
+/// Maximum number of transfers that can be queued on a single endpoint.
+pub const TX_QUEUE_MAX_TRANSFERS: usize = 8;
+
+/// Access to the shared eptri `EP_IN` FIFO.
+pub trait TxFifo {
+    /// Returns `true` if the `EP_IN` FIFO is empty.
+    fn tx_fifo_is_empty(&self) -> bool;
+
+    /// Write a single packet to the `EP_IN` FIFO and prime the given
+    /// endpoint to send it.
+    fn tx_fifo_send<I>(&self, endpoint_number: u8, packet: I)
+    where
+        I: Iterator<Item = u8>;
+}
+
+#[derive(Clone, Copy)]
+struct Transfer {
+    /// Number of bytes remaining in the transfer.
+    remaining: usize,
+    /// Terminate the transfer with a zero length packet if it ends on a packet boundary.
+    zlp: bool,
+}
+
+struct EndpointQueue<const N: usize> {
+    data: Deque<u8, N>,
+    transfers: Deque<Transfer, TX_QUEUE_MAX_TRANSFERS>,
+    packet_size: usize,
+}
+
+impl<const N: usize> EndpointQueue<N> {
+    const fn new() -> Self {
+        Self {
+            data: Deque::new(),
+            transfers: Deque::new(),
+            packet_size: 0,
+        }
+    }
+
+    /// Returns the length of the next packet to send, if any.
+    fn next_packet_len(&mut self) -> Option<usize> {
+        while let Some(transfer) = self.transfers.front() {
+            if transfer.remaining == 0 && !transfer.zlp {
+                self.transfers.pop_front();
+                continue;
+            }
+            return Some(transfer.remaining.min(self.packet_size));
+        }
+        None
+    }
+
+    /// Update the current transfer after a packet of `len` bytes was sent.
+    fn packet_sent(&mut self, len: usize) {
+        if let Some(transfer) = self.transfers.front_mut() {
+            transfer.remaining -= len;
+            if len < self.packet_size || (transfer.remaining == 0 && !transfer.zlp) {
+                self.transfers.pop_front();
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.data.clear();
+        self.transfers.clear();
+    }
+}
+
+/// Software transmit queues for the IN endpoints of an eptri controller.
+///
+/// `N` is the queue capacity, in bytes, of each endpoint.
+pub struct TxQueue<const N: usize> {
+    endpoints: [EndpointQueue<N>; EP_MAX_ENDPOINTS],
+    in_flight: Option<u8>,
+    next_endpoint: usize,
+}
+
+impl<const N: usize> TxQueue<N> {
+    const EMPTY: EndpointQueue<N> = EndpointQueue::new();
+
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            endpoints: [Self::EMPTY; EP_MAX_ENDPOINTS],
+            in_flight: None,
+            next_endpoint: 0,
+        }
+    }
+
+    /// Queue a transfer for the given IN endpoint.
+    ///
+    /// The transfer is split into packets of `packet_size` bytes. If
+    /// `zlp` is set and the transfer ends on a packet boundary it will
+    /// be terminated with a zero length packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPacketSize`] if `packet_size` is zero,
+    /// or [`Error::Overflow`] with the number of bytes that could not
+    /// be queued if there is insufficient space. No data is queued in
+    /// either case.
+    pub fn enqueue(
+        &mut self,
+        endpoint_number: u8,
+        data: &[u8],
+        packet_size: usize,
+        zlp: bool,
+    ) -> Result<(), Error> {
+        if packet_size == 0 {
+            return Err(Error::InvalidPacketSize);
+        }
+
+        let queue = &mut self.endpoints[usize::from(endpoint_number) % EP_MAX_ENDPOINTS];
+
+        let free = queue.data.capacity() - queue.data.len();
+        if data.len() > free {
+            return Err(Error::Overflow(data.len() - free));
+        }
+        if queue.transfers.is_full() {
+            return Err(Error::Overflow(data.len()));
+        }
+
+        queue.packet_size = packet_size;
+        for &byte in data {
+            // can't fail, we checked for space above
+            let _ = queue.data.push_back(byte);
+        }
+        let _ = queue.transfers.push_back(Transfer {
+            remaining: data.len(),
+            zlp,
+        });
+
+        Ok(())
+    }
+
+    /// Start sending the next queued packet if the `EP_IN` FIFO is idle.
+    ///
+    /// Returns `true` if a packet was written to the FIFO.
+    pub fn service<F: TxFifo>(&mut self, fifo: &F) -> bool {
+        if self.in_flight.is_some() || !fifo.tx_fifo_is_empty() {
+            return false;
+        }
+
+        for offset in 0..EP_MAX_ENDPOINTS {
+            let index = (self.next_endpoint + offset) % EP_MAX_ENDPOINTS;
+            let queue = &mut self.endpoints[index];
+            let Some(len) = queue.next_packet_len() else {
+                continue;
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            let endpoint_number = index as u8;
+            let data = &mut queue.data;
+            fifo.tx_fifo_send(
+                endpoint_number,
+                core::iter::from_fn(|| data.pop_front()).take(len),
+            );
+            queue.packet_sent(len);
+
+            self.in_flight = Some(endpoint_number);
+            self.next_endpoint = index + 1;
+            return true;
+        }
+
+        false
+    }
+
+    /// Handle a `SendComplete` event for the given endpoint.
+    ///
+    /// Returns `true` if the event completed a packet sent by the queue.
+    pub fn on_send_complete(&mut self, endpoint_number: u8) -> bool {
+        if self.in_flight == Some(endpoint_number) {
+            self.in_flight = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if no packets are queued or in flight.
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_none()
+            && self
+                .endpoints
+                .iter()
+                .all(|queue| queue.transfers.is_empty())
+    }
+
+    /// Returns `true` if the given endpoint has no queued transfers.
+    #[must_use]
+    pub fn is_empty(&self, endpoint_number: u8) -> bool {
+        self.endpoints[usize::from(endpoint_number) % EP_MAX_ENDPOINTS]
+            .transfers
+            .is_empty()
+    }
+
+    /// Discard all queued transfers for the given endpoint.
+    pub fn clear_endpoint(&mut self, endpoint_number: u8) {
+        self.endpoints[usize::from(endpoint_number) % EP_MAX_ENDPOINTS].clear();
+    }
+
+    /// Discard all queued transfers, e.g. after a bus reset.
+    ///
+    /// The caller is responsible for resetting the `EP_IN` FIFO.
+    pub fn clear(&mut self) {
+        for queue in &mut self.endpoints {
+            queue.clear();
+        }
+        self.in_flight = None;
+    }
+}
+
+impl<const N: usize> Default for TxQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+
+    use crate::sim::usb::Handshake;
+    use crate::sim::{self, pac};
+    use crate::smolusb::device::Speed;
+    use crate::smolusb::traits::UsbDriverOperations;
+
+    use drivers::Usb0;
+
+    #[allow(dead_code)]
+    mod drivers {
+        use crate::smolusb::device::Speed;
+        use crate::smolusb::setup::Direction;
+        use crate::smolusb::traits::{
+            ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriver, UsbDriverOperations,
+            WriteEndpoint,
+        };
+        use crate::usb::DEFAULT_TIMEOUT;
+
+        use super::pac;
+
+        crate::impl_usb! {
+            Usb0: usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+        }
+    }
+
+    fn usb0() -> Usb0 {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut usb0 = Usb0::new(
+            peripherals.USB0,
+            peripherals.USB0_EP_CONTROL,
+            peripherals.USB0_EP_IN,
+            peripherals.USB0_EP_OUT,
+        );
+        usb0.connect(Speed::High);
+        usb0
+    }
+
+    /// Collect the packet in flight on `endpoint_number` and start the next one.
+    fn host_in<const N: usize>(
+        tx_queue: &mut TxQueue<N>,
+        usb0: &Usb0,
+        endpoint_number: u8,
+    ) -> Result<Vec<u8>, Handshake> {
+        let packet = sim::with(|soc| soc.usb0.host_in(endpoint_number))?;
+        assert!(tx_queue.on_send_complete(endpoint_number));
+        tx_queue.service(usb0);
+        Ok(packet)
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_interleave() {
+        let usb0 = usb0();
+        let mut tx_queue: TxQueue<64> = TxQueue::new();
+
+        tx_queue.enqueue(1, b"0123456789", 4, true).unwrap();
+        tx_queue.enqueue(2, b"abcdef", 4, true).unwrap();
+        assert!(tx_queue.service(&usb0));
+        assert!(!tx_queue.service(&usb0));
+
+        // packets are sent round-robin, one at a time
+        let q = &mut tx_queue;
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(2)), Err(Handshake::Nak));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"0123".to_vec()));
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+        assert_eq!(host_in(q, &usb0, 2), Ok(b"abcd".to_vec()));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"4567".to_vec()));
+        assert_eq!(host_in(q, &usb0, 2), Ok(b"ef".to_vec()));
+        assert!(q.is_empty(2));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"89".to_vec()));
+        assert!(q.is_idle());
+
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(2)), Err(Handshake::Nak));
+    }
+
+    #[test]
+    fn test_zlp() {
+        let usb0 = usb0();
+        let mut tx_queue: TxQueue<64> = TxQueue::new();
+        let q = &mut tx_queue;
+
+        // a transfer ending on a packet boundary is terminated with a ZLP
+        q.enqueue(1, b"01234567", 4, true).unwrap();
+        q.service(&usb0);
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"0123".to_vec()));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"4567".to_vec()));
+        assert_eq!(host_in(q, &usb0, 1), Ok(vec![]));
+        assert!(q.is_idle());
+
+        // ...unless it was not requested
+        q.enqueue(1, b"01234567", 4, false).unwrap();
+        q.service(&usb0);
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"0123".to_vec()));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"4567".to_vec()));
+        assert!(q.is_idle());
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+
+        // a short final packet needs no ZLP
+        q.enqueue(1, b"012345", 4, true).unwrap();
+        q.service(&usb0);
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"0123".to_vec()));
+        assert_eq!(host_in(q, &usb0, 1), Ok(b"45".to_vec()));
+        assert!(q.is_idle());
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+    }
+
+    #[test]
+    fn test_empty_payload() {
+        let usb0 = usb0();
+        let mut tx_queue: TxQueue<64> = TxQueue::new();
+        let q = &mut tx_queue;
+
+        // an empty transfer is sent as a single ZLP
+        q.enqueue(1, &[], 4, true).unwrap();
+        assert!(q.service(&usb0));
+        assert_eq!(host_in(q, &usb0, 1), Ok(vec![]));
+        assert!(q.is_idle());
+
+        // ...or not at all
+        q.enqueue(1, &[], 4, false).unwrap();
+        assert!(!q.service(&usb0));
+        assert!(q.is_idle());
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Err(Handshake::Nak));
+    }
+
+    #[test]
+    fn test_enqueue_errors() {
+        let mut tx_queue: TxQueue<8> = TxQueue::new();
+
+        // unconfigured endpoint
+        assert_eq!(
+            tx_queue.enqueue(1, b"data", 0, true),
+            Err(Error::InvalidPacketSize)
+        );
+        assert!(tx_queue.is_empty(1));
+
+        // queue capacity
+        tx_queue.enqueue(1, b"0123", 4, true).unwrap();
+        assert_eq!(
+            tx_queue.enqueue(1, b"456789", 4, true),
+            Err(Error::Overflow(2))
+        );
+        tx_queue.enqueue(1, b"4567", 4, true).unwrap();
+
+        // other endpoints have their own queue
+        tx_queue.enqueue(2, b"01234567", 4, true).unwrap();
+    }
+}
@@ -0,0 +1,724 @@
+//! `usb-device` bus implementation for luna eptri peripherals
+//!
+//! The bus is generated for each smolusb hal instance using the
+//! [`impl_usb_device!`](crate::impl_usb_device) macro.
+//!
+//! `usb-device` uses a polling model so no interrupt handler is
+//! required. If interrupts are enabled for the controller, simply call
+//! `UsbDevice::poll()` from the interrupt handler instead.
+//!
+//! Eptri only provides a single `EP_IN` and a single `EP_OUT` FIFO
+//! which are shared by all endpoints. The bus therefore only allows a
+//! single IN packet to be in flight at a time and will return
+//! [`UsbError::WouldBlock`](usb_device::UsbError::WouldBlock) to any
+//! other writer until it has been acknowledged by the host. Received
+//! OUT packets are left in the FIFO until they are read, during which
+//! time eptri will NAK any other OUT endpoints.
+
+use core::cell::UnsafeCell;
+
+use crate::smolusb::EP_MAX_ENDPOINTS;
+
+/// Endpoint flags recorded by [`UsbBus::poll()`](usb_device::bus::UsbBus::poll).
+#[derive(Clone, Copy)]
+struct Flags {
+    /// A setup packet has been read into `State::setup`.
+    setup_ready: bool,
+    /// The shared `EP_IN` FIFO has a packet in flight.
+    ep_in_busy: bool,
+    /// Bitmask of IN endpoints that have completed a send.
+    ep_in_complete: u16,
+    /// Bitmask of OUT endpoints with a packet waiting in the `EP_OUT` FIFO.
+    ep_out_ready: u16,
+    /// Bitmask of stalled IN endpoints.
+    ep_in_stalled: u16,
+    /// Bitmask of stalled OUT endpoints.
+    ep_out_stalled: u16,
+}
+
+impl Flags {
+    const fn new() -> Self {
+        Self {
+            setup_ready: false,
+            ep_in_busy: false,
+            ep_in_complete: 0,
+            ep_out_ready: 0,
+            ep_in_stalled: 0,
+            ep_out_stalled: 0,
+        }
+    }
+}
+
+/// Per-endpoint allocation table.
+#[derive(Clone, Copy)]
+pub struct Endpoints {
+    /// Bitmask of allocated endpoints.
+    pub allocated: u16,
+    /// Maximum packet size for each allocated endpoint.
+    pub max_packet_size: [u16; EP_MAX_ENDPOINTS],
+}
+
+impl Endpoints {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            allocated: 0,
+            max_packet_size: [0; EP_MAX_ENDPOINTS],
+        }
+    }
+
+    /// Allocate the requested endpoint number or, if `None`, the
+    /// next free endpoint number.
+    pub fn alloc(
+        &mut self,
+        endpoint_number: Option<usize>,
+        max_packet_size: u16,
+    ) -> usb_device::Result<usize> {
+        if usize::from(max_packet_size) > crate::smolusb::EP_MAX_PACKET_SIZE {
+            return Err(usb_device::UsbError::EndpointMemoryOverflow);
+        }
+
+        let endpoint_number = match endpoint_number {
+            Some(endpoint_number) if endpoint_number >= EP_MAX_ENDPOINTS => {
+                return Err(usb_device::UsbError::InvalidEndpoint);
+            }
+            Some(endpoint_number) if self.is_allocated(endpoint_number) => {
+                return Err(usb_device::UsbError::InvalidEndpoint);
+            }
+            Some(endpoint_number) => endpoint_number,
+            None => (1..EP_MAX_ENDPOINTS)
+                .find(|endpoint_number| !self.is_allocated(*endpoint_number))
+                .ok_or(usb_device::UsbError::EndpointOverflow)?,
+        };
+
+        self.allocated |= 1 << endpoint_number;
+        self.max_packet_size[endpoint_number] = max_packet_size;
+
+        Ok(endpoint_number)
+    }
+
+    #[must_use]
+    pub fn is_allocated(&self, endpoint_number: usize) -> bool {
+        endpoint_number < EP_MAX_ENDPOINTS && self.allocated & (1 << endpoint_number) != 0
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interior mutable bus state.
+pub struct State {
+    flags: UnsafeCell<Flags>,
+    setup: UnsafeCell<[u8; 8]>,
+}
+
+impl State {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            flags: UnsafeCell::new(Flags::new()),
+            setup: UnsafeCell::new([0; 8]),
+        }
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut Flags) -> R) -> R {
+        crate::interrupt::free(|| f(unsafe { &mut *self.flags.get() }))
+    }
+
+    /// Reset all state.
+    pub fn reset(&self) {
+        self.with(|flags| *flags = Flags::new());
+    }
+
+    /// Record the arrival of a setup packet.
+    pub fn set_setup(&self, setup: [u8; 8]) {
+        crate::interrupt::free(|| unsafe {
+            *self.setup.get() = setup;
+            let flags = &mut *self.flags.get();
+            flags.setup_ready = true;
+            flags.ep_in_stalled &= !1;
+            flags.ep_out_stalled &= !1;
+        });
+    }
+
+    /// Take the pending setup packet, if any.
+    pub fn take_setup(&self) -> Option<[u8; 8]> {
+        crate::interrupt::free(|| unsafe {
+            let flags = &mut *self.flags.get();
+            if flags.setup_ready {
+                flags.setup_ready = false;
+                Some(*self.setup.get())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Try to claim the shared `EP_IN` FIFO.
+    pub fn try_claim_ep_in(&self) -> bool {
+        self.with(|flags| !core::mem::replace(&mut flags.ep_in_busy, true))
+    }
+
+    /// Record the completion of a send on the given IN endpoint.
+    pub fn set_send_complete(&self, endpoint_number: usize) {
+        self.with(|flags| {
+            flags.ep_in_busy = false;
+            flags.ep_in_complete |= 1 << (endpoint_number % EP_MAX_ENDPOINTS);
+        });
+    }
+
+    /// Record the arrival of a packet for the given OUT endpoint.
+    pub fn set_receive_packet(&self, endpoint_number: usize) {
+        self.with(|flags| flags.ep_out_ready |= 1 << (endpoint_number % EP_MAX_ENDPOINTS));
+    }
+
+    /// Take the packet received flag for the given OUT endpoint.
+    pub fn take_receive_packet(&self, endpoint_number: usize) -> bool {
+        self.with(|flags| {
+            let mask = 1 << endpoint_number;
+            let ready = flags.ep_out_ready & mask != 0;
+            flags.ep_out_ready &= !mask;
+            ready
+        })
+    }
+
+    /// Returns the `(ep_out, ep_in_complete, ep_setup)` bitmasks to
+    /// report from `poll()`.
+    ///
+    /// IN completions are only reported once.
+    pub fn take_poll_result(&self) -> (u16, u16, u16) {
+        self.with(|flags| {
+            (
+                flags.ep_out_ready,
+                core::mem::take(&mut flags.ep_in_complete),
+                u16::from(flags.setup_ready),
+            )
+        })
+    }
+
+    pub fn set_stalled(&self, endpoint_address: u8, stalled: bool) {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            let bits = if endpoint_address & 0x80 == 0 {
+                &mut flags.ep_out_stalled
+            } else {
+                &mut flags.ep_in_stalled
+            };
+            if stalled {
+                *bits |= mask;
+            } else {
+                *bits &= !mask;
+            }
+        });
+    }
+
+    pub fn is_stalled(&self, endpoint_address: u8) -> bool {
+        let mask = 1 << (endpoint_address & 0xf);
+        self.with(|flags| {
+            if endpoint_address & 0x80 == 0 {
+                flags.ep_out_stalled & mask != 0
+            } else {
+                flags.ep_in_stalled & mask != 0
+            }
+        })
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Macro to generate `usb-device` bus wrappers for smolusb hal instances
+///
+/// For example:
+///
+///     impl_usb_device! {
+///         Usb0Bus: Usb0,
+///         Usb1Bus: Usb1,
+///     }
+///
+#[macro_export]
+macro_rules! impl_usb_device {
+    ($(
+        $BUS:ident: $USBX:ty,
+    )+) => {
+        $(
+            /// `usb-device` bus for a smolusb hal instance.
+            pub struct $BUS {
+                usb: $USBX,
+                device_speed: $crate::smolusb::device::Speed,
+                ep_in: $crate::usb::usb_device::Endpoints,
+                ep_out: $crate::usb::usb_device::Endpoints,
+                state: $crate::usb::usb_device::State,
+            }
+
+            // Safety: The bus is only used on a single hart and all
+            //         mutable state is accessed inside critical sections.
+            unsafe impl Sync for $BUS {}
+
+            impl $BUS {
+                /// Create a new `usb-device` bus from the given smolusb hal instance.
+                pub fn new(usb: $USBX, device_speed: $crate::smolusb::device::Speed) -> Self {
+                    Self {
+                        usb,
+                        device_speed,
+                        ep_in: $crate::usb::usb_device::Endpoints::new(),
+                        ep_out: $crate::usb::usb_device::Endpoints::new(),
+                        state: $crate::usb::usb_device::State::new(),
+                    }
+                }
+
+                /// Release the smolusb hal instance and consume self.
+                pub fn free(self) -> $USBX {
+                    self.usb
+                }
+
+                /// Prime the given OUT endpoint without touching the `EP_OUT` FIFO.
+                fn prime_receive(&self, endpoint_number: u8) {
+                    self.usb.ep_out.epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                    self.usb.ep_out.prime().write(|w| w.prime().bit(true));
+                    self.usb.ep_out.enable().write(|w| w.enable().bit(true));
+                }
+            }
+
+            impl $crate::usb_device::bus::UsbBus for $BUS {
+                fn alloc_ep(
+                    &mut self,
+                    ep_dir: $crate::usb_device::UsbDirection,
+                    ep_addr: Option<$crate::usb_device::endpoint::EndpointAddress>,
+                    _ep_type: $crate::usb_device::endpoint::EndpointType,
+                    max_packet_size: u16,
+                    _interval: u8,
+                ) -> $crate::usb_device::Result<$crate::usb_device::endpoint::EndpointAddress> {
+                    use $crate::usb_device::UsbDirection;
+
+                    if let Some(ep_addr) = ep_addr {
+                        if ep_addr.direction() != ep_dir {
+                            return Err($crate::usb_device::UsbError::InvalidEndpoint);
+                        }
+                    }
+
+                    let endpoints = match ep_dir {
+                        UsbDirection::In => &mut self.ep_in,
+                        UsbDirection::Out => &mut self.ep_out,
+                    };
+                    let endpoint_number = endpoints.alloc(ep_addr.map(|ep_addr| ep_addr.index()), max_packet_size)?;
+
+                    Ok($crate::usb_device::endpoint::EndpointAddress::from_parts(endpoint_number, ep_dir))
+                }
+
+                fn enable(&mut self) {
+                    use $crate::smolusb::traits::UsbDriverOperations;
+                    self.state.reset();
+                    self.usb.connect(self.device_speed);
+                    self.usb.enable_events();
+                }
+
+                fn reset(&self) {
+                    use $crate::smolusb::traits::UsbDriverOperations;
+
+                    self.usb.set_address(0);
+
+                    // reset FIFOs
+                    self.usb.ep_control.reset().write(|w| w.reset().bit(true));
+                    self.usb.ep_in.reset().write(|w| w.reset().bit(true));
+                    self.usb.ep_out.reset().write(|w| w.reset().bit(true));
+
+                    self.state.reset();
+
+                    // reset data toggles and prime all allocated OUT endpoints
+                    for endpoint_number in 0..($crate::smolusb::EP_MAX_ENDPOINTS as u8) {
+                        if self.ep_in.is_allocated(usize::from(endpoint_number)) {
+                            self.usb.ep_in.stall().write(|w| w.stall().bit(false));
+                            self.usb.clear_feature_endpoint_halt(0x80 | endpoint_number);
+                        }
+                        if self.ep_out.is_allocated(usize::from(endpoint_number)) {
+                            self.usb.ep_out.stall().write(|w| w.stall().bit(false));
+                            self.usb.clear_feature_endpoint_halt(endpoint_number);
+                            self.prime_receive(endpoint_number);
+                        }
+                    }
+                }
+
+                fn set_device_address(&self, addr: u8) {
+                    use $crate::smolusb::traits::UsbDriverOperations;
+                    self.usb.set_address(addr);
+                }
+
+                fn write(
+                    &self,
+                    ep_addr: $crate::usb_device::endpoint::EndpointAddress,
+                    buf: &[u8],
+                ) -> $crate::usb_device::Result<usize> {
+                    let index = ep_addr.index();
+                    if !ep_addr.is_in() || !self.ep_in.is_allocated(index) {
+                        return Err($crate::usb_device::UsbError::InvalidEndpoint);
+                    }
+                    if buf.len() > usize::from(self.ep_in.max_packet_size[index]) {
+                        return Err($crate::usb_device::UsbError::BufferOverflow);
+                    }
+
+                    // only one packet can be in flight
+                    if !self.state.try_claim_ep_in() {
+                        return Err($crate::usb_device::UsbError::WouldBlock);
+                    }
+
+                    // fill FIFO and prime endpoint
+                    for &byte in buf {
+                        self.usb.ep_in.data().write(|w| unsafe { w.data().bits(byte) });
+                    }
+                    #[allow(clippy::cast_possible_truncation)]
+                    self.usb.ep_in.epno().write(|w| unsafe { w.epno().bits(index as u8) });
+
+                    Ok(buf.len())
+                }
+
+                fn read(
+                    &self,
+                    ep_addr: $crate::usb_device::endpoint::EndpointAddress,
+                    buf: &mut [u8],
+                ) -> $crate::usb_device::Result<usize> {
+                    let index = ep_addr.index();
+                    if !ep_addr.is_out() || !self.ep_out.is_allocated(index) {
+                        return Err($crate::usb_device::UsbError::InvalidEndpoint);
+                    }
+
+                    // setup packets are read via the control OUT endpoint
+                    if index == 0 {
+                        if let Some(setup) = self.state.take_setup() {
+                            if buf.len() < setup.len() {
+                                return Err($crate::usb_device::UsbError::BufferOverflow);
+                            }
+                            buf[..setup.len()].copy_from_slice(&setup);
+                            self.prime_receive(0);
+                            return Ok(setup.len());
+                        }
+                    }
+
+                    if !self.state.take_receive_packet(index) {
+                        return Err($crate::usb_device::UsbError::WouldBlock);
+                    }
+
+                    // drain FIFO
+                    let mut bytes_read = 0;
+                    let mut overflow = false;
+                    while self.usb.ep_out.have().read().have().bit() {
+                        let byte = self.usb.ep_out.data().read().data().bits();
+                        if let Some(dest) = buf.get_mut(bytes_read) {
+                            *dest = byte;
+                            bytes_read += 1;
+                        } else {
+                            overflow = true;
+                        }
+                    }
+
+                    // prepare endpoint to receive the next packet
+                    #[allow(clippy::cast_possible_truncation)]
+                    self.prime_receive(index as u8);
+
+                    if overflow {
+                        Err($crate::usb_device::UsbError::BufferOverflow)
+                    } else {
+                        Ok(bytes_read)
+                    }
+                }
+
+                fn set_stalled(
+                    &self,
+                    ep_addr: $crate::usb_device::endpoint::EndpointAddress,
+                    stalled: bool,
+                ) {
+                    use $crate::smolusb::traits::UsbDriverOperations;
+
+                    #[allow(clippy::cast_possible_truncation)]
+                    let endpoint_number = ep_addr.index() as u8;
+                    match (ep_addr.is_in(), stalled) {
+                        (true, true) => self.usb.stall_endpoint_in(endpoint_number),
+                        (false, true) => self.usb.stall_endpoint_out(endpoint_number),
+                        (true, false) => {
+                            self.usb.ep_in.stall().write(|w| w.stall().bit(false));
+                            self.usb.clear_feature_endpoint_halt(0x80 | endpoint_number);
+                        }
+                        (false, false) => {
+                            self.usb.ep_out.epno().write(|w| unsafe { w.epno().bits(endpoint_number) });
+                            self.usb.ep_out.stall().write(|w| w.stall().bit(false));
+                            self.usb.clear_feature_endpoint_halt(endpoint_number);
+                            self.prime_receive(endpoint_number);
+                        }
+                    }
+                    self.state.set_stalled(u8::from(ep_addr), stalled);
+                }
+
+                fn is_stalled(&self, ep_addr: $crate::usb_device::endpoint::EndpointAddress) -> bool {
+                    self.state.is_stalled(u8::from(ep_addr))
+                }
+
+                fn suspend(&self) {}
+
+                fn resume(&self) {}
+
+                fn poll(&self) -> $crate::usb_device::bus::PollResult {
+                    use $crate::smolusb::traits::ReadControl;
+                    use $crate::usb_device::bus::PollResult;
+
+                    // bus reset
+                    if self.usb.controller.ev_pending().read().pending().bit() {
+                        self.usb.controller.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        return PollResult::Reset;
+                    }
+
+                    // setup packet received
+                    if self.usb.ep_control.ev_pending().read().pending().bit() {
+                        self.usb.ep_control.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        let mut setup = [0_u8; 8];
+                        if self.usb.read_control(&mut setup) == setup.len() {
+                            // a new setup packet clears any control endpoint stall
+                            self.usb.ep_in.stall().write(|w| w.stall().bit(false));
+                            self.usb.ep_in.epno().write(|w| unsafe { w.epno().bits(0) });
+                            self.state.set_setup(setup);
+                        }
+                    }
+
+                    // send complete
+                    if self.usb.ep_in.ev_pending().read().pending().bit() {
+                        self.usb.ep_in.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        let endpoint_number = self.usb.ep_in.epno().read().bits() as usize;
+                        self.state.set_send_complete(endpoint_number);
+                    }
+
+                    // packet received
+                    if self.usb.ep_out.ev_pending().read().pending().bit() {
+                        self.usb.ep_out.ev_pending().modify(|r, w| w.pending().bit(r.pending().bit()));
+                        let endpoint_number = self.usb.ep_out.data_ep().read().bits() as usize;
+                        self.state.set_receive_packet(endpoint_number);
+                    }
+
+                    match self.state.take_poll_result() {
+                        (0, 0, 0) => PollResult::None,
+                        (ep_out, ep_in_complete, ep_setup) => PollResult::Data {
+                            ep_out,
+                            ep_in_complete,
+                            ep_setup,
+                        },
+                    }
+                }
+            }
+        )+
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use usb_device::bus::{PollResult, UsbBus as _, UsbBusAllocator};
+    use usb_device::device::{UsbDeviceBuilder, UsbDeviceState, UsbVidPid};
+    use usb_device::endpoint::{EndpointAddress, EndpointType};
+    use usb_device::{UsbDirection, UsbError};
+
+    use crate::sim::usb::Handshake;
+    use crate::sim::{self, pac};
+    use crate::smolusb::device::Speed;
+    use crate::smolusb::EP_MAX_ENDPOINTS;
+
+    use drivers::{Usb0, Usb0Bus};
+
+    #[allow(dead_code)]
+    mod drivers {
+        use crate::smolusb::device::Speed;
+        use crate::smolusb::setup::Direction;
+        use crate::smolusb::traits::{
+            ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriver, UsbDriverOperations,
+            WriteEndpoint,
+        };
+        use crate::usb::DEFAULT_TIMEOUT;
+
+        use super::pac;
+
+        crate::impl_usb! {
+            Usb0: usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+        }
+
+        crate::impl_usb_device! {
+            Usb0Bus: Usb0,
+        }
+    }
+
+    fn ep0_in() -> EndpointAddress {
+        EndpointAddress::from_parts(0, UsbDirection::In)
+    }
+
+    fn ep0_out() -> EndpointAddress {
+        EndpointAddress::from_parts(0, UsbDirection::Out)
+    }
+
+    fn ep1_out() -> EndpointAddress {
+        EndpointAddress::from_parts(1, UsbDirection::Out)
+    }
+
+    fn usb0() -> Usb0 {
+        let peripherals = pac::Peripherals::take().unwrap();
+        Usb0::new(
+            peripherals.USB0,
+            peripherals.USB0_EP_CONTROL,
+            peripherals.USB0_EP_IN,
+            peripherals.USB0_EP_OUT,
+        )
+    }
+
+    fn alloc(
+        bus: &mut Usb0Bus,
+        ep_dir: UsbDirection,
+        ep_addr: Option<EndpointAddress>,
+        max_packet_size: u16,
+    ) -> usb_device::Result<EndpointAddress> {
+        bus.alloc_ep(ep_dir, ep_addr, EndpointType::Bulk, max_packet_size, 0)
+    }
+
+    // - tests ----------------------------------------------------------------
+
+    #[test]
+    fn test_alloc_ep() {
+        let mut bus = Usb0Bus::new(usb0(), Speed::High);
+
+        assert_eq!(
+            alloc(&mut bus, UsbDirection::In, Some(ep0_in()), 64),
+            Ok(ep0_in())
+        );
+        assert_eq!(
+            alloc(&mut bus, UsbDirection::In, Some(ep0_in()), 64),
+            Err(UsbError::InvalidEndpoint)
+        );
+        assert_eq!(
+            alloc(&mut bus, UsbDirection::Out, Some(ep0_in()), 64),
+            Err(UsbError::InvalidEndpoint)
+        );
+        assert_eq!(
+            alloc(&mut bus, UsbDirection::In, None, 1024),
+            Err(UsbError::EndpointMemoryOverflow)
+        );
+
+        // every remaining IN endpoint can be allocated once
+        for endpoint_number in 1..EP_MAX_ENDPOINTS {
+            assert_eq!(
+                alloc(&mut bus, UsbDirection::In, None, 512),
+                Ok(EndpointAddress::from_parts(
+                    endpoint_number,
+                    UsbDirection::In
+                ))
+            );
+        }
+        assert_eq!(
+            alloc(&mut bus, UsbDirection::In, None, 64),
+            Err(UsbError::EndpointOverflow)
+        );
+
+        // OUT endpoints are allocated separately
+        assert_eq!(alloc(&mut bus, UsbDirection::Out, None, 64), Ok(ep1_out()));
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut bus = Usb0Bus::new(usb0(), Speed::High);
+        alloc(&mut bus, UsbDirection::In, Some(ep0_in()), 64).unwrap();
+        alloc(&mut bus, UsbDirection::Out, Some(ep0_out()), 64).unwrap();
+        alloc(&mut bus, UsbDirection::Out, Some(ep1_out()), 64).unwrap();
+        bus.enable();
+        assert!(sim::with(|soc| soc.usb0.is_connected()));
+        assert!(matches!(bus.poll(), PollResult::None));
+
+        // bus reset
+        assert_eq!(sim::with(|soc| soc.usb0.host_bus_reset()), Handshake::Ack);
+        assert!(matches!(bus.poll(), PollResult::Reset));
+        bus.reset();
+
+        // setup packet
+        let setup = [0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00];
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_setup(0, setup)),
+            Handshake::Ack
+        );
+        assert!(matches!(
+            bus.poll(),
+            PollResult::Data {
+                ep_out: 0,
+                ep_in_complete: 0,
+                ep_setup: 1
+            }
+        ));
+        let mut buffer = [0; 64];
+        assert_eq!(bus.read(ep0_out(), &mut buffer), Ok(8));
+        assert_eq!(buffer[..8], setup);
+        assert!(matches!(bus.poll(), PollResult::None));
+
+        // IN completion is reported once the host has collected the packet
+        let descriptor = [0x12, 0x01, 0x00, 0x02];
+        assert_eq!(bus.write(ep0_in(), &descriptor), Ok(descriptor.len()));
+        assert_eq!(bus.write(ep0_in(), &descriptor), Err(UsbError::WouldBlock));
+        assert!(matches!(bus.poll(), PollResult::None));
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_in(0)),
+            Ok(descriptor.to_vec())
+        );
+        assert!(matches!(
+            bus.poll(),
+            PollResult::Data {
+                ep_out: 0,
+                ep_in_complete: 1,
+                ep_setup: 0
+            }
+        ));
+        assert!(matches!(bus.poll(), PollResult::None));
+
+        // OUT packet stays reported until it is read
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_out(1, b"data")),
+            Handshake::Ack
+        );
+        assert!(matches!(
+            bus.poll(),
+            PollResult::Data {
+                ep_out: 2,
+                ep_in_complete: 0,
+                ep_setup: 0
+            }
+        ));
+        assert_eq!(bus.read(ep1_out(), &mut buffer), Ok(4));
+        assert_eq!(&buffer[..4], b"data");
+        assert!(matches!(bus.poll(), PollResult::None));
+        assert_eq!(bus.read(ep1_out(), &mut buffer), Err(UsbError::WouldBlock));
+    }
+
+    #[test]
+    fn test_set_device_address() {
+        let bus = UsbBusAllocator::new(Usb0Bus::new(usb0(), Speed::High));
+        let mut device = UsbDeviceBuilder::new(&bus, UsbVidPid(0x1d50, 0x615c)).build();
+
+        assert_eq!(sim::with(|soc| soc.usb0.host_bus_reset()), Handshake::Ack);
+        device.poll(&mut []);
+        assert_eq!(device.state(), UsbDeviceState::Default);
+
+        // SET_ADDRESS 0x12
+        let setup = [0x00, 0x05, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_setup(0, setup)),
+            Handshake::Ack
+        );
+        device.poll(&mut []);
+
+        // the status stage completes at the default address
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0);
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(0)), Ok(vec![]));
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0);
+
+        // and the new address only takes effect afterwards
+        device.poll(&mut []);
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0x12);
+        assert_eq!(device.state(), UsbDeviceState::Addressed);
+    }
+}
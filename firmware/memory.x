@@ -1,12 +0,0 @@
-MEMORY {
-    bootrom  : ORIGIN = 0x00000000, LENGTH = 0x00000020
-    spiflash : ORIGIN = 0x100b0000, LENGTH = 0x00400000
-    mainram  : ORIGIN = 0x40000000, LENGTH = 0x00010000
-}
-
-REGION_ALIAS("REGION_TEXT",   spiflash);
-REGION_ALIAS("REGION_RODATA", spiflash);
-REGION_ALIAS("REGION_DATA",   mainram);
-REGION_ALIAS("REGION_BSS",    mainram);
-REGION_ALIAS("REGION_HEAP",   mainram);
-REGION_ALIAS("REGION_STACK",  mainram);
@@ -0,0 +1,83 @@
+//! Attributes for the Cynthion Moondancer `SoC` peripheral access crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Error, ItemFn, ReturnType, Type, Visibility};
+
+/// Register a function as the handler of a `SoC` interrupt.
+///
+/// The name of the function is the name of the interrupt and it must
+/// have the signature `[unsafe] fn() [-> !]`. Handlers are called by
+/// `moondancer_pac::dispatch::Dispatcher`, interrupts without a handler
+/// call `DefaultHandler`.
+///
+/// The attribute must be imported together with the `interrupt` module
+/// of the PAC:
+///
+/// ```ignore
+/// use moondancer_pac::interrupt;
+///
+/// #[interrupt]
+/// fn TIMER() {
+///     ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as ItemFn);
+
+    if !args.is_empty() {
+        return Error::new(Span::call_site(), "`#[interrupt]` takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let valid_signature = f.sig.constness.is_none()
+        && f.sig.asyncness.is_none()
+        && f.sig.abi.is_none()
+        && f.sig.inputs.is_empty()
+        && f.sig.generics.params.is_empty()
+        && f.sig.generics.where_clause.is_none()
+        && f.sig.variadic.is_none()
+        && matches!(f.vis, Visibility::Inherited)
+        && match &f.sig.output {
+            ReturnType::Default => true,
+            ReturnType::Type(_, ty) => matches!(**ty, Type::Never(_)),
+        };
+    if !valid_signature {
+        return Error::new(
+            f.sig.span(),
+            "`#[interrupt]` handlers must have signature `[unsafe] fn() [-> !]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // the handler is renamed so that it can't be called from software and
+    // doesn't shadow items sharing the interrupt's name, e.g. peripherals
+    let name = &f.sig.ident;
+    let export_name = name.to_string();
+    let trampoline = format_ident!("__moondancer_pac_interrupt_{}", name);
+    let attrs = &f.attrs;
+    let unsafety = &f.sig.unsafety;
+    let output = &f.sig.output;
+    let block = &f.block;
+
+    quote!(
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #[export_name = #export_name]
+        pub unsafe extern "C" fn #trampoline() {
+            // fails to compile if the SoC has no interrupt of this name
+            let _ = interrupt::Interrupt::#name;
+
+            #(#attrs)*
+            #unsafety fn handler() #output #block
+
+            handler();
+        }
+    )
+    .into()
+}
@@ -1,26 +1,199 @@
 use std::env;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Offset of the firmware image in the SPI flash, the bitstream comes first.
+const FIRMWARE_OFFSET: u64 = 0x000b_0000;
 
 fn main() {
+    let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let svd = svd_path();
+    let xml = fs::read_to_string(&svd)
+        .unwrap_or_else(|e| panic!("failed to read '{}': {e}", svd.display()));
+
+    // peripheral access api and interrupts
+    let mut config = svd2rust::Config::default();
+    config.target = svd2rust::Target::RISCV;
+    config.skip_crate_attributes = true;
+    config.ident_formats_theme = Some(svd2rust::config::IdentFormatsTheme::Legacy);
+    let generation = svd2rust::generate(&xml, &config)
+        .unwrap_or_else(|e| panic!("failed to generate PAC from '{}': {e:?}", svd.display()));
+    let mut file = syn::parse_file(&generation.lib_rs).expect("svd2rust generated invalid code");
+    remove_interrupt_macro(&mut file);
+    fill_reserved_vectors(&mut file);
+    fs::write(out.join("generated.rs"), prettyplease::unparse(&file)).unwrap();
+
     if env::var_os("CARGO_FEATURE_RT").is_some() {
-        let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
-        File::create(out.join("device.x"))
-            .unwrap()
-            .write_all(include_bytes!("device.x"))
-            .unwrap();
-        println!("cargo:rustc-link-search={}", out.display());
-        println!("cargo:rerun-if-changed=device.x");
+        let device_x = generation
+            .device_specific
+            .map(|device_specific| device_specific.device_x)
+            .unwrap_or_default();
+        fs::write(out.join("device.x"), device_x).unwrap();
     }
 
+    // linker memory layout
+    let mut memory_x = memory_x(&xml);
+    if env::var_os("CARGO_FEATURE_RT").is_some() {
+        memory_x.push_str("\nINCLUDE device.x\n");
+    }
+    fs::write(out.join("memory.x"), memory_x).unwrap();
+    println!("cargo:rustc-link-search={}", out.display());
+
     // TODO Tracking Issue: https://github.com/rust-lang/rust/issues/94039
-    let Some(target) = rustc_target() else { return };
-    if target_has_atomic(&target) {
-        println!("cargo:rustc-cfg=target_has_atomic");
+    if let Some(target) = rustc_target() {
+        if target_has_atomic(&target) {
+            println!("cargo:rustc-cfg=target_has_atomic");
+        }
     }
 
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", svd.display());
+    println!("cargo:rerun-if-env-changed=MOONDANCER_PAC_SVD");
+    println!("cargo:rerun-if-env-changed=MOONDANCER_PAC_TEXT_REGION");
+}
+
+/// Returns the SVD given by `MOONDANCER_PAC_SVD`, otherwise
+/// `svd/facedancer.svd`.
+fn svd_path() -> PathBuf {
+    env::var_os("MOONDANCER_PAC_SVD")
+        .map_or_else(|| Path::new("svd").join("facedancer.svd"), PathBuf::from)
+}
+
+/// Remove svd2rust's `interrupt!` macro, handlers are registered with the
+/// `#[interrupt]` attribute which shares its name.
+fn remove_interrupt_macro(file: &mut syn::File) {
+    for item in &mut file.items {
+        if let syn::Item::Mod(syn::ItemMod {
+            ident,
+            content: Some((_, items)),
+            ..
+        }) = item
+        {
+            if ident == "interrupt" {
+                items.retain(|item| {
+                    !matches!(item, syn::Item::Macro(item) if item.mac.path.is_ident("macro_rules"))
+                });
+            }
+        }
+    }
+}
+
+/// Point the reserved slots of svd2rust's `__EXTERNAL_INTERRUPTS` table at
+/// `DefaultHandler` so that a spurious interrupt isn't dispatched to a
+/// null handler.
+fn fill_reserved_vectors(file: &mut syn::File) {
+    let mut reserved = false;
+    for item in &mut file.items {
+        let syn::Item::Static(item) = item else {
+            continue;
+        };
+        let syn::Expr::Array(vectors) = &mut *item.expr else {
+            continue;
+        };
+        if item.ident != "__EXTERNAL_INTERRUPTS" {
+            continue;
+        }
+        for vector in &mut vectors.elems {
+            let is_reserved = matches!(vector, syn::Expr::Struct(vector)
+            if vector.fields.iter().any(|field| {
+                matches!(&field.member, syn::Member::Named(name) if name == "_reserved")
+            }));
+            if is_reserved {
+                *vector = syn::parse_quote!(Vector {
+                    _handler: DefaultHandler
+                });
+                reserved = true;
+            }
+        }
+    }
+    if !reserved {
+        return;
+    }
+
+    // declare DefaultHandler alongside the interrupt handlers
+    for item in &mut file.items {
+        if let syn::Item::ForeignMod(handlers) = item {
+            handlers.items.push(syn::parse_quote!(
+                fn DefaultHandler();
+            ));
+            return;
+        }
+    }
+    panic!("svd2rust output has no interrupt handler declarations");
+}
+
+/// Generate a `memory.x` linker script from the SVD's memory regions.
+///
+/// Code and read-only data are placed in the `spiflash` region unless
+/// `MOONDANCER_PAC_TEXT_REGION` names another region, e.g. `mainram`
+/// when loading firmware over JTAG.
+fn memory_x(xml: &str) -> String {
+    let document = roxmltree::Document::parse(xml).expect("failed to parse SVD");
+    let regions = document
+        .descendants()
+        .filter(|node| node.has_tag_name("memoryRegion"))
+        .map(|node| {
+            let child = |name: &str| {
+                node.children()
+                    .find(|child| child.has_tag_name(name))
+                    .and_then(|child| child.text())
+                    .unwrap_or_else(|| panic!("memory region is missing <{name}>"))
+                    .trim()
+            };
+            let name = child("name").to_lowercase();
+            let origin = parse_u64(child("baseAddress"));
+            let length = parse_u64(child("size"));
+            if name == "spiflash" {
+                let length = length.checked_sub(FIRMWARE_OFFSET).unwrap_or_else(|| {
+                    panic!(
+                        "spiflash region of {length:#x} bytes is smaller than the \
+                         firmware offset of {FIRMWARE_OFFSET:#x} bytes"
+                    )
+                });
+                (name, origin + FIRMWARE_OFFSET, length)
+            } else {
+                (name, origin, length)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let text_region = env::var("MOONDANCER_PAC_TEXT_REGION").unwrap_or("spiflash".into());
+    for region in ["spiflash", "mainram", text_region.as_str()] {
+        assert!(
+            regions.iter().any(|(name, _, _)| name == region),
+            "SVD has no '{region}' memory region"
+        );
+    }
+
+    let mut memory_x = String::from("MEMORY {\n");
+    for (name, origin, length) in &regions {
+        writeln!(
+            memory_x,
+            "    {name:<8} : ORIGIN = {origin:#010x}, LENGTH = {length:#010x}"
+        )
+        .unwrap();
+    }
+    memory_x.push_str("}\n\n");
+    for (alias, region) in [
+        ("REGION_TEXT", text_region.as_str()),
+        ("REGION_RODATA", text_region.as_str()),
+        ("REGION_DATA", "mainram"),
+        ("REGION_BSS", "mainram"),
+        ("REGION_HEAP", "mainram"),
+        ("REGION_STACK", "mainram"),
+    ] {
+        writeln!(memory_x, "REGION_ALIAS(\"{alias}\", {region});").unwrap();
+    }
+    memory_x
+}
+
+fn parse_u64(text: &str) -> u64 {
+    let value = match text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    value.unwrap_or_else(|_| panic!("invalid number in SVD: '{text}'"))
 }
 
 fn rustc_target() -> Option<String> {
@@ -1,7 +1,31 @@
 //! Support for various vendor defined softcore extensions.
 
+#[cfg(feature = "minerva")]
+pub use minerva::{flush_dcache, flush_icache};
+#[cfg(feature = "vexriscv")]
+pub use vexriscv::{flush_dcache, flush_icache};
+
 pub mod minerva {
 
+    /// Invalidate the instruction cache with `fence.i`.
+    #[inline(always)]
+    pub fn flush_icache() {
+        unsafe {
+            core::arch::asm!(".word(0x100f)");
+        }
+    }
+
+    /// Invalidate the data cache.
+    ///
+    /// The Minerva data cache is write-through and is invalidated
+    /// along with the instruction cache by `fence.i`.
+    #[inline(always)]
+    pub fn flush_dcache() {
+        unsafe {
+            core::arch::asm!(".word(0x100f)");
+        }
+    }
+
     pub mod register {
         //! Micro-architecture specific CSR extensions for the Minerva RISC-V
         //! soft processor.
@@ -0,0 +1,180 @@
+//! Vectored interrupt dispatch
+//!
+//! All `SoC` interrupts arrive through the single `MachineExternal` trap
+//! and [`csr::interrupt::pending`](crate::csr::interrupt::pending) only
+//! reports the highest numbered of them. A [`Dispatcher`] instead
+//! services every pending interrupt on each trap, in a configurable
+//! priority order, by calling the handlers registered with the
+//! [`interrupt`](macro@crate::interrupt) attribute:
+//!
+//!     use moondancer_pac::{self as pac, interrupt};
+//!     use pac::dispatch::Dispatcher;
+//!
+//!     // USB2 is serviced before USB0, anything not listed comes last
+//!     static DISPATCHER: Dispatcher<2> =
+//!         Dispatcher::new([pac::Interrupt::USB2, pac::Interrupt::USB0]).nested();
+//!
+//!     #[no_mangle]
+//!     extern "C" fn MachineExternal() {
+//!         unsafe { DISPATCHER.dispatch() };
+//!     }
+//!
+//!     #[interrupt]
+//!     fn USB0() {
+//!         ...
+//!     }
+//!
+//! Enabled interrupts without a handler call `DefaultHandler`.
+//!
+//! When nesting is enabled each handler runs with interrupts re-enabled
+//! and the interrupts listed after it masked, so only interrupts of a
+//! higher priority can preempt it. Interrupts that are not listed have
+//! the lowest priority and do not preempt each other. Handlers should
+//! not change `mim` while nesting as it is restored when they return.
+
+use crate::register;
+use crate::Interrupt;
+
+/// Number of interrupts supported by the `mim` and `mip` CSRs.
+pub const NUM_INTERRUPTS: usize = 32;
+
+extern "C" {
+    fn DefaultHandler();
+}
+
+/// Dispatches pending `SoC` interrupts to their handlers.
+pub struct Dispatcher<const N: usize> {
+    /// Interrupts in priority order, highest first.
+    priorities: [Interrupt; N],
+    /// Interrupts listed in `priorities`.
+    listed: usize,
+    /// Interrupts allowed to preempt the handler of each interrupt.
+    preempt: [usize; NUM_INTERRUPTS],
+    nested: bool,
+}
+
+impl<const N: usize> Dispatcher<N> {
+    /// Create a new `Dispatcher` servicing the given interrupts in order
+    /// of priority, highest first.
+    ///
+    /// Pending interrupts that are not listed are serviced after the
+    /// listed ones, in order of their interrupt number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an interrupt is listed more than once.
+    #[must_use]
+    pub const fn new(priorities: [Interrupt; N]) -> Self {
+        let mut listed = 0;
+        let mut preempt = [0; NUM_INTERRUPTS];
+
+        let mut index = 0;
+        while index < N {
+            let bit = 1 << priorities[index] as usize;
+            assert!(listed & bit == 0, "interrupt listed more than once");
+            preempt[priorities[index] as usize] = listed;
+            listed |= bit;
+            index += 1;
+        }
+
+        let mut irq = 0;
+        while irq < NUM_INTERRUPTS {
+            if listed & (1 << irq) == 0 {
+                preempt[irq] = listed;
+            }
+            irq += 1;
+        }
+
+        Self {
+            priorities,
+            listed,
+            preempt,
+            nested: false,
+        }
+    }
+
+    /// Allow higher priority interrupts to preempt the running handler.
+    #[must_use]
+    pub const fn nested(mut self) -> Self {
+        self.nested = true;
+        self
+    }
+
+    /// Service pending interrupts until none remain.
+    ///
+    /// Each pass calls the handler of every interrupt that was pending
+    /// at its start, so a busy interrupt can't starve the others.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the `MachineExternal` trap handler.
+    pub unsafe fn dispatch(&self) {
+        loop {
+            let pending = register::mip::read();
+            if pending == 0 {
+                break;
+            }
+
+            for interrupt in &self.priorities {
+                let irq = *interrupt as usize;
+                if pending & (1 << irq) != 0 {
+                    self.call(irq);
+                }
+            }
+
+            let unlisted = pending & !self.listed;
+            for irq in 0..NUM_INTERRUPTS {
+                if unlisted & (1 << irq) != 0 {
+                    self.call(irq);
+                }
+            }
+        }
+    }
+
+    unsafe fn call(&self, irq: usize) {
+        let handler = match crate::__EXTERNAL_INTERRUPTS.get(irq) {
+            Some(vector) => vector._handler,
+            None => DefaultHandler,
+        };
+
+        if !self.nested {
+            handler();
+            return;
+        }
+
+        let mask = register::mim::read();
+        set_mask(mask & self.preempt[irq]);
+
+        // the trap handler does not preserve these across a nested trap
+        let mepc = riscv::register::mepc::read();
+        let mstatus = read_mstatus();
+
+        riscv::interrupt::enable();
+        handler();
+        riscv::interrupt::disable();
+
+        riscv::register::mepc::write(mepc);
+        write_mstatus(mstatus);
+
+        set_mask(mask);
+    }
+}
+
+fn set_mask(mask: usize) {
+    register::mim::write(mask);
+    while register::mim::read() != mask {}
+}
+
+#[inline(always)]
+fn read_mstatus() -> usize {
+    let bits: usize;
+    unsafe {
+        core::arch::asm!("csrr {0}, mstatus", out(reg) bits);
+    }
+    bits
+}
+
+#[inline(always)]
+unsafe fn write_mstatus(bits: usize) {
+    core::arch::asm!("csrw mstatus, {0}", in(reg) bits);
+}
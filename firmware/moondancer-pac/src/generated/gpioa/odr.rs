@@ -1,27 +0,0 @@
-#[doc = "Register `odr` writer"]
-pub type W = crate::W<ODR_SPEC>;
-#[doc = "Field `odr` writer - "]
-pub type ODR_W<'a, REG> = crate::FieldWriter<'a, REG, 8>;
-impl W {
-    #[doc = "Bits 0:7"]
-    #[inline(always)]
-    #[must_use]
-    pub fn odr(&mut self) -> ODR_W<ODR_SPEC> {
-        ODR_W::new(self, 0)
-    }
-}
-#[doc = "gpioa odr register\n\nYou can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`odr::W`](W). See [API](https://docs.rs/svd2rust/#read--modify--write-api)."]
-pub struct ODR_SPEC;
-impl crate::RegisterSpec for ODR_SPEC {
-    type Ux = u32;
-}
-#[doc = "`write(|w| ..)` method takes [`odr::W`](W) writer structure"]
-impl crate::Writable for ODR_SPEC {
-    type Safety = crate::Unsafe;
-    const ZERO_TO_MODIFY_FIELDS_BITMAP: u32 = 0;
-    const ONE_TO_MODIFY_FIELDS_BITMAP: u32 = 0;
-}
-#[doc = "`reset()` method sets odr to value 0"]
-impl crate::Resettable for ODR_SPEC {
-    const RESET_VALUE: u32 = 0;
-}
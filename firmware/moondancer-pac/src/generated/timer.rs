@@ -1,79 +0,0 @@
-#[repr(C)]
-#[doc = "Register block"]
-pub struct RegisterBlock {
-    reload: RELOAD,
-    en: EN,
-    ctr: CTR,
-    _reserved3: [u8; 0x04],
-    ev_status: EV_STATUS,
-    ev_pending: EV_PENDING,
-    ev_enable: EV_ENABLE,
-}
-impl RegisterBlock {
-    #[doc = "0x00 - timer reload register"]
-    #[inline(always)]
-    pub const fn reload(&self) -> &RELOAD {
-        &self.reload
-    }
-    #[doc = "0x04 - timer en register"]
-    #[inline(always)]
-    pub const fn en(&self) -> &EN {
-        &self.en
-    }
-    #[doc = "0x08 - timer ctr register"]
-    #[inline(always)]
-    pub const fn ctr(&self) -> &CTR {
-        &self.ctr
-    }
-    #[doc = "0x10 - timer ev_status register"]
-    #[inline(always)]
-    pub const fn ev_status(&self) -> &EV_STATUS {
-        &self.ev_status
-    }
-    #[doc = "0x14 - timer ev_pending register"]
-    #[inline(always)]
-    pub const fn ev_pending(&self) -> &EV_PENDING {
-        &self.ev_pending
-    }
-    #[doc = "0x18 - timer ev_enable register"]
-    #[inline(always)]
-    pub const fn ev_enable(&self) -> &EV_ENABLE {
-        &self.ev_enable
-    }
-}
-#[doc = "reload (rw) register accessor: timer reload register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`reload::R`].  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`reload::W`]. You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@reload`]
-module"]
-#[doc(alias = "reload")]
-pub type RELOAD = crate::Reg<reload::RELOAD_SPEC>;
-#[doc = "timer reload register"]
-pub mod reload;
-#[doc = "en (rw) register accessor: timer en register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`en::R`].  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`en::W`]. You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@en`]
-module"]
-#[doc(alias = "en")]
-pub type EN = crate::Reg<en::EN_SPEC>;
-#[doc = "timer en register"]
-pub mod en;
-#[doc = "ctr (rw) register accessor: timer ctr register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`ctr::R`].  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`ctr::W`]. You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@ctr`]
-module"]
-#[doc(alias = "ctr")]
-pub type CTR = crate::Reg<ctr::CTR_SPEC>;
-#[doc = "timer ctr register"]
-pub mod ctr;
-#[doc = "ev_status (r) register accessor: timer ev_status register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`ev_status::R`].  See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@ev_status`]
-module"]
-#[doc(alias = "ev_status")]
-pub type EV_STATUS = crate::Reg<ev_status::EV_STATUS_SPEC>;
-#[doc = "timer ev_status register"]
-pub mod ev_status;
-#[doc = "ev_pending (rw) register accessor: timer ev_pending register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`ev_pending::R`].  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`ev_pending::W`]. You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@ev_pending`]
-module"]
-#[doc(alias = "ev_pending")]
-pub type EV_PENDING = crate::Reg<ev_pending::EV_PENDING_SPEC>;
-#[doc = "timer ev_pending register"]
-pub mod ev_pending;
-#[doc = "ev_enable (rw) register accessor: timer ev_enable register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`ev_enable::R`].  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`ev_enable::W`]. You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api).\n\nFor information about available fields see [`mod@ev_enable`]
-module"]
-#[doc(alias = "ev_enable")]
-pub type EV_ENABLE = crate::Reg<ev_enable::EV_ENABLE_SPEC>;
-#[doc = "timer ev_enable register"]
-pub mod ev_enable;
@@ -1,40 +0,0 @@
-#[doc = "Register `en` reader"]
-pub type R = crate::R<EN_SPEC>;
-#[doc = "Register `en` writer"]
-pub type W = crate::W<EN_SPEC>;
-#[doc = "Field `en` reader - timer en register field"]
-pub type EN_R = crate::BitReader;
-#[doc = "Field `en` writer - timer en register field"]
-pub type EN_W<'a, REG> = crate::BitWriter<'a, REG>;
-impl R {
-    #[doc = "Bit 0 - timer en register field"]
-    #[inline(always)]
-    pub fn en(&self) -> EN_R {
-        EN_R::new((self.bits & 1) != 0)
-    }
-}
-impl W {
-    #[doc = "Bit 0 - timer en register field"]
-    #[inline(always)]
-    #[must_use]
-    pub fn en(&mut self) -> EN_W<EN_SPEC> {
-        EN_W::new(self, 0)
-    }
-}
-#[doc = "timer en register\n\nYou can [`read`](crate::generic::Reg::read) this register and get [`en::R`](R).  You can [`reset`](crate::generic::Reg::reset), [`write`](crate::generic::Reg::write), [`write_with_zero`](crate::generic::Reg::write_with_zero) this register using [`en::W`](W). You can also [`modify`](crate::generic::Reg::modify) this register. See [API](https://docs.rs/svd2rust/#read--modify--write-api)."]
-pub struct EN_SPEC;
-impl crate::RegisterSpec for EN_SPEC {
-    type Ux = u32;
-}
-#[doc = "`read()` method returns [`en::R`](R) reader structure"]
-impl crate::Readable for EN_SPEC {}
-#[doc = "`write(|w| ..)` method takes [`en::W`](W) writer structure"]
-impl crate::Writable for EN_SPEC {
-    type Safety = crate::Unsafe;
-    const ZERO_TO_MODIFY_FIELDS_BITMAP: u32 = 0;
-    const ONE_TO_MODIFY_FIELDS_BITMAP: u32 = 0;
-}
-#[doc = "`reset()` method sets en to value 0"]
-impl crate::Resettable for EN_SPEC {
-    const RESET_VALUE: u32 = 0;
-}
@@ -11,6 +11,8 @@ mod macros;
 
 pub mod cpu;
 pub mod csr;
+#[cfg(feature = "rt")]
+pub mod dispatch;
 pub mod register {
     #[cfg(feature = "minerva")]
     pub use crate::cpu::minerva::register::*;
@@ -44,7 +46,12 @@ pub mod clock {
 #[allow(non_snake_case)]
 #[allow(clippy::must_use_candidate)]
 #[allow(clippy::semicolon_if_nothing_returned)]
-mod generated;
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+}
 
 pub use generated::generic::*;
 pub use generated::*;
+
+#[cfg(feature = "rt")]
+pub use moondancer_pac_macros::interrupt;
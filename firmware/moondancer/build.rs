@@ -9,7 +9,6 @@ fn main() {
     }
 
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rerun-if-changed=memory.x");
 }
 
 fn rustc_target() -> Option<String> {
@@ -12,6 +12,8 @@ use smolusb::event::UsbEvent;
 use smolusb::setup::{Request, RequestType, SetupPacket};
 use smolusb::traits::{ReadControl, ReadEndpoint, UsbDriverOperations, WriteEndpoint};
 
+use hal::shared;
+use moondancer::util::{USB0_EVENTS, USB1_EVENTS};
 use moondancer::{hal, pac};
 use pac::csr::interrupt;
 
@@ -76,8 +78,6 @@ extern "C" fn MachineExternal() {
     // peripherals
     let peripherals = unsafe { pac::Peripherals::steal() };
     let leds = &peripherals.LEDS;
-    let usb0 = unsafe { hal::Usb0::summon() };
-    let usb1 = unsafe { hal::Usb1::summon() };
 
     // debug
     leds.output()
@@ -92,43 +92,28 @@ extern "C" fn MachineExternal() {
         }
     };
 
-    match pending {
+    let handled = match pending {
         // - Usb0 (Target) interrupts --
-        pac::Interrupt::USB0 => {
-            usb0.controller
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            usb0.bus_reset();
-        }
-        pac::Interrupt::USB0_EP_CONTROL => {
-            usb0.ep_control
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            let endpoint = usb0.ep_control.epno().read().bits() as u8;
+        pac::Interrupt::USB0 => USB0_EVENTS.with(hal::usb0::Events::bus_reset),
+        pac::Interrupt::USB0_EP_CONTROL => USB0_EVENTS.with(|usb0| {
             let mut buffer = [0_u8; 8];
-            let _bytes_read = usb0.read_control(&mut buffer);
+            let (endpoint, _bytes_read) = usb0.receive_setup_packet(&mut buffer);
             let setup_packet = SetupPacket::from(buffer);
             dispatch_event(InterruptEvent::Usb(
                 Target,
                 UsbEvent::ReceiveSetupPacket(endpoint, setup_packet),
             ));
-        }
-        pac::Interrupt::USB0_EP_IN => {
-            usb0.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            let endpoint = usb0.ep_in.epno().read().bits() as u8;
+        }),
+        pac::Interrupt::USB0_EP_IN => USB0_EVENTS.with(|usb0| {
+            let endpoint = usb0.send_complete();
             dispatch_event(InterruptEvent::Usb(
                 Target,
                 UsbEvent::SendComplete(endpoint),
             ));
-        }
-        pac::Interrupt::USB0_EP_OUT => {
-            usb0.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
+        }),
+        pac::Interrupt::USB0_EP_OUT => USB0_EVENTS.with(|usb0| {
             // read data from endpoint
-            let endpoint = usb0.ep_out.data_ep().read().bits() as u8;
+            let endpoint = usb0.receive_packet();
             let mut receive_packet = UsbDataPacket {
                 interface: Target,
                 endpoint,
@@ -137,41 +122,26 @@ extern "C" fn MachineExternal() {
             };
             receive_packet.bytes_read = usb0.read(endpoint, &mut receive_packet.buffer);
             dispatch_receive_packet(receive_packet);
-        }
+        }),
 
         // - Usb1 (Aux) interrupts --
-        pac::Interrupt::USB1 => {
-            usb1.controller
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            usb1.bus_reset();
-        }
-        pac::Interrupt::USB1_EP_CONTROL => {
-            usb1.ep_control
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            let endpoint = usb1.ep_control.epno().read().bits() as u8;
+        pac::Interrupt::USB1 => USB1_EVENTS.with(hal::usb1::Events::bus_reset),
+        pac::Interrupt::USB1_EP_CONTROL => USB1_EVENTS.with(|usb1| {
             let mut buffer = [0_u8; 8];
-            let _bytes_read = usb1.read_control(&mut buffer);
+            let (endpoint, _bytes_read) = usb1.receive_setup_packet(&mut buffer);
             let setup_packet = SetupPacket::from(buffer);
             dispatch_event(InterruptEvent::Usb(
                 Aux,
                 UsbEvent::ReceiveSetupPacket(endpoint, setup_packet),
             ));
-        }
-        pac::Interrupt::USB1_EP_IN => {
-            usb1.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-            let endpoint = usb1.ep_in.epno().read().bits() as u8;
+        }),
+        pac::Interrupt::USB1_EP_IN => USB1_EVENTS.with(|usb1| {
+            let endpoint = usb1.send_complete();
             dispatch_event(InterruptEvent::Usb(Aux, UsbEvent::SendComplete(endpoint)));
-        }
-        pac::Interrupt::USB1_EP_OUT => {
-            usb1.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
+        }),
+        pac::Interrupt::USB1_EP_OUT => USB1_EVENTS.with(|usb1| {
             // read data from endpoint
-            let endpoint = usb1.ep_out.data_ep().read().bits() as u8;
+            let endpoint = usb1.receive_packet();
             let mut receive_packet = UsbDataPacket {
                 interface: Aux,
                 endpoint,
@@ -180,20 +150,24 @@ extern "C" fn MachineExternal() {
             };
             receive_packet.bytes_read = usb1.read(endpoint, &mut receive_packet.buffer);
             dispatch_receive_packet(receive_packet);
-        }
+        }),
+
         // - Unhandled Interrupt --
-        _ => dispatch_event(InterruptEvent::UnhandledInterrupt(pending)),
+        _ => Err(shared::Error::Uninitialized),
+    };
+
+    if handled.is_err() {
+        dispatch_event(InterruptEvent::UnhandledInterrupt(pending));
     }
 }
 
 // - main entry point ---------------------------------------------------------
 
-#[cfg(feature = "vexriscv")]
 #[riscv_rt::pre_init]
 unsafe fn pre_main() {
-    pac::cpu::vexriscv::flush_icache();
-    #[cfg(feature = "vexriscv_dcache")]
-    pac::cpu::vexriscv::flush_dcache();
+    pac::cpu::flush_icache();
+    #[cfg(any(feature = "minerva", feature = "vexriscv_dcache"))]
+    pac::cpu::flush_dcache();
 }
 
 #[riscv_rt::entry]
@@ -203,7 +177,10 @@ fn main() -> ! {
     leds.output().write(|w| unsafe { w.output().bits(0x0) });
 
     // initialize logging
-    moondancer::log::init();
+    moondancer::log::init(
+        hal::Serial0::new(peripherals.UART),
+        hal::Serial1::new(peripherals.UART1),
+    );
     info!("logging initialized");
 
     // usb0: Target
@@ -262,6 +239,10 @@ fn main() -> ! {
     let speed: Speed = usb1.controller.speed().read().speed().bits().into();
     info!("Connected USB1 device: {:?}", speed);
 
+    // move the handles used to service usb interrupts into their cells
+    USB0_EVENTS.init(usb0.events().unwrap()).ok();
+    USB1_EVENTS.init(usb1.events().unwrap()).ok();
+
     // enable interrupts
     unsafe {
         // set mstatus register: interrupt enable
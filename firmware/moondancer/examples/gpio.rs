@@ -3,28 +3,29 @@
 
 use moondancer::pac;
 
+use hal::gpio::Edge;
 use hal::hal::delay::DelayUs;
+use hal::hal::digital::{InputPin, OutputPin};
 use moondancer::hal;
+use moondancer::hal::shared::Shared;
 
 use log::{error, info};
 
 use riscv_rt::entry;
 
+// - global static state ------------------------------------------------------
+
+static GPIOA_EVENTS: Shared<hal::gpioa::Events> = Shared::new();
+
 // - interrupt handler --------------------------------------------------------
 
 #[allow(non_snake_case)]
 #[no_mangle]
 extern "C" fn MachineExternal() {
-    let peripherals = unsafe { pac::Peripherals::steal() };
-    let gpioa = &peripherals.GPIOA;
-
     if pac::csr::interrupt::is_pending(pac::Interrupt::GPIOA) {
-        let pending = gpioa.ev_pending().read().pending().bit();
-        gpioa.ev_pending().write(|w| w.pending().bit(pending));
-
-        let bits_all: u32 = gpioa.idr().read().bits();
-        let bits_in: u32 = gpioa.idr().read().bits() & 0b0000_1111;
-        info!("gpioa bits: {bits_all:#010b} {bits_in:#010b}");
+        if let Ok(pins) = GPIOA_EVENTS.with(hal::gpioa::Events::take_pending) {
+            info!("gpioa edges: {pins:#010b}");
+        }
     } else {
         error!("MachineExternal - unknown interrupt");
     }
@@ -38,16 +39,22 @@ fn main() -> ! {
     let leds = &peripherals.LEDS;
 
     // initialize logging
-    moondancer::log::init();
+    moondancer::log::init(
+        hal::Serial0::new(peripherals.UART),
+        hal::Serial1::new(peripherals.UART1),
+    );
 
     // configure gpioa pins 7-4:output, 3-0:input
-    let gpioa = &peripherals.GPIOA;
-    gpioa
-        .moder()
-        .write(|w| unsafe { w.moder().bits(0b0000_1111) }); // 0=output, 1=input
+    let gpioa = hal::GpioA::new(peripherals.GPIOA).split();
+    let mut inputs = (gpioa.p0, gpioa.p1, gpioa.p2, gpioa.p3);
+    let mut p4 = gpioa.p4.into_output();
+    let mut p5 = gpioa.p5.into_output();
+    let mut p6 = gpioa.p6.into_output();
+    let mut p7 = gpioa.p7.into_output();
 
-    // enable gpioa events
-    gpioa.ev_enable().write(|w| w.enable().bit(true));
+    // enable gpioa events for rising and falling edges on pin 0
+    inputs.0.listen(Edge::Both);
+    GPIOA_EVENTS.init(gpioa.events).ok();
 
     // configure and enable timer
     let mut timer = hal::Timer0::new(peripherals.TIMER, pac::clock::sysclk());
@@ -69,9 +76,13 @@ fn main() -> ! {
     let mut counter = 0;
 
     loop {
-        gpioa
-            .odr()
-            .write(|w| unsafe { w.odr().bits(counter & 0b1111_0000) });
+        p4.set_state((counter & 0b0001_0000 != 0).into()).unwrap();
+        p5.set_state((counter & 0b0010_0000 != 0).into()).unwrap();
+        p6.set_state((counter & 0b0100_0000 != 0).into()).unwrap();
+        p7.set_state((counter & 0b1000_0000 != 0).into()).unwrap();
+        if inputs.1.is_high().unwrap() {
+            info!("gpioa pin 1 is high");
+        }
         leds.output().write(|w| unsafe { w.output().bits(counter) });
 
         timer.delay_ms(100).unwrap();
@@ -10,12 +10,11 @@ use log::info;
 
 use riscv_rt::entry;
 
-#[cfg(feature = "vexriscv")]
 #[riscv_rt::pre_init]
 unsafe fn pre_main() {
-    pac::cpu::vexriscv::flush_icache();
-    #[cfg(feature = "vexriscv_dcache")]
-    pac::cpu::vexriscv::flush_dcache();
+    pac::cpu::flush_icache();
+    #[cfg(any(feature = "minerva", feature = "vexriscv_dcache"))]
+    pac::cpu::flush_dcache();
 }
 
 #[entry]
@@ -25,7 +24,10 @@ fn main() -> ! {
 
     // initialize logging
     moondancer::log::set_port(moondancer::log::Port::Both);
-    moondancer::log::init();
+    moondancer::log::init(
+        hal::Serial0::new(peripherals.UART),
+        hal::Serial1::new(peripherals.UART1),
+    );
 
     let mut timer = hal::Timer0::new(peripherals.TIMER, pac::clock::sysclk());
     let mut counter = 0;
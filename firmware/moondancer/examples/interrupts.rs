@@ -3,32 +3,34 @@
 
 use moondancer::{hal, pac};
 
+use hal::shared::Shared;
+
 use log::{error, info};
 use riscv_rt::entry;
 
+// - shared peripherals -------------------------------------------------------
+
+static TIMER: Shared<hal::Timer0> = Shared::new();
+static LEDS: Shared<(pac::LEDS, bool)> = Shared::new();
+
 // - interrupt handler --------------------------------------------------------
 
 #[allow(non_snake_case)]
 #[no_mangle]
 extern "C" fn MachineExternal() {
-    static mut TOGGLE: bool = true;
-
     if pac::csr::interrupt::is_pending(pac::Interrupt::TIMER) {
-        let timer = unsafe { hal::Timer0::summon() };
-        timer.clear_pending();
-
-        //writeln!(serial, "MachineExternal - timer interrupt").unwrap();
+        TIMER.with(|timer| timer.clear_pending()).unwrap();
 
         // blinkenlights
-        let peripherals = unsafe { pac::Peripherals::steal() };
-        let leds = &peripherals.LEDS;
-
-        if unsafe { TOGGLE } {
-            leds.output().write(|w| unsafe { w.output().bits(0b1) });
-        } else {
-            leds.output().write(|w| unsafe { w.output().bits(0b0) });
-        }
-        unsafe { TOGGLE = !TOGGLE };
+        LEDS.with(|(leds, toggle)| {
+            if *toggle {
+                leds.output().write(|w| unsafe { w.output().bits(0b1) });
+            } else {
+                leds.output().write(|w| unsafe { w.output().bits(0b0) });
+            }
+            *toggle = !*toggle;
+        })
+        .unwrap();
     } else {
         error!("MachineExternal - unknown interrupt");
     }
@@ -41,7 +43,10 @@ fn main() -> ! {
     let peripherals = pac::Peripherals::take().unwrap();
 
     // initialize logging
-    moondancer::log::init();
+    moondancer::log::init(
+        hal::Serial0::new(peripherals.UART),
+        hal::Serial1::new(peripherals.UART1),
+    );
 
     // configure and enable timer
     let one_second = pac::clock::sysclk();
@@ -52,6 +57,10 @@ fn main() -> ! {
     // enable timer events
     timer.listen(hal::timer::Event::TimeOut);
 
+    // share timer and leds with the interrupt handler
+    TIMER.init(timer).ok();
+    LEDS.init((peripherals.LEDS, true)).ok();
+
     // enable interrupts
     unsafe {
         // set mstatus register: interrupt enable
@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+use moondancer::{hal, pac};
+
+use hal::embedded_io::{Read, Write};
+use hal::serial::{Buffered, State};
+
+use log::{error, info};
+use riscv_rt::entry;
+
+static UART1_STATE: State<hal::Serial1, 64, 256> = State::new();
+
+// - interrupt handler --------------------------------------------------------
+
+#[allow(non_snake_case)]
+#[no_mangle]
+extern "C" fn MachineExternal() {
+    if pac::csr::interrupt::is_pending(pac::Interrupt::UART1) {
+        UART1_STATE.on_interrupt();
+    } else {
+        error!("MachineExternal - unknown interrupt");
+    }
+}
+
+// - main entry point ---------------------------------------------------------
+
+#[entry]
+fn main() -> ! {
+    let peripherals = pac::Peripherals::take().unwrap();
+
+    // initialize logging
+    moondancer::log::set_port(moondancer::log::Port::Uart0);
+    moondancer::log::init(hal::Serial0::new(peripherals.UART), None);
+
+    // create an interrupt driven driver for uart1
+    let mut serial1 = Buffered::new(hal::Serial1::new(peripherals.UART1), &UART1_STATE);
+
+    // enable interrupts
+    unsafe {
+        // set mstatus register: interrupt enable
+        riscv::interrupt::enable();
+
+        // set mie register: machine external interrupts enable
+        riscv::register::mie::set_mext();
+
+        // write csr: enable uart1 interrupt
+        pac::csr::interrupt::enable(pac::Interrupt::UART1);
+    }
+
+    info!("Peripherals initialized, entering main loop.");
+
+    serial1.write_all(b"echo> ").unwrap();
+
+    let mut buffer = [0_u8; 64];
+    loop {
+        match Read::read(&mut serial1, &mut buffer) {
+            Ok(count) => {
+                serial1.write_all(&buffer[..count]).unwrap();
+            }
+            Err(e) => {
+                error!("uart1 receive error: {:?}", e);
+            }
+        }
+    }
+}
@@ -14,7 +14,6 @@ use smolusb::descriptor::{
     LanguageId, StringDescriptor, StringDescriptorZero,
 };
 use smolusb::device::{Descriptors, Speed};
-use smolusb::event::UsbEvent;
 use smolusb::setup::{Direction, SetupPacket};
 use smolusb::traits::{
     ReadControl, ReadEndpoint, UnsafeUsbDriverOperations, UsbDriverOperations, WriteEndpoint,
@@ -58,89 +57,16 @@ fn dispatch_event(event: InterruptEvent) {
 #[allow(non_snake_case)]
 #[no_mangle]
 extern "C" fn MachineExternal() {
-    use moondancer::UsbInterface::Target;
-
-    let usb0 = unsafe { hal::Usb0::summon() };
-
-    let pending = match pac::csr::interrupt::pending() {
-        Ok(interrupt) => interrupt,
-        Err(pending) => {
-            dispatch_event(InterruptEvent::UnknownInterrupt(pending));
-            return;
-        }
-    };
-
-    match pending {
-        // - usb0 interrupts - "target_phy" --
-
-        // USB0 BusReset
-        pac::Interrupt::USB0 => {
-            usb0.controller
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // handle bus reset in interrupt handler for lowest latency
-            usb0.bus_reset();
-            dispatch_event(InterruptEvent::Usb(Target, UsbEvent::BusReset));
-        }
-        // USB0_EP_CONTROL ReceiveControl
-        pac::Interrupt::USB0_EP_CONTROL => {
-            usb0.ep_control
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint = usb0.ep_control.epno().read().bits() as u8;
-            let mut buffer = [0_u8; 8];
-            let _bytes_read = usb0.read_control(&mut buffer);
-            let setup_packet = SetupPacket::from(buffer);
-            dispatch_event(InterruptEvent::Usb(
-                Target,
-                UsbEvent::ReceiveSetupPacket(endpoint, setup_packet),
-            ));
-        }
-        // USB0_EP_IN SendComplete
-        pac::Interrupt::USB0_EP_IN => {
-            usb0.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // TODO something a little safer would be nice
-            let endpoint = usb0.ep_in.epno().read().bits() as u8;
-            unsafe {
-                usb0.clear_tx_ack_active(endpoint);
-            }
-
-            dispatch_event(InterruptEvent::Usb(
-                Target,
-                UsbEvent::SendComplete(endpoint),
-            ));
-        }
-        // USB0_EP_OUT ReceivePacket
-        pac::Interrupt::USB0_EP_OUT => {
-            usb0.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint = usb0.ep_out.data_ep().read().bits() as u8;
-            dispatch_event(InterruptEvent::Usb(
-                Target,
-                UsbEvent::ReceivePacket(endpoint),
-            ));
-        }
-
-        // - Unhandled Interrupt --
-        _ => dispatch_event(InterruptEvent::UnhandledInterrupt(pending)),
-    }
+    dispatch_event(moondancer::util::get_usb_interrupt_event());
 }
 
 // - main entry point ---------------------------------------------------------
 
-#[cfg(feature = "vexriscv")]
 #[riscv_rt::pre_init]
 unsafe fn pre_main() {
-    pac::cpu::vexriscv::flush_icache();
-    #[cfg(feature = "vexriscv_dcache")]
-    pac::cpu::vexriscv::flush_dcache();
+    pac::cpu::flush_icache();
+    #[cfg(any(feature = "minerva", feature = "vexriscv_dcache"))]
+    pac::cpu::flush_dcache();
 }
 
 #[riscv_rt::entry]
@@ -163,7 +89,10 @@ fn main_loop() -> GreatResult<()> {
     let peripherals = pac::Peripherals::take().unwrap();
 
     // initialize logging
-    moondancer::log::init();
+    moondancer::log::init(
+        hal::Serial0::new(peripherals.UART),
+        hal::Serial1::new(peripherals.UART1),
+    );
     info!("Logging initialized");
 
     // usb0: Target
@@ -192,6 +121,11 @@ fn main_loop() -> GreatResult<()> {
     usb0.connect(DEVICE_SPEED);
     info!("Connected usb0 device");
 
+    // move the handle used to service usb interrupts into its cell
+    moondancer::util::USB0_EVENTS
+        .init(usb0.events().unwrap())
+        .ok();
+
     // enable interrupts
     unsafe {
         // set mstatus register: interrupt enable
@@ -1 +0,0 @@
-../memory.x
\ No newline at end of file
@@ -1,6 +1,8 @@
 #![no_std]
 #![no_main]
 
+use core::mem::MaybeUninit;
+
 use heapless::mpmc::MpMcQueue as Queue;
 use log::{debug, error, info, trace, warn};
 
@@ -9,16 +11,25 @@ use smolusb::control::Control;
 use smolusb::descriptor::StringDescriptor;
 use smolusb::device::{Descriptors, Speed};
 use smolusb::setup::{Direction, Recipient, RequestType, SetupPacket};
-use smolusb::traits::{ReadEndpoint, UsbDriverOperations, WriteEndpoint};
+use smolusb::traits::{
+    ReadEndpoint, UnsafeUsbDriverOperations, UsbDriverOperations, WriteEndpoint,
+};
 
-use libgreat::gcp::{GreatDispatch, GreatResponse, LIBGREAT_MAX_COMMAND_SIZE};
+use libgreat::gcp::frame::{FrameReceiver, FrameTransmitter, Received};
+use libgreat::gcp::serial::{SerialReceiver, SerialTransmitter};
+use libgreat::gcp::{GreatResponse, LIBGREAT_MAX_COMMAND_SIZE};
 use libgreat::{GreatError, GreatResult};
 
 use moondancer::event::InterruptEvent;
 use moondancer::usb::vendor::{VendorRequest, VendorValue};
-use moondancer::{hal, pac, util};
+use moondancer::{hal, pac, util, BoardInformation};
+
+use hal::serial::{Buffered, State};
+use hal::usb::TxFifo;
 
-use pac::csr::interrupt;
+use pac::csr;
+use pac::dispatch::Dispatcher;
+use pac::interrupt;
 
 // - configuration ------------------------------------------------------------
 
@@ -28,6 +39,8 @@ const DEVICE_SPEED: Speed = Speed::High;
 
 static EVENT_QUEUE: Queue<InterruptEvent, 64> = Queue::new();
 
+static SERIAL1_STATE: State<hal::Serial1, 128, 256> = State::new();
+
 #[inline(always)]
 fn dispatch_event(event: InterruptEvent) {
     match EVENT_QUEUE.enqueue(event) {
@@ -46,27 +59,96 @@ fn dispatch_event(event: InterruptEvent) {
     }
 }
 
+// the monotonic clock and serial fifo are serviced before usb events,
+// endpoint completions are queued before the setup packets that follow them
+static DISPATCHER: Dispatcher<10> = Dispatcher::new([
+    pac::Interrupt::TIMER,
+    pac::Interrupt::UART1,
+    pac::Interrupt::USB2_EP_OUT,
+    pac::Interrupt::USB2_EP_IN,
+    pac::Interrupt::USB2_EP_CONTROL,
+    pac::Interrupt::USB2,
+    pac::Interrupt::USB0_EP_OUT,
+    pac::Interrupt::USB0_EP_IN,
+    pac::Interrupt::USB0_EP_CONTROL,
+    pac::Interrupt::USB0,
+]);
+
 #[allow(non_snake_case)]
 #[no_mangle]
 extern "C" fn MachineExternal() {
-    let event = moondancer::util::get_usb_interrupt_event();
-    dispatch_event(event);
+    unsafe { DISPATCHER.dispatch() };
+}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+extern "C" fn DefaultHandler() {
+    // an interrupt without a handler would stay pending forever
+    panic!(
+        "MachineExternal - unhandled interrupts pending: {:#x}",
+        csr::interrupt::bits_pending()
+    );
+}
+
+#[interrupt]
+fn TIMER() {
+    hal::Timer0::monotonic().on_interrupt();
+}
+
+#[interrupt]
+fn UART1() {
+    SERIAL1_STATE.on_interrupt();
+}
+
+macro_rules! usb_interrupts {
+    ($($IRQ:ident),+) => {
+        $(
+            #[interrupt]
+            fn $IRQ() {
+                dispatch_event(util::usb_interrupt_event(pac::Interrupt::$IRQ));
+            }
+        )+
+    };
+}
+
+usb_interrupts!(
+    USB0,
+    USB0_EP_CONTROL,
+    USB0_EP_IN,
+    USB0_EP_OUT,
+    USB2,
+    USB2_EP_CONTROL,
+    USB2_EP_IN,
+    USB2_EP_OUT
+);
+
+// - exception handler --------------------------------------------------------
+
+#[export_name = "ExceptionHandler"]
+fn exception_handler(trap_frame: &riscv_rt::TrapFrame) -> ! {
+    error!(
+        "Exception: {:?} mepc:{:#010x} mtval:{:#010x} ra:{:#010x}",
+        riscv::register::mcause::read().cause(),
+        riscv::register::mepc::read(),
+        riscv::register::mtval::read(),
+        trap_frame.ra,
+    );
+    panic!("Unhandled exception");
 }
 
 // - main entry point ---------------------------------------------------------
 
-#[cfg(feature = "vexriscv")]
 #[riscv_rt::pre_init]
 unsafe fn pre_main() {
-    pac::cpu::vexriscv::flush_icache();
-    #[cfg(feature = "vexriscv_dcache")]
-    pac::cpu::vexriscv::flush_dcache();
+    pac::cpu::flush_icache();
+    #[cfg(any(feature = "minerva", feature = "vexriscv_dcache"))]
+    pac::cpu::flush_dcache();
 }
 
 #[riscv_rt::entry]
 fn main() -> ! {
     // initialize firmware
-    let mut firmware = Firmware::new(pac::Peripherals::take().unwrap());
+    let firmware = Firmware::init(pac::Peripherals::take().unwrap());
     match firmware.initialize() {
         Ok(()) => (),
         Err(e) => {
@@ -86,35 +168,55 @@ fn main() -> ! {
 struct Firmware<'a> {
     // peripherals
     leds: pac::LEDS,
+    timer0: hal::Timer0,
+    usb1: hal::Usb1,
     usb2: hal::Usb2,
 
     // usb2 control endpoint
     usb2_control: Control<'a, hal::Usb2, LIBGREAT_MAX_COMMAND_SIZE>,
 
+    // usb2 bulk endpoints
+    usb2_bulk_rx: FrameReceiver,
+    usb2_bulk_tx: FrameTransmitter,
+    usb2_bulk_frame: Option<Received>,
+    usb2_bulk_in_flight: bool,
+
+    // uart1 serial transport
+    serial1: Buffered<'static, hal::Serial1, 128, 256>,
+    serial1_rx: SerialReceiver,
+    serial1_tx: SerialTransmitter,
+
+    // the response to the last command, shared by all transports
+    response: GreatResponse,
+
     // state
-    libgreat_response: Option<GreatResponse>,
+    libgreat_response_pending: bool,
     libgreat_response_last_error: Option<GreatError>,
 
     // classes
-    core: libgreat::gcp::class_core::Core,
-    moondancer: moondancer::gcp::moondancer::Moondancer,
+    classes: Classes,
 
     pub _marker: core::marker::PhantomData<&'a ()>,
 }
 
 // - lifecycle ----------------------------------------------------------------
 
-impl<'a> Firmware<'a> {
-    fn new(peripherals: pac::Peripherals) -> Self {
-        // initialize libgreat class registry
-        static CLASSES: [libgreat::gcp::Class; 4] = [
-            libgreat::gcp::class_core::CLASS,
-            moondancer::gcp::firmware::CLASS,
-            moondancer::gcp::selftest::CLASS,
-            moondancer::gcp::moondancer::CLASS,
-        ];
-        let classes = libgreat::gcp::Classes(&CLASSES);
+impl Firmware<'static> {
+    /// Creates the firmware in a static, rather than in the stack frame
+    /// of `main` where it would take up a good part of the stack for as
+    /// long as the firmware runs.
+    ///
+    /// Only called once, it takes the peripherals.
+    #[inline(never)]
+    fn init(peripherals: pac::Peripherals) -> &'static mut Self {
+        static mut FIRMWARE: MaybeUninit<Firmware<'static>> = MaybeUninit::uninit();
+        unsafe { (*core::ptr::addr_of_mut!(FIRMWARE)).write(Self::new(peripherals)) }
+    }
+}
 
+impl Firmware<'_> {
+    #[allow(clippy::too_many_lines)]
+    fn new(peripherals: pac::Peripherals) -> Self {
         // enable ApolloAdvertiser to disconnect the Cynthion USB2 control port from Apollo
         let advertiser = peripherals.ADVERTISER;
         advertiser.enable().write(|w| w.enable().bit(true));
@@ -124,9 +226,9 @@ impl<'a> Firmware<'a> {
         let board_major = info.version_major().read().bits() as u8;
         let board_minor = info.version_minor().read().bits() as u8;
 
-        // initialize logging
-        moondancer::log::set_port(moondancer::log::Port::Both);
-        moondancer::log::init();
+        // initialize logging, uart1 is used for the libgreat serial transport
+        moondancer::log::set_port(moondancer::log::Port::Uart0);
+        moondancer::log::init(hal::Serial0::new(peripherals.UART), None);
         info!(
             "{} {} v{}",
             env!("CARGO_PKG_AUTHORS"),
@@ -135,11 +237,23 @@ impl<'a> Firmware<'a> {
         );
         info!("Logging initialized");
 
+        // start monotonic clock
+        let mut timer0 = hal::Timer0::new(peripherals.TIMER, pac::clock::sysclk());
+        timer0.start_monotonic(hal::timer::MONOTONIC_FREE_RUNNING);
+
         // initialize ladybug
         moondancer::debug::init(peripherals.GPIOA, peripherals.GPIOB);
 
         // get Cynthion SPI Flash uuid from the SoC
-        let uuid = util::read_flash_uuid(&peripherals.SPI0).unwrap_or([0_u8; 8]);
+        let mut flash = hal::spiflash::Flash::new(hal::Spi0::new(peripherals.SPI0));
+        let uuid = flash
+            .wait_idle()
+            .and_then(|()| flash.unique_id())
+            .unwrap_or_else(|e| {
+                error!("Failed to read flash uuid: {:?}", e);
+                [0_u8; 8]
+            });
+        let board_information = util::board_information(uuid);
         let uuid = util::format_flash_uuid(uuid);
 
         // build string descriptor table
@@ -175,6 +289,14 @@ impl<'a> Firmware<'a> {
             peripherals.USB2_EP_OUT,
         );
 
+        // usb1: aux, unused but reset along with the other ports
+        let usb1 = hal::Usb1::new(
+            peripherals.USB1,
+            peripherals.USB1_EP_CONTROL,
+            peripherals.USB1_EP_IN,
+            peripherals.USB1_EP_OUT,
+        );
+
         // usb0: target
         let usb0 = hal::Usb0::new(
             peripherals.USB0,
@@ -183,6 +305,10 @@ impl<'a> Firmware<'a> {
             peripherals.USB0_EP_OUT,
         );
 
+        // move the handles used to service usb interrupts into their cells
+        util::USB2_EVENTS.init(usb2.events().unwrap()).ok();
+        util::USB0_EVENTS.init(usb0.events().unwrap()).ok();
+
         // format bcdDevice
         let bcd_device: u16 = u16::from_be_bytes([board_major, board_minor]);
 
@@ -205,18 +331,23 @@ impl<'a> Firmware<'a> {
             },
         );
 
-        // initialize libgreat classes
-        let core = libgreat::gcp::class_core::Core::new(classes, moondancer::BOARD_INFORMATION);
-        let moondancer = moondancer::gcp::moondancer::Moondancer::new(usb0);
-
         Self {
             leds: peripherals.LEDS,
+            timer0,
+            usb1,
             usb2,
             usb2_control,
-            libgreat_response: None,
+            usb2_bulk_rx: FrameReceiver::new(),
+            usb2_bulk_tx: FrameTransmitter::new(),
+            usb2_bulk_frame: None,
+            usb2_bulk_in_flight: false,
+            serial1: Buffered::new(hal::Serial1::new(peripherals.UART1), &SERIAL1_STATE),
+            serial1_rx: SerialReceiver::new(),
+            serial1_tx: SerialTransmitter::new(),
+            response: GreatResponse::new(),
+            libgreat_response_pending: false,
             libgreat_response_last_error: None,
-            core,
-            moondancer,
+            classes: Classes::with_target(board_information, usb0),
             _marker: core::marker::PhantomData,
         }
     }
@@ -239,11 +370,17 @@ impl<'a> Firmware<'a> {
             // set mie register: machine external interrupts enable
             riscv::register::mie::set_mext();
 
+            // write csr: enable timer interrupt for the monotonic clock
+            csr::interrupt::enable(pac::Interrupt::TIMER);
+
+            // write csr: enable uart1 interrupt for the serial transport
+            csr::interrupt::enable(pac::Interrupt::UART1);
+
             // write csr: enable usb2 interrupts
-            interrupt::enable(pac::Interrupt::USB2);
-            interrupt::enable(pac::Interrupt::USB2_EP_CONTROL);
-            interrupt::enable(pac::Interrupt::USB2_EP_IN);
-            interrupt::enable(pac::Interrupt::USB2_EP_OUT);
+            csr::interrupt::enable(pac::Interrupt::USB2);
+            csr::interrupt::enable(pac::Interrupt::USB2_EP_CONTROL);
+            csr::interrupt::enable(pac::Interrupt::USB2_EP_IN);
+            csr::interrupt::enable(pac::Interrupt::USB2_EP_OUT);
 
             // enable usb2 interrupt events
             self.usb2.enable_events();
@@ -276,6 +413,12 @@ impl<'a> Firmware<'a> {
             }
             queue_length = 0;
 
+            // libgreat serial transport
+            self.poll_serial();
+
+            // libgreat jobs
+            self.classes.poll_jobs();
+
             while let Some(interrupt_event) = EVENT_QUEUE.dequeue() {
                 use moondancer::{
                     event::InterruptEvent::*,
@@ -315,12 +458,33 @@ impl<'a> Firmware<'a> {
                             // vendor requests are not handled by control
                             self.handle_vendor_request(setup_packet)?;
                         }
+                        match event {
+                            BusReset => self.reset_bulk(),
+                            ReceivePacket(0) => self.handle_control_receive_packet(),
+                            _ => (),
+                        }
+                    }
+
+                    // - usb2 bulk event handlers --
+
+                    // Usb2 received a packet on the libgreat bulk OUT endpoint
+                    Usb(Control, ReceivePacket(endpoint_number))
+                        if endpoint_number == bulk_out_endpoint() =>
+                    {
+                        self.handle_bulk_receive_packet();
+                    }
+
+                    // Usb2 sent a packet on the libgreat bulk IN endpoint
+                    Usb(Control, SendComplete(endpoint_number))
+                        if endpoint_number == bulk_in_endpoint() =>
+                    {
+                        self.handle_bulk_send_complete();
                     }
 
                     // - usb0 Target event handlers --
 
                     // enqueue moondancer events
-                    Usb(Target, usb_event) => self.moondancer.dispatch_event(usb_event),
+                    Usb(Target, usb_event) => self.classes.moondancer.dispatch_event(usb_event),
 
                     // Unhandled event
                     _ => {
@@ -334,7 +498,7 @@ impl<'a> Firmware<'a> {
 
 // - usb2 control handler -----------------------------------------------------
 
-impl<'a> Firmware<'a> {
+impl Firmware<'_> {
     /// Handle GCP vendor requests
     fn handle_vendor_request(&mut self, setup_packet: SetupPacket) -> GreatResult<()> {
         let direction = setup_packet.direction();
@@ -403,18 +567,17 @@ impl<'a> Firmware<'a> {
                     Direction::DeviceToHost => self.usb2.stall_endpoint_in(0),
                 }
             }
-            (RequestType::Vendor, _, _vendor_request) => {
-                // TODO this is from one of the legacy boards which we
-                // need to support to get `greatfet info` to finish
-                // enumerating through the supported devices.
-                //
-                // see: host/greatfet/boards/legacy.py
-
-                // The greatfet board scan code expects the IN endpoint
-                // to be stalled if this is not a legacy device.
-                self.usb2.stall_endpoint_in(0);
-
-                warn!("handle_vendor_request Legacy libgreat vendor request");
+            // handle legacy GreatFET requests
+            (
+                RequestType::Vendor,
+                _,
+                VendorRequest::LegacyReadBoardId
+                | VendorRequest::LegacyReadVersionString
+                | VendorRequest::LegacyReadPartId
+                | VendorRequest::LegacyReset
+                | VendorRequest::LegacyReadDmesg,
+            ) => {
+                self.handle_legacy_request(setup_packet, &vendor_request);
             }
             _ => {
                 error!(
@@ -432,71 +595,156 @@ impl<'a> Firmware<'a> {
     }
 }
 
-// - libgreat command dispatch ------------------------------------------------
+// - legacy GreatFET requests -------------------------------------------------
 
-impl<'a> Firmware<'a> {
-    fn dispatch_libgreat_request(&mut self) -> GreatResult<()> {
-        let command_buffer = self.usb2_control.data();
+impl Firmware<'_> {
+    /// Handle the vendor requests used by the legacy `greatfet` tooling
+    ///
+    /// see: host/greatfet/boards/legacy.py
+    fn handle_legacy_request(&mut self, setup_packet: SetupPacket, vendor_request: &VendorRequest) {
+        let direction = setup_packet.direction();
+        let length = usize::from(setup_packet.length);
+        let board_information = self.classes.board_information();
 
-        // parse command
-        let (class_id, verb_number, arguments) = match libgreat::gcp::Command::parse(command_buffer)
-        {
-            Some(command) => (command.class_id(), command.verb_number(), command.arguments),
-            None => {
-                error!("dispatch_libgreat_request failed to parse libgreat command");
-                return Ok(());
+        match (vendor_request, &direction) {
+            (VendorRequest::LegacyReadBoardId, Direction::DeviceToHost) => {
+                let board_id = board_information.board_id.into_iter();
+                self.usb2.write(0, board_id.take(length));
             }
-        };
-
-        // dispatch command
-        let response_buffer: [u8; LIBGREAT_MAX_COMMAND_SIZE] = [0; LIBGREAT_MAX_COMMAND_SIZE];
-        let response = match class_id {
-            // class: core
-            libgreat::gcp::ClassId::core => {
-                self.core.dispatch(verb_number, arguments, response_buffer)
+            (VendorRequest::LegacyReadVersionString, Direction::DeviceToHost) => {
+                let version_string = board_information.version_string.bytes();
+                self.usb2.write(0, version_string.take(length));
             }
-            // class: firmware
-            libgreat::gcp::ClassId::firmware => {
-                moondancer::gcp::firmware::dispatch(verb_number, arguments, response_buffer)
+            (VendorRequest::LegacyReadPartId, Direction::DeviceToHost) => {
+                // the part id is followed by the serial number
+                let part_id = board_information.part_id.into_iter();
+                let serial_number = board_information.serial_number.into_iter();
+                self.usb2
+                    .write(0, part_id.chain(serial_number).take(length));
             }
-            // class: selftest
-            libgreat::gcp::ClassId::selftest => {
-                moondancer::gcp::selftest::dispatch(verb_number, arguments, response_buffer)
+            (VendorRequest::LegacyReadDmesg, Direction::DeviceToHost) => {
+                // a non-zero value clears the log once it has been read
+                let clear = setup_packet.value != 0;
+
+                // copy the log out so it isn't locked for the length of the transfer
+                let mut buffer = [0_u8; moondancer::log::DMESG_SIZE];
+                let count = moondancer::log::dmesg(|dmesg| {
+                    let mut count = 0;
+                    for (dest, byte) in buffer.iter_mut().zip(dmesg.iter()).take(length) {
+                        *dest = byte;
+                        count += 1;
+                    }
+                    if clear {
+                        dmesg.clear();
+                    }
+                    count
+                })
+                .unwrap_or(0);
+                self.usb2.write(0, buffer[..count].iter().copied());
             }
-            // class: moondancer
-            libgreat::gcp::ClassId::moondancer => {
-                self.moondancer
-                    .dispatch(verb_number, arguments, response_buffer)
+            (VendorRequest::LegacyReset, Direction::HostToDevice) => {
+                // send zlp
+                self.usb2.write(0, [].into_iter());
+
+                info!("Resetting firmware on legacy reset request");
+                self.reset();
             }
-            // class: unsupported
             _ => {
                 error!(
-                    "dispatch_libgreat_request error: Class id '{:?}' not found",
-                    class_id
+                    "handle_legacy_request stall: unsupported direction {:?} for {:?}",
+                    direction, vendor_request
                 );
-                Err(GreatError::InvalidArgument)
+                match direction {
+                    Direction::HostToDevice => self.usb2.stall_endpoint_out(0),
+                    Direction::DeviceToHost => self.usb2.stall_endpoint_in(0),
+                }
+                return;
             }
+        }
+
+        // prime to receive host zlp - TODO should control do this in send_complete?
+        if direction == Direction::DeviceToHost {
+            self.usb2.ep_out_prime_receive(0);
+        }
+    }
+
+    /// Restart the firmware from its entry point.
+    ///
+    /// This is a soft reset: the CPU jumps back to `_start` while the
+    /// peripherals keep running. All USB controllers are disconnected
+    /// first, which disables their events and empties their FIFOs, so
+    /// that their hosts enumerate the devices again. The peripherals
+    /// owned by the firmware are then put back into the state it
+    /// expects to find them in at power on.
+    fn reset(&mut self) -> ! {
+        extern "C" {
+            fn _start() -> !;
+        }
+
+        // give the host time to complete the status stage
+        let timer = hal::Timer0::monotonic();
+        timer.delay_ms(10);
+
+        // disconnect and reset all usb controllers and give their hosts
+        // time to notice
+        self.classes.moondancer.disconnect().ok();
+        self.usb1.disconnect();
+        self.usb2.disconnect();
+        timer.delay_ms(100);
+        log::logger().flush();
+
+        // stop handling interrupts
+        unsafe {
+            riscv::interrupt::disable();
+            pac::register::mim::write(0);
+        }
+
+        // disable and clear the events of the remaining peripherals so
+        // that nothing interrupts before the restarted firmware is ready
+        // for it
+        self.serial1.quiesce();
+
+        // the monotonic clock is restarted by the firmware
+        self.timer0.disable();
+        self.timer0.unlisten(hal::timer::Event::TimeOut);
+        self.timer0.clear_pending();
+
+        unsafe { _start() }
+    }
+}
+
+// - libgreat command dispatch ------------------------------------------------
+
+impl Firmware<'_> {
+    /// Returns `true` while the response is being sent on the bulk or
+    /// serial transport and can't be replaced.
+    fn response_busy(&self) -> bool {
+        !self.usb2_bulk_tx.is_idle() || !self.serial1_tx.is_idle()
+    }
+
+    fn dispatch_libgreat_request(&mut self) -> GreatResult<()> {
+        // dispatch command
+        let result = if self.response_busy() {
+            error!("dispatch_libgreat_request: response busy on another transport");
+            Err(GreatError::DeviceOrResourceBusy)
+        } else {
+            let (classes, command) = (&mut self.classes, self.usb2_control.data());
+            self.response
+                .dispatch(|buffer| classes.dispatch_command(command, buffer))
         };
 
         // queue response
-        match response {
-            Ok(response) => {
-                self.libgreat_response = Some(response);
+        self.libgreat_response_pending = result.is_ok();
+        match result {
+            Ok(()) => {
                 self.libgreat_response_last_error = None;
             }
             Err(e) => {
-                error!(
-                    "dispatch_libgreat_request error: failed to dispatch command {:?} 0x{:X} {}",
-                    class_id, verb_number, e
-                );
-                self.libgreat_response = None;
                 self.libgreat_response_last_error = Some(e);
 
                 // TODO this is... weird...
                 self.usb2.stall_endpoint_in(0);
-                unsafe {
-                    riscv::asm::delay(2000);
-                }
+                hal::Timer0::monotonic().delay_us(35);
                 self.usb2.ep_in.reset().write(|w| w.reset().bit(true));
             }
         }
@@ -504,14 +752,26 @@ impl<'a> Firmware<'a> {
         Ok(())
     }
 
-    fn dispatch_libgreat_response(&mut self, _setup_packet: SetupPacket) -> GreatResult<()> {
+    fn dispatch_libgreat_response(&mut self, setup_packet: SetupPacket) -> GreatResult<()> {
         // do we have a response ready?
-        if let Some(response) = &mut self.libgreat_response {
-            // send response
-            self.usb2.write(0, response);
+        if self.libgreat_response_pending {
+            // send as much of the response as the host asked for
+            let max_length = usize::from(setup_packet.length);
+            let chunk = self.response.next_chunk(max_length);
+            let chunk_length = chunk.len();
+            if chunk_length == 0 {
+                // the response ended on a chunk boundary, terminate it with a ZLP
+                self.usb2.write(0, core::iter::empty());
+            } else {
+                self.usb2.write(0, chunk.iter().copied());
+            }
 
-            // clear cached response
-            self.libgreat_response = None;
+            // clear cached response once the host has seen a short
+            // chunk or ZLP, otherwise keep the remainder for the next
+            // request as the host keeps reading until it sees one
+            if chunk_length == 0 || chunk_length < max_length {
+                self.libgreat_response_pending = false;
+            }
 
             // prime to receive host zlp - aka ep_out_prime_receive() TODO should control do this in send_complete?
             self.usb2.ep_out_prime_receive(0);
@@ -538,8 +798,20 @@ impl<'a> Firmware<'a> {
     fn dispatch_libgreat_abort(&mut self, _setup_packet: SetupPacket) -> GreatResult<()> {
         error!("dispatch_libgreat_response abort");
 
+        // cancel any running jobs
+        self.classes.cancel_jobs();
+
+        // cancel the bulk transport too, in case the host lost track of it
+        if self.usb2_bulk_in_flight {
+            self.usb2.ep_in.reset().write(|w| w.reset().bit(true));
+            unsafe {
+                self.usb2.clear_tx_ack_active(bulk_in_endpoint());
+            }
+        }
+        self.reset_bulk();
+
         // send an arbitrary error code if we're aborting mid-response
-        if let Some(_response) = &self.libgreat_response {
+        if self.libgreat_response_pending {
             // prime to receive host zlp - TODO should control do this in send_complete?
             self.usb2.ep_out_prime_receive(0);
 
@@ -548,9 +820,282 @@ impl<'a> Firmware<'a> {
         }
 
         // cancel any queued response
-        self.libgreat_response = None;
+        self.libgreat_response_pending = false;
         self.libgreat_response_last_error = None;
 
         Ok(())
     }
 }
+
+// - libgreat bulk transport --------------------------------------------------
+
+fn bulk_in_endpoint() -> u8 {
+    cynthion::shared::libgreat::endpoints::bulk_in_address & 0x7f
+}
+
+fn bulk_out_endpoint() -> u8 {
+    cynthion::shared::libgreat::endpoints::bulk_out_address & 0x7f
+}
+
+impl Firmware<'_> {
+    fn bulk_max_packet_size(&self) -> usize {
+        match self.usb2.device_speed {
+            Speed::High => smolusb::EP_MAX_PACKET_SIZE,
+            _ => 64,
+        }
+    }
+
+    /// Discard any partially received command or unsent response and
+    /// get ready to receive the next command.
+    fn reset_bulk(&mut self) {
+        self.usb2_bulk_rx.reset();
+        self.usb2_bulk_tx.reset();
+        self.usb2_bulk_frame = None;
+        self.usb2_bulk_in_flight = false;
+        self.prime_bulk_receive();
+    }
+
+    /// Prime the bulk OUT endpoint unless a command is waiting to be
+    /// dispatched.
+    fn prime_bulk_receive(&self) {
+        if self.usb2_bulk_frame.is_some() {
+            return;
+        }
+        if self.usb2.ep_out.have().read().have().bit() {
+            // the OUT FIFO is shared by all endpoints, receiving is
+            // enabled again once the packet waiting in it has been read
+            self.set_out_primed(bulk_out_endpoint(), true);
+        } else {
+            self.usb2.ep_out_prime_receive(bulk_out_endpoint());
+        }
+    }
+
+    fn set_out_primed(&self, endpoint_number: u8, primed: bool) {
+        self.usb2
+            .ep_out
+            .epno()
+            .write(|w| unsafe { w.epno().bits(endpoint_number) });
+        self.usb2.ep_out.prime().write(|w| w.prime().bit(primed));
+    }
+
+    /// Receiving a packet disables all OUT endpoints. Once control
+    /// has received the last packet of a transfer, un-prime endpoint 0
+    /// so the host can't send data for the next transfer before its
+    /// setup packet has been handled, and enable the bulk OUT endpoint
+    /// again.
+    fn handle_control_receive_packet(&self) {
+        if !self.usb2.ep_out.enable().read().enable().bit() {
+            self.set_out_primed(0, false);
+            self.prime_bulk_receive();
+        }
+    }
+
+    fn handle_bulk_receive_packet(&mut self) {
+        let mut packet = [0_u8; smolusb::EP_MAX_PACKET_SIZE];
+        let bytes_read = self.usb2.read(bulk_out_endpoint(), &mut packet);
+        let max_packet_size = self.bulk_max_packet_size();
+
+        // the host must wait for a response before sending the next command
+        if self.usb2_bulk_frame.is_some() {
+            warn!("handle_bulk_receive_packet dropping packet while a command is pending");
+            self.usb2.ep_out.enable().write(|w| w.enable().bit(true));
+            return;
+        }
+
+        match self
+            .usb2_bulk_rx
+            .receive(&packet[..bytes_read.min(packet.len())], max_packet_size)
+        {
+            // a cancel can't wait for the response it cancels
+            Some(Received::Cancel { tag }) => {
+                debug!("handle_bulk_receive_packet cancel tag:{}", tag);
+                self.usb2_bulk_tx.cancel(tag);
+                self.prime_bulk_receive();
+                self.send_bulk_packet();
+            }
+            Some(frame) => {
+                // hold off the host until the command has been dispatched
+                self.set_out_primed(bulk_out_endpoint(), false);
+                self.usb2_bulk_frame = Some(frame);
+                self.dispatch_bulk_frame();
+            }
+            None => self.prime_bulk_receive(),
+        }
+    }
+
+    fn handle_bulk_send_complete(&mut self) {
+        self.usb2_bulk_in_flight = false;
+        self.send_bulk_packet();
+
+        // a command may be waiting for the previous response to be sent
+        if self.usb2_bulk_tx.is_idle() {
+            self.dispatch_bulk_frame();
+        }
+    }
+
+    /// Dispatch the last frame received once the response to the
+    /// previous command has been sent, on this or the serial transport.
+    ///
+    /// Until then the bulk OUT endpoint is left unprimed so the host
+    /// can't send another command.
+    fn dispatch_bulk_frame(&mut self) {
+        if self.response_busy() {
+            return;
+        }
+        let Some(frame) = self.usb2_bulk_frame.take() else {
+            return;
+        };
+
+        match frame {
+            Received::Command { tag } => {
+                // an unread control response is discarded
+                self.libgreat_response_pending = false;
+                let (classes, command) = (&mut self.classes, self.usb2_bulk_rx.command());
+                let result = self
+                    .response
+                    .dispatch(|buffer| classes.dispatch_command(command, buffer));
+                self.usb2_bulk_tx
+                    .start(tag, result.map(|()| &self.response));
+            }
+            Received::Cancel { tag } => self.usb2_bulk_tx.cancel(tag),
+            Received::Error { tag, error } => {
+                error!("dispatch_bulk_frame error: tag:{} {}", tag, error);
+                self.usb2_bulk_tx.start(tag, Err(error));
+            }
+        }
+
+        self.prime_bulk_receive();
+        self.send_bulk_packet();
+    }
+
+    /// Send the next packet of the current response if the last one has
+    /// been collected by the host.
+    fn send_bulk_packet(&mut self) {
+        if self.usb2_bulk_in_flight {
+            return;
+        }
+        let max_packet_size = self.bulk_max_packet_size();
+        if let Some(packet) = self
+            .usb2_bulk_tx
+            .next_packet(max_packet_size, &mut self.response)
+        {
+            self.usb2.tx_fifo_send(bulk_in_endpoint(), packet);
+            self.usb2_bulk_in_flight = true;
+        }
+    }
+}
+
+// - libgreat serial transport -----------------------------------------------
+
+impl Firmware<'_> {
+    /// Send the pending response on UART1 and receive the next command
+    /// once it has been sent.
+    ///
+    /// No bytes are received while a bulk response is being sent, the
+    /// next command would have nowhere to put its response.
+    fn poll_serial(&mut self) {
+        use hal::nb;
+
+        while !self.serial1_tx.is_idle() {
+            match self.serial1.write(self.serial1_tx.pending(&self.response)) {
+                Ok(count) => self.serial1_tx.consume(count, &mut self.response),
+                Err(_) => return,
+            }
+        }
+
+        // a bulk command may be waiting for the response to be sent
+        self.dispatch_bulk_frame();
+
+        let mut byte = [0];
+        while !self.response_busy() {
+            let received = match self.serial1.read(&mut byte) {
+                Ok(_) => self.serial1_rx.receive(byte[0]),
+                Err(nb::Error::WouldBlock) => {
+                    // give up on a frame the host has stopped sending
+                    let now = hal::Timer0::monotonic().now_micros();
+                    match self.serial1_rx.idle(now) {
+                        Some(received) => Some(received),
+                        None => return,
+                    }
+                }
+                Err(nb::Error::Other(e)) => {
+                    warn!("poll_serial receive error: {:?}", e);
+                    self.serial1_rx.reset();
+                    return;
+                }
+            };
+
+            match received {
+                Some(Received::Command { tag }) => {
+                    // an unread control response is discarded
+                    self.libgreat_response_pending = false;
+                    let (classes, command) = (&mut self.classes, self.serial1_rx.command());
+                    let result = self
+                        .response
+                        .dispatch(|buffer| classes.dispatch_command(command, buffer));
+                    self.serial1_tx.start(tag, result.map(|()| &self.response));
+                }
+                Some(Received::Error { tag, error }) => {
+                    error!("poll_serial error: tag:{} {}", tag, error);
+                    self.serial1_tx.start(tag, Err(error));
+                }
+                Some(Received::Cancel { .. }) | None => (),
+            }
+        }
+    }
+}
+
+// - libgreat classes ---------------------------------------------------------
+
+libgreat::registry! {
+    /// The libgreat classes supported by the firmware
+    struct Classes {
+        firmware: moondancer::gcp::firmware::Firmware => moondancer::gcp::firmware::CLASS,
+        selftest: moondancer::gcp::selftest::Selftest => moondancer::gcp::selftest::CLASS,
+        moondancer: moondancer::gcp::moondancer::Moondancer => moondancer::gcp::moondancer::CLASS,
+    }
+}
+
+impl Classes {
+    /// Creates the libgreat classes with the board information served
+    /// by the `core` class and the target port they control.
+    fn with_target(board_information: BoardInformation, usb0: hal::Usb0) -> Self {
+        Self::new(
+            board_information,
+            moondancer::gcp::firmware::Firmware::new(),
+            moondancer::gcp::selftest::Selftest::new(),
+            moondancer::gcp::moondancer::Moondancer::new(usb0),
+        )
+    }
+
+    /// Dispatches a libgreat command to its class, writing its response
+    /// to the start of `response` and returning the response length.
+    fn dispatch_command(
+        &mut self,
+        command_buffer: &[u8],
+        response: &mut [u8],
+    ) -> GreatResult<usize> {
+        // parse command
+        let Some(command) = libgreat::gcp::Command::parse(command_buffer) else {
+            error!("dispatch_libgreat_command failed to parse libgreat command");
+            return Err(GreatError::BadMessage);
+        };
+        let (class_id, verb_number) = (command.class_id(), command.verb_number());
+        if self.classes().class(class_id).is_none() {
+            error!(
+                "dispatch_libgreat_command error: Class id '{:?}' not found",
+                class_id
+            );
+        }
+
+        // dispatch command
+        self.dispatch(class_id, verb_number, command.arguments, response)
+            .map_err(|e| {
+                error!(
+                    "dispatch_libgreat_command error: failed to dispatch command {:?} 0x{:X} {}",
+                    class_id, verb_number, e
+                );
+                e
+            })
+    }
+}
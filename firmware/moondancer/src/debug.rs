@@ -30,20 +30,24 @@ impl Bit {
 #[allow(clippy::missing_panics_doc)]
 pub fn init(_gpioa: pac::GPIOA, _gpiob: pac::GPIOB) {
     #[cfg(feature = "ladybug")]
-    unsafe {
+    {
         use crate::debug::ladybug_impl::{LadybugCynthion, LADYBUG_CYNTHION};
-        LADYBUG_CYNTHION = Some(LadybugCynthion::new(_gpioa, _gpiob));
-        ladybug::set_analyzer(LADYBUG_CYNTHION.as_ref().expect("surprises"));
+        let analyzer = LADYBUG_CYNTHION
+            .init(LadybugCynthion::new(_gpioa, _gpiob))
+            .ok()
+            .expect("ladybug already initialized");
+        ladybug::set_analyzer(analyzer);
     }
 }
 
 #[cfg(feature = "ladybug")]
 mod ladybug_impl {
+    use crate::hal::shared::Once;
     use crate::pac;
     use core::sync::atomic::{AtomicU8, Ordering};
     use ladybug::{Channel, LogicAnalyzer};
 
-    pub static mut LADYBUG_CYNTHION: Option<LadybugCynthion> = None;
+    pub static LADYBUG_CYNTHION: Once<LadybugCynthion> = Once::new();
 
     #[cfg(feature = "ladybug")]
     pub struct LadybugCynthion {
@@ -53,6 +57,9 @@ mod ladybug_impl {
         b: AtomicU8,
     }
 
+    // Safety: pin state is kept in atomics and each update is a single register write
+    unsafe impl Sync for LadybugCynthion {}
+
     impl LadybugCynthion {
         #[allow(clippy::similar_names)]
         pub fn new(gpioa: pac::GPIOA, gpiob: pac::GPIOB) -> Self {
@@ -1,3 +1,146 @@
 pub mod firmware;
 pub mod moondancer;
 pub mod selftest;
+pub mod target;
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(all(test, not(target_os = "none")))]
+mod tests {
+    use cynthion::gcp::{Client, Error, Loopback, Transport, Value};
+    use libgreat::GreatError;
+
+    use super::moondancer::Moondancer;
+    use super::target::VirtualTarget;
+    use super::{firmware, moondancer, selftest};
+    use crate::hal::smolusb::event::UsbEvent;
+
+    libgreat::registry! {
+        struct Classes {
+            firmware: firmware::Firmware => firmware::CLASS,
+            selftest: selftest::Selftest => selftest::CLASS,
+            moondancer: Moondancer<VirtualTarget> => moondancer::CLASS,
+        }
+    }
+
+    fn classes() -> Classes {
+        Classes::new(
+            crate::BOARD_INFORMATION,
+            firmware::Firmware::new(),
+            selftest::Selftest::new(),
+            Moondancer::new(VirtualTarget::new()),
+        )
+    }
+
+    /// Run `f` with a client connected to the firmware's classes.
+    fn with_client(classes: &mut Classes, f: impl FnOnce(&mut Client<&mut dyn Transport>)) {
+        let mut transport = Loopback::new(|class, verb, arguments, buffer| {
+            classes.dispatch(class, verb, arguments, buffer)
+        });
+        let mut client = Client::connect(&mut transport as &mut dyn Transport).unwrap();
+        f(&mut client);
+    }
+
+    fn int(value: u32) -> Value {
+        Value::from(value)
+    }
+
+    #[test]
+    fn test_discover() {
+        let mut classes = classes();
+        with_client(&mut classes, |client| {
+            let names: Vec<&str> = client.classes().iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, ["core", "firmware", "selftest", "moondancer"]);
+
+            let class = client.class("moondancer").unwrap();
+            assert_eq!(
+                class.docs,
+                "API for fine-grained control of the Target USB port."
+            );
+            let verb = class.verb("write_endpoint").unwrap();
+            assert_eq!(verb.in_signature, "<BB*X");
+            assert_eq!(verb.in_param_names, "endpoint_number, blocking, payload");
+        });
+    }
+
+    #[test]
+    fn test_selftest() {
+        let mut classes = classes();
+        with_client(&mut classes, |client| {
+            let result = client.call("selftest", "test_error_return_code", &[int(0)]);
+            assert_eq!(result.unwrap(), [Value::from("ok")]);
+
+            let result = client.call("selftest", "test_error_return_code", &[int(22)]);
+            assert!(matches!(
+                result,
+                Err(Error::Device(GreatError::InvalidArgument))
+            ));
+
+            let result = client.call("firmware", "initialize", &[]);
+            assert_eq!(result.unwrap(), [int(256), int(256 * 8192)]);
+        });
+    }
+
+    #[test]
+    fn test_moondancer() {
+        let mut classes = classes();
+
+        with_client(&mut classes, |client| {
+            let speed_high = Value::from(3_u8);
+            let connect = [Value::from(64_u16), speed_high, Value::from(0_u16)];
+            client.call("moondancer", "connect", &connect).unwrap();
+
+            let endpoint = |address: u8| {
+                Value::List(vec![
+                    Value::from(address),
+                    Value::from(64_u16),
+                    Value::from(2_u8),
+                ])
+            };
+            let endpoints = Value::List(vec![endpoint(0x01), endpoint(0x82)]);
+            client
+                .call("moondancer", "configure_endpoints", &[endpoints])
+                .unwrap();
+
+            let payload = Value::from((0..100).collect::<Vec<u8>>());
+            let write = [Value::from(2_u8), Value::from(1_u8), payload];
+            client.call("moondancer", "write_endpoint", &write).unwrap();
+
+            client
+                .call("moondancer", "stall_endpoint_in", &[Value::from(3_u8)])
+                .unwrap();
+        });
+
+        let port = classes.moondancer.port();
+        let packets: Vec<(u8, Vec<u8>)> = core::iter::from_fn(|| port.host_in())
+            .map(|(endpoint, packet)| (endpoint, packet.to_vec()))
+            .collect();
+        assert_eq!(
+            packets,
+            [(2, (0..64).collect()), (2, (64..100).collect::<Vec<u8>>())]
+        );
+        assert!(port.is_stalled_in(3));
+
+        // the host sends a packet to the primed OUT endpoint
+        assert!(port.host_out(1, b"moondancer"));
+        assert!(!port.host_out(1, b"not primed"));
+        classes
+            .moondancer
+            .dispatch_event(UsbEvent::ReceivePacket(1));
+
+        with_client(&mut classes, |client| {
+            let events = client.call("moondancer", "get_interrupt_events", &[]);
+            let [event_type, endpoint] = UsbEvent::ReceivePacket(1).into_bytes();
+            assert_eq!(
+                events.unwrap(),
+                [Value::List(vec![Value::List(vec![
+                    Value::from(event_type),
+                    Value::from(endpoint)
+                ])])]
+            );
+
+            let data = client.call("moondancer", "read_endpoint", &[Value::from(1_u8)]);
+            assert_eq!(data.unwrap(), [Value::from(b"moondancer".to_vec())]);
+        });
+    }
+}
@@ -1,26 +1,24 @@
 //! Implementation for the GCP `moondancer` class.
 
 use log::{debug, error, trace, warn};
-use zerocopy::byteorder::{LittleEndian, U16, U32};
+use zerocopy::byteorder::{LittleEndian, U16};
 use zerocopy::{FromBytes, FromZeroes, Unaligned};
 
-use crate::{hal, pac};
+use crate::hal;
 use hal::smolusb;
-use pac::csr::interrupt;
 
 use smolusb::device::Speed;
 use smolusb::event::UsbEvent;
 use smolusb::setup::{Direction, SetupPacket};
-use smolusb::traits::{ReadEndpoint, UnsafeUsbDriverOperations, UsbDriverOperations};
 
 use libgreat::error::{GreatError, GreatResult};
-use libgreat::gcp::{
-    self, iter_to_response, GreatDispatch, GreatResponse, Verb, LIBGREAT_MAX_COMMAND_SIZE,
-};
+use libgreat::gcp::{self, verbs, LIBGREAT_MAX_COMMAND_SIZE};
 
 use crate::debug::Bit;
 use ladybug::Channel;
 
+use super::target::TargetPort;
+
 // - types --------------------------------------------------------------------
 
 /// USB quirk flags
@@ -50,33 +48,47 @@ impl Packet {
 use heapless::spsc::Queue;
 use heapless::Vec;
 
+/// Capacity, in bytes, of each IN endpoint's transmit queue.
+const TX_QUEUE_SIZE: usize = 512;
+
 /// Moondancer
-pub struct Moondancer {
-    usb0: hal::Usb0,
+///
+/// Runs on the Target port's [`hal::Usb0`] controller in the firmware, or
+/// on any other [`TargetPort`] such as a
+/// [`VirtualTarget`](super::target::VirtualTarget) on a host.
+pub struct Moondancer<P: TargetPort = hal::Usb0> {
+    port: P,
     quirk_flags: u16,
     ep_in_max_packet_size: [u16; smolusb::EP_MAX_ENDPOINTS],
     ep_out_max_packet_size: [u16; smolusb::EP_MAX_ENDPOINTS],
     irq_queue: Queue<UsbEvent, 64>,
     control_queue: Queue<SetupPacket, 8>,
     packet_buffer: Vec<Packet, 4>,
+    tx_queue: hal::usb::TxQueue<TX_QUEUE_SIZE>,
     pending_set_address: Option<u8>,
 }
 
-impl Moondancer {
+impl<P: TargetPort> Moondancer<P> {
     #[must_use]
-    pub fn new(usb0: hal::Usb0) -> Self {
+    pub fn new(port: P) -> Self {
         Self {
-            usb0,
+            port,
             quirk_flags: 0,
             ep_in_max_packet_size: [0; smolusb::EP_MAX_ENDPOINTS],
             ep_out_max_packet_size: [0; smolusb::EP_MAX_ENDPOINTS],
             irq_queue: Queue::new(),
             control_queue: Queue::new(),
             packet_buffer: Vec::new(),
+            tx_queue: hal::usb::TxQueue::new(),
             pending_set_address: None,
         }
     }
 
+    /// Returns the Target port.
+    pub fn port(&self) -> &P {
+        &self.port
+    }
+
     pub fn dispatch_event(&mut self, event: UsbEvent) {
         // filter interrupt events
         let event = match event {
@@ -84,6 +96,7 @@ impl Moondancer {
                 // flush queues, the actual bus reset is handled in the irq handler for lower latency
                 //while let Some(_) = self.irq_queue.dequeue() {}
                 //while let Some(_) = self.control_queue.dequeue() {}
+                self.tx_queue.clear();
                 self.pending_set_address = None;
                 event
             }
@@ -109,7 +122,9 @@ impl Moondancer {
                     self.pending_set_address = Some(address);
 
                     // send ZLP to host to end status stage
-                    self.usb0.ack(endpoint_number, Direction::HostToDevice);
+                    if let Err(e) = self.ack_status(endpoint_number) {
+                        error!("Moondancer - failed to ack SetAddress: {:?}", e);
+                    }
                     return;
                 }
 
@@ -128,13 +143,18 @@ impl Moondancer {
                 UsbEvent::ReceiveControl(endpoint_number)
             }
 
-            UsbEvent::SendComplete(_endpoint_number) => {
+            UsbEvent::SendComplete(endpoint_number) => {
                 // catch EP_IN SendComplete after SetAddress ack
-                if let Some(address) = self.pending_set_address.take() {
-                    self.usb0.set_address(address);
-                    return;
+                let completed = self.tx_queue.on_send_complete(endpoint_number);
+                if completed && endpoint_number == 0 && self.tx_queue.is_empty(0) {
+                    if let Some(address) = self.pending_set_address.take() {
+                        self.port.set_address(address);
+                    }
                 }
 
+                // feed the next queued packet to EP_IN
+                self.tx_queue.service(&self.port);
+
                 // drop event, because - currently - we're not using it in moondancer.py
                 return;
             }
@@ -143,7 +163,7 @@ impl Moondancer {
                 // drain FIFO
                 let mut rx_buffer: [u8; smolusb::EP_MAX_PACKET_SIZE] =
                     [0; smolusb::EP_MAX_PACKET_SIZE];
-                let bytes_read = self.usb0.read(endpoint_number, &mut rx_buffer);
+                let bytes_read = self.port.read(endpoint_number, &mut rx_buffer);
 
                 // create Packet
                 let mut packet = Packet::new(endpoint_number, bytes_read);
@@ -191,7 +211,7 @@ impl Moondancer {
 
 // - usb0 interrupt handlers --------------------------------------------------
 
-impl Moondancer {
+impl<P: TargetPort> Moondancer<P> {
     /// Enable USB events and CPU interrupts for the USB controller.
     ///
     /// # Safety
@@ -200,13 +220,7 @@ impl Moondancer {
     /// register. It is not interrupt-safe and any pending interrupts
     /// may be dropped when calling it.
     pub unsafe fn enable_usb_interrupts(&self) {
-        interrupt::enable(pac::Interrupt::USB0);
-        interrupt::enable(pac::Interrupt::USB0_EP_CONTROL);
-        interrupt::enable(pac::Interrupt::USB0_EP_IN);
-        interrupt::enable(pac::Interrupt::USB0_EP_OUT);
-
-        // enable all usb events
-        self.usb0.enable_events();
+        self.port.enable_interrupts();
     }
 
     /// Disable USB events and CPU interrupts for the USB controller.
@@ -217,62 +231,87 @@ impl Moondancer {
     /// register. It is not interrupt-safe and any pending interrupts
     /// may be dropped when calling it.
     pub unsafe fn disable_usb_interrupts(&self) {
-        // disable all usb events
-        self.usb0.disable_events();
-
-        interrupt::disable(pac::Interrupt::USB0);
-        interrupt::disable(pac::Interrupt::USB0_EP_CONTROL);
-        interrupt::disable(pac::Interrupt::USB0_EP_IN);
-        interrupt::disable(pac::Interrupt::USB0_EP_OUT);
+        self.port.disable_interrupts();
     }
 }
 
-// - verb implementations: device connection ----------------------------------
-
-impl Moondancer {
-    /// Connect the USB interface.
-    pub fn connect(&mut self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            ep0_max_packet_size: U16<LittleEndian>,
-            device_speed: u8,
-            quirk_flags: U16<LittleEndian>,
+// - class information --------------------------------------------------------
+
+pub static CLASS: gcp::Class = gcp::Class {
+    id: gcp::ClassId::moondancer,
+    name: "moondancer",
+    docs: CLASS_DOCS,
+    verbs: &<Moondancer>::VERBS,
+};
+
+pub static CLASS_DOCS: &str = "API for fine-grained control of the Target USB port.\0";
+
+/// Endpoint descriptor argument of `moondancer::configure_endpoints`.
+#[repr(C)]
+#[derive(Debug, FromBytes, FromZeroes, Unaligned)]
+pub struct ArgEndpoint {
+    address: u8,
+    max_packet_size: U16<LittleEndian>,
+    transfer_type: u8,
+}
+
+// - verb implementations -----------------------------------------------------
+
+#[verbs]
+impl<P: TargetPort> Moondancer<P> {
+    // - device connection --
+
+    /// Connect the target to the host. `device_speed` is 3:high, 2:full, 1:low
+    #[verb(id = 0x0)]
+    pub fn connect(
+        &mut self,
+        ep0_max_packet_size: u16,
+        device_speed: u8,
+        quirk_flags: u16,
+    ) -> GreatResult<()> {
+        let requested_max_packet_size = ep0_max_packet_size;
+        let mut ep0_max_packet_size = ep0_max_packet_size;
+        let device_speed = Speed::from_libusb(device_speed);
+
+        // low-speed devices only support a max packet size of 8 bytes
+        if device_speed == Speed::Low
+            && usize::from(ep0_max_packet_size) != hal::usb::LOW_SPEED_MAX_PACKET_SIZE
+        {
+            warn!(
+                "  ep0_max_packet_size of {} is not supported for low-speed devices, using {}",
+                ep0_max_packet_size,
+                hal::usb::LOW_SPEED_MAX_PACKET_SIZE
+            );
+            ep0_max_packet_size = hal::usb::LOW_SPEED_MAX_PACKET_SIZE as u16;
         }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let ep0_max_packet_size = args.ep0_max_packet_size.into();
-        let device_speed = Speed::from_libusb(args.device_speed);
-        let quirk_flags = args.quirk_flags.into();
 
         self.ep_in_max_packet_size[0] = ep0_max_packet_size;
         self.ep_out_max_packet_size[0] = ep0_max_packet_size;
         self.quirk_flags = quirk_flags;
 
-        // connect usb0 device and enable interrupts
-        self.usb0.connect(device_speed);
+        // connect target device and enable interrupts
+        self.port.connect(device_speed);
         unsafe { self.enable_usb_interrupts() };
 
         // wait for things to settle and get connection speed
-        unsafe {
-            riscv::asm::delay(10_000_000);
-        }
-        let speed: Speed = self.usb0.controller.speed().read().speed().bits().into();
+        let speed = self.port.wait_for_connection();
 
         log::info!("Moondancer connected {:?}-speed device to host.", speed);
 
         log::debug!(
             "MD moondancer::connect(ep0_max_packet_size:{}, device_speed:{:?}, quirk_flags:{}) -> {:?}",
-            args.ep0_max_packet_size, device_speed, args.quirk_flags, speed
+            requested_max_packet_size, device_speed, quirk_flags, speed
         );
 
-        Ok([].into_iter())
+        Ok(())
     }
 
     /// Terminate all existing communication and disconnects the USB interface.
-    pub fn disconnect(&mut self, _arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
+    #[verb(id = 0x1)]
+    pub fn disconnect(&mut self) -> GreatResult<()> {
         // disable interrupts and disconnect USB interface
         unsafe { self.disable_usb_interrupts() };
-        self.usb0.disconnect();
+        self.port.disconnect();
 
         // reset connection state
         self.quirk_flags = 0;
@@ -283,94 +322,75 @@ impl Moondancer {
         // flush queues
         while self.irq_queue.dequeue().is_some() {}
         while self.control_queue.dequeue().is_some() {}
+        self.tx_queue.clear();
 
         // clear quirk flags
         self.quirk_flags = 0;
 
         log::info!("Moondancer disconnected");
 
-        Ok([].into_iter())
+        Ok(())
     }
 
     /// Perform a USB bus reset.
-    pub fn bus_reset(&mut self, _arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
+    #[verb(id = 0x2)]
+    pub fn bus_reset(&mut self) -> GreatResult<()> {
         // We sent the event to facedancer but the actual bus reset already happened locally
         // in the interrupt handler.
 
         debug!("MD moondancer::bus_reset()");
 
-        Ok([].into_iter())
+        Ok(())
     }
-}
 
-// - verb implementations: status & control -----------------------------------
+    // - status & control --
 
-impl Moondancer {
     /// Returns the earliest control packet in the queue.
-    pub fn read_control(&mut self, _arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        let Some(setup_packet) = self.control_queue.dequeue() else {
-            error!("Moondancer - no packets in control queue");
-            loop {
-                unsafe {
-                    riscv::asm::nop();
+    #[verb(id = 0x3, out_param_names = "setup_packet")]
+    pub fn read_control(&mut self) -> GreatResult<[u8; 8]> {
+        ladybug::trace(Channel::A, Bit::A_READ_CONTROL, || {
+            let Some(setup_packet) = self.control_queue.dequeue() else {
+                error!("Moondancer - no packets in control queue");
+                loop {
+                    unsafe {
+                        riscv::asm::nop();
+                    }
                 }
-            }
-        };
+            };
 
-        debug!("MD moondancer::read_control() -> {:?}", setup_packet);
+            debug!("MD moondancer::read_control() -> {:?}", setup_packet);
 
-        Ok(SetupPacket::as_bytes(setup_packet).into_iter())
+            Ok(SetupPacket::as_bytes(setup_packet))
+        })
     }
 
     /// Set the device address.
-    pub fn set_address(&self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            address: u8,
-            deferred: u8,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let address = args.address & 0x7f;
-
+    #[verb(id = 0x4)]
+    pub fn set_address(&mut self, address: u8, deferred: u8) -> GreatResult<()> {
         // TODO handle
-        let _deferred = args.deferred != 0;
+        let _deferred = deferred != 0;
 
         // activate new address
-        self.usb0.set_address(address);
+        self.port.set_address(address & 0x7f);
 
         // ack status
-        self.usb0.ack(0, Direction::HostToDevice);
+        self.ack_status(0)?;
 
         trace!(
             "MD moondancer::set_address(address:{}, deferred:{})",
-            args.address,
-            args.deferred
+            address,
+            deferred
         );
 
-        Ok([].into_iter())
+        Ok(())
     }
 
     /// Configure endoints.
-    pub fn configure_endpoints(
-        &mut self,
-        arguments: &[u8],
-    ) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(Debug, FromBytes, FromZeroes, Unaligned)]
-        struct ArgEndpoint {
-            address: u8,
-            max_packet_size: U16<LittleEndian>,
-            transfer_type: u8,
-        }
-
+    #[verb(id = 0x5, in_signature = "<*(BHB)")] // TODO s/prime_out_endpoint
+    pub fn configure_endpoints(&mut self, endpoint_descriptors: &[ArgEndpoint]) -> GreatResult<()> {
         log::debug!("MD moondancer::configure_endpoints()");
 
-        // while we have endpoint triplets to handle
-        let mut byte_slice = arguments;
-        while let Some((endpoint, next)) =
-            zerocopy::Ref::<_, ArgEndpoint>::new_from_prefix(byte_slice)
-        {
+        for endpoint in endpoint_descriptors {
             let endpoint_number = endpoint.address & 0x7f;
 
             log::debug!(
@@ -379,7 +399,6 @@ impl Moondancer {
                 endpoint_number,
                 endpoint.max_packet_size
             );
-            byte_slice = next;
 
             // endpoint zero is always the control endpoint, and can't be configured
             if endpoint_number == 0x00 {
@@ -391,12 +410,14 @@ impl Moondancer {
             }
 
             // ignore endpoint configurations we won't be able to handle
-            if endpoint.max_packet_size.get() as usize > smolusb::EP_MAX_PACKET_SIZE {
+            let max_packet_size = match self.port.device_speed() {
+                Speed::Low => hal::usb::LOW_SPEED_MAX_PACKET_SIZE,
+                _ => smolusb::EP_MAX_PACKET_SIZE,
+            };
+            if endpoint.max_packet_size.get() as usize > max_packet_size {
                 error!(
                     "  failed to configure endpoint address 0x{:x} with max packet size {} > {}",
-                    endpoint.address,
-                    endpoint.max_packet_size,
-                    smolusb::EP_MAX_PACKET_SIZE,
+                    endpoint.address, endpoint.max_packet_size, max_packet_size,
                 );
                 return Err(GreatError::InvalidArgument);
             }
@@ -416,102 +437,137 @@ impl Moondancer {
                     "  priming HostToDevice (OUT) endpoint address: {}",
                     endpoint.address
                 );
-                self.usb0.ep_out_prime_receive(endpoint_number);
+                self.port.ep_out_prime_receive(endpoint_number);
             }
         }
 
-        let iter = [].into_iter();
-        Ok(iter)
+        Ok(())
     }
 
     /// Stall the given USB IN endpoint number.
-    pub fn stall_endpoint_in(&self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            endpoint_number: u8,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let endpoint_number = args.endpoint_number;
-
+    #[verb(id = 0x6)]
+    pub fn stall_endpoint_in(&self, endpoint_number: u8) -> GreatResult<()> {
         // stall IN end
-        self.usb0.stall_endpoint_in(endpoint_number);
+        self.port.stall_endpoint_in(endpoint_number);
 
-        log::debug!("MD moondancer::stall_endpoint_in({})", args.endpoint_number);
+        log::debug!("MD moondancer::stall_endpoint_in({})", endpoint_number);
 
-        Ok([].into_iter())
+        Ok(())
     }
 
     /// Stall the given USB OUT endpoint number.
-    pub fn stall_endpoint_out(&self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            endpoint_number: u8,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let endpoint_number = args.endpoint_number;
-
+    #[verb(id = 0x7)]
+    pub fn stall_endpoint_out(&self, endpoint_number: u8) -> GreatResult<()> {
         // stall OUT end
-        self.usb0.stall_endpoint_out(endpoint_number);
+        self.port.stall_endpoint_out(endpoint_number);
 
-        log::debug!(
-            "MD moondancer::stall_endpoint_out({})",
-            args.endpoint_number
-        );
+        log::debug!("MD moondancer::stall_endpoint_out({})", endpoint_number);
 
-        Ok([].into_iter())
+        Ok(())
     }
-}
 
-// - verb implementations: data transfer --------------------------------------
+    // - data transfer --
 
-impl Moondancer {
-    pub fn read_endpoint(&mut self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            endpoint_number: u8,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let endpoint_number = args.endpoint_number;
+    /// Read a packet from an OUT endpoint.
+    #[verb(id = 0x8, out_signature = "<*X", out_param_names = "read_data")]
+    pub fn read_endpoint(&mut self, endpoint_number: u8) -> GreatResult<impl Iterator<Item = u8>> {
+        ladybug::trace(Channel::A, Bit::A_READ_ENDPOINT, || {
+            let packet = match self
+                .packet_buffer
+                .iter()
+                .position(|packet| packet.endpoint_number == endpoint_number)
+            {
+                Some(index) => self.packet_buffer.remove(index),
+                None => {
+                    error!(
+                        "MD moondancer::read_endpoint({}) has no packet buffered for endpoint",
+                        endpoint_number
+                    );
+                    // TODO actually handle this case in moondancer.py
+                    Packet::new(endpoint_number, 0)
+                }
+            };
 
-        let packet = match self
-            .packet_buffer
-            .iter()
-            .position(|packet| packet.endpoint_number == endpoint_number)
-        {
-            Some(index) => self.packet_buffer.remove(index),
-            None => {
-                error!(
-                    "MD moondancer::read_endpoint({}) has no packet buffered for endpoint",
-                    endpoint_number
-                );
-                // TODO actually handle this case in moondancer.py
-                Packet::new(endpoint_number, 0)
+            log::debug!(
+                "MD moondancer::read_endpoint({}) -> bytes_read:{}",
+                packet.endpoint_number,
+                packet.bytes_read
+            );
+
+            Ok(packet.buffer.into_iter().take(packet.bytes_read))
+        })
+    }
+
+    /// Prepare OUT endpoint to receive a single packet.
+    #[verb(id = 0x9)]
+    pub fn ep_out_prime_receive(&mut self, endpoint_number: u8) -> GreatResult<()> {
+        ladybug::trace(Channel::A, Bit::A_PRIME_RECEIVE, || {
+            self.port.ep_out_prime_receive(endpoint_number);
+
+            debug!("MD moondancer::ep_out_prime_receive({})", endpoint_number);
+
+            Ok(())
+        })
+    }
+
+    /// Write a packet to an IN endpoint.
+    #[verb(id = 0xa)]
+    pub fn write_endpoint(
+        &mut self,
+        endpoint_number: u8,
+        blocking: u8,
+        payload: &[u8],
+    ) -> GreatResult<()> {
+        ladybug::trace(Channel::A, Bit::A_WRITE_ENDPOINT, || {
+            self.write_endpoint_payload(endpoint_number, blocking != 0, payload)
+        })
+    }
+
+    // - interrupts --
+
+    /// Get the most recent USB driver messages.
+    ///
+    /// # Return Value
+    ///
+    /// [(type, interface, endpoint)]
+    #[verb(id = 0xb, out_signature = "<*(BB)", out_param_names = "type, endpoint")]
+    pub fn get_interrupt_events(&mut self) -> GreatResult<impl Iterator<Item = u8>> {
+        ladybug::trace(Channel::A, Bit::A_GET_EVENTS, || {
+            let mut tx_buffer = [0_u8; LIBGREAT_MAX_COMMAND_SIZE];
+
+            let clone = self.irq_queue.clone();
+            self.irq_queue = Queue::new();
+
+            let length = clone.len() * 2;
+            let response = clone.iter().flat_map(|event| event.into_bytes());
+
+            for (dest, src) in tx_buffer.iter_mut().zip(response) {
+                *dest = src;
             }
-        };
 
-        log::debug!(
-            "MD moondancer::read_endpoint({}) -> bytes_read:{}",
-            packet.endpoint_number,
-            packet.bytes_read
-        );
+            Ok(tx_buffer.into_iter().take(length))
+        })
+    }
 
-        Ok(packet.buffer.into_iter().take(packet.bytes_read))
+    /// Get endpoint NAK status.
+    ///
+    /// # Return Value
+    ///
+    /// bitmask
+    #[verb(id = 0xc, out_param_names = "bitmask")]
+    pub fn get_nak_status(&mut self) -> GreatResult<u16> {
+        Ok(self.port.nak_status())
     }
 
+    // - tests --
+
+    /// Return `read_endpoint` with `payload_length` of test data.
+    #[verb(id = 0x28, out_signature = "<*X", out_param_names = "read_data")]
     pub fn test_read_endpoint(
         &mut self,
-        arguments: &[u8],
+        payload_length: u32,
     ) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            payload_length: U32<LittleEndian>,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-        let payload_length: usize = u32::from(args.payload_length) as usize;
+        let payload_length = payload_length as usize;
 
         log::debug!("MD moondancer::test_read_endpoint({})", payload_length);
 
@@ -532,533 +588,275 @@ impl Moondancer {
         Ok(rx_buffer.into_iter().take(payload_length))
     }
 
-    pub fn ep_out_prime_receive(
-        &mut self,
-        arguments: &[u8],
-    ) -> GreatResult<impl Iterator<Item = u8>> {
-        #[repr(C)]
-        #[derive(FromBytes, FromZeroes, Unaligned)]
-        struct Args {
-            endpoint_number: u8,
-        }
-        let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
-
-        self.usb0.ep_out_prime_receive(args.endpoint_number);
+    /// Write a packet to an IN endpoint and return the length received.
+    #[verb(id = 0x2a, out_param_names = "payload_length")]
+    pub fn test_write_endpoint(&mut self, endpoint_number: u8, payload: &[u8]) -> GreatResult<u32> {
+        let payload_length = payload.len();
 
         debug!(
-            "MD moondancer::ep_out_prime_receive({})",
-            args.endpoint_number
+            "MD moondancer::test_write_endpoint(endpoint_number:{}, payload.len:{})",
+            endpoint_number, payload_length,
         );
 
-        Ok([].into_iter())
+        #[allow(clippy::cast_possible_truncation)] // payloads are smaller than a command
+        Ok(payload_length as u32)
     }
 
-    #[allow(clippy::too_many_lines)]
-    pub fn write_endpoint(&mut self, arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        struct Args<B: zerocopy::ByteSlice> {
-            endpoint_number: zerocopy::Ref<B, u8>,
-            blocking: zerocopy::Ref<B, u8>,
-            payload: B,
-        }
-        let (endpoint_number, arguments) = zerocopy::Ref::new_unaligned_from_prefix(arguments)
-            .ok_or(GreatError::InvalidArgument)?;
-        let (blocking, payload) = zerocopy::Ref::new_unaligned_from_prefix(arguments)
-            .ok_or(GreatError::InvalidArgument)?;
-        let args = Args {
-            endpoint_number,
-            blocking,
-            payload,
-        };
+    /// Returns test data containing USB driver messages.
+    ///
+    /// # Return value
+    ///
+    /// [(type, interface, endpoint)]
+    #[verb(
+        id = 0x2b,
+        out_signature = "<*(BB)",
+        out_param_names = "type, endpoint"
+    )]
+    pub fn test_get_interrupt_events(&mut self) -> GreatResult<impl Iterator<Item = u8>> {
+        debug!("MD moondancer::test_get_interrupt_events()");
 
-        let endpoint_number: u8 = args.endpoint_number.read();
-        let blocking = args.blocking.read() != 0;
-        let payload_length = args.payload.len();
-        let iter = args.payload.iter();
-        let max_packet_size = self.ep_in_max_packet_size[endpoint_number as usize] as usize;
+        self.irq_queue.enqueue(UsbEvent::BusReset).ok();
+        self.irq_queue.enqueue(UsbEvent::ReceiveControl(1)).ok();
+        self.irq_queue.enqueue(UsbEvent::ReceivePacket(2)).ok();
+        self.irq_queue.enqueue(UsbEvent::SendComplete(3)).ok();
 
-        // TODO clean up tx_ack_active semantics!!!
-        unsafe {
-            self.usb0.set_tx_ack_active(endpoint_number);
-        }
+        self.get_interrupt_events()
+    }
+}
 
-        // check if output FIFO is empty
-        // FIXME return a GreatError::DeviceOrResourceBusy on timeout
-        let mut timeout = 0;
-        while self.usb0.ep_in.have().read().have().bit() {
-            if timeout == 0 {
-                warn!("  moondancer clear tx ep{}", endpoint_number);
-            } else if timeout > hal::usb::DEFAULT_TIMEOUT {
-                self.usb0.ep_in.reset().write(|w| w.reset().bit(true));
-                unsafe {
-                    self.usb0.clear_tx_ack_active(endpoint_number);
-                }
-                error!("  moondancer clear tx timeout ep{}", endpoint_number);
-                return Err(GreatError::StreamIoctlTimeout);
-            }
-            timeout += 1;
-        }
+// - helpers ------------------------------------------------------------------
 
-        // write data out to EP_IN, splitting into packets of max_packet_size
-        let mut bytes_written: usize = 0;
-        for byte in iter {
-            self.usb0
-                .ep_in
-                .data()
-                .write(|w| unsafe { w.data().bits(*byte) });
-            bytes_written += 1;
-
-            // send data if we've written max_packet_size
-            if bytes_written % max_packet_size == 0 {
-                // TODO clean up tx_ack_active semantics!!!
-                unsafe {
-                    self.usb0.set_tx_ack_active(endpoint_number);
-                }
-                self.usb0
-                    .ep_in
-                    .epno()
-                    .write(|w| unsafe { w.epno().bits(endpoint_number) });
-
-                // TODO should we wait for send complete interrupt to fire
-                // or do we eke out the smallest bit of performance if we
-                // just wait for the FIFO to empty?
-                let mut timeout = 0;
-                //while !self.usb0.ep_in.idle().read().idle().bit() {
-                //while self.usb0.ep_in.have().read().have().bit() {
-                while unsafe { self.usb0.is_tx_ack_active(endpoint_number) } {
-                    timeout += 1;
-                    if timeout > hal::usb::DEFAULT_TIMEOUT {
-                        unsafe {
-                            self.usb0.clear_tx_ack_active(endpoint_number);
-                        }
-                        log::error!(
-                            "moondancer::write_endpoint timed out after {} bytes",
-                            bytes_written
-                        );
-                        return Err(GreatError::StreamIoctlTimeout);
-                    }
-                }
-            }
-        }
+impl<P: TargetPort> Moondancer<P> {
+    /// Queue a ZLP to end the status stage of a control transfer.
+    ///
+    /// The ZLP goes through the transmit queue as eptri shares its
+    /// `EP_IN` FIFO between all endpoints.
+    fn ack_status(&mut self, endpoint_number: u8) -> GreatResult<()> {
+        let max_packet_size = self.ep_in_max_packet_size[usize::from(endpoint_number)];
+        self.tx_queue
+            .enqueue(endpoint_number, &[], max_packet_size.into(), true)
+            .map_err(|e| match e {
+                hal::usb::Error::InvalidPacketSize => GreatError::InvalidArgument,
+                _ => GreatError::NoBufferSpaceAvailable,
+            })?;
+        self.tx_queue.service(&self.port);
+
+        Ok(())
+    }
 
-        // finally, prime IN endpoint to either send
-        // remaining queued data or a ZLP if the fifo is
-        // empty.
-        //
-        // FIXME this conditional is to work around a problem where
-        // Facedancer has taken responsibility for splitting the
-        // packets up. We probably need two moondancer write methods
-        // to be honest.
-        if bytes_written != max_packet_size {
-            unsafe {
-                self.usb0.set_tx_ack_active(endpoint_number);
-            }
-            self.usb0
-                .ep_in
-                .epno()
-                .write(|w| unsafe { w.epno().bits(endpoint_number) });
+    fn write_endpoint_payload(
+        &mut self,
+        endpoint_number: u8,
+        blocking: bool,
+        payload: &[u8],
+    ) -> GreatResult<()> {
+        let payload_length = payload.len();
+        let max_packet_size = self.ep_in_max_packet_size[endpoint_number as usize] as usize;
+        if max_packet_size == 0 {
+            warn!("  moondancer ep{} is not configured", endpoint_number);
+            return Err(GreatError::InvalidArgument);
         }
 
-        // wait for send to complete if we're blocking
-        let mut timeout = 0;
-        while blocking & unsafe { self.usb0.is_tx_ack_active(endpoint_number) } {
-            timeout += 1;
-            if timeout > hal::usb::DEFAULT_TIMEOUT {
-                unsafe {
-                    self.usb0.clear_tx_ack_active(endpoint_number);
+        // queue non-blocking writes so other endpoints don't have to wait for the FIFO
+        //
+        // FIXME see below, a payload of exactly max_packet_size is not terminated with a ZLP
+        if !blocking {
+            let zlp = payload_length != max_packet_size;
+            match self
+                .tx_queue
+                .enqueue(endpoint_number, payload, max_packet_size, zlp)
+            {
+                Ok(()) => {
+                    self.tx_queue.service(&self.port);
+                    log::debug!(
+                        "MD moondancer::write_endpoint(endpoint_number:{}, blocking:{} payload.len:{}) queued",
+                        endpoint_number,
+                        blocking,
+                        payload_length,
+                    );
+                    return Ok(());
+                }
+                Err(_) if self.tx_queue.is_idle() => {
+                    // payload doesn't fit the queue, fall back to writing it directly
+                }
+                Err(_) => {
+                    warn!("  moondancer tx queue full ep{}", endpoint_number);
+                    return Err(GreatError::NoBufferSpaceAvailable);
                 }
-                log::error!(
-                    "moondancer::write_endpoint timed out after {} bytes during write of {} bytes",
-                    payload_length,
-                    bytes_written
-                );
-                // TODO return an error on timeout
             }
+        } else if !self.tx_queue.is_idle() {
+            // we can't wait for the queue to drain without processing events
+            warn!("  moondancer tx queue busy ep{}", endpoint_number);
+            return Err(GreatError::DeviceOrResourceBusy);
         }
 
+        self.port
+            .write_packets(endpoint_number, payload, max_packet_size, blocking)?;
+
         log::debug!(
-            "MD moondancer::write_endpoint(endpoint_number:{}, blocking:{} payload.len:{} ({})) max_packet_size:{} bytes_written:{}",
+            "MD moondancer::write_endpoint(endpoint_number:{}, blocking:{} payload.len:{}) max_packet_size:{}",
             endpoint_number,
             blocking,
             payload_length,
-            args.payload.iter().len(),
             max_packet_size,
-            bytes_written,
         );
 
-        Ok([].into_iter())
+        Ok(())
     }
+}
 
-    pub fn test_write_endpoint(
-        &mut self,
-        arguments: &[u8],
-    ) -> GreatResult<impl Iterator<Item = u8>> {
-        struct Args<B: zerocopy::ByteSlice> {
-            endpoint_number: zerocopy::Ref<B, u8>,
-            payload: B,
-        }
-        let (endpoint_number, payload) = zerocopy::Ref::new_unaligned_from_prefix(arguments)
-            .ok_or(GreatError::InvalidArgument)?;
-        let args = Args {
-            endpoint_number,
-            payload,
-        };
+// - tests --------------------------------------------------------------------
 
-        let endpoint: u8 = args.endpoint_number.read();
-        let payload_length = args.payload.len();
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
 
-        debug!(
-            "MD moondancer::test_write_endpoint(endpoint_number:{}, payload.len:{})",
-            endpoint, payload_length,
+    use crate::pac;
+    use crate::util::{get_usb_interrupt_event, USB0_EVENTS};
+    use lunasoc_hal::sim;
+    use smolusb::traits::{ReadEndpoint, UsbDriverOperations};
+
+    fn moondancer() -> Moondancer {
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut usb0 = hal::Usb0::new(
+            peripherals.USB0,
+            peripherals.USB0_EP_CONTROL,
+            peripherals.USB0_EP_IN,
+            peripherals.USB0_EP_OUT,
         );
+        usb0.connect(Speed::High);
 
-        Ok(payload_length.to_le_bytes().into_iter())
-    }
-}
+        // the handle is shared by all test threads, each servicing its
+        // own simulated controller
+        static EVENTS: std::sync::Once = std::sync::Once::new();
+        EVENTS.call_once(|| {
+            if let Some(events) = usb0.events() {
+                USB0_EVENTS.init(events).ok();
+            }
+        });
 
-// - verb implementations: interrupts -------------------------------------------
+        let moondancer = Moondancer::new(usb0);
+        unsafe { moondancer.enable_usb_interrupts() };
+        moondancer
+    }
 
-impl Moondancer {
-    /// Get the most recent USB driver messages.
-    ///
-    /// # Return Value
-    ///
-    /// [(type, interface, endpoint)]
-    pub fn get_interrupt_events(
-        &mut self,
-        _arguments: &[u8],
-    ) -> GreatResult<impl Iterator<Item = u8>> {
-        let mut tx_buffer = [0_u8; LIBGREAT_MAX_COMMAND_SIZE];
+    fn next_event() -> UsbEvent {
+        UsbEvent::try_from(get_usb_interrupt_event()).expect("expected a usb event")
+    }
 
-        let clone = self.irq_queue.clone();
-        self.irq_queue = Queue::new();
+    #[test]
+    fn test_verbs() {
+        let verb = |id| {
+            <Moondancer>::VERBS
+                .iter()
+                .find(|verb| verb.id == id)
+                .unwrap()
+        };
 
-        let length = clone.len() * 2;
-        let response = clone.iter().flat_map(|event| event.into_bytes());
+        assert_eq!(verb(0x0).name, "connect\0");
+        assert_eq!(verb(0x0).in_signature, "<HBH\0");
+        assert_eq!(
+            verb(0x0).in_param_names,
+            "ep0_max_packet_size, device_speed, quirk_flags\0"
+        );
 
-        for (dest, src) in tx_buffer.iter_mut().zip(response) {
-            *dest = src;
-        }
+        assert_eq!(verb(0xa).name, "write_endpoint\0");
+        assert_eq!(verb(0xa).in_signature, "<BB*X\0");
+        assert_eq!(verb(0xa).out_signature, "\0");
 
-        Ok(tx_buffer.into_iter().take(length))
+        assert_eq!(verb(0xb).name, "get_interrupt_events\0");
+        assert_eq!(verb(0xb).out_signature, "<*(BB)\0");
+        assert_eq!(verb(0xb).out_param_names, "type, endpoint\0");
     }
 
-    /// Returns test data containing USB driver messages.
-    ///
-    /// # Return value
-    ///
-    /// [(type, interface, endpoint)]
-    pub fn test_get_interrupt_events(
-        &mut self,
-        arguments: &[u8],
-    ) -> GreatResult<impl Iterator<Item = u8>> {
-        debug!("MD moondancer::test_get_interrupt_events()");
+    #[test]
+    fn test_set_address() {
+        let mut moondancer = moondancer();
+        moondancer.ep_in_max_packet_size[0] = 64;
+        sim::with(|soc| soc.usb0.host_poll_in(0, true));
+
+        // SET_ADDRESS(0x12) is acked locally
+        let setup = [0x00, 0x05, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00];
+        sim::with(|soc| soc.usb0.host_setup(0, setup));
+        let event = next_event();
+        assert!(matches!(event, UsbEvent::ReceiveSetupPacket(0, _)));
+        moondancer.dispatch_event(event);
+        assert!(moondancer.irq_queue.is_empty());
+
+        // and the address only changes once the status stage is complete
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0);
+        let event = next_event();
+        assert!(matches!(event, UsbEvent::SendComplete(0)));
+        moondancer.dispatch_event(event);
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0x12);
+        assert_eq!(sim::with(|soc| soc.mip()), 0);
+    }
 
-        self.irq_queue.enqueue(UsbEvent::BusReset).ok();
-        self.irq_queue.enqueue(UsbEvent::ReceiveControl(1)).ok();
-        self.irq_queue.enqueue(UsbEvent::ReceivePacket(2)).ok();
-        self.irq_queue.enqueue(UsbEvent::SendComplete(3)).ok();
+    #[test]
+    fn test_set_address_queued() {
+        let mut moondancer = moondancer();
+        moondancer.ep_in_max_packet_size[0] = 64;
+        moondancer.ep_in_max_packet_size[1] = 64;
+
+        // a packet is in flight on another endpoint
+        moondancer
+            .write_endpoint_payload(1, false, b"data")
+            .unwrap();
+
+        // SET_ADDRESS(0x12) waits for the EP_IN FIFO
+        let setup = [0x00, 0x05, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00];
+        sim::with(|soc| soc.usb0.host_setup(0, setup));
+        moondancer.dispatch_event(next_event());
+        assert_eq!(
+            sim::with(|soc| soc.usb0.host_in(0)),
+            Err(sim::usb::Handshake::Nak)
+        );
 
-        self.get_interrupt_events(arguments)
+        // until the host has collected the packet
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(1)), Ok(b"data".to_vec()));
+        let event = next_event();
+        assert!(matches!(event, UsbEvent::SendComplete(1)));
+        moondancer.dispatch_event(event);
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0);
+
+        assert_eq!(sim::with(|soc| soc.usb0.host_in(0)), Ok(vec![]));
+        let event = next_event();
+        assert!(matches!(event, UsbEvent::SendComplete(0)));
+        moondancer.dispatch_event(event);
+        assert_eq!(sim::with(|soc| soc.usb0.address()), 0x12);
     }
 
-    /// Get endpoint NAK status.
-    ///
-    /// # Return Value
-    ///
-    /// bitmask
-    pub fn get_nak_status(&mut self, _arguments: &[u8]) -> GreatResult<impl Iterator<Item = u8>> {
-        let nak_status = (self.usb0.ep_in.nak().read().bits() & 0xffff) as u16;
-        Ok(nak_status.to_le_bytes().into_iter())
+    #[test]
+    fn test_receive_control() {
+        let mut moondancer = moondancer();
+
+        // GET_DESCRIPTOR(Device) is forwarded to the host
+        let setup = [0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00];
+        sim::with(|soc| soc.usb0.host_setup(0, setup));
+        moondancer.dispatch_event(next_event());
+        assert!(matches!(
+            moondancer.irq_queue.dequeue(),
+            Some(UsbEvent::ReceiveControl(0))
+        ));
+
+        let response = moondancer.read_control().unwrap();
+        assert_eq!(response, setup);
     }
-}
-
-// - class information --------------------------------------------------------
-
-pub static CLASS: gcp::Class = gcp::Class {
-    id: gcp::ClassId::moondancer,
-    name: "moondancer",
-    docs: CLASS_DOCS,
-    verbs: &VERBS,
-};
-
-pub static CLASS_DOCS: &str = "API for fine-grained control of the Target USB port.\0";
 
-/// Verb definitions for class: moondancer
-///
-/// Fields are `"\0"`  where C implementation has `""`
-/// Fields are `"*\0"` where C implementation has `NULL`
-pub static VERBS: [Verb; 16] = [
-    // - device connection --
-    Verb {
-        id: 0x0,
-        name: "connect\0",
-        doc: "\0", //"Connect the target to the host. device_speed is 3:high, 2:full, 1:low\0",
-        in_signature: "<HBH\0",
-        in_param_names: "ep0_max_packet_size, device_speed, quirk_flags\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0x1,
-        name: "disconnect\0",
-        doc: "\0", //"Disconnect the target port from the host.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0x2,
-        name: "bus_reset\0",
-        doc: "\0", //"Cause the target device to handle a bus reset.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    // - status & control --
-    Verb {
-        id: 0x3,
-        name: "read_control\0",
-        doc: "\0", //"Read a setup packet from the control endpoint.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "<8X\0",
-        out_param_names: "setup_packet\0",
-    },
-    Verb {
-        id: 0x4,
-        name: "set_address\0",
-        doc: "\0", //"Set the address of the target device.\nIf deferred is set this action won't complete until the setup phase ends.\0",
-        in_signature: "<BB\0",
-        in_param_names: "address, deferred\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0x5,
-        name: "configure_endpoints\0", // TODO s/prime_out_endpoint
-        doc: "\0", //"Set up all of the non-control endpoints for the device.\0",
-        in_signature: "<*(BHB)\0",
-        in_param_names: "endpoint_descriptors\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0x6,
-        name: "stall_endpoint_in\0",
-        doc: "\0", //"Stall the IN endpoint with the provided endpoint number.\0",
-        in_signature: "<B\0",
-        in_param_names: "endpoint_number\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0x7,
-        name: "stall_endpoint_out\0",
-        doc: "\0", //"Stall the OUT endpoint with the provided endpoint number.\0",
-        in_signature: "<B\0",
-        in_param_names: "endpoint_number\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    // - data transfer --
-    Verb {
-        id: 0x8,
-        name: "read_endpoint\0",
-        doc: "\0", //"Read a packet from an OUT endpoint.\0",
-        in_signature: "<B\0",
-        in_param_names: "endpoint_number\0",
-        out_signature: "<*X\0",
-        out_param_names: "read_data\0",
-    },
-    Verb {
-        id: 0x9,
-        name: "ep_out_prime_receive\0",
-        doc: "\0", //"Prepare OUT endpoint to receive a single packet.\0",
-        in_signature: "<B\0",
-        in_param_names: "endpoint_number\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    Verb {
-        id: 0xa,
-        name: "write_endpoint\0",
-        doc: "\0", //"Write a packet to an IN endpoint.\0",
-        in_signature: "<BB*X\0",
-        in_param_names: "endpoint_number, blocking, payload\0",
-        out_signature: "\0",
-        out_param_names: "*\0",
-    },
-    // - interrupts --
-    Verb {
-        id: 0xb,
-        name: "get_interrupt_events\0",
-        doc: "\0", //"Return the most recent driver messages.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "<*(BB)\0",
-        out_param_names: "type, endpoint\0",
-    },
-    Verb {
-        id: 0xc,
-        name: "get_nak_status\0",
-        doc: "\0", //"Return endpoint NAK status.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "<H\0",
-        out_param_names: "bitmask\0",
-    },
-    // - tests --
-    Verb {
-        id: 0x28,
-        name: "test_read_endpoint\0",
-        doc: "\0", //"Return read_endpoint with payload_length of test data.\0",
-        in_signature: "<I\0",
-        in_param_names: "payload_length\0",
-        out_signature: "<*X\0",
-        out_param_names: "read_data\0",
-    },
-    Verb {
-        id: 0x2a,
-        name: "test_write_endpoint\0",
-        doc: "\0", //"Write a packet to an IN endpoint and return the length received.\0",
-        in_signature: "<B*X\0",
-        in_param_names: "endpoint_number, payload\0",
-        out_signature: "<I\0",
-        out_param_names: "payload_length\0",
-    },
-    Verb {
-        id: 0x2b,
-        name: "test_get_interrupt_events\0",
-        doc: "\0", //"Return get_interrupt_events() with test data.\0",
-        in_signature: "\0",
-        in_param_names: "*\0",
-        out_signature: "<*(BB)\0",
-        out_param_names: "type, endpoint\0",
-    },
-];
-
-// - dispatch -----------------------------------------------------------------
-
-impl GreatDispatch for Moondancer {
-    fn dispatch(
-        &mut self,
-        verb_number: u32,
-        arguments: &[u8],
-        response_buffer: [u8; LIBGREAT_MAX_COMMAND_SIZE],
-    ) -> GreatResult<GreatResponse> {
-        match verb_number {
-            0x0 => {
-                // moondancer::connect
-                let iter = self.connect(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x1 => {
-                // moondancer::disconnect
-                let iter = self.disconnect(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x2 => {
-                // moondancer::bus_reset
-                let iter = self.bus_reset(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x3 => {
-                // moondancer::read_control
-                ladybug::trace(Channel::A, Bit::A_READ_CONTROL, || {
-                    let iter = self.read_control(arguments)?;
-                    let response = iter_to_response(iter, response_buffer);
-                    Ok(response)
-                })
-            }
-            0x4 => {
-                // moondancer::set_address
-                let iter = self.set_address(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x5 => {
-                // moondancer::configure_endpoints
-                let iter = self.configure_endpoints(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x6 => {
-                // moondancer::stall_endpoint_in
-                let iter = self.stall_endpoint_in(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x7 => {
-                // moondancer::stall_endpoint_out
-                let iter = self.stall_endpoint_out(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x8 => {
-                // moondancer::read_endpoint
-                ladybug::trace(Channel::A, Bit::A_READ_ENDPOINT, || {
-                    let iter = self.read_endpoint(arguments)?;
-                    let response = iter_to_response(iter, response_buffer);
-                    Ok(response)
-                })
-            }
-            0x9 => {
-                // moondancer::ep_out_prime_receive
-                ladybug::trace(Channel::A, Bit::A_PRIME_RECEIVE, || {
-                    let iter = self.ep_out_prime_receive(arguments)?;
-                    let response = iter_to_response(iter, response_buffer);
-                    Ok(response)
-                })
-            }
-            0xa => {
-                // moondancer::write_endpoint
-                ladybug::trace(Channel::A, Bit::A_WRITE_ENDPOINT, || {
-                    let iter = self.write_endpoint(arguments)?;
-                    let response = iter_to_response(iter, response_buffer);
-                    Ok(response)
-                })
-            }
-            0xb => {
-                // moondancer::get_interrupt_events
-                ladybug::trace(Channel::A, Bit::A_GET_EVENTS, || {
-                    let iter = self.get_interrupt_events(arguments)?;
-                    let response = iter_to_response(iter, response_buffer);
-                    Ok(response)
-                })
-            }
-            0xc => {
-                // moondancer::get_nak_status
-                let iter = self.get_nak_status(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-
-            // test APIs
-            0x28 => {
-                // moondancer::test_read_endpoint
-                let iter = self.test_read_endpoint(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x2a => {
-                // moondancer::test_write_endpoint
-                let iter = self.test_write_endpoint(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-            0x2b => {
-                // moondancer::test_get_interrupt_events
-                let iter = self.test_get_interrupt_events(arguments)?;
-                let response = iter_to_response(iter, response_buffer);
-                Ok(response)
-            }
-
-            _verb_number => Err(GreatError::InvalidArgument),
-        }
+    #[test]
+    fn test_receive_packet() {
+        let mut moondancer = moondancer();
+        moondancer.port.ep_out_prime_receive(1);
+
+        sim::with(|soc| soc.usb0.host_out(1, b"moondancer"));
+        moondancer.dispatch_event(next_event());
+        assert!(matches!(
+            moondancer.irq_queue.dequeue(),
+            Some(UsbEvent::ReceivePacket(1))
+        ));
+
+        let packet = &moondancer.packet_buffer[0];
+        assert_eq!(packet.endpoint_number, 1);
+        assert_eq!(&packet.buffer[..packet.bytes_read], b"moondancer");
     }
 }
@@ -1,78 +1,113 @@
 use libgreat::error::{GreatError, GreatResult};
-use libgreat::gcp::{self, Verb};
+use libgreat::gcp::job::{Job, JobPoll, Jobs};
+use libgreat::gcp::{self, verbs};
 
 use log::debug;
-use zerocopy::{FromBytes, FromZeroes, LittleEndian, Unaligned, U32};
-
-use core::any::Any;
 
 pub static CLASS: gcp::Class = gcp::Class {
     id: gcp::ClassId::selftest,
     name: "selftest",
     docs: CLASS_DOCS,
-    verbs: &VERBS,
+    verbs: &Selftest::VERBS,
 };
 
 pub static CLASS_DOCS: &str = "Provides functionality for a Cynthion to self-test itself.\0";
 
-/// Fields are `"\0"`  where C implementation has `""`
-/// Fields are `"*\0"` where C implementation has `NULL`
-pub static VERBS: [Verb; 1] = [Verb {
-    id: 0x10,
-    name: "test_error_return_code\0",
-    doc: "\0", // "Returns the string 'ok' if code is 0, otherwise an error with the given code.\0",
-    in_signature: "<I\0",
-    in_param_names: "code\0",
-    out_signature: "<S\0",
-    out_param_names: "result\0",
-}];
+// - Selftest -----------------------------------------------------------------
 
-// - verb implementations -----------------------------------------------------
+pub struct Selftest {
+    pub jobs: Jobs<TestJob>,
+}
+
+impl Selftest {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { jobs: Jobs::new() }
+    }
+}
 
-pub fn test_error_return_code<'a>(
-    arguments: &[u8],
-    _context: &'a dyn Any,
-) -> GreatResult<impl Iterator<Item = u8> + 'a> {
-    #[repr(C)]
-    #[derive(FromBytes, FromZeroes, Unaligned)]
-    struct Args {
-        code: U32<LittleEndian>,
+impl Default for Selftest {
+    fn default() -> Self {
+        Self::new()
     }
-    let args = Args::read_from(arguments).ok_or(GreatError::InvalidArgument)?;
+}
+
+// - TestJob ------------------------------------------------------------------
+
+/// A job that takes the given number of steps to complete and returns
+/// the number of steps taken.
+pub struct TestJob {
+    steps: u32,
+    done: u32,
+}
 
-    match args.code.into() {
-        0_u32 => {
-            let s = "ok";
-            debug!("  test_error_return_code -> 0 -> Ok('ok')");
-            Ok(s.as_bytes().iter().copied())
+impl Job for TestJob {
+    fn poll(&mut self) -> JobPoll {
+        if self.done == self.steps {
+            return JobPoll::Ready(Ok(()));
         }
-        code => {
-            let code: GreatError = unsafe { core::mem::transmute(code) };
-            debug!("  test_error_return_code -> {} -> Err({})", args.code, code);
-            Err(code)
+        self.done += 1;
+        JobPoll::Pending {
+            done: self.done,
+            total: self.steps,
         }
     }
-}
 
-// - dispatch -----------------------------------------------------------------
+    fn result(&mut self, response: &mut [u8]) -> GreatResult<usize> {
+        gcp::iter_to_response(self.done.to_le_bytes().into_iter(), response)
+    }
 
-use libgreat::gcp::{iter_to_response, GreatResponse, LIBGREAT_MAX_COMMAND_SIZE};
+    fn cancel(&mut self) {
+        debug!(
+            "  test_job canceled after {} of {} steps",
+            self.done, self.steps
+        );
+    }
+}
 
-pub fn dispatch(
-    verb_number: u32,
-    arguments: &[u8],
-    response_buffer: [u8; LIBGREAT_MAX_COMMAND_SIZE],
-) -> GreatResult<GreatResponse> {
-    let no_context: Option<u8> = None;
+// - verb implementations -----------------------------------------------------
 
-    match verb_number {
-        0x10 => {
-            // selftest::test_error_return_code
-            let iter = test_error_return_code(arguments, &no_context)?;
-            let response = iter_to_response(iter, response_buffer);
-            Ok(response)
+#[verbs(jobs = "jobs")]
+impl Selftest {
+    /// Returns the string 'ok' if code is 0, otherwise an error with the given code.
+    #[verb(id = 0x10, out_param_names = "result")]
+    pub fn test_error_return_code(&self, code: u32) -> GreatResult<&'static str> {
+        match code {
+            0_u32 => {
+                debug!("  test_error_return_code -> 0 -> Ok('ok')");
+                Ok("ok")
+            }
+            code => {
+                let error: GreatError = unsafe { core::mem::transmute(code) };
+                debug!("  test_error_return_code -> {} -> Err({})", code, error);
+                Err(error)
+            }
         }
+    }
+
+    /// Start a job that takes the given number of steps to complete.
+    #[verb(id = 0x20, out_param_names = "job_id")]
+    pub fn test_job(&mut self, steps: u32) -> GreatResult<u32> {
+        self.jobs.start(TestJob { steps, done: 0 })
+    }
+}
+
+// - tests --------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbs() {
+        let verb = |id| Selftest::VERBS.iter().find(|verb| verb.id == id).unwrap();
+
+        assert_eq!(verb(0x10).name, "test_error_return_code\0");
+        assert_eq!(verb(0x10).in_signature, "<I\0");
+        assert_eq!(verb(0x10).out_signature, "<S\0");
 
-        _verb_number => Err(GreatError::InvalidArgument),
+        assert_eq!(verb(0x20).name, "test_job\0");
+        assert_eq!(verb(0x20).in_param_names, "steps\0");
+        assert_eq!(verb(0x20).out_param_names, "job_id\0");
     }
 }
@@ -0,0 +1,471 @@
+//! Target port used by the GCP `moondancer` class.
+//!
+//! The `moondancer` class only talks to the Target port's USB controller
+//! through [`TargetPort`], which is implemented for the [`hal::Usb0`]
+//! peripheral and for [`VirtualTarget`], an in-memory model that lets
+//! the class run in a normal host process:
+//!
+//! ```ignore
+//! let mut moondancer = Moondancer::new(VirtualTarget::new());
+//!
+//! // the host sends a packet to the device
+//! moondancer.port().host_out(1, b"data");
+//! moondancer.dispatch_event(UsbEvent::ReceivePacket(1));
+//! ```
+
+use core::cell::RefCell;
+
+use heapless::{Deque, Vec};
+use log::{error, warn};
+
+use crate::{hal, pac};
+use hal::smolusb;
+use hal::usb::TxFifo;
+use pac::csr::interrupt;
+
+use smolusb::device::Speed;
+use smolusb::setup::Direction;
+use smolusb::traits::{ReadEndpoint, UnsafeUsbDriverOperations, UsbDriverOperations};
+
+use libgreat::error::{GreatError, GreatResult};
+
+// - TargetPort ---------------------------------------------------------------
+
+/// Operations of the Target port's USB controller used by the
+/// `moondancer` class.
+pub trait TargetPort: UsbDriverOperations + ReadEndpoint + TxFifo {
+    /// Returns the device speed requested by the last `connect`.
+    fn device_speed(&self) -> Speed;
+
+    /// Wait for a new connection to settle and return the speed
+    /// negotiated with the host.
+    fn wait_for_connection(&self) -> Speed;
+
+    /// Returns the bitmask of IN endpoints that have sent the host a NAK.
+    fn nak_status(&self) -> u16;
+
+    /// Write a payload to an IN endpoint, bypassing the transmit queue.
+    ///
+    /// The payload is split into packets of `max_packet_size`. If
+    /// `blocking` is set, wait until the host has received the last packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GreatError::StreamIoctlTimeout`] if the endpoint does
+    /// not become ready in time, or a blocking write is not received by
+    /// the host in time.
+    fn write_packets(
+        &self,
+        endpoint_number: u8,
+        payload: &[u8],
+        max_packet_size: usize,
+        blocking: bool,
+    ) -> GreatResult<()>;
+
+    /// Enable USB events and CPU interrupts for the USB controller.
+    ///
+    /// # Safety
+    ///
+    /// See [`Moondancer::enable_usb_interrupts`](super::moondancer::Moondancer::enable_usb_interrupts).
+    unsafe fn enable_interrupts(&self);
+
+    /// Disable USB events and CPU interrupts for the USB controller.
+    ///
+    /// # Safety
+    ///
+    /// See [`Moondancer::disable_usb_interrupts`](super::moondancer::Moondancer::disable_usb_interrupts).
+    unsafe fn disable_interrupts(&self);
+}
+
+// - hal::Usb0 ----------------------------------------------------------------
+
+impl TargetPort for hal::Usb0 {
+    fn device_speed(&self) -> Speed {
+        self.device_speed
+    }
+
+    fn wait_for_connection(&self) -> Speed {
+        hal::Timer0::monotonic().delay_ms(170);
+        self.controller.speed().read().speed().bits().into()
+    }
+
+    fn nak_status(&self) -> u16 {
+        (self.ep_in.nak().read().bits() & 0xffff) as u16
+    }
+
+    fn write_packets(
+        &self,
+        endpoint_number: u8,
+        payload: &[u8],
+        max_packet_size: usize,
+        blocking: bool,
+    ) -> GreatResult<()> {
+        let payload_length = payload.len();
+
+        // TODO clean up tx_ack_active semantics!!!
+        unsafe {
+            self.set_tx_ack_active(endpoint_number);
+        }
+
+        // check if output FIFO is empty
+        // FIXME return a GreatError::DeviceOrResourceBusy on timeout
+        let mut timeout = 0;
+        while self.ep_in.have().read().have().bit() {
+            if timeout == 0 {
+                warn!("  moondancer clear tx ep{}", endpoint_number);
+            } else if timeout > self.timeout() {
+                self.ep_in.reset().write(|w| w.reset().bit(true));
+                unsafe {
+                    self.clear_tx_ack_active(endpoint_number);
+                }
+                error!("  moondancer clear tx timeout ep{}", endpoint_number);
+                return Err(GreatError::StreamIoctlTimeout);
+            }
+            timeout += 1;
+        }
+
+        // write data out to EP_IN, splitting into packets of max_packet_size
+        let mut bytes_written: usize = 0;
+        for byte in payload {
+            self.ep_in.data().write(|w| unsafe { w.data().bits(*byte) });
+            bytes_written += 1;
+
+            // send data if we've written max_packet_size
+            if bytes_written % max_packet_size == 0 {
+                // TODO clean up tx_ack_active semantics!!!
+                unsafe {
+                    self.set_tx_ack_active(endpoint_number);
+                }
+                self.ep_in
+                    .epno()
+                    .write(|w| unsafe { w.epno().bits(endpoint_number) });
+
+                // TODO should we wait for send complete interrupt to fire
+                // or do we eke out the smallest bit of performance if we
+                // just wait for the FIFO to empty?
+                let mut timeout = 0;
+                //while !self.ep_in.idle().read().idle().bit() {
+                //while self.ep_in.have().read().have().bit() {
+                while unsafe { self.is_tx_ack_active(endpoint_number) } {
+                    timeout += 1;
+                    if timeout > self.timeout() {
+                        unsafe {
+                            self.clear_tx_ack_active(endpoint_number);
+                        }
+                        log::error!(
+                            "moondancer::write_endpoint timed out after {} bytes",
+                            bytes_written
+                        );
+                        return Err(GreatError::StreamIoctlTimeout);
+                    }
+                }
+            }
+        }
+
+        // finally, prime IN endpoint to either send
+        // remaining queued data or a ZLP if the fifo is
+        // empty.
+        //
+        // FIXME this conditional is to work around a problem where
+        // Facedancer has taken responsibility for splitting the
+        // packets up. We probably need two moondancer write methods
+        // to be honest.
+        if bytes_written != max_packet_size {
+            unsafe {
+                self.set_tx_ack_active(endpoint_number);
+            }
+            self.ep_in
+                .epno()
+                .write(|w| unsafe { w.epno().bits(endpoint_number) });
+        }
+
+        // wait for send to complete if we're blocking
+        let mut timeout = 0;
+        while blocking & unsafe { self.is_tx_ack_active(endpoint_number) } {
+            timeout += 1;
+            if timeout > self.timeout() {
+                unsafe {
+                    self.clear_tx_ack_active(endpoint_number);
+                }
+                log::error!(
+                    "moondancer::write_endpoint timed out after {} bytes during write of {} bytes",
+                    payload_length,
+                    bytes_written
+                );
+                return Err(GreatError::StreamIoctlTimeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    unsafe fn enable_interrupts(&self) {
+        interrupt::enable(pac::Interrupt::USB0);
+        interrupt::enable(pac::Interrupt::USB0_EP_CONTROL);
+        interrupt::enable(pac::Interrupt::USB0_EP_IN);
+        interrupt::enable(pac::Interrupt::USB0_EP_OUT);
+
+        // enable all usb events
+        self.enable_events();
+    }
+
+    unsafe fn disable_interrupts(&self) {
+        // disable all usb events
+        self.disable_events();
+
+        interrupt::disable(pac::Interrupt::USB0);
+        interrupt::disable(pac::Interrupt::USB0_EP_CONTROL);
+        interrupt::disable(pac::Interrupt::USB0_EP_IN);
+        interrupt::disable(pac::Interrupt::USB0_EP_OUT);
+    }
+}
+
+// - VirtualTarget ------------------------------------------------------------
+
+/// A packet sent or received by a [`VirtualTarget`].
+pub type VirtualPacket = (u8, Vec<u8, { smolusb::EP_MAX_PACKET_SIZE }>);
+
+/// Maximum number of packets queued in each direction.
+const VIRTUAL_QUEUE_SIZE: usize = 16;
+
+/// An in-memory Target port.
+///
+/// Plays the part of the host with [`host_out`](Self::host_out) and
+/// [`host_in`](Self::host_in). No events are generated, they are passed
+/// to [`Moondancer::dispatch_event`](super::moondancer::Moondancer::dispatch_event)
+/// by the caller instead.
+pub struct VirtualTarget {
+    state: RefCell<VirtualState>,
+}
+
+struct VirtualState {
+    device_speed: Speed,
+    address: u8,
+    primed_out: u16,
+    stalled_in: u16,
+    stalled_out: u16,
+    /// Packets sent by the host, waiting to be read.
+    out_packets: Deque<VirtualPacket, VIRTUAL_QUEUE_SIZE>,
+    /// Packets written by the device, waiting to be collected.
+    in_packets: Deque<VirtualPacket, VIRTUAL_QUEUE_SIZE>,
+}
+
+impl VirtualTarget {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: RefCell::new(VirtualState {
+                device_speed: Speed::Unknown,
+                address: 0,
+                primed_out: 0,
+                stalled_in: 0,
+                stalled_out: 0,
+                out_packets: Deque::new(),
+                in_packets: Deque::new(),
+            }),
+        }
+    }
+
+    /// Send a packet from the host to an OUT endpoint.
+    ///
+    /// Returns `false` if the endpoint is not primed to receive a packet,
+    /// is stalled, or too many packets are waiting to be read.
+    pub fn host_out(&self, endpoint_number: u8, data: &[u8]) -> bool {
+        let mut state = self.state.borrow_mut();
+        let mask = endpoint_mask(endpoint_number);
+        if state.primed_out & mask == 0 || state.stalled_out & mask != 0 {
+            return false;
+        }
+        let Ok(data) = Vec::from_slice(data) else {
+            return false;
+        };
+        if state
+            .out_packets
+            .push_back((endpoint_number, data))
+            .is_err()
+        {
+            return false;
+        }
+        state.primed_out &= !mask;
+        true
+    }
+
+    /// Collect the next packet written by the device.
+    pub fn host_in(&self) -> Option<VirtualPacket> {
+        self.state.borrow_mut().in_packets.pop_front()
+    }
+
+    /// Returns the device address.
+    #[must_use]
+    pub fn address(&self) -> u8 {
+        self.state.borrow().address
+    }
+
+    /// Returns `true` if the OUT endpoint is primed to receive a packet.
+    #[must_use]
+    pub fn is_primed_out(&self, endpoint_number: u8) -> bool {
+        self.state.borrow().primed_out & endpoint_mask(endpoint_number) != 0
+    }
+
+    /// Returns `true` if the IN endpoint is stalled.
+    #[must_use]
+    pub fn is_stalled_in(&self, endpoint_number: u8) -> bool {
+        self.state.borrow().stalled_in & endpoint_mask(endpoint_number) != 0
+    }
+
+    /// Returns `true` if the OUT endpoint is stalled.
+    #[must_use]
+    pub fn is_stalled_out(&self, endpoint_number: u8) -> bool {
+        self.state.borrow().stalled_out & endpoint_mask(endpoint_number) != 0
+    }
+
+    fn send(&self, endpoint_number: u8, packet: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        let packet = Vec::from_slice(packet).unwrap_or_default();
+        if state
+            .in_packets
+            .push_back((endpoint_number, packet))
+            .is_err()
+        {
+            error!("VirtualTarget - in packet queue overflow");
+        }
+    }
+}
+
+impl Default for VirtualTarget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsbDriverOperations for VirtualTarget {
+    fn connect(&mut self, device_speed: Speed) {
+        let mut state = self.state.borrow_mut();
+        *state = Self::new().state.into_inner();
+        state.device_speed = match device_speed {
+            Speed::High | Speed::Full | Speed::Low => device_speed,
+            _ => Speed::High,
+        };
+    }
+
+    fn disconnect(&mut self) {
+        *self.state.borrow_mut() = Self::new().state.into_inner();
+    }
+
+    fn bus_reset(&self) {
+        self.set_address(0);
+    }
+
+    fn ack(&self, endpoint_number: u8, direction: Direction) {
+        match direction {
+            Direction::HostToDevice => self.send(endpoint_number, &[]),
+            Direction::DeviceToHost => self.ep_out_prime_receive(endpoint_number),
+        }
+    }
+
+    fn set_address(&self, address: u8) {
+        self.state.borrow_mut().address = address & 0x7f;
+    }
+
+    fn stall_endpoint_in(&self, endpoint_number: u8) {
+        self.state.borrow_mut().stalled_in |= endpoint_mask(endpoint_number);
+    }
+
+    fn stall_endpoint_out(&self, endpoint_number: u8) {
+        self.state.borrow_mut().stalled_out |= endpoint_mask(endpoint_number);
+    }
+
+    fn clear_feature_endpoint_halt(&self, endpoint_address: u8) {
+        let mask = endpoint_mask(endpoint_address & 0x7f);
+        let mut state = self.state.borrow_mut();
+        match Direction::from(endpoint_address) {
+            Direction::HostToDevice => state.stalled_out &= !mask,
+            Direction::DeviceToHost => state.stalled_in &= !mask,
+        }
+    }
+}
+
+impl ReadEndpoint for VirtualTarget {
+    fn ep_out_prime_receive(&self, endpoint_number: u8) {
+        self.state.borrow_mut().primed_out |= endpoint_mask(endpoint_number);
+    }
+
+    fn read(&self, endpoint_number: u8, buffer: &mut [u8]) -> usize {
+        let mut state = self.state.borrow_mut();
+
+        // take the first packet for the endpoint, keeping the order of the others
+        let mut packet = None;
+        for _ in 0..state.out_packets.len() {
+            let Some(next) = state.out_packets.pop_front() else {
+                break;
+            };
+            if packet.is_none() && next.0 == endpoint_number {
+                packet = Some(next);
+            } else {
+                let _ = state.out_packets.push_back(next);
+            }
+        }
+
+        let Some((_, data)) = packet else {
+            return 0;
+        };
+        let length = data.len().min(buffer.len());
+        buffer[..length].copy_from_slice(&data[..length]);
+        length
+    }
+}
+
+impl TxFifo for VirtualTarget {
+    fn tx_fifo_is_empty(&self) -> bool {
+        true
+    }
+
+    fn tx_fifo_send<I>(&self, endpoint_number: u8, packet: I)
+    where
+        I: Iterator<Item = u8>,
+    {
+        let packet: Vec<u8, { smolusb::EP_MAX_PACKET_SIZE }> = packet.collect();
+        self.send(endpoint_number, &packet);
+    }
+}
+
+impl TargetPort for VirtualTarget {
+    fn device_speed(&self) -> Speed {
+        self.state.borrow().device_speed
+    }
+
+    fn wait_for_connection(&self) -> Speed {
+        self.device_speed()
+    }
+
+    fn nak_status(&self) -> u16 {
+        0
+    }
+
+    fn write_packets(
+        &self,
+        endpoint_number: u8,
+        payload: &[u8],
+        max_packet_size: usize,
+        _blocking: bool,
+    ) -> GreatResult<()> {
+        // like the hardware, a payload of exactly max_packet_size is not
+        // terminated with a zero length packet
+        let mut chunks = payload.chunks_exact(max_packet_size.max(1));
+        for packet in &mut chunks {
+            self.send(endpoint_number, packet);
+        }
+        if payload.len() != max_packet_size {
+            self.send(endpoint_number, chunks.remainder());
+        }
+        Ok(())
+    }
+
+    unsafe fn enable_interrupts(&self) {}
+
+    unsafe fn disable_interrupts(&self) {}
+}
+
+fn endpoint_mask(endpoint_number: u8) -> u16 {
+    1 << (endpoint_number & 0xf)
+}
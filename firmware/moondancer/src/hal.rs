@@ -2,7 +2,8 @@ pub use crate::pac;
 pub use lunasoc_hal::*;
 
 lunasoc_hal::impl_gpio! {
-    Gpio0: pac::GPIOA,
+    GpioA: gpioa, pac::GPIOA,
+    GpioB: gpiob, pac::GPIOB,
 }
 
 lunasoc_hal::impl_serial! {
@@ -10,10 +11,17 @@ lunasoc_hal::impl_serial! {
     Serial1: pac::UART1,
 }
 
+lunasoc_hal::impl_spi! {
+    Spi0: pac::SPI0,
+}
+
 lunasoc_hal::impl_timer! {
     Timer0: pac::TIMER,
 }
 
+#[cfg(feature = "embassy-time")]
+lunasoc_hal::impl_embassy_time_driver!(Timer0);
+
 pub use lunasoc_hal::smolusb;
 use lunasoc_hal::smolusb::device::Speed;
 use lunasoc_hal::smolusb::setup::Direction;
@@ -27,3 +35,17 @@ lunasoc_hal::impl_usb! {
     Usb1: usb1, pac::USB1, pac::USB1_EP_CONTROL, pac::USB1_EP_IN, pac::USB1_EP_OUT,
     Usb2: usb2, pac::USB2, pac::USB2_EP_CONTROL, pac::USB2_EP_IN, pac::USB2_EP_OUT,
 }
+
+#[cfg(feature = "embassy-usb")]
+lunasoc_hal::impl_embassy_usb! {
+    Usb0Driver: usb0_embassy, Usb0, pac::USB0, pac::USB0_EP_CONTROL, pac::USB0_EP_IN, pac::USB0_EP_OUT,
+    Usb1Driver: usb1_embassy, Usb1, pac::USB1, pac::USB1_EP_CONTROL, pac::USB1_EP_IN, pac::USB1_EP_OUT,
+    Usb2Driver: usb2_embassy, Usb2, pac::USB2, pac::USB2_EP_CONTROL, pac::USB2_EP_IN, pac::USB2_EP_OUT,
+}
+
+#[cfg(feature = "usb-device")]
+lunasoc_hal::impl_usb_device! {
+    Usb0Bus: Usb0,
+    Usb1Bus: Usb1,
+    Usb2Bus: Usb2,
+}
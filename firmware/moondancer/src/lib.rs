@@ -1,6 +1,6 @@
 #![cfg_attr(feature = "nightly", feature(error_in_core))]
 #![cfg_attr(feature = "nightly", feature(panic_info_message))]
-#![no_std]
+#![cfg_attr(not(any(test, feature = "sim")), no_std)]
 
 // - modules ------------------------------------------------------------------
 
@@ -11,6 +11,7 @@ pub mod gcp;
 pub mod hal;
 pub mod log;
 pub mod macros;
+#[cfg(not(any(test, feature = "sim")))]
 pub mod panic_log;
 pub mod usb;
 pub mod util;
@@ -20,21 +21,23 @@ pub mod util;
 pub use error::FirmwareError;
 pub use libgreat::error::GreatResult;
 pub use libgreat::firmware::BoardInformation;
+#[cfg(feature = "sim")]
+pub use lunasoc_hal::sim::pac;
+#[cfg(not(feature = "sim"))]
 pub use moondancer_pac as pac;
 
 // - constants ----------------------------------------------------------------
 
 pub const SYSTEM_CLOCK_FREQUENCY: u32 = pac::clock::sysclk();
 
-// TODO these need to be populated at runtime
+// the serial number is read from the SPI flash at runtime, see
+// `util::board_information`
+// TODO the part id still needs to be populated at runtime
 pub const BOARD_INFORMATION: BoardInformation = BoardInformation {
     board_id: 0x10_u32.to_le_bytes(),
     version_string: "r1.0\0",
     part_id: [0x30, 0xa, 0x00, 0xa0, 0x5e, 0x4f, 0x60, 0x00],
-    serial_number: [
-        0xea, 0x8e, 0xc5, 0x4f, 0x64, 0x74, 0x15, 0x15, 0x02, 0x02, 0x02, 0x03, 0xb3, 0xe0, 0x51,
-        0xff,
-    ],
+    serial_number: [0; 16],
 };
 
 // - types --------------------------------------------------------------------
@@ -1,24 +1,47 @@
 //! A simple logger for Cynthion's serial ports.
+//!
+//! The most recent log output is also kept in a ring buffer which can be
+//! read back with [`dmesg`].
 
 use core::fmt::Write;
 use core::ptr::addr_of_mut;
 
+use heapless::HistoryBuffer;
 use log::{Level, LevelFilter, Metadata, Record};
 
-use hal::hal::serial::Write as _;
+use hal::hal::serial::Write as SerialWrite;
 
 use crate::hal;
+use hal::shared::Shared;
 
 // - initialization -----------------------------------------------------------
 
 static mut LOGGER: CynthionLogger = CynthionLogger::new(Port::Both, Level::Trace);
 
-/// Initializes logging using the given serial port
+static SERIAL0: Shared<hal::Serial0> = Shared::new();
+static SERIAL1: Shared<hal::Serial1> = Shared::new();
+
+/// Size of the buffer holding the most recent log output.
+pub const DMESG_SIZE: usize = 2048;
+
+static DMESG: Shared<Dmesg> = Shared::new();
+
+/// Initializes logging using the given serial ports
+///
+/// Pass `None` for a port that is used for another purpose.
 ///
 /// # Panics
 ///
 /// This function will panic if the logger cannot be initialized.
-pub fn init() {
+pub fn init(serial0: impl Into<Option<hal::Serial0>>, serial1: impl Into<Option<hal::Serial1>>) {
+    if let Some(serial0) = serial0.into() {
+        SERIAL0.init(serial0).ok();
+    }
+    if let Some(serial1) = serial1.into() {
+        SERIAL1.init(serial1).ok();
+    }
+    DMESG.init(Dmesg::new()).ok();
+
     let logger = unsafe { &mut *addr_of_mut!(LOGGER) };
 
     #[cfg(target_has_atomic)]
@@ -85,47 +108,99 @@ impl log::Log for CynthionLogger {
     }
 
     /// Write the given record to the log
+    ///
+    /// Records logged from an interrupt handler while the same port is
+    /// writing a record are dropped.
     fn log(&self, record: &Record) {
         if !self.enabled(record.metadata()) {
             return;
         }
 
-        match self.port {
-            Port::Uart0 => {
-                let mut writer = unsafe { hal::Serial0::summon() };
-                writeln!(writer, "{}\t{}", record.level(), record.args()).unwrap_or(());
-            }
-            Port::Uart1 => {
-                let mut writer = unsafe { hal::Serial1::summon() };
-                writeln!(writer, "{}\t{}", record.level(), record.args()).unwrap_or(());
-            }
-            Port::Both => {
-                let mut writer = unsafe { hal::Serial0::summon() };
-                writeln!(writer, "{}\t{}", record.level(), record.args()).unwrap_or(());
-                let mut writer = unsafe { hal::Serial1::summon() };
-                writeln!(writer, "{}\t{}", record.level(), record.args()).unwrap_or(());
-            }
+        if matches!(self.port, Port::Uart0 | Port::Both) {
+            SERIAL0
+                .lock(|writer| writeln!(writer, "{}\t{}", record.level(), record.args()))
+                .ok();
+        }
+        if matches!(self.port, Port::Uart1 | Port::Both) {
+            SERIAL1
+                .lock(|writer| writeln!(writer, "{}\t{}", record.level(), record.args()))
+                .ok();
         }
+        DMESG
+            .lock(|dmesg| writeln!(dmesg, "{}\t{}", record.level(), record.args()))
+            .ok();
     }
 
     fn flush(&self) {
-        match self.port {
-            Port::Uart0 => {
-                let mut writer = unsafe { hal::Serial0::summon() };
-                writer.flush().ok();
-            }
-            Port::Uart1 => {
-                let mut writer = unsafe { hal::Serial1::summon() };
-                writer.flush().ok();
-            }
-            Port::Both => {
-                let mut writer = unsafe { hal::Serial0::summon() };
-                writer.flush().ok();
-                let mut writer = unsafe { hal::Serial1::summon() };
-                writer.flush().ok();
-            }
+        if matches!(self.port, Port::Uart0 | Port::Both) {
+            SERIAL0.lock(SerialWrite::flush).ok();
+        }
+        if matches!(self.port, Port::Uart1 | Port::Both) {
+            SERIAL1.lock(SerialWrite::flush).ok();
+        }
+    }
+}
+
+// - dmesg --------------------------------------------------------------------
+
+/// Ring buffer holding the most recent log output.
+///
+/// Once the buffer is full the oldest output is overwritten.
+pub struct Dmesg {
+    buffer: HistoryBuffer<u8, DMESG_SIZE>,
+}
+
+impl Dmesg {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: HistoryBuffer::new(),
         }
     }
+
+    /// Returns the number of bytes in the buffer.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if nothing has been logged since the buffer was
+    /// last cleared.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len() == 0
+    }
+
+    /// Returns the contents of the buffer, oldest output first.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.buffer.oldest_ordered().copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+impl Default for Dmesg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for Dmesg {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.buffer.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Call `f` with the buffer holding the most recent log output.
+///
+/// Records logged while `f` runs are not added to the buffer.
+///
+/// Returns `None` if logging has not been initialized.
+pub fn dmesg<R>(f: impl FnOnce(&mut Dmesg) -> R) -> Option<R> {
+    DMESG.lock(f).ok()
 }
 
 // - format! ------------------------------------------------------------------
@@ -1,13 +1,11 @@
 use crate::hal::smolusb;
 use pac::csr::interrupt;
 
-use libgreat::GreatError;
-
 use smolusb::event::UsbEvent;
 use smolusb::setup::SetupPacket;
-use smolusb::traits::{ReadControl, UnsafeUsbDriverOperations, UsbDriverOperations};
 
 use crate::event::InterruptEvent;
+use crate::hal::shared::Shared;
 use crate::{hal, pac};
 
 use crate::debug::Bit;
@@ -15,203 +13,138 @@ use ladybug::Channel;
 
 // - generic usb isr ----------------------------------------------------------
 
+/// Handle for servicing `usb0` (`target_phy`) interrupts.
+pub static USB0_EVENTS: Shared<hal::usb0::Events> = Shared::new();
+
+/// Handle for servicing `usb1` (`aux_phy`) interrupts.
+pub static USB1_EVENTS: Shared<hal::usb1::Events> = Shared::new();
+
+/// Handle for servicing `usb2` (`control_phy`) interrupts.
+pub static USB2_EVENTS: Shared<hal::usb2::Events> = Shared::new();
+
+/// Service the pending USB interrupt and return its event.
+///
+/// See [`usb_interrupt_event`].
 #[must_use]
-#[allow(clippy::cast_possible_truncation)]
-#[allow(clippy::too_many_lines)]
 pub fn get_usb_interrupt_event() -> InterruptEvent {
-    use crate::UsbInterface::{Aux, Control, Target};
+    match interrupt::pending() {
+        Ok(interrupt) => usb_interrupt_event(interrupt),
+        Err(pending) => InterruptEvent::UnknownInterrupt(pending),
+    }
+}
 
-    let usb0 = unsafe { hal::Usb0::summon() }; // target
-    let usb1 = unsafe { hal::Usb1::summon() }; // aux
-    let usb2 = unsafe { hal::Usb2::summon() }; // control
+/// Service the given USB interrupt and return its event.
+///
+/// Interrupts are only serviced for the controllers whose
+/// [`Events`](hal::usb0::Events) handle has been moved into
+/// [`USB0_EVENTS`], [`USB1_EVENTS`] or [`USB2_EVENTS`], all others
+/// are returned as [`InterruptEvent::UnhandledInterrupt`].
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub fn usb_interrupt_event(interrupt: pac::Interrupt) -> InterruptEvent {
+    use crate::UsbInterface::{Aux, Control, Target};
 
-    let pending = match interrupt::pending() {
-        Ok(interrupt) => interrupt,
-        Err(pending) => return InterruptEvent::UnknownInterrupt(pending),
+    let setup_packet_event = |interface, (endpoint_number, bytes_read), buffer| {
+        if bytes_read == 0 {
+            InterruptEvent::ErrorMessage("ERROR received 0 bytes for setup packet")
+        } else {
+            InterruptEvent::Usb(
+                interface,
+                UsbEvent::ReceiveSetupPacket(endpoint_number, SetupPacket::from(buffer)),
+            )
+        }
     };
 
-    match pending {
+    let event = match interrupt {
         // - usb0 interrupts - "target_phy" --
 
         // USB0 BusReset
-        pac::Interrupt::USB0 => {
+        pac::Interrupt::USB0 => USB0_EVENTS.with(|usb0| {
             ladybug::trace(Channel::A, Bit::B_IRQ_BUS_RESET, || {
-                usb0.controller
-                    .ev_pending()
-                    .modify(|r, w| w.pending().bit(r.pending().bit()));
-
                 // handle bus reset in interrupt handler for lowest latency
                 usb0.bus_reset();
                 InterruptEvent::Usb(Target, UsbEvent::BusReset)
             })
-        }
+        }),
 
         // USB0_EP_CONTROL ReceiveSetupPacket
-        pac::Interrupt::USB0_EP_CONTROL => {
+        pac::Interrupt::USB0_EP_CONTROL => USB0_EVENTS.with(|usb0| {
             ladybug::trace(Channel::B, Bit::B_IRQ_EP_CONTROL, || {
-                usb0.ep_control
-                    .ev_pending()
-                    .modify(|r, w| w.pending().bit(r.pending().bit()));
-
                 // read setup packet in interrupt handler for lowest latency
-                let endpoint_number = usb0.ep_control.epno().read().bits() as u8;
-                let mut setup_packet_buffer = [0_u8; 8];
-                let bytes_read = usb0.read_control(&mut setup_packet_buffer);
-                let setup_packet = SetupPacket::from(setup_packet_buffer);
-                if bytes_read == 0 {
-                    InterruptEvent::ErrorMessage("ERROR USB0 received 0 bytes for setup packet")
-                } else {
-                    InterruptEvent::Usb(
-                        Target,
-                        UsbEvent::ReceiveSetupPacket(endpoint_number, setup_packet),
-                    )
-                }
+                let mut buffer = [0_u8; 8];
+                let received = usb0.receive_setup_packet(&mut buffer);
+                setup_packet_event(Target, received, buffer)
             })
-        }
+        }),
 
         // USB0_EP_IN SendComplete
-        pac::Interrupt::USB0_EP_IN => ladybug::trace(Channel::B, Bit::B_IRQ_EP_IN, || {
-            usb0.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb0.ep_in.epno().read().bits() as u8;
-            unsafe {
-                usb0.clear_tx_ack_active(endpoint_number);
-            }
-
-            InterruptEvent::Usb(Target, UsbEvent::SendComplete(endpoint_number))
+        pac::Interrupt::USB0_EP_IN => USB0_EVENTS.with(|usb0| {
+            ladybug::trace(Channel::B, Bit::B_IRQ_EP_IN, || {
+                InterruptEvent::Usb(Target, UsbEvent::SendComplete(usb0.send_complete()))
+            })
         }),
 
         // USB0_EP_OUT ReceivePacket
-        pac::Interrupt::USB0_EP_OUT => ladybug::trace(Channel::B, Bit::B_IRQ_EP_OUT, || {
-            usb0.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb0.ep_out.data_ep().read().bits() as u8;
-            InterruptEvent::Usb(Target, UsbEvent::ReceivePacket(endpoint_number))
+        pac::Interrupt::USB0_EP_OUT => USB0_EVENTS.with(|usb0| {
+            ladybug::trace(Channel::B, Bit::B_IRQ_EP_OUT, || {
+                InterruptEvent::Usb(Target, UsbEvent::ReceivePacket(usb0.receive_packet()))
+            })
         }),
 
         // - usb1 interrupts - "aux_phy" (host on r0.4) --
 
         // USB1 BusReset
-        pac::Interrupt::USB1 => {
-            usb1.controller
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // handle bus reset in interrupt handler for lowest latency
+        pac::Interrupt::USB1 => USB1_EVENTS.with(|usb1| {
             usb1.bus_reset();
             InterruptEvent::Usb(Aux, UsbEvent::BusReset)
-        }
+        }),
 
         // USB1_EP_CONTROL ReceiveSetupPacket
-        pac::Interrupt::USB1_EP_CONTROL => {
-            usb1.ep_control
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // read setup packet in interrupt handler for lowest latency
-            let endpoint_number = usb1.ep_control.epno().read().bits() as u8;
-            let mut setup_packet_buffer = [0_u8; 8];
-            let bytes_read = usb1.read_control(&mut setup_packet_buffer);
-            let setup_packet = SetupPacket::from(setup_packet_buffer);
-            if bytes_read == 0 {
-                InterruptEvent::ErrorMessage("ERROR USB1 received 0 bytes for setup packet")
-            } else {
-                InterruptEvent::Usb(
-                    Aux,
-                    UsbEvent::ReceiveSetupPacket(endpoint_number, setup_packet),
-                )
-            }
-        }
+        pac::Interrupt::USB1_EP_CONTROL => USB1_EVENTS.with(|usb1| {
+            let mut buffer = [0_u8; 8];
+            let received = usb1.receive_setup_packet(&mut buffer);
+            setup_packet_event(Aux, received, buffer)
+        }),
 
         // USB1_EP_IN SendComplete
-        pac::Interrupt::USB1_EP_IN => {
-            usb1.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb1.ep_in.epno().read().bits() as u8;
-            unsafe {
-                usb1.clear_tx_ack_active(endpoint_number);
-            }
-
-            InterruptEvent::Usb(Aux, UsbEvent::SendComplete(endpoint_number))
-        }
+        pac::Interrupt::USB1_EP_IN => USB1_EVENTS
+            .with(|usb1| InterruptEvent::Usb(Aux, UsbEvent::SendComplete(usb1.send_complete()))),
 
-        // USB1_EP_OUT ReceivePacket}
-        pac::Interrupt::USB1_EP_OUT => {
-            usb1.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb1.ep_out.data_ep().read().bits() as u8;
-            InterruptEvent::Usb(Aux, UsbEvent::ReceivePacket(endpoint_number))
-        }
+        // USB1_EP_OUT ReceivePacket
+        pac::Interrupt::USB1_EP_OUT => USB1_EVENTS
+            .with(|usb1| InterruptEvent::Usb(Aux, UsbEvent::ReceivePacket(usb1.receive_packet()))),
 
         // - usb2 interrupts - "control_phy" (sideband on r0.4) --
 
         // USB2 BusReset
-        pac::Interrupt::USB2 => {
-            usb2.controller
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // handle bus reset in interrupt handler for lowest latency
+        pac::Interrupt::USB2 => USB2_EVENTS.with(|usb2| {
             usb2.bus_reset();
             InterruptEvent::Usb(Control, UsbEvent::BusReset)
-        }
+        }),
 
         // USB2_EP_CONTROL ReceiveControl
-        pac::Interrupt::USB2_EP_CONTROL => {
-            usb2.ep_control
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            // read setup packet in interrupt handler for lowest latency
-            let endpoint_number = usb2.ep_control.epno().read().bits() as u8;
-            let mut setup_packet_buffer = [0_u8; 8];
-            let bytes_read = usb2.read_control(&mut setup_packet_buffer);
-            let setup_packet = SetupPacket::from(setup_packet_buffer);
-            if bytes_read == 0 {
-                InterruptEvent::ErrorMessage("ERROR USB1 received 0 bytes for setup packet")
-            } else {
-                InterruptEvent::Usb(
-                    Control,
-                    UsbEvent::ReceiveSetupPacket(endpoint_number, setup_packet),
-                )
-            }
-        }
+        pac::Interrupt::USB2_EP_CONTROL => USB2_EVENTS.with(|usb2| {
+            let mut buffer = [0_u8; 8];
+            let received = usb2.receive_setup_packet(&mut buffer);
+            setup_packet_event(Control, received, buffer)
+        }),
 
         // USB2_EP_IN SendComplete / NAK
-        pac::Interrupt::USB2_EP_IN => {
-            usb2.ep_in
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb2.ep_in.epno().read().bits() as u8;
-            unsafe {
-                usb2.clear_tx_ack_active(endpoint_number);
-            }
-
-            InterruptEvent::Usb(Control, UsbEvent::SendComplete(endpoint_number))
-        }
+        pac::Interrupt::USB2_EP_IN => USB2_EVENTS.with(|usb2| {
+            InterruptEvent::Usb(Control, UsbEvent::SendComplete(usb2.send_complete()))
+        }),
 
         // USB2_EP_OUT ReceivePacket
-        pac::Interrupt::USB2_EP_OUT => {
-            usb2.ep_out
-                .ev_pending()
-                .modify(|r, w| w.pending().bit(r.pending().bit()));
-
-            let endpoint_number = usb2.ep_out.data_ep().read().bits() as u8;
-            InterruptEvent::Usb(Control, UsbEvent::ReceivePacket(endpoint_number))
-        }
+        pac::Interrupt::USB2_EP_OUT => USB2_EVENTS.with(|usb2| {
+            InterruptEvent::Usb(Control, UsbEvent::ReceivePacket(usb2.receive_packet()))
+        }),
 
         // Unhandled
-        _ => InterruptEvent::UnhandledInterrupt(pending),
-    }
+        _ => return InterruptEvent::UnhandledInterrupt(interrupt),
+    };
+
+    // controllers without a registered handle are left pending
+    event.unwrap_or(InterruptEvent::UnhandledInterrupt(interrupt))
 }
 
 // - multi event queue --------------------------------------------------------
@@ -339,66 +272,6 @@ impl MultiEventQueue {
     }
 }
 
-/// Reads Cynthion's SPI Flash UUID
-pub fn read_flash_uuid(spi0: &pac::SPI0) -> Result<[u8; 8], GreatError> {
-    // FIXME wait for things to settle
-    unsafe {
-        riscv::asm::delay(80_000_000);
-    }
-
-    // configure spi0 phy
-    spi0.phy_len().write(|w| unsafe { w.phy_len().bits(8) });
-    spi0.phy_width().write(|w| unsafe { w.phy_width().bits(1) });
-    spi0.phy_mask().write(|w| unsafe { w.phy_mask().bits(1) });
-    spi0.cs().write(|w| w.cs().bit(false));
-
-    // check if we can write to spi0
-    if !spi_ready(&|| spi0.tx_rdy().read().tx_rdy().bit()) {
-        log::error!("spi write timeout");
-        return Err(GreatError::StreamIoctlTimeout);
-    }
-
-    // write flash id command to spi0
-    let command: [u8; 13] = [0x4b, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-    for byte in command {
-        spi0.rxtx()
-            .write(|w| unsafe { w.rxtx().bits(u32::from(byte)) });
-    }
-
-    // check if we can read from spi0
-    if !spi_ready(&|| spi0.rx_rdy().read().rx_rdy().bit()) {
-        log::error!("read_flash_uuid spi read timeout");
-        return Err(GreatError::StreamIoctlTimeout);
-    }
-
-    // read response
-    let mut response = [0_u8; 32];
-    let mut n = 0;
-    while spi0.rx_rdy().read().rx_rdy().bit() {
-        response[n] = spi0.rxtx().read().bits() as u8;
-        n += 1;
-        if n >= response.len() {
-            log::error!("read_flash_uuid read overflow");
-            return Err(GreatError::BadMessage);
-        }
-    }
-
-    // check response
-    if n != 13 {
-        log::error!(
-            "read_flash_uuid invalid response length: {} - {:02x?}",
-            n,
-            &response[..n]
-        );
-        return Err(GreatError::BadMessage);
-    }
-
-    let mut ret = [0_u8; 8];
-    ret[..].copy_from_slice(&response[5..13]);
-
-    Ok(ret)
-}
-
 /// Formats a buffer containing a flash uuid into a String
 #[must_use]
 pub fn format_flash_uuid(uuid: [u8; 8]) -> heapless::String<16> {
@@ -415,16 +288,73 @@ pub fn format_flash_uuid(uuid: [u8; 8]) -> heapless::String<16> {
     ret
 }
 
-/// Retries the provided closure until it either returns true or times out.
-fn spi_ready(f: &dyn Fn() -> bool) -> bool {
-    let mut timeout = 0;
+/// Returns the board information with the serial number of the given
+/// flash uuid
+///
+/// The uuid fills the last 8 bytes of the serial number in the order
+/// used by [`format_flash_uuid`] for the `iSerialNumber` string
+/// descriptor.
+#[must_use]
+pub fn board_information(uuid: [u8; 8]) -> crate::BoardInformation {
+    let mut serial_number = [0_u8; 16];
+    for (dest, byte) in serial_number[8..].iter_mut().zip(uuid.iter().rev()) {
+        *dest = *byte;
+    }
+
+    crate::BoardInformation {
+        serial_number,
+        ..crate::BOARD_INFORMATION
+    }
+}
 
-    while !f() {
-        timeout += 1;
-        if timeout > 1000 {
-            return false;
-        }
+// - tests --------------------------------------------------------------------
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+
+    use lunasoc_hal::sim;
+    use smolusb::device::Speed;
+    use smolusb::traits::UsbDriverOperations;
+
+    #[test]
+    fn test_usb_interrupt_without_handle() {
+        assert!(matches!(
+            get_usb_interrupt_event(),
+            InterruptEvent::UnknownInterrupt(0)
+        ));
+
+        let peripherals = pac::Peripherals::take().unwrap();
+        let mut usb1 = hal::Usb1::new(
+            peripherals.USB1,
+            peripherals.USB1_EP_CONTROL,
+            peripherals.USB1_EP_IN,
+            peripherals.USB1_EP_OUT,
+        );
+        usb1.connect(Speed::High);
+        usb1.enable_events();
+        unsafe { interrupt::enable(pac::Interrupt::USB1) };
+        sim::with(|soc| soc.usb1.host_bus_reset());
+
+        // interrupts for controllers without a handle are left pending
+        assert!(matches!(
+            get_usb_interrupt_event(),
+            InterruptEvent::UnhandledInterrupt(pac::Interrupt::USB1)
+        ));
+        assert!(interrupt::is_pending(pac::Interrupt::USB1));
     }
 
-    true
+    #[test]
+    fn test_board_information() {
+        let uuid = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        let board_information = board_information(uuid);
+        assert_eq!(
+            board_information.serial_number,
+            [0, 0, 0, 0, 0, 0, 0, 0, 0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01]
+        );
+        assert_eq!(
+            board_information.board_id,
+            crate::BOARD_INFORMATION.board_id
+        );
+    }
 }
@@ -4,6 +4,7 @@
 pub enum ErrorKind {
     Timeout(usize),
     Overflow(usize),
+    InvalidPacketSize,
 }
 
 impl core::fmt::Display for ErrorKind {
@@ -20,6 +21,7 @@ impl core::error::Error for ErrorKind {
         match self {
             Timeout(_) => "Blocking operation timed-out",
             Overflow(_) => "Read operation overflowed receive buffer",
+            InvalidPacketSize => "Endpoint packet size is invalid",
         }
     }
 }